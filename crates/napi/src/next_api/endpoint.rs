@@ -7,7 +7,7 @@ use next_api::{
     route::{Endpoint, WrittenEndpoint},
 };
 use tracing::Instrument;
-use turbo_tasks::{Completion, ReadRef, Vc, VcValueType};
+use turbo_tasks::{Completion, ReadRef, TaskPriority, Vc, VcValueType};
 use turbopack_core::{
     diagnostics::PlainDiagnostic,
     error::PrettyPrintError,
@@ -147,8 +147,10 @@ pub async fn endpoint_write_to_disk(
 ) -> napi::Result<TurbopackResult<NapiWrittenEndpoint>> {
     let turbo_tasks = endpoint.turbo_tasks().clone();
     let endpoint = ***endpoint;
+    // Someone, usually a request to the dev server, waits for the endpoint, so
+    // it's preferred over background work like eager compilation.
     let (written, issues, diags) = turbo_tasks
-        .run_once(async move {
+        .run_once_with_priority(TaskPriority::High, async move {
             let WrittenEndpointWithIssues {
                 written,
                 issues,
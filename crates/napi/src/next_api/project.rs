@@ -23,7 +23,7 @@ use rand::Rng;
 use tokio::{io::AsyncWriteExt, time::Instant};
 use tracing::Instrument;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Registry};
-use turbo_tasks::{Completion, RcStr, ReadRef, TransientInstance, UpdateInfo, Vc};
+use turbo_tasks::{Completion, RcStr, ReadRef, TaskId, TransientInstance, UpdateInfo, Vc};
 use turbo_tasks_fs::{
     util::uri_from_file, DiskFileSystem, FileContent, FileSystem, FileSystemPath,
 };
@@ -630,6 +630,7 @@ async fn get_entrypoints_with_issues(
 ) -> Result<Vc<EntrypointsWithIssues>> {
     let entrypoints_operation = container.entrypoints();
     let entrypoints = entrypoints_operation.strongly_consistent().await?;
+    container.emit_typed_routes().strongly_consistent().await?;
     let issues = get_issues(entrypoints_operation).await?;
     let diagnostics = get_diagnostics(entrypoints_operation).await?;
     Ok(EntrypointsWithIssues {
@@ -993,6 +994,75 @@ pub fn project_update_info_subscribe(
     Ok(())
 }
 
+#[napi(object)]
+pub struct NapiTaskGraphOptions {
+    /// Only returns the tasks at most `depth` edges away from this task.
+    pub task: Option<u32>,
+    /// Defaults to 2 when `task` is set.
+    pub depth: Option<u32>,
+    /// Either `json` (default) or `dot`.
+    pub format: Option<String>,
+}
+
+fn task_id_from_napi(task: u32) -> napi::Result<TaskId> {
+    if task == 0 {
+        return Err(napi::Error::from_reason("task ids start at 1"));
+    }
+    Ok(TaskId::from(task))
+}
+
+/// Returns a snapshot of the task graph as JSON or in the DOT format. Returns
+/// `null` when the backend doesn't support inspecting the task graph.
+#[napi]
+pub fn project_task_graph(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    options: NapiTaskGraphOptions,
+) -> napi::Result<Option<String>> {
+    let Some(mut graph) = project.turbo_tasks.task_graph() else {
+        return Ok(None);
+    };
+    if let Some(task) = options.task {
+        graph = graph.subgraph(
+            task_id_from_napi(task)?,
+            options.depth.unwrap_or(2) as usize,
+        );
+    }
+    match options.format.as_deref() {
+        None | Some("json") => graph
+            .to_json()
+            .map(Some)
+            .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string())),
+        Some("dot") => Ok(Some(graph.to_dot())),
+        Some(format) => Err(napi::Error::from_reason(format!(
+            "unknown task graph format {format}, expected json or dot"
+        ))),
+    }
+}
+
+/// Enables recording why tasks are invalidated, which is needed by
+/// [project_explain_invalidation]. Disabling it drops all records.
+#[napi]
+pub fn project_set_track_invalidation_causes(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    enabled: bool,
+) {
+    project.turbo_tasks.set_track_invalidation_causes(enabled);
+}
+
+/// Returns the chain of invalidations, as JSON, that caused `task` to be
+/// recomputed the last time, starting with `task` and ending with the
+/// originating change.
+#[napi]
+pub fn project_explain_invalidation(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    task: u32,
+) -> napi::Result<String> {
+    let steps = project
+        .turbo_tasks
+        .explain_invalidation(task_id_from_napi(task)?);
+    serde_json::to_string(&steps).map_err(|e| napi::Error::from_reason(e.to_string()))
+}
+
 #[turbo_tasks::value]
 #[derive(Debug)]
 #[napi(object)]
@@ -1064,6 +1134,185 @@ pub async fn get_source_map(
     Ok(Some(map))
 }
 
+#[turbo_tasks::value(serialization = "none")]
+struct BundleSizeReportWithIssues {
+    table: ReadRef<RcStr>,
+    issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
+}
+
+#[turbo_tasks::function]
+async fn get_bundle_size_report_with_issues(
+    container: Vc<ProjectContainer>,
+) -> Result<Vc<BundleSizeReportWithIssues>> {
+    let report = container
+        .bundle_size_report()
+        .resolve_strongly_consistent()
+        .await?;
+    let table = report.table().await?;
+    // The compilation issues of the endpoints have been reported when they
+    // were written, so only the budget issues are collected.
+    let check_operation = container.check_bundle_size_budgets(report);
+    check_operation.strongly_consistent().await?;
+    let issues = get_issues(check_operation).await?;
+    let diagnostics = get_diagnostics(check_operation).await?;
+    Ok(BundleSizeReportWithIssues {
+        table,
+        issues,
+        diagnostics,
+    }
+    .cell())
+}
+
+/// Computes the bundle sizes of all routes and returns them as a table. Routes
+/// exceeding their budget are reported as issues.
+#[napi]
+pub async fn project_bundle_size_report(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<TurbopackResult<String>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let (table, issues, diagnostics) = turbo_tasks
+        .run_once(async move {
+            let BundleSizeReportWithIssues {
+                table,
+                issues,
+                diagnostics,
+            } = &*get_bundle_size_report_with_issues(container)
+                .strongly_consistent()
+                .await?;
+            Ok((table.clone(), issues.clone(), diagnostics.clone()))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: table.to_string(),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diagnostics
+            .iter()
+            .map(|d| NapiDiagnostic::from(d))
+            .collect(),
+    })
+}
+
+#[turbo_tasks::value(serialization = "none")]
+struct CssOrderWithIssues {
+    issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
+}
+
+#[turbo_tasks::function]
+async fn get_css_order_with_issues(
+    container: Vc<ProjectContainer>,
+) -> Result<Vc<CssOrderWithIssues>> {
+    let client_assets = container
+        .route_client_assets()
+        .resolve_strongly_consistent()
+        .await?;
+    // The compilation issues of the pages have been reported when they were
+    // written, so only the order issues are collected.
+    let check_operation = container.check_css_order(client_assets);
+    check_operation.strongly_consistent().await?;
+    let issues = get_issues(check_operation).await?;
+    let diagnostics = get_diagnostics(check_operation).await?;
+    Ok(CssOrderWithIssues {
+        issues,
+        diagnostics,
+    }
+    .cell())
+}
+
+/// Checks that all pages load their CSS modules in the same order. Modules
+/// loaded in conflicting orders are reported as issues.
+#[napi]
+pub async fn project_check_css_order(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+) -> napi::Result<TurbopackResult<()>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    let (issues, diagnostics) = turbo_tasks
+        .run_once(async move {
+            let CssOrderWithIssues {
+                issues,
+                diagnostics,
+            } = &*get_css_order_with_issues(container)
+                .strongly_consistent()
+                .await?;
+            Ok((issues.clone(), diagnostics.clone()))
+        })
+        .await
+        .map_err(|e| napi::Error::from_reason(PrettyPrintError(&e).to_string()))?;
+    Ok(TurbopackResult {
+        result: (),
+        issues: issues.iter().map(|i| NapiIssue::from(&**i)).collect(),
+        diagnostics: diagnostics
+            .iter()
+            .map(|d| NapiDiagnostic::from(d))
+            .collect(),
+    })
+}
+
+#[turbo_tasks::value(serialization = "none")]
+struct ChecksWithIssues {
+    issues: Arc<Vec<ReadRef<PlainIssue>>>,
+    diagnostics: Arc<Vec<ReadRef<PlainDiagnostic>>>,
+}
+
+#[turbo_tasks::function]
+async fn get_checks_with_issues(container: Vc<ProjectContainer>) -> Result<Vc<ChecksWithIssues>> {
+    let checks_operation = container.checks();
+    checks_operation.strongly_consistent().await?;
+    let issues = get_issues(checks_operation).await?;
+    let diagnostics = get_diagnostics(checks_operation).await?;
+    Ok(ChecksWithIssues {
+        issues,
+        diagnostics,
+    }
+    .cell())
+}
+
+/// Type checks and lints the module graph and calls `func` with the problems as
+/// issues, again whenever they change.
+#[napi(ts_return_type = "{ __napiType: \"RootTask\" }")]
+pub fn project_checks_subscribe(
+    #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
+    func: JsFunction,
+) -> napi::Result<External<RootTask>> {
+    let turbo_tasks = project.turbo_tasks.clone();
+    let container = project.container;
+    subscribe(
+        turbo_tasks.clone(),
+        func,
+        move || {
+            async move {
+                let ChecksWithIssues {
+                    issues,
+                    diagnostics,
+                } = &*get_checks_with_issues(container)
+                    .strongly_consistent()
+                    .await?;
+                Ok((issues.clone(), diagnostics.clone()))
+            }
+            .instrument(tracing::info_span!("checks subscription"))
+        },
+        move |ctx| {
+            let (issues, diagnostics) = ctx.value;
+
+            Ok(vec![TurbopackResult {
+                result: (),
+                issues: issues
+                    .iter()
+                    .map(|issue| NapiIssue::from(&**issue))
+                    .collect(),
+                diagnostics: diagnostics
+                    .iter()
+                    .map(|d| NapiDiagnostic::from(d))
+                    .collect(),
+            }])
+        },
+    )
+}
+
 #[napi]
 pub async fn project_trace_source(
     #[napi(ts_arg_type = "{ __napiType: \"Project\" }")] project: External<ProjectInstance>,
@@ -10,7 +10,9 @@ use napi::{
 };
 use serde::Serialize;
 use turbo_tasks::{
-    trace::TraceRawVcs, ReadRef, TaskId, TryJoinIterExt, TurboTasks, UpdateInfo, Vc,
+    task_graph::{InvalidationStep, TaskGraph},
+    trace::TraceRawVcs,
+    ReadRef, TaskCanceled, TaskId, TaskPriority, TryJoinIterExt, TurboTasks, UpdateInfo, Vc,
 };
 use turbo_tasks_backend::{default_backing_storage, DefaultBackingStorage};
 use turbo_tasks_fs::FileContent;
@@ -61,6 +63,21 @@ impl NextTurboTasks {
         }
     }
 
+    pub async fn run_once_with_priority<T: TraceRawVcs + Send + 'static>(
+        &self,
+        priority: TaskPriority,
+        future: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => {
+                turbo_tasks.run_once_with_priority(priority, future).await
+            }
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.run_once_with_priority(priority, future).await
+            }
+        }
+    }
+
     pub fn spawn_once_task<T, Fut>(&self, future: Fut) -> TaskId
     where
         T: Send,
@@ -113,6 +130,33 @@ impl NextTurboTasks {
         }
     }
 
+    pub fn task_graph(&self) -> Option<TaskGraph> {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.task_graph(),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => turbo_tasks.task_graph(),
+        }
+    }
+
+    pub fn set_track_invalidation_causes(&self, enabled: bool) {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => {
+                turbo_tasks.set_track_invalidation_causes(enabled)
+            }
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.set_track_invalidation_causes(enabled)
+            }
+        }
+    }
+
+    pub fn explain_invalidation(&self, task: TaskId) -> Vec<InvalidationStep> {
+        match self {
+            NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.explain_invalidation(task),
+            NextTurboTasks::PersistentCaching(turbo_tasks) => {
+                turbo_tasks.explain_invalidation(task)
+            }
+        }
+    }
+
     pub async fn stop_and_wait(&self) {
         match self {
             NextTurboTasks::Memory(turbo_tasks) => turbo_tasks.stop_and_wait().await,
@@ -178,15 +222,17 @@ pub fn serde_enum_to_string<T: Serialize>(value: &T) -> Result<String> {
 
 /// The root of our turbopack computation.
 pub struct RootTask {
-    #[allow(dead_code)]
     turbo_tasks: NextTurboTasks,
-    #[allow(dead_code)]
     task_id: Option<TaskId>,
 }
 
 impl Drop for RootTask {
     fn drop(&mut self) {
-        // TODO stop the root task
+        // Stops the work that is only needed by this subscription, when it's
+        // garbage collected without being disposed.
+        if let Some(task) = self.task_id.take() {
+            self.turbo_tasks.dispose_root_task(task);
+        }
     }
 }
 
@@ -433,7 +479,11 @@ pub fn subscribe<T: 'static + Send + Sync, F: Future<Output = Result<T>> + Send,
         let handler = handler.clone();
         let func = func.clone();
         Box::pin(async move {
-            let result = handler().await;
+            let result = match handler().await {
+                // The subscription has been disposed, nobody is listening any more.
+                Err(error) if error.is::<TaskCanceled>() => return Err(error),
+                result => result,
+            };
 
             let status = func.call(
                 result.map_err(|e| {
@@ -584,6 +584,20 @@ impl AppProject {
         .resolve_entries(Vc::upcast(self.client_module_context())))
     }
 
+    /// The pathnames of the routes generated for metadata files, like
+    /// `/robots.txt` or `/opengraph-image`.
+    #[turbo_tasks::function]
+    pub async fn metadata_route_pathnames(self: Vc<Self>) -> Result<Vc<Vec<RcStr>>> {
+        Ok(Vc::cell(
+            self.app_entrypoints()
+                .await?
+                .iter()
+                .filter(|(_, entrypoint)| matches!(entrypoint, AppEntrypoint::AppMetadata { .. }))
+                .map(|(pathname, _)| pathname.to_string().into())
+                .collect(),
+        ))
+    }
+
     #[turbo_tasks::function]
     pub async fn routes(self: Vc<Self>) -> Result<Vc<Routes>> {
         let app_entrypoints = self.app_entrypoints();
@@ -1599,6 +1613,10 @@ impl Endpoint for AppEndpoint {
                 .project()
                 .emit_all_output_assets(Vc::cell(output_assets))
                 .await?;
+            this.app_project
+                .project()
+                .endpoint_written(Vc::upcast(self))
+                .await?;
 
             let node_root = this.app_project.project().node_root();
             let server_paths = all_server_paths(output_assets, node_root)
@@ -1654,6 +1672,11 @@ impl Endpoint for AppEndpoint {
         let rsc_entry = self.app_endpoint_entry().await?.rsc_entry;
         Ok(Vc::cell(vec![rsc_entry]))
     }
+
+    #[turbo_tasks::function]
+    fn client_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().client_assets()
+    }
 }
 
 #[turbo_tasks::value]
@@ -0,0 +1,566 @@
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    ops::{Add, AddAssign},
+};
+
+use anyhow::Result;
+use flate2::{write::GzEncoder, Compression};
+use next_core::next_config::{BundleSizeBudget, BundleSizeBudgets};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    trace::TraceRawVcs, FxIndexMap, FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt, Vc,
+};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    output::OutputAsset,
+    virtual_output::VirtualOutputAsset,
+};
+
+use crate::{
+    project::Project,
+    route::{Endpoint, Route, WrittenEndpoint},
+};
+
+/// The router of a route. The pages of each router load a different
+/// framework runtime, so their shared files are computed separately.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, TraceRawVcs,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Router {
+    Pages,
+    App,
+}
+
+impl Router {
+    fn shared_label(self) -> &'static str {
+        match self {
+            Router::Pages => "+ First Load shared by all pages",
+            Router::App => "+ First Load shared by all app routes",
+        }
+    }
+}
+
+/// The sizes of one or more output files.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Copy, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CompressedSizes {
+    pub raw: u64,
+    pub gzip: u64,
+    pub brotli: u64,
+}
+
+impl Add for CompressedSizes {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        CompressedSizes {
+            raw: self.raw + other.raw,
+            gzip: self.gzip + other.gzip,
+            brotli: self.brotli + other.brotli,
+        }
+    }
+}
+
+impl AddAssign for CompressedSizes {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// The client files loaded by every route of a router.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedBundleSizes {
+    pub files: Vec<RcStr>,
+    pub js: CompressedSizes,
+    pub css: CompressedSizes,
+}
+
+/// The client files of a route. `js` and `css` only count the files that are
+/// not shared by all routes of its router, the first load sizes count all of
+/// them.
+///
+/// The written endpoints don't tell apart the chunks loaded on navigation from
+/// the ones loaded on demand, so the latter are counted as well.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteBundleSizes {
+    pub router: Router,
+    pub files: Vec<RcStr>,
+    pub js: CompressedSizes,
+    pub css: CompressedSizes,
+    pub first_load_js: CompressedSizes,
+    pub first_load_css: CompressedSizes,
+}
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSizeReport {
+    pub shared: FxIndexMap<Router, SharedBundleSizes>,
+    pub routes: FxIndexMap<RcStr, RouteBundleSizes>,
+}
+
+#[turbo_tasks::value_impl]
+impl BundleSizeReport {
+    /// The report as `bundle-size-report.json` in `node_root`.
+    #[turbo_tasks::function]
+    pub async fn json_asset(
+        self: Vc<Self>,
+        node_root: Vc<FileSystemPath>,
+    ) -> Result<Vc<Box<dyn OutputAsset>>> {
+        let json = serde_json::to_string_pretty(&*self.await?)?;
+        Ok(Vc::upcast(VirtualOutputAsset::new(
+            node_root.join("bundle-size-report.json".into()),
+            AssetContent::file(File::from(json).into()),
+        )))
+    }
+
+    /// The report as a table with the first load sizes of each route.
+    #[turbo_tasks::function]
+    pub async fn table(self: Vc<Self>) -> Result<Vc<RcStr>> {
+        let this = self.await?;
+        let width = this
+            .routes
+            .keys()
+            .map(|pathname| pathname.len())
+            .chain(["Route".len()])
+            .chain(this.shared.keys().map(|router| router.shared_label().len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut table = String::new();
+        writeln!(
+            table,
+            "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Route", "Size", "First Load", "Gzip", "Brotli"
+        )?;
+        for (pathname, sizes) in &this.routes {
+            let first_load = sizes.first_load_js + sizes.first_load_css;
+            writeln!(
+                table,
+                "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}",
+                &**pathname,
+                format_bytes(sizes.js.raw + sizes.css.raw),
+                format_bytes(first_load.raw),
+                format_bytes(first_load.gzip),
+                format_bytes(first_load.brotli),
+            )?;
+        }
+        for (router, shared) in &this.shared {
+            let shared = shared.js + shared.css;
+            writeln!(
+                table,
+                "{:width$}  {:>10}  {:>10}  {:>10}  {:>10}",
+                router.shared_label(),
+                "",
+                format_bytes(shared.raw),
+                format_bytes(shared.gzip),
+                format_bytes(shared.brotli),
+            )?;
+        }
+        Ok(Vc::cell(table.into()))
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    if bytes < 1000 {
+        format!("{bytes} B")
+    } else {
+        format!("{:.1} kB", bytes as f64 / 1000.0)
+    }
+}
+
+/// Computes the sizes of the client JS and CSS of each route. The endpoints
+/// are written to disk to know their client files.
+#[turbo_tasks::function]
+pub async fn bundle_size_report(project: Vc<Project>) -> Result<Vc<BundleSizeReport>> {
+    let entrypoints = project.entrypoints().await?;
+    let client_relative_path = project.client_relative_path();
+
+    let mut route_files = FxIndexMap::default();
+    for (pathname, route) in &entrypoints.routes {
+        let (router, endpoints) = match route {
+            Route::Page { html_endpoint, .. } => (
+                Router::Pages,
+                vec![*html_endpoint, entrypoints.pages_app_endpoint],
+            ),
+            Route::AppPage(pages) => (
+                Router::App,
+                pages.iter().map(|page| page.html_endpoint).collect(),
+            ),
+            Route::PageApi { .. } | Route::AppRoute { .. } | Route::Conflict => continue,
+        };
+        let mut files = FxIndexSet::default();
+        for endpoint in endpoints {
+            files.extend(endpoint_client_files(endpoint).await?);
+        }
+        route_files.insert(pathname.clone(), (router, files));
+    }
+
+    let mut shared = FxIndexMap::default();
+    for (router, files) in shared_files(&route_files) {
+        let (js, css) = total_sizes(client_relative_path, files.iter()).await?;
+        shared.insert(
+            router,
+            SharedBundleSizes {
+                files: files.into_iter().collect(),
+                js,
+                css,
+            },
+        );
+    }
+
+    let mut routes = FxIndexMap::default();
+    for (pathname, (router, files)) in route_files {
+        let shared = &shared[&router];
+        let (js, css) = total_sizes(
+            client_relative_path,
+            files.iter().filter(|file| !shared.files.contains(*file)),
+        )
+        .await?;
+        routes.insert(
+            pathname,
+            RouteBundleSizes {
+                router,
+                files: files.into_iter().collect(),
+                js,
+                css,
+                first_load_js: js + shared.js,
+                first_load_css: css + shared.css,
+            },
+        );
+    }
+
+    Ok(BundleSizeReport { shared, routes }.cell())
+}
+
+/// The files loaded by all routes of each router.
+fn shared_files(
+    route_files: &FxIndexMap<RcStr, (Router, FxIndexSet<RcStr>)>,
+) -> FxIndexMap<Router, FxIndexSet<RcStr>> {
+    let mut shared: FxIndexMap<Router, FxIndexSet<RcStr>> = FxIndexMap::default();
+    for (router, files) in route_files.values() {
+        match shared.get_mut(router) {
+            Some(shared) => shared.retain(|file| files.contains(file)),
+            None => {
+                shared.insert(*router, files.clone());
+            }
+        }
+    }
+    shared
+}
+
+/// Emits an error for each route whose first load sizes exceed its budget.
+/// The report is passed resolved, so these errors are the only issues of this
+/// task and the compilation issues of the endpoints aren't reported again.
+#[turbo_tasks::function]
+pub async fn check_bundle_size_budgets(
+    project_path: ResolvedVc<FileSystemPath>,
+    budgets: Vc<BundleSizeBudgets>,
+    report: Vc<BundleSizeReport>,
+) -> Result<Vc<()>> {
+    for exceeded in exceeded_budgets(&*budgets.await?, &report.await?.routes) {
+        BundleSizeBudgetIssue {
+            path: project_path,
+            pathname: exceeded.pathname,
+            kind: exceeded.kind.into(),
+            size: exceeded.size,
+            limit: exceeded.limit,
+        }
+        .cell()
+        .emit();
+    }
+
+    Ok(Vc::cell(()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ExceededBudget {
+    pathname: RcStr,
+    kind: &'static str,
+    size: u64,
+    limit: u64,
+}
+
+/// The gzipped first load sizes over the budget of their route, or over the
+/// `*` budget for routes without their own.
+fn exceeded_budgets(
+    budgets: &FxIndexMap<RcStr, BundleSizeBudget>,
+    routes: &FxIndexMap<RcStr, RouteBundleSizes>,
+) -> Vec<ExceededBudget> {
+    let mut exceeded = Vec::new();
+    for (pathname, sizes) in routes {
+        let Some(budget) = budgets.get(pathname).or_else(|| budgets.get("*")) else {
+            continue;
+        };
+        let BundleSizeBudget {
+            first_load_js,
+            first_load_css,
+        } = budget;
+        for (kind, limit, size) in [
+            ("JS", first_load_js, sizes.first_load_js.gzip),
+            ("CSS", first_load_css, sizes.first_load_css.gzip),
+        ] {
+            if let Some(limit) = *limit {
+                if size > limit {
+                    exceeded.push(ExceededBudget {
+                        pathname: pathname.clone(),
+                        kind,
+                        size,
+                        limit,
+                    });
+                }
+            }
+        }
+    }
+    exceeded
+}
+
+async fn endpoint_client_files(endpoint: Vc<Box<dyn Endpoint>>) -> Result<Vec<RcStr>> {
+    let written = endpoint.write_to_disk().await?;
+    let (WrittenEndpoint::NodeJs { client_paths, .. } | WrittenEndpoint::Edge { client_paths, .. }) =
+        &*written;
+    Ok(client_paths
+        .iter()
+        .filter(|path| path.ends_with(".js") || path.ends_with(".css"))
+        .cloned()
+        .collect())
+}
+
+/// Sums up the sizes of the JS and of the CSS files.
+async fn total_sizes(
+    root: Vc<FileSystemPath>,
+    files: impl Iterator<Item = &RcStr>,
+) -> Result<(CompressedSizes, CompressedSizes)> {
+    let sizes = files
+        .map(|file| async move {
+            let sizes = *file_sizes(root.join(file.clone())).await?;
+            Ok((file.ends_with(".css"), sizes))
+        })
+        .try_join()
+        .await?;
+
+    let mut js = CompressedSizes::default();
+    let mut css = CompressedSizes::default();
+    for (is_css, sizes) in sizes {
+        if is_css {
+            css += sizes;
+        } else {
+            js += sizes;
+        }
+    }
+    Ok((js, css))
+}
+
+#[turbo_tasks::function]
+async fn file_sizes(path: Vc<FileSystemPath>) -> Result<Vc<CompressedSizes>> {
+    let FileContent::Content(file) = &*path.read().await? else {
+        return Ok(CompressedSizes::default().cell());
+    };
+    Ok(compressed_sizes(&file.content().to_bytes()?)?.cell())
+}
+
+/// The brotli quality used to estimate the compressed sizes. The highest
+/// quality is several times slower for about 10% smaller files.
+const BROTLI_QUALITY: i32 = 5;
+
+/// Compresses `content` with the default levels of gzip and with
+/// [`BROTLI_QUALITY`], which are close to what servers use for on-the-fly
+/// compression and much faster than the best levels.
+fn compressed_sizes(content: &[u8]) -> Result<CompressedSizes> {
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(content)?;
+    let gzip = gzip.finish()?;
+
+    let mut brotli = Vec::new();
+    brotli::BrotliCompress(
+        &mut &*content,
+        &mut brotli,
+        &brotli::enc::BrotliEncoderParams {
+            quality: BROTLI_QUALITY,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(CompressedSizes {
+        raw: content.len() as u64,
+        gzip: gzip.len() as u64,
+        brotli: brotli.len() as u64,
+    })
+}
+
+#[turbo_tasks::value(shared)]
+struct BundleSizeBudgetIssue {
+    path: ResolvedVc<FileSystemPath>,
+    pathname: RcStr,
+    kind: RcStr,
+    size: u64,
+    limit: u64,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for BundleSizeBudgetIssue {
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Misc.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            format!(
+                "Route {} exceeds its first load {} budget",
+                self.pathname, self.kind
+            )
+            .into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                format!(
+                    "The first load {} of {} is {} gzipped, over the budget of {} set in \
+                     `experimental.turbo.bundleSizeBudgets`.",
+                    self.kind,
+                    self.pathname,
+                    format_bytes(self.size),
+                    format_bytes(self.limit),
+                )
+                .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use next_core::next_config::BundleSizeBudget;
+    use turbo_tasks::{FxIndexMap, FxIndexSet, RcStr};
+
+    use super::{
+        compressed_sizes, exceeded_budgets, format_bytes, shared_files, CompressedSizes,
+        ExceededBudget, RouteBundleSizes, Router,
+    };
+
+    fn files(files: &[&str]) -> FxIndexSet<RcStr> {
+        files.iter().map(|file| RcStr::from(*file)).collect()
+    }
+
+    #[test]
+    fn shares_files_per_router() {
+        let route_files = FxIndexMap::from_iter([
+            (
+                "/".into(),
+                (Router::Pages, files(&["framework.js", "a.js"])),
+            ),
+            (
+                "/b".into(),
+                (Router::Pages, files(&["framework.js", "b.js"])),
+            ),
+            ("/c".into(), (Router::App, files(&["app.js", "c.js"]))),
+            (
+                "/d".into(),
+                (Router::App, files(&["app.js", "c.js", "d.js"])),
+            ),
+        ]);
+        let shared = shared_files(&route_files);
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared[&Router::Pages], files(&["framework.js"]));
+        assert_eq!(shared[&Router::App], files(&["app.js", "c.js"]));
+    }
+
+    fn route(first_load_js: u64, first_load_css: u64) -> RouteBundleSizes {
+        RouteBundleSizes {
+            router: Router::App,
+            files: vec![],
+            js: CompressedSizes::default(),
+            css: CompressedSizes::default(),
+            first_load_js: CompressedSizes {
+                gzip: first_load_js,
+                ..Default::default()
+            },
+            first_load_css: CompressedSizes {
+                gzip: first_load_css,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn checks_budgets_with_fallback() {
+        let budgets = FxIndexMap::from_iter([
+            (
+                "*".into(),
+                BundleSizeBudget {
+                    first_load_js: Some(100),
+                    first_load_css: None,
+                },
+            ),
+            (
+                "/large".into(),
+                BundleSizeBudget {
+                    first_load_js: Some(300),
+                    first_load_css: Some(10),
+                },
+            ),
+        ]);
+        let routes = FxIndexMap::from_iter([
+            ("/".into(), route(150, 50)),
+            ("/small".into(), route(100, 50)),
+            ("/large".into(), route(200, 20)),
+        ]);
+        assert_eq!(
+            exceeded_budgets(&budgets, &routes),
+            vec![
+                ExceededBudget {
+                    pathname: "/".into(),
+                    kind: "JS",
+                    size: 150,
+                    limit: 100,
+                },
+                ExceededBudget {
+                    pathname: "/large".into(),
+                    kind: "CSS",
+                    size: 20,
+                    limit: 10,
+                },
+            ]
+        );
+        assert!(exceeded_budgets(&FxIndexMap::default(), &routes).is_empty());
+    }
+
+    #[test]
+    fn compresses_content() {
+        let content = "console.log('hello');\n".repeat(100);
+        let sizes = compressed_sizes(content.as_bytes()).unwrap();
+        assert_eq!(sizes.raw, content.len() as u64);
+        assert!(sizes.gzip > 0 && sizes.gzip < sizes.raw);
+        assert!(sizes.brotli > 0 && sizes.brotli < sizes.raw);
+    }
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(999), "999 B");
+        assert_eq!(format_bytes(1234), "1.2 kB");
+    }
+}
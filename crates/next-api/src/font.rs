@@ -1,14 +1,19 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::Result;
-use next_core::{all_assets_from_entries, next_manifests::NextFontManifest};
-use turbo_tasks::{RcStr, ResolvedVc, ValueToString, Vc};
+use next_core::{
+    all_assets_from_entries, font_metrics_from_content, next_manifests::NextFontManifest,
+    FontMetrics,
+};
+use turbo_tasks::{RcStr, ResolvedVc, TryFlatJoinIterExt, ValueToString, Vc};
 use turbo_tasks_fs::{File, FileSystemPath};
 use turbopack_core::{
-    asset::AssetContent,
+    asset::{Asset, AssetContent},
     output::{OutputAsset, OutputAssets},
     virtual_output::VirtualOutputAsset,
 };
 
-use crate::paths::get_font_paths_from_root;
+use crate::paths::{get_file_stem, get_font_paths_from_root};
 
 pub(crate) async fn create_font_manifest(
     client_root: Vc<FileSystemPath>,
@@ -21,15 +26,15 @@ pub(crate) async fn create_font_manifest(
     app_dir: bool,
 ) -> Result<ResolvedVc<Box<dyn OutputAsset>>> {
     let all_client_output_assets = all_assets_from_entries(client_assets).await?;
+    let client_root = &*client_root.await?;
 
     // `_next` gets added again later, so we "strip" it here via
     // `get_font_paths_from_root`.
-    let font_paths: Vec<String> =
-        get_font_paths_from_root(&*client_root.await?, &all_client_output_assets)
-            .await?
-            .iter()
-            .filter_map(|p| p.split("_next/").last().map(|f| f.to_string()))
-            .collect();
+    let font_paths: Vec<String> = get_font_paths_from_root(client_root, &all_client_output_assets)
+        .await?
+        .iter()
+        .filter_map(|p| p.split("_next/").last().map(|f| f.to_string()))
+        .collect();
 
     let path = if app_dir {
         node_root.join(format!("server/app{manifest_path_prefix}/next-font-manifest.json",).into())
@@ -38,7 +43,7 @@ pub(crate) async fn create_font_manifest(
     };
 
     let has_fonts = !font_paths.is_empty();
-    let using_size_adjust = font_paths.iter().any(|path| path.contains("-s"));
+    let using_size_adjust = font_paths.iter().any(|path| has_size_adjust_marker(path));
 
     let font_paths = font_paths
         .into_iter()
@@ -46,6 +51,12 @@ pub(crate) async fn create_font_manifest(
         .map(RcStr::from)
         .collect::<Vec<_>>();
 
+    let font_metrics = if using_size_adjust {
+        get_size_adjusted_font_metrics(client_root, &all_client_output_assets).await?
+    } else {
+        Default::default()
+    };
+
     let next_font_manifest = if !has_fonts {
         Default::default()
     } else if app_dir {
@@ -55,12 +66,14 @@ pub(crate) async fn create_font_manifest(
         NextFontManifest {
             app: [(page_path, font_paths)].into_iter().collect(),
             app_using_size_adjust: using_size_adjust,
+            font_metrics,
             ..Default::default()
         }
     } else {
         NextFontManifest {
             pages: [(pathname.into(), font_paths)].into_iter().collect(),
             pages_using_size_adjust: using_size_adjust,
+            font_metrics,
             ..Default::default()
         }
     };
@@ -76,3 +89,73 @@ pub(crate) async fn create_font_manifest(
         .await?,
     ))
 }
+
+/// Whether the font file has a size-adjusted fallback. The font loaders replace
+/// the dashes of the file name and append `-s` to it for these files, before
+/// the `.p` preload marker, the content hash and the extension.
+fn has_size_adjust_marker(path: &str) -> bool {
+    get_file_stem(path).ends_with("-s")
+}
+
+/// Reads the metrics of every font file that has a size-adjusted fallback (the
+/// `-s` marker), keyed the same way as the font paths in the manifest.
+async fn get_size_adjusted_font_metrics(
+    client_root: &FileSystemPath,
+    output_assets: &[ResolvedVc<Box<dyn OutputAsset>>],
+) -> Result<HashMap<RcStr, FontMetrics>> {
+    let size_adjusted_font_paths: HashSet<RcStr> =
+        get_font_paths_from_root(client_root, output_assets)
+            .await?
+            .into_iter()
+            .filter(|path| has_size_adjust_marker(path))
+            .collect();
+
+    Ok(output_assets
+        .iter()
+        .map(|&asset| {
+            let size_adjusted_font_paths = &size_adjusted_font_paths;
+            async move {
+                let path = &*asset.ident().path().await?;
+                let Some(relative) = client_root.get_path_to(path) else {
+                    return Ok(None);
+                };
+                if !size_adjusted_font_paths.contains(relative) {
+                    return Ok(None);
+                }
+
+                let Some(metrics) = &*font_metrics_from_content(asset.content()).await? else {
+                    return Ok(None);
+                };
+                let key = relative.split("_next/").last().unwrap_or(relative);
+
+                Ok(Some((RcStr::from(key), metrics.clone())))
+            }
+        })
+        .try_flat_join()
+        .await?
+        .into_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::has_size_adjust_marker;
+
+    #[test]
+    fn matches_the_size_adjust_marker_exactly() {
+        for path in [
+            "static/media/inter_var-s.p.0e4b1a2c.woff2",
+            "static/media/inter_var-s.0e4b1a2c.woff2",
+        ] {
+            assert!(has_size_adjust_marker(path), "{path}");
+        }
+        for path in [
+            "static/media/inter_var.p.0e4b1a2c.woff2",
+            "static/media/open-sans.0e4b1a2c.woff2",
+            "my-site/media/font.p.0e4b1a2c.woff2",
+            "static/media/font.p-s.0e4b1a2c.woff2",
+        ] {
+            assert!(!has_size_adjust_marker(path), "{path}");
+        }
+    }
+}
@@ -233,6 +233,7 @@ impl Endpoint for InstrumentationEndpoint {
             this.project
                 .emit_all_output_assets(Vc::cell(output_assets))
                 .await?;
+            this.project.endpoint_written(Vc::upcast(self)).await?;
 
             let node_root = this.project.node_root();
             let server_paths = all_server_paths(output_assets, node_root)
@@ -267,4 +268,9 @@ impl Endpoint for InstrumentationEndpoint {
             core_modules.edge_entry_module,
         ]))
     }
+
+    #[turbo_tasks::function]
+    fn client_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        OutputAssets::empty()
+    }
 }
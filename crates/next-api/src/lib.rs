@@ -4,6 +4,7 @@
 #![feature(impl_trait_in_assoc_type)]
 
 mod app;
+pub mod bundle_size;
 mod dynamic_imports;
 mod empty;
 pub mod entrypoints;
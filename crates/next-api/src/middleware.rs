@@ -278,6 +278,7 @@ impl Endpoint for MiddlewareEndpoint {
             this.project
                 .emit_all_output_assets(Vc::cell(output_assets))
                 .await?;
+            this.project.endpoint_written(Vc::upcast(self)).await?;
 
             let node_root = this.project.node_root();
             let server_paths = all_server_paths(output_assets, node_root)
@@ -314,4 +315,9 @@ impl Endpoint for MiddlewareEndpoint {
     async fn root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
         Ok(Vc::cell(vec![self.userland_module().to_resolved().await?]))
     }
+
+    #[turbo_tasks::function]
+    fn client_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        OutputAssets::empty()
+    }
 }
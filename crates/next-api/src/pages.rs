@@ -1282,6 +1282,10 @@ impl Endpoint for PageEndpoint {
                 .project()
                 .emit_all_output_assets(Vc::cell(output_assets))
                 .await?;
+            this.pages_project
+                .project()
+                .endpoint_written(Vc::upcast(self))
+                .await?;
 
             let node_root = this.pages_project.project().node_root();
             let server_paths = all_server_paths(output_assets, node_root)
@@ -1348,6 +1352,11 @@ impl Endpoint for PageEndpoint {
 
         Ok(Vc::cell(modules))
     }
+
+    #[turbo_tasks::function]
+    fn client_assets(self: Vc<Self>) -> Vc<OutputAssets> {
+        self.output().client_assets()
+    }
 }
 
 #[turbo_tasks::value]
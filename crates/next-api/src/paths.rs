@@ -120,7 +120,7 @@ pub(crate) async fn get_font_paths_from_root(
     .await
 }
 
-fn get_file_stem(path: &str) -> &str {
+pub(crate) fn get_file_stem(path: &str) -> &str {
     let file_name = if let Some((_, file_name)) = path.rsplit_once('/') {
         file_name
     } else {
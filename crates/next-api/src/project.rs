@@ -1,12 +1,16 @@
-use std::{path::MAIN_SEPARATOR, time::Duration};
+use std::{
+    path::{Path, MAIN_SEPARATOR},
+    time::Duration,
+};
 
 use anyhow::{bail, Context, Result};
 use indexmap::map::Entry;
 use next_core::{
     all_assets_from_entries,
     app_structure::find_app_dir,
-    emit_assets, get_edge_chunking_context, get_edge_chunking_context_with_client_assets,
-    get_edge_compile_time_info, get_edge_resolve_options_context,
+    emit_assets, emitted_paths, get_edge_chunking_context,
+    get_edge_chunking_context_with_client_assets, get_edge_compile_time_info,
+    get_edge_resolve_options_context,
     instrumentation::instrumentation_files,
     middleware::middleware_files,
     mode::NextMode,
@@ -18,6 +22,7 @@ use next_core::{
         get_server_resolve_options_context, ServerContextType,
     },
     next_telemetry::NextFeatureTelemetry,
+    typed_routes::route_type_definitions,
     util::NextRuntime,
 };
 use serde::{Deserialize, Serialize};
@@ -27,15 +32,20 @@ use turbo_tasks::{
     fxindexmap,
     graph::{AdjacencyMap, GraphTraversal},
     trace::TraceRawVcs,
-    Completion, Completions, FxIndexMap, IntoTraitRef, RcStr, ReadRef, ResolvedVc, State,
-    TaskInput, TransientInstance, TryFlatJoinIterExt, Value, Vc,
+    Completion, Completions, FxIndexMap, FxIndexSet, IntoTraitRef, RcStr, ReadRef, ResolvedVc,
+    State, TaskInput, TransientInstance, TryFlatJoinIterExt, Value, Vc,
 };
 use turbo_tasks_env::{EnvMap, ProcessEnv};
-use turbo_tasks_fs::{DiskFileSystem, FileSystem, FileSystemPath, VirtualFileSystem};
+use turbo_tasks_fs::{
+    DiskFileSystem, File, FileContent, FileSystem, FileSystemPath, VirtualFileSystem,
+};
 use turbopack::{
-    evaluate_context::node_build_environment, transition::TransitionOptions, ModuleAssetContext,
+    evaluate_context::{node_build_environment, node_evaluate_asset_context},
+    transition::TransitionOptions,
+    ModuleAssetContext,
 };
 use turbopack_core::{
+    asset::Asset,
     changed::content_changed,
     chunk::{
         module_id_strategies::{DevModuleIdStrategy, ModuleIdStrategy},
@@ -55,19 +65,22 @@ use turbopack_core::{
     },
     PROJECT_FILESYSTEM_NAME,
 };
-use turbopack_node::execution_context::ExecutionContext;
+use turbopack_css::chunk::order::check_css_order_conflicts;
+use turbopack_ecmascript::typescript::resolve::tsconfig;
+use turbopack_node::{eslint, execution_context::ExecutionContext, typescript};
 use turbopack_nodejs::NodeJsChunkingContext;
 
 use crate::{
     app::{AppProject, OptionAppProject, ECMASCRIPT_CLIENT_TRANSITION_NAME},
     build,
+    bundle_size::{self, BundleSizeReport},
     empty::EmptyEndpoint,
     entrypoints::Entrypoints,
     global_module_id_strategy::GlobalModuleIdStrategyBuilder,
     instrumentation::InstrumentationEndpoint,
     middleware::MiddlewareEndpoint,
     pages::PagesProject,
-    route::{Endpoint, Route},
+    route::{Endpoint, Endpoints, Route},
     versioned_content_map::{OutputAssetsOperation, VersionedContentMap},
 };
 
@@ -192,11 +205,29 @@ pub struct Instrumentation {
     pub edge: Vc<Box<dyn Endpoint>>,
 }
 
+/// The endpoints that have been written to disk in development.
+#[turbo_tasks::value]
+struct WrittenEndpoints {
+    endpoints: State<FxIndexSet<Vc<Box<dyn Endpoint>>>>,
+}
+
+impl WrittenEndpoints {
+    // This must not be a `#[turbo_tasks::function]` because it should be a
+    // singleton for each project.
+    fn new() -> Vc<Self> {
+        WrittenEndpoints {
+            endpoints: State::new(FxIndexSet::default()),
+        }
+        .cell()
+    }
+}
+
 #[turbo_tasks::value]
 pub struct ProjectContainer {
     name: RcStr,
     options_state: State<Option<ProjectOptions>>,
     versioned_content_map: Option<Vc<VersionedContentMap>>,
+    written_endpoints: Option<Vc<WrittenEndpoints>>,
 }
 
 #[turbo_tasks::value_impl]
@@ -208,6 +239,9 @@ impl ProjectContainer {
             // we only need to enable versioning in dev mode, since build
             // is assumed to be operating over a static snapshot
             versioned_content_map: dev.then(VersionedContentMap::new),
+            // Checks of the whole project only look at the endpoints that
+            // have been compiled in dev mode
+            written_endpoints: dev.then(WrittenEndpoints::new),
             options_state: State::new(None),
         }
         .cell()
@@ -384,6 +418,7 @@ impl ProjectContainer {
                 NextMode::Build.cell()
             },
             versioned_content_map: self.versioned_content_map,
+            written_endpoints: self.written_endpoints,
             build_id,
             encryption_key,
             preview_props,
@@ -397,12 +432,50 @@ impl ProjectContainer {
         self.project().entrypoints()
     }
 
+    /// See [Project::emit_typed_routes].
+    #[turbo_tasks::function]
+    pub fn emit_typed_routes(self: Vc<Self>) -> Vc<()> {
+        self.project().emit_typed_routes()
+    }
+
     /// See [Project::hmr_identifiers].
     #[turbo_tasks::function]
     pub fn hmr_identifiers(self: Vc<Self>) -> Vc<Vec<RcStr>> {
         self.project().hmr_identifiers()
     }
 
+    /// See [Project::bundle_size_report].
+    #[turbo_tasks::function]
+    pub fn bundle_size_report(self: Vc<Self>) -> Vc<BundleSizeReport> {
+        self.project().bundle_size_report()
+    }
+
+    /// See [Project::check_bundle_size_budgets].
+    #[turbo_tasks::function]
+    pub fn check_bundle_size_budgets(self: Vc<Self>, report: Vc<BundleSizeReport>) -> Vc<()> {
+        self.project().check_bundle_size_budgets(report)
+    }
+
+    /// See [Project::route_client_assets].
+    #[turbo_tasks::function]
+    pub fn route_client_assets(self: Vc<Self>) -> Vc<RouteClientAssets> {
+        self.project().route_client_assets()
+    }
+
+    /// See [Project::check_css_order].
+    #[turbo_tasks::function]
+    pub fn check_css_order(self: Vc<Self>, client_assets: Vc<RouteClientAssets>) -> Vc<()> {
+        self.project().check_css_order(client_assets)
+    }
+
+    /// Type checks and lints the project. See [Project::type_check] and
+    /// [Project::lint].
+    #[turbo_tasks::function]
+    pub fn checks(self: Vc<Self>) -> Vc<Completion> {
+        let project = self.project();
+        Vc::<Completions>::cell(vec![project.type_check(), project.lint()]).completed()
+    }
+
     /// Gets a source map for a particular `file_path`. If `dev` mode is
     /// disabled, this will always return [`OptionSourceMap::none`].
     #[turbo_tasks::function]
@@ -454,6 +527,8 @@ pub struct Project {
 
     versioned_content_map: Option<Vc<VersionedContentMap>>,
 
+    written_endpoints: Option<Vc<WrittenEndpoints>>,
+
     build_id: RcStr,
 
     encryption_key: RcStr,
@@ -486,6 +561,10 @@ impl ProjectDefineEnv {
     }
 }
 
+/// The client assets of each page, in the order they are loaded.
+#[turbo_tasks::value(transparent)]
+pub struct RouteClientAssets(Vec<(RcStr, ResolvedVc<OutputAssets>)>);
+
 #[turbo_tasks::value(shared)]
 struct ConflictIssue {
     path: Vc<FileSystemPath>,
@@ -539,12 +618,26 @@ impl Project {
     }
 
     #[turbo_tasks::function]
-    pub fn project_fs(&self) -> Vc<DiskFileSystem> {
-        DiskFileSystem::new(
+    pub async fn project_fs(&self) -> Result<Vc<DiskFileSystem>> {
+        let mut watch_ignores = self.next_config.watch_ignore_options().await?.clone_value();
+        // The globs of the config are relative to the project directory, but the root of the
+        // filesystem can be a parent of it.
+        let project_dir = Path::new(&*self.project_path)
+            .strip_prefix(&*self.root_path)
+            .ok()
+            .and_then(|path| path.to_str())
+            .map(|path| path.replace(MAIN_SEPARATOR, "/"))
+            .unwrap_or_default();
+        if !project_dir.is_empty() {
+            for glob in &mut watch_ignores.ignored_globs {
+                *glob = format!("{project_dir}/{}", glob.trim_start_matches("./")).into();
+            }
+        }
+        Ok(DiskFileSystem::new_with_watch_ignores(
             PROJECT_FILESYSTEM_NAME.into(),
             self.root_path.clone(),
-            vec![],
-        )
+            watch_ignores.cell(),
+        ))
     }
 
     #[turbo_tasks::function]
@@ -555,7 +648,7 @@ impl Project {
 
     #[turbo_tasks::function]
     pub fn output_fs(&self) -> Vc<DiskFileSystem> {
-        DiskFileSystem::new("output".into(), self.project_path.clone(), vec![])
+        DiskFileSystem::new_owned_output("output".into(), self.project_path.clone(), vec![])
     }
 
     #[turbo_tasks::function]
@@ -652,7 +745,8 @@ impl Project {
             self.project_path(),
             node_execution_chunking_context,
             self.env(),
-        ))
+        )
+        .with_pool_options(self.next_config().node_pool_options()))
     }
 
     #[turbo_tasks::function]
@@ -930,6 +1024,236 @@ impl Project {
         .cell())
     }
 
+    /// Writes the route type definitions for `experimental.typedRoutes` to
+    /// `<distDir>/types/link.d.ts`. The file is only rewritten when the set of
+    /// routes changes.
+    #[turbo_tasks::function]
+    pub async fn emit_typed_routes(self: Vc<Self>) -> Result<Vc<()>> {
+        // Like webpack, typed routes are only generated for projects with an app directory.
+        if !*self.next_config().typed_routes().await? {
+            return Ok(Vc::cell(()));
+        }
+        let Some(app_project) = *self.app_project().await? else {
+            return Ok(Vc::cell(()));
+        };
+
+        let entrypoints = self.entrypoints().await?;
+        // Webpack only collects pages and route handlers, so metadata routes are left out.
+        let metadata_routes = app_project.metadata_route_pathnames().await?;
+        let redirect_and_rewrite_sources =
+            self.next_config().redirect_and_rewrite_sources().await?;
+        let content = route_type_definitions(
+            entrypoints
+                .routes
+                .keys()
+                .filter(|pathname| !metadata_routes.contains(pathname))
+                .map(|pathname| &**pathname),
+            redirect_and_rewrite_sources.iter().map(|source| &**source),
+        );
+
+        let _ = self
+            .node_root()
+            .join("types/link.d.ts".into())
+            .write(FileContent::Content(File::from(content)).cell())
+            .resolve()
+            .await?;
+        Ok(Vc::cell(()))
+    }
+
+    /// Computes the client JS and CSS sizes of each route and writes them to
+    /// `<distDir>/bundle-size-report.json`. This writes all endpoints to disk,
+    /// so it's meant for production builds.
+    #[turbo_tasks::function]
+    pub async fn bundle_size_report(self: Vc<Self>) -> Result<Vc<BundleSizeReport>> {
+        let report = bundle_size::bundle_size_report(self);
+        let asset = report.json_asset(self.node_root());
+        let _ = asset
+            .content()
+            .write(asset.ident().path())
+            .resolve()
+            .await?;
+        Ok(report)
+    }
+
+    /// Checks the `report` against `experimental.turbo.bundleSizeBudgets`. The
+    /// routes over their budget are the only issues of this task.
+    #[turbo_tasks::function]
+    pub async fn check_bundle_size_budgets(
+        self: Vc<Self>,
+        report: Vc<BundleSizeReport>,
+    ) -> Result<Vc<()>> {
+        Ok(bundle_size::check_bundle_size_budgets(
+            self.project_path().to_resolved().await?,
+            self.next_config().bundle_size_budgets(),
+            report.resolve().await?,
+        ))
+    }
+
+    /// Type checks the TypeScript files of the module graph of the checked
+    /// endpoints when `experimental.turbo.typeCheck` is enabled. The
+    /// diagnostics are emitted as issues of the returned completion.
+    #[turbo_tasks::function]
+    pub async fn type_check(self: Vc<Self>) -> Result<Vc<Completion>> {
+        let next_config = self.next_config();
+        if !*next_config.turbo_type_check().await? {
+            return Ok(Completion::immutable());
+        }
+        let project_path = self.project_path();
+        let tsconfig = match &*next_config.typescript_tsconfig_path().await? {
+            Some(path) => project_path.join(path.clone()),
+            None => match *find_context_file(project_path, tsconfig()).await? {
+                FindContextFileResult::Found(path, _) => *path,
+                FindContextFileResult::NotFound(_) => return Ok(Completion::immutable()),
+            },
+        };
+
+        Ok(typescript::type_check(
+            node_evaluate_asset_context(
+                self.execution_context(),
+                None,
+                None,
+                "next_type_check".into(),
+                false,
+            ),
+            self.execution_context(),
+            tsconfig,
+            self.checked_root_modules(),
+        ))
+    }
+
+    /// Lints the modules of the module graph of the checked endpoints when
+    /// `experimental.turbo.lint` is enabled. The problems are emitted as issues
+    /// of the returned completion.
+    #[turbo_tasks::function]
+    pub async fn lint(self: Vc<Self>) -> Result<Vc<Completion>> {
+        let next_config = self.next_config();
+        if !*next_config.turbo_lint().await? {
+            return Ok(Completion::immutable());
+        }
+        Ok(eslint::lint(
+            node_evaluate_asset_context(
+                self.execution_context(),
+                None,
+                None,
+                "next_lint".into(),
+                false,
+            ),
+            self.execution_context(),
+            self.checked_root_modules(),
+            next_config.lint_options(self.next_mode()),
+        ))
+    }
+
+    /// All endpoints of the project.
+    #[turbo_tasks::function]
+    async fn endpoints(self: Vc<Self>) -> Result<Vc<Endpoints>> {
+        let entrypoints = self.entrypoints().await?;
+        let mut endpoints = vec![
+            entrypoints.pages_document_endpoint,
+            entrypoints.pages_app_endpoint,
+            entrypoints.pages_error_endpoint,
+        ];
+        endpoints.extend(entrypoints.middleware.as_ref().map(|m| m.endpoint));
+        endpoints.extend(entrypoints.instrumentation.as_ref().map(|i| i.node_js));
+        for route in entrypoints.routes.values() {
+            match route {
+                Route::Page { html_endpoint, .. } => endpoints.push(*html_endpoint),
+                Route::PageApi { endpoint } | Route::AppRoute { endpoint, .. } => {
+                    endpoints.push(*endpoint)
+                }
+                Route::AppPage(pages) => {
+                    endpoints.extend(pages.iter().map(|page| page.html_endpoint))
+                }
+                Route::Conflict => {}
+            }
+        }
+        Ok(Vc::cell(endpoints))
+    }
+
+    /// The root modules of the endpoints that checks of the whole project
+    /// look at. These are all endpoints in a build, but only the endpoints
+    /// that have been written to disk in development, so a check doesn't
+    /// compile pages that haven't been requested.
+    #[turbo_tasks::function]
+    async fn checked_root_modules(self: Vc<Self>) -> Result<Vc<Modules>> {
+        let written_endpoints = match self.await?.written_endpoints {
+            Some(written_endpoints) => Some(written_endpoints.await?.endpoints.get().clone()),
+            None => None,
+        };
+        let mut entries = Vec::new();
+        for endpoint in self.endpoints().await?.iter() {
+            if let Some(written_endpoints) = &written_endpoints {
+                if !written_endpoints.contains(endpoint) {
+                    continue;
+                }
+            }
+            entries.extend(endpoint.root_modules().await?.iter().copied());
+        }
+
+        Ok(Vc::cell(entries))
+    }
+
+    /// Records that `endpoint` has been written to disk, so checks of the
+    /// whole project include it in development.
+    #[turbo_tasks::function]
+    pub async fn endpoint_written(
+        self: Vc<Self>,
+        endpoint: Vc<Box<dyn Endpoint>>,
+    ) -> Result<Vc<()>> {
+        if let Some(written_endpoints) = self.await?.written_endpoints {
+            written_endpoints
+                .await?
+                .endpoints
+                .update_conditionally(|endpoints| endpoints.insert(endpoint));
+        }
+        Ok(Vc::cell(()))
+    }
+
+    /// The client assets of each page, in the order they are loaded.
+    #[turbo_tasks::function]
+    pub async fn route_client_assets(self: Vc<Self>) -> Result<Vc<RouteClientAssets>> {
+        let entrypoints = self.entrypoints().await?;
+        let mut client_assets = Vec::new();
+        for (pathname, route) in &entrypoints.routes {
+            match route {
+                Route::Page { html_endpoint, .. } => {
+                    client_assets.push((
+                        pathname.clone(),
+                        html_endpoint.client_assets().to_resolved().await?,
+                    ));
+                }
+                Route::AppPage(pages) => {
+                    for page in pages {
+                        client_assets.push((
+                            pathname.clone(),
+                            page.html_endpoint.client_assets().to_resolved().await?,
+                        ));
+                    }
+                }
+                Route::PageApi { .. } | Route::AppRoute { .. } | Route::Conflict => {}
+            }
+        }
+        Ok(Vc::cell(client_assets))
+    }
+
+    /// Emits a warning for CSS modules that pages load in conflicting orders.
+    /// The `client_assets` are computed by [Project::route_client_assets] on
+    /// their own, so these warnings are the only issues of this task.
+    #[turbo_tasks::function]
+    pub async fn check_css_order(
+        self: Vc<Self>,
+        client_assets: Vc<RouteClientAssets>,
+    ) -> Result<Vc<()>> {
+        check_css_order_conflicts(
+            client_assets
+                .await?
+                .iter()
+                .map(|(pathname, assets)| (pathname.clone(), **assets)),
+        )
+        .await?;
+        Ok(Vc::cell(()))
+    }
+
     #[turbo_tasks::function]
     async fn middleware_context(self: Vc<Self>) -> Result<Vc<Box<dyn AssetContext>>> {
         let mut transitions = vec![];
@@ -1172,8 +1496,6 @@ impl Project {
                     )
                     .resolve()
                     .await?;
-
-                Ok(Vc::cell(()))
             } else {
                 let _ = emit_assets(
                     *all_output_assets.await?,
@@ -1183,8 +1505,23 @@ impl Project {
                 )
                 .resolve()
                 .await?;
-                Ok(Vc::cell(()))
             }
+
+            // Endpoints are emitted one at a time, so only the files that this
+            // operation emitted before, but doesn't anymore, are known to be stale.
+            let paths = emitted_paths(
+                *all_output_assets.await?,
+                node_root,
+                client_relative_path,
+                node_root,
+            )
+            .await?;
+            self.output_fs()
+                .await?
+                .update_emitted_paths(*output_assets.await?, paths)
+                .await?;
+
+            Ok(Vc::cell(()))
         }
         .instrument(span)
         .await
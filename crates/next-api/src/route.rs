@@ -1,7 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{debug::ValueDebugFormat, trace::TraceRawVcs, Completion, FxIndexMap, RcStr, Vc};
-use turbopack_core::module::Modules;
+use turbopack_core::{module::Modules, output::OutputAssets};
 
 use crate::paths::ServerPath;
 
@@ -76,6 +76,8 @@ pub trait Endpoint {
     fn server_changed(self: Vc<Self>) -> Vc<Completion>;
     fn client_changed(self: Vc<Self>) -> Vc<Completion>;
     fn root_modules(self: Vc<Self>) -> Vc<Modules>;
+    /// The assets loaded by the browser, in the order they are loaded.
+    fn client_assets(self: Vc<Self>) -> Vc<OutputAssets>;
 }
 
 #[turbo_tasks::value(shared)]
@@ -97,3 +99,6 @@ pub enum WrittenEndpoint {
 /// slash)
 #[turbo_tasks::value(transparent)]
 pub struct Routes(FxIndexMap<RcStr, Route>);
+
+#[turbo_tasks::value(transparent)]
+pub struct Endpoints(Vec<Vc<Box<dyn Endpoint>>>);
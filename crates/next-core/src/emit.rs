@@ -74,6 +74,28 @@ pub async fn emit_assets(
     .completed())
 }
 
+/// Returns the paths [emit_assets] writes the given assets to.
+pub async fn emitted_paths(
+    assets: Vc<OutputAssets>,
+    node_root: Vc<FileSystemPath>,
+    client_relative_path: Vc<FileSystemPath>,
+    client_output_path: Vc<FileSystemPath>,
+) -> Result<Vec<Vc<FileSystemPath>>> {
+    let node_root = &*node_root.await?;
+    let client_relative_path_ref = &*client_relative_path.await?;
+    let mut paths = Vec::new();
+    for asset in assets.await?.iter() {
+        let path = asset.ident().path();
+        let path_ref = path.await?;
+        if path_ref.is_inside_ref(node_root) {
+            paths.push(path);
+        } else if path_ref.is_inside_ref(client_relative_path_ref) {
+            paths.push(rebase(path, client_relative_path, client_output_path));
+        }
+    }
+    Ok(paths)
+}
+
 #[turbo_tasks::function]
 fn emit(asset: Vc<Box<dyn OutputAsset>>) -> Vc<Completion> {
     asset.content().write(asset.ident().path())
@@ -37,17 +37,19 @@ mod page_loader;
 pub mod pages_structure;
 pub mod tracing_presets;
 mod transform_options;
+pub mod typed_routes;
 pub mod url_node;
 pub mod util;
 
 pub use app_segment_config::{
     parse_segment_config_from_loader_tree, parse_segment_config_from_source,
 };
-pub use emit::{all_assets_from_entries, emit_all_assets, emit_assets};
+pub use emit::{all_assets_from_entries, emit_all_assets, emit_assets, emitted_paths};
 pub use next_edge::context::{
     get_edge_chunking_context, get_edge_chunking_context_with_client_assets,
     get_edge_compile_time_info, get_edge_resolve_options_context,
 };
+pub use next_font::{font_fallback::FontMetrics, local::font_fallback::font_metrics_from_content};
 pub use next_import_map::get_next_package;
 pub use page_loader::{create_page_loader_entry_module, PageLoaderAsset};
 pub use util::{get_asset_path_from_pathname, pathname_for_path, PathType};
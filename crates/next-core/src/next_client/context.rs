@@ -53,6 +53,7 @@ use crate::{
             styled_jsx::get_styled_jsx_transform_rule,
             swc_ecma_transform_plugins::get_swc_ecma_transform_plugin_rule,
         },
+        url_imports::get_url_imports_options,
         webpack_rules::webpack_loader_options,
     },
     transform_options::{
@@ -190,6 +191,9 @@ pub async fn get_client_resolve_options_context(
                 .to_resolved()
                 .await?,
         )],
+        url_imports: *get_url_imports_options(project_path, next_config).await?,
+        extension_alias: next_config.extension_alias().await?.clone_value(),
+        esm_fully_specified: *next_config.fully_specified().await?,
         ..Default::default()
     };
     Ok(ResolveOptionsContext {
@@ -409,6 +413,10 @@ pub async fn get_client_chunking_context(
 
     if next_mode.is_development() {
         builder = builder.hot_module_replacement().use_file_source_map_uris();
+    } else {
+        // Sharing library CSS between routes only pays off for long-term
+        // caching, and in development it changes the chunks on every import.
+        builder = builder.ordered_vendors_split(true);
     }
 
     Ok(Vc::upcast(builder.build()))
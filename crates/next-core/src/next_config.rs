@@ -5,7 +5,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, RcStr, ResolvedVc, TaskInput, Vc};
 use turbo_tasks_env::EnvMap;
-use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks_fs::{FileSystemPath, WatchIgnoreOptions};
 use turbopack::module_options::{
     module_options_context::MdxTransformOptions, LoaderRuleItem, OptionWebpackRules,
 };
@@ -18,7 +18,11 @@ use turbopack_ecmascript_plugins::transform::{
     emotion::EmotionTransformConfig, relay::RelayConfig,
     styled_components::StyledComponentsTransformConfig,
 };
-use turbopack_node::transforms::webpack::{WebpackLoaderItem, WebpackLoaderItems};
+use turbopack_node::{
+    eslint::LintOptions,
+    transforms::webpack::{WebpackLoaderItem, WebpackLoaderItems},
+    NodeJsPoolConfig, NodeJsPoolOptions,
+};
 
 use crate::{
     mode::NextMode, next_import_map::mdx_import_source_file,
@@ -87,17 +91,22 @@ pub struct NextConfig {
 
     #[serde(rename = "_originalRedirects")]
     pub original_redirects: Option<Vec<Redirect>>,
+    #[serde(rename = "_originalRewrites")]
+    pub original_rewrites: Option<Rewrites>,
 
     // Partially supported
     pub compiler: Option<CompilerConfig>,
 
     pub optimize_fonts: Option<bool>,
 
+    pub typescript: TypeScriptConfig,
+
+    pub eslint: EslintConfig,
+
     // unsupported
     amp: AmpConfig,
     clean_dist_dir: bool,
     compress: bool,
-    eslint: EslintConfig,
     exclude_default_moment_locales: bool,
     // this can be a function in js land
     export_path_map: Option<serde_json::Value>,
@@ -112,7 +121,6 @@ pub struct NextConfig {
     server_runtime_config: FxIndexMap<String, serde_json::Value>,
     static_page_generation_timeout: f64,
     target: Option<String>,
-    typescript: TypeScriptConfig,
     use_file_system_public_routes: bool,
     webpack: Option<serde_json::Value>,
 }
@@ -132,9 +140,9 @@ struct AmpConfig {
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TraceRawVcs)]
 #[serde(rename_all = "camelCase")]
-struct EslintConfig {
-    dirs: Option<Vec<String>>,
-    ignore_during_builds: Option<bool>,
+pub struct EslintConfig {
+    pub dirs: Option<Vec<String>>,
+    pub ignore_during_builds: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TraceRawVcs)]
@@ -405,14 +413,65 @@ pub struct ExperimentalTurboConfig {
     pub tree_shaking: Option<bool>,
     pub module_id_strategy: Option<ModuleIdStrategy>,
     pub minify: Option<bool>,
+    /// Size limits per route pathname, checked after a build. The `*` entry
+    /// applies to routes without their own.
+    pub bundle_size_budgets: Option<FxIndexMap<RcStr, BundleSizeBudget>>,
+    /// Type checks the TypeScript files of the module graph with the
+    /// `typescript` package of the project and reports the diagnostics as
+    /// issues.
+    pub type_check: Option<bool>,
+    /// Lints the modules of the module graph with the `eslint` package of the
+    /// project and reports the problems as issues.
+    pub lint: Option<bool>,
+    /// The lowest ESLint severity that fails the build, `error` by default.
+    pub lint_fail_on: Option<LintFailOn>,
+    /// Limits for the Node.js workers that run webpack loaders, PostCSS and
+    /// the type checker and linter.
+    pub node_pool: Option<NodeJsPoolConfig>,
+    /// Changes to these files don't invalidate anything and, on Linux, ignored
+    /// directories are not watched at all.
+    pub watch_options: Option<TurboWatchOptions>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct TurboWatchOptions {
+    /// Glob patterns relative to the project directory, e.g. `**/*.log`.
+    #[serde(default)]
+    pub ignored: Vec<RcStr>,
+    /// Also ignores the files excluded by `.gitignore` files.
+    #[serde(default)]
+    pub respect_gitignore: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "lowercase")]
+pub enum LintFailOn {
+    Error,
+    Warning,
+}
+
+/// Limits for the JS and CSS loaded by a route, in gzip-compressed bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSizeBudget {
+    pub first_load_js: Option<u64>,
+    pub first_load_css: Option<u64>,
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct BundleSizeBudgets(FxIndexMap<RcStr, BundleSizeBudget>);
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
 #[serde(rename_all = "camelCase")]
 pub struct RuleConfigItemOptions {
     pub loaders: Vec<LoaderItem>,
     #[serde(default, alias = "as")]
     pub rename_as: Option<RcStr>,
+    /// Whether ESM imports in the matching files need to include the file
+    /// extension, like `resolve.fullySpecified` of a webpack module rule.
+    #[serde(default)]
+    pub fully_specified: Option<bool>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
@@ -482,6 +541,12 @@ pub enum ReactCompilerOptionsOrBoolean {
     Option(ReactCompilerOptions),
 }
 
+#[turbo_tasks::value(transparent)]
+pub struct ExtensionAlias(FxIndexMap<RcStr, Vec<RcStr>>);
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionUrlImportsConfig(Option<UrlImportsOptionsConfig>);
+
 #[turbo_tasks::value(transparent)]
 pub struct OptionalReactCompilerOptions(Option<ResolvedVc<ReactCompilerOptions>>);
 
@@ -513,6 +578,7 @@ pub struct ExperimentalConfig {
     pub middleware_prefetch: Option<MiddlewarePrefetchType>,
     /// optimizeCss can be boolean or critters' option object
     /// Use Record<string, unknown> as critters doesn't export its Option type ([link](https://github.com/GoogleChromeLabs/critters/blob/a590c05f9197b656d2aeaae9369df2483c26b072/packages/critters/src/index.d.ts))
+    /// Not supported by Turbopack yet, `next build --turbopack` warns about it.
     pub optimize_css: Option<serde_json::Value>,
     pub next_script_workers: Option<bool>,
     pub web_vitals_attribution: Option<Vec<RcStr>>,
@@ -521,6 +587,19 @@ pub struct ExperimentalConfig {
     react_compiler: Option<ReactCompilerOptionsOrBoolean>,
     #[serde(rename = "dynamicIO")]
     pub dynamic_io: Option<bool>,
+    /// Generate Route types and enable type checking for Link and Router.push,
+    /// etc. This option requires `appDir` to be enabled first.
+    /// @see [api reference](https://nextjs.org/docs/app/api-reference/next-config-js/typedRoutes)
+    pub typed_routes: Option<bool>,
+    /// Allows importing modules from the listed URL prefixes. Imported
+    /// modules are pinned in `next.lock/lock.json` and cached in
+    /// `next.lock/data`.
+    pub url_imports: Option<UrlImportsConfig>,
+    /// Extensions to try instead of the extension of a relative request, e.g.
+    /// `{ ".js": [".ts", ".tsx", ".js"] }`.
+    pub extension_alias: Option<FxIndexMap<RcStr, ExtensionAliasValue>>,
+    /// Require ESM requests to include the file extension.
+    pub fully_specified: Option<bool>,
     // ---
     // UNSUPPORTED
     // ---
@@ -536,14 +615,12 @@ pub struct ExperimentalConfig {
     disable_optimized_loading: Option<bool>,
     disable_postcss_preset_env: Option<bool>,
     esm_externals: Option<EsmExternals>,
-    extension_alias: Option<serde_json::Value>,
     external_dir: Option<bool>,
     /// If set to `false`, webpack won't fall back to polyfill Node.js modules
     /// in the browser Full list of old polyfills is accessible here:
     /// [webpack/webpack#Module_notound_error.js#L13-L42](https://github.com/webpack/webpack/blob/2a0536cf510768111a3a6dceeb14cb79b9f59273/lib/Module_not_found_error.js#L13-L42)
     fallback_node_polyfills: Option<bool>, // false
     force_swc_transforms: Option<bool>,
-    fully_specified: Option<bool>,
     gzip_size: Option<bool>,
 
     instrumentation_hook: Option<bool>,
@@ -572,11 +649,6 @@ pub struct ExperimentalConfig {
     swc_trace_profiling: Option<bool>,
     /// @internal Used by the Next.js internals only.
     trust_host_header: Option<bool>,
-    /// Generate Route types and enable type checking for Link and Router.push,
-    /// etc. This option requires `appDir` to be enabled first.
-    /// @see [api reference](https://nextjs.org/docs/app/api-reference/next-config-js/typedRoutes)
-    typed_routes: Option<bool>,
-    url_imports: Option<serde_json::Value>,
     /// This option is to enable running the Webpack build in a worker thread
     /// (doesn't apply to Turbopack).
     webpack_build_worker: Option<bool>,
@@ -698,6 +770,30 @@ pub enum ServerActionsOrLegacyBool {
     LegacyBool(bool),
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TraceRawVcs)]
+#[serde(untagged)]
+pub enum ExtensionAliasValue {
+    Single(RcStr),
+    Multiple(Vec<RcStr>),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TraceRawVcs)]
+#[serde(untagged)]
+pub enum UrlImportsConfig {
+    AllowedUris(Vec<RcStr>),
+    Options(UrlImportsOptionsConfig),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlImportsOptionsConfig {
+    #[serde(default)]
+    pub allowed_uris: Vec<RcStr>,
+    /// Fail instead of fetching URLs that are not in the lockfile yet.
+    #[serde(default)]
+    pub frozen: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TraceRawVcs)]
 #[serde(rename_all = "kebab-case")]
 pub enum EsmExternalsValue {
@@ -730,6 +826,148 @@ fn test_esm_externals_deserialization() {
     );
 }
 
+#[test]
+fn test_extension_alias_deserialization() {
+    let json = serde_json::json!({
+        "extensionAlias": { ".js": [".ts", ".js"], ".mjs": ".mts" }
+    });
+    let config: ExperimentalConfig = serde_json::from_value(json).unwrap();
+    let extension_alias = config.extension_alias.unwrap();
+    assert_eq!(
+        extension_alias[".js"],
+        ExtensionAliasValue::Multiple(vec![".ts".into(), ".js".into()])
+    );
+    assert_eq!(
+        extension_alias[".mjs"],
+        ExtensionAliasValue::Single(".mts".into())
+    );
+}
+
+#[test]
+fn test_lint_fail_on_deserialization() {
+    let json = serde_json::json!({
+        "lint": true,
+        "lintFailOn": "warning"
+    });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(config.lint, Some(true));
+    assert_eq!(config.lint_fail_on, Some(LintFailOn::Warning));
+}
+
+#[test]
+fn test_fully_specified_rule_deserialization() {
+    let json = serde_json::json!({
+        "rules": {
+            "*.mjs": { "loaders": [], "fullySpecified": true }
+        }
+    });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    let rules = config.rules.unwrap();
+    assert_eq!(
+        rules["*.mjs"],
+        RuleConfigItemOrShortcut::Advanced(RuleConfigItem::Options(RuleConfigItemOptions {
+            loaders: vec![],
+            rename_as: None,
+            fully_specified: Some(true),
+        }))
+    );
+}
+
+#[test]
+fn test_node_pool_deserialization() {
+    let json = serde_json::json!({
+        "nodePool": {
+            "maxWorkers": 2,
+            "maxWorkerMemory": 1024,
+            "idleTimeout": 30000
+        }
+    });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        config.node_pool,
+        Some(NodeJsPoolConfig {
+            max_workers: Some(2),
+            max_worker_memory: Some(1024),
+            idle_timeout: Some(30000),
+            ..Default::default()
+        })
+    );
+}
+
+#[test]
+fn test_watch_options_deserialization() {
+    let json = serde_json::json!({
+        "watchOptions": {
+            "ignored": ["**/*.log", "tmp"],
+            "respectGitignore": true
+        }
+    });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        config.watch_options,
+        Some(TurboWatchOptions {
+            ignored: vec!["**/*.log".into(), "tmp".into()],
+            respect_gitignore: true,
+        })
+    );
+
+    let json = serde_json::json!({ "watchOptions": {} });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(config.watch_options, Some(TurboWatchOptions::default()));
+}
+
+#[test]
+fn test_bundle_size_budgets_deserialization() {
+    let json = serde_json::json!({
+        "bundleSizeBudgets": {
+            "*": { "firstLoadJs": 150000 },
+            "/dashboard": { "firstLoadJs": 250000, "firstLoadCss": 20000 }
+        }
+    });
+    let config: ExperimentalTurboConfig = serde_json::from_value(json).unwrap();
+    let budgets = config.bundle_size_budgets.unwrap();
+    assert_eq!(
+        budgets["*"],
+        BundleSizeBudget {
+            first_load_js: Some(150000),
+            first_load_css: None,
+        }
+    );
+    assert_eq!(
+        budgets["/dashboard"],
+        BundleSizeBudget {
+            first_load_js: Some(250000),
+            first_load_css: Some(20000),
+        }
+    );
+}
+
+#[test]
+fn test_url_imports_deserialization() {
+    let json = serde_json::json!({
+        "urlImports": ["https://esm.sh/"]
+    });
+    let config: ExperimentalConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        config.url_imports,
+        Some(UrlImportsConfig::AllowedUris(
+            vec!["https://esm.sh/".into()]
+        ))
+    );
+
+    let json = serde_json::json!({
+        "urlImports": { "allowedUris": ["https://esm.sh/"], "frozen": true }
+    });
+    let config: ExperimentalConfig = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        config.url_imports,
+        Some(UrlImportsConfig::Options(UrlImportsOptionsConfig {
+            allowed_uris: vec!["https://esm.sh/".into()],
+            frozen: true,
+        }))
+    );
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, TraceRawVcs)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerActions {
@@ -973,18 +1211,23 @@ impl NextConfig {
                         LoaderRuleItem {
                             loaders: transform_loaders(loaders),
                             rename_as: None,
+                            fully_specified: None,
                         },
                     );
                 }
                 RuleConfigItemOrShortcut::Advanced(rule) => {
-                    if let FindRuleResult::Found(RuleConfigItemOptions { loaders, rename_as }) =
-                        find_rule(rule, &active_conditions)
+                    if let FindRuleResult::Found(RuleConfigItemOptions {
+                        loaders,
+                        rename_as,
+                        fully_specified,
+                    }) = find_rule(rule, &active_conditions)
                     {
                         rules.insert(
                             ext.clone(),
                             LoaderRuleItem {
                                 loaders: transform_loaders(loaders),
                                 rename_as: rename_as.clone(),
+                                fully_specified: *fully_specified,
                             },
                         );
                     }
@@ -1156,6 +1399,75 @@ impl NextConfig {
         ))
     }
 
+    #[turbo_tasks::function]
+    pub fn extension_alias(&self) -> Vc<ExtensionAlias> {
+        Vc::cell(
+            self.experimental
+                .extension_alias
+                .iter()
+                .flatten()
+                .map(|(extension, aliases)| {
+                    let aliases = match aliases {
+                        ExtensionAliasValue::Single(alias) => vec![alias.clone()],
+                        ExtensionAliasValue::Multiple(aliases) => aliases.clone(),
+                    };
+                    (extension.clone(), aliases)
+                })
+                .collect(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn fully_specified(&self) -> Vc<bool> {
+        Vc::cell(self.experimental.fully_specified.unwrap_or(false))
+    }
+
+    #[turbo_tasks::function]
+    pub fn url_imports(&self) -> Vc<OptionUrlImportsConfig> {
+        Vc::cell(
+            self.experimental
+                .url_imports
+                .clone()
+                .map(|url_imports| match url_imports {
+                    UrlImportsConfig::AllowedUris(allowed_uris) => UrlImportsOptionsConfig {
+                        allowed_uris,
+                        ..Default::default()
+                    },
+                    UrlImportsConfig::Options(options) => options,
+                }),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn typed_routes(&self) -> Vc<bool> {
+        Vc::cell(self.experimental.typed_routes.unwrap_or(false))
+    }
+
+    /// The sources of the rewrites and redirects as configured by the user,
+    /// without the ones added by Next.js.
+    #[turbo_tasks::function]
+    pub fn redirect_and_rewrite_sources(&self) -> Vc<Vec<RcStr>> {
+        let rewrites = self.original_rewrites.iter().flat_map(|rewrites| {
+            rewrites
+                .before_files
+                .iter()
+                .chain(&rewrites.after_files)
+                .chain(&rewrites.fallback)
+                .map(|rewrite| &rewrite.source)
+        });
+        let redirects = self
+            .original_redirects
+            .iter()
+            .flatten()
+            .map(|redirect| &redirect.source);
+        Vc::cell(
+            rewrites
+                .chain(redirects)
+                .map(|source| source.as_str().into())
+                .collect(),
+        )
+    }
+
     #[turbo_tasks::function]
     pub fn use_swc_css(&self) -> Vc<bool> {
         Vc::cell(
@@ -1237,6 +1549,100 @@ impl NextConfig {
         Vc::cell(Some(module_id_strategy.clone()))
     }
 
+    #[turbo_tasks::function]
+    pub fn bundle_size_budgets(&self) -> Vc<BundleSizeBudgets> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.bundle_size_budgets.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn turbo_type_check(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.type_check)
+                .unwrap_or(false),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn turbo_lint(&self) -> Vc<bool> {
+        Vc::cell(
+            self.experimental
+                .turbo
+                .as_ref()
+                .and_then(|t| t.lint)
+                .unwrap_or(false),
+        )
+    }
+
+    #[turbo_tasks::function]
+    pub fn node_pool_options(&self) -> Vc<NodeJsPoolOptions> {
+        self.experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.node_pool.as_ref())
+            .map(|config| config.to_options())
+            .unwrap_or_default()
+            .cell()
+    }
+
+    /// The watch ignores of the project filesystem. `ignored_globs` are
+    /// relative to the project directory.
+    #[turbo_tasks::function]
+    pub fn watch_ignore_options(&self) -> Vc<WatchIgnoreOptions> {
+        let options = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.watch_options.as_ref());
+        WatchIgnoreOptions {
+            ignored_subpaths: vec![],
+            ignored_globs: options.map(|o| o.ignored.clone()).unwrap_or_default(),
+            respect_gitignore: options.is_some_and(|o| o.respect_gitignore),
+        }
+        .cell()
+    }
+
+    /// `lintFailOn` only applies to builds, so warnings stay warnings in
+    /// development.
+    #[turbo_tasks::function]
+    pub async fn lint_options(&self, mode: Vc<NextMode>) -> Result<Vc<LintOptions>> {
+        let lint_fail_on = self
+            .experimental
+            .turbo
+            .as_ref()
+            .and_then(|t| t.lint_fail_on);
+        Ok(LintOptions {
+            dirs: self
+                .eslint
+                .dirs
+                .iter()
+                .flatten()
+                .map(|dir| dir.as_str().into())
+                .collect(),
+            warnings_as_errors: lint_fail_on == Some(LintFailOn::Warning)
+                && mode.await?.is_production(),
+        }
+        .cell())
+    }
+
+    #[turbo_tasks::function]
+    pub fn typescript_tsconfig_path(&self) -> Vc<Option<RcStr>> {
+        Vc::cell(
+            self.typescript
+                .ts_config_path
+                .as_ref()
+                .map(|path| path.as_str().into()),
+        )
+    }
+
     #[turbo_tasks::function]
     pub async fn turbo_minify(&self, mode: Vc<NextMode>) -> Result<Vc<bool>> {
         let minify = self.experimental.turbo.as_ref().and_then(|t| t.minify);
@@ -21,9 +21,12 @@ use crate::{
     next_font::local::NextFontLocalResolvePlugin,
     next_import_map::get_next_edge_import_map,
     next_server::context::ServerContextType,
-    next_shared::resolve::{
-        get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
-        ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
+    next_shared::{
+        resolve::{
+            get_invalid_client_only_resolve_plugin, get_invalid_styled_jsx_resolve_plugin,
+            ModuleFeatureReportResolvePlugin, NextSharedRuntimeResolvePlugin,
+        },
+        url_imports::get_url_imports_options,
     },
     util::{foreign_code_context_condition, NextRuntime},
 };
@@ -169,6 +172,9 @@ pub async fn get_edge_resolve_options_context(
         browser: true,
         after_resolve_plugins,
         before_resolve_plugins,
+        url_imports: *get_url_imports_options(project_path, next_config).await?,
+        extension_alias: next_config.extension_alias().await?.clone_value(),
+        esm_fully_specified: *next_config.fully_specified().await?,
         ..Default::default()
     };
 
@@ -6,16 +6,18 @@ use turbo_tasks::{trace::TraceRawVcs, RcStr, Vc};
 pub(crate) struct DefaultFallbackFont {
     pub name: RcStr,
     pub capsize_key: RcStr,
-    pub az_avg_width: f64,
+    /// The weighted average character width, as computed by capsize's
+    /// `xWidthAvg`.
+    pub x_width_avg: f64,
     pub units_per_em: u32,
 }
 
-// From https://github.com/vercel/next.js/blob/a3893bf69c83fb08e88c87bf8a21d987a0448c8e/packages/font/src/utils.ts#L4
+// Metrics from https://github.com/seek-oss/capsize/tree/master/packages/metrics
 pub(crate) static DEFAULT_SANS_SERIF_FONT: Lazy<DefaultFallbackFont> =
     Lazy::new(|| DefaultFallbackFont {
         name: "Arial".into(),
         capsize_key: "arial".into(),
-        az_avg_width: 934.5116279069767,
+        x_width_avg: 904.0,
         units_per_em: 2048,
     });
 
@@ -23,7 +25,15 @@ pub(crate) static DEFAULT_SERIF_FONT: Lazy<DefaultFallbackFont> =
     Lazy::new(|| DefaultFallbackFont {
         name: "Times New Roman".into(),
         capsize_key: "timesNewRoman".into(),
-        az_avg_width: 854.3953488372093,
+        x_width_avg: 819.0,
+        units_per_em: 2048,
+    });
+
+pub(crate) static DEFAULT_MONOSPACE_FONT: Lazy<DefaultFallbackFont> =
+    Lazy::new(|| DefaultFallbackFont {
+        name: "Courier New".into(),
+        capsize_key: "courierNew".into(),
+        x_width_avg: 1229.0,
         units_per_em: 2048,
     });
 
@@ -89,3 +99,40 @@ pub(crate) struct FontAdjustment {
 // Necessary since floating points in this struct don't implement Eq, but it's
 // required for turbo tasks values.
 impl Eq for FontAdjustment {}
+
+/// Metrics read from a font file, in the same shape as the entries of
+/// capsize's metrics collection. These are the inputs to a [[FontAdjustment]]
+/// and are included in `next-font-manifest.json` for debugging.
+#[turbo_tasks::value(shared, eq = "manual")]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FontMetrics {
+    pub ascent: f64,
+    pub descent: f64,
+    pub line_gap: f64,
+    pub units_per_em: u32,
+    /// The average character width, weighted by letter frequency. See
+    /// https://github.com/seek-oss/capsize/blob/42d6dc39d58247bc6b9e013a4b1c4463bf287dca/packages/unpack/src/index.ts#L7-L83
+    pub x_width_avg: f64,
+}
+
+impl FontMetrics {
+    /// Computes the overrides for `fallback_font` so that it approximates the
+    /// geometry of a font with these metrics.
+    pub(crate) fn adjustment_for(&self, fallback_font: &DefaultFallbackFont) -> FontAdjustment {
+        let units_per_em = self.units_per_em as f64;
+        let main_font_avg_width = self.x_width_avg / units_per_em;
+        let fallback_font_avg_width = fallback_font.x_width_avg / fallback_font.units_per_em as f64;
+        let size_adjust = main_font_avg_width / fallback_font_avg_width;
+
+        FontAdjustment {
+            ascent: self.ascent / (units_per_em * size_adjust),
+            descent: self.descent / (units_per_em * size_adjust),
+            line_gap: self.line_gap / (units_per_em * size_adjust),
+            size_adjust,
+        }
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionFontMetrics(Option<FontMetrics>);
@@ -664,6 +664,7 @@ async fn get_mock_stylesheet(
         env,
         project_path: _,
         chunking_context,
+        pool_options,
     } = *execution_context.await?;
     let asset_context =
         node_evaluate_asset_context(execution_context, None, None, "next_font".into(), false);
@@ -699,6 +700,7 @@ async fn get_mock_stylesheet(
         None,
         vec![],
         Completion::immutable(),
+        *pool_options,
         should_debug("next_font::google"),
     )
     .await?;
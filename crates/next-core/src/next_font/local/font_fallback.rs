@@ -1,10 +1,13 @@
 use allsorts::{
+    binary::read::ReadScope,
+    font::MatchingPresentation,
     font_data::{DynamicFontTableProvider, FontData},
     Font,
 };
 use anyhow::{bail, Context, Result};
 use turbo_tasks::Vc;
 use turbo_tasks_fs::{FileContent, FileSystemPath};
+use turbopack_core::asset::AssetContent;
 
 use super::{
     options::{FontDescriptor, FontDescriptors, FontWeight, NextFontLocalOptions},
@@ -13,15 +16,47 @@ use super::{
 use crate::next_font::{
     font_fallback::{
         AutomaticFontFallback, DefaultFallbackFont, FontAdjustment, FontFallback, FontFallbacks,
-        DEFAULT_SANS_SERIF_FONT, DEFAULT_SERIF_FONT,
+        FontMetrics, OptionFontMetrics, DEFAULT_MONOSPACE_FONT, DEFAULT_SANS_SERIF_FONT,
+        DEFAULT_SERIF_FONT,
     },
     local::errors::FontError,
     util::{get_scoped_font_family, FontFamilyType},
 };
 
 // From
-// https://github.com/vercel/next.js/blob/7457be0c74e64b4d0617943ed27f4d557cc916be/packages/font/src/local/get-fallback-metrics-from-font-file.ts#L34
-static AVG_CHARACTERS: &str = "aaabcdeeeefghiijklmnnoopqrrssttuvwxyz      ";
+// https://github.com/seek-oss/capsize/blob/42d6dc39d58247bc6b9e013a4b1c4463bf287dca/packages/unpack/src/weightings.ts
+//
+// Letter frequencies used to weight the advance width of each character when
+// computing `xWidthAvg`.
+static X_WIDTH_AVG_WEIGHTINGS: &[(char, f64)] = &[
+    ('a', 0.0668),
+    ('b', 0.0122),
+    ('c', 0.0228),
+    ('d', 0.0348),
+    ('e', 0.1039),
+    ('f', 0.0182),
+    ('g', 0.0165),
+    ('h', 0.0499),
+    ('i', 0.057),
+    ('j', 0.0013),
+    ('k', 0.0063),
+    ('l', 0.0329),
+    ('m', 0.0197),
+    ('n', 0.0552),
+    ('o', 0.0614),
+    ('p', 0.0158),
+    ('q', 0.0008),
+    ('r', 0.049),
+    ('s', 0.0518),
+    ('t', 0.0741),
+    ('u', 0.0226),
+    ('v', 0.008),
+    ('w', 0.0193),
+    ('x', 0.0012),
+    ('y', 0.0162),
+    ('z', 0.0006),
+    (' ', 0.1818),
+];
 static NORMAL_WEIGHT: f64 = 400.0;
 static BOLD_WEIGHT: f64 = 700.0;
 
@@ -35,29 +70,25 @@ pub(super) async fn get_font_fallbacks(
     let scoped_font_family =
         get_scoped_font_family(FontFamilyType::Fallback.cell(), options_vc.font_family());
 
-    match options.adjust_font_fallback {
-        AdjustFontFallback::Arial => font_fallbacks.push(
-            FontFallback::Automatic(AutomaticFontFallback {
-                scoped_font_family,
-                local_font_family: Vc::cell("Arial".into()),
-                adjustment: Some(
-                    get_font_adjustment(lookup_path, options_vc, &DEFAULT_SANS_SERIF_FONT).await?,
-                ),
-            })
-            .into(),
-        ),
-        AdjustFontFallback::TimesNewRoman => font_fallbacks.push(
+    let fallback_font = match options.adjust_font_fallback {
+        AdjustFontFallback::Arial => Some(&*DEFAULT_SANS_SERIF_FONT),
+        AdjustFontFallback::TimesNewRoman => Some(&*DEFAULT_SERIF_FONT),
+        AdjustFontFallback::CourierNew => Some(&*DEFAULT_MONOSPACE_FONT),
+        AdjustFontFallback::None => None,
+    };
+
+    if let Some(fallback_font) = fallback_font {
+        font_fallbacks.push(
             FontFallback::Automatic(AutomaticFontFallback {
                 scoped_font_family,
-                local_font_family: Vc::cell("Times New Roman".into()),
+                local_font_family: Vc::cell(fallback_font.name.clone()),
                 adjustment: Some(
-                    get_font_adjustment(lookup_path, options_vc, &DEFAULT_SERIF_FONT).await?,
+                    get_font_adjustment(lookup_path, options_vc, fallback_font).await?,
                 ),
             })
             .into(),
-        ),
-        AdjustFontFallback::None => (),
-    };
+        );
+    }
 
     if let Some(fallback) = &options.fallback {
         font_fallbacks.push(FontFallback::Manual(fallback.clone()).into());
@@ -82,59 +113,88 @@ async fn get_font_adjustment(
         FileContent::Content(file) => file.content(),
     };
 
-    let font_file_binary = font_file_rope.to_bytes()?;
-    let scope = allsorts::binary::read::ReadScope::new(&font_file_binary);
-    let mut font = Font::new(scope.read::<FontData>()?.table_provider(0)?)?.context(format!(
-        "Unable to read font metrics from font file at {}",
-        &main_descriptor.path,
-    ))?;
+    let metrics = read_font_metrics(&font_file_rope.to_bytes()?).with_context(|| {
+        format!(
+            "Unable to read font metrics from font file at {}",
+            &main_descriptor.path,
+        )
+    })?;
 
-    let az_avg_width = calc_average_width(&mut font);
-    let units_per_em = font
-        .head_table()?
-        .context(format!(
-            "Unable to read font scale from font file at {}",
-            &main_descriptor.path
-        ))?
-        .units_per_em as f64;
-
-    let fallback_avg_width = fallback_font.az_avg_width / fallback_font.units_per_em as f64;
-    // TODO: Use xWidthAvg like next/google.
-    //       JS implementation: https://github.com/seek-oss/capsize/blob/42d6dc39d58247bc6b9e013a4b1c4463bf287dca/packages/unpack/src/index.ts#L7-L83
-    let size_adjust = match az_avg_width {
-        Some(az_avg_width) => az_avg_width as f64 / units_per_em / fallback_avg_width,
-        None => 1.0,
+    Ok(metrics.adjustment_for(fallback_font))
+}
+
+/// Reads the [FontMetrics] of an emitted font file. Resolves to `None` when the
+/// content can't be parsed as a font.
+#[turbo_tasks::function]
+pub async fn font_metrics_from_content(content: Vc<AssetContent>) -> Result<Vc<OptionFontMetrics>> {
+    let FileContent::Content(file) = &*content.file_content().await? else {
+        return Ok(Vc::cell(None));
     };
 
-    Ok(FontAdjustment {
-        ascent: font.hhea_table.ascender as f64 / (units_per_em * size_adjust),
-        descent: font.hhea_table.descender as f64 / (units_per_em * size_adjust),
-        line_gap: font.hhea_table.line_gap as f64 / (units_per_em * size_adjust),
-        size_adjust,
+    Ok(Vc::cell(
+        read_font_metrics(&file.content().to_bytes()?).ok(),
+    ))
+}
+
+/// Reads the [FontMetrics] of a font file, computing `xWidthAvg` the same way
+/// capsize does.
+pub(crate) fn read_font_metrics(font_file_binary: &[u8]) -> Result<FontMetrics> {
+    let scope = ReadScope::new(font_file_binary);
+    let mut font = Font::new(scope.read::<FontData>()?.table_provider(0)?)?
+        .context("Unable to parse font file")?;
+
+    let units_per_em = font
+        .head_table()?
+        .context("Unable to read font scale")?
+        .units_per_em;
+    let x_avg_char_width = font.os2_table()?.map(|os2| os2.x_avg_char_width as f64);
+    let x_width_avg =
+        calc_x_width_avg(&mut font, x_avg_char_width).context("Unable to read glyph widths")?;
+
+    Ok(FontMetrics {
+        ascent: font.hhea_table.ascender as f64,
+        descent: font.hhea_table.descender as f64,
+        line_gap: font.hhea_table.line_gap as f64,
+        units_per_em,
+        x_width_avg,
     })
 }
 
-fn calc_average_width(font: &mut Font<DynamicFontTableProvider>) -> Option<f32> {
-    let has_all_glyphs = AVG_CHARACTERS.chars().all(|c| {
-        font.lookup_glyph_index(c, allsorts::font::MatchingPresentation::NotRequired, None)
-            .0
-            > 0
-    });
-    if !has_all_glyphs {
-        return None;
+fn calc_x_width_avg(
+    font: &mut Font<DynamicFontTableProvider>,
+    x_avg_char_width: Option<f64>,
+) -> Option<f64> {
+    // Icon and CJK fonts usually lack some of the latin glyphs. Like capsize, use
+    // the font-wide average from the OS/2 table for those, and the width of
+    // `.notdef` if there is no such table.
+    let fallback_width =
+        x_avg_char_width.or_else(|| font.horizontal_advance(0).map(|width| width as f64));
+
+    weighted_average_width(
+        |c| {
+            let (glyph_index, _) =
+                font.lookup_glyph_index(c, MatchingPresentation::NotRequired, None);
+            if glyph_index > 0 {
+                font.horizontal_advance(glyph_index)
+                    .map(|width| width as f64)
+            } else {
+                None
+            }
+        },
+        fallback_width,
+    )
+}
+
+fn weighted_average_width(
+    mut glyph_width: impl FnMut(char) -> Option<f64>,
+    fallback_width: Option<f64>,
+) -> Option<f64> {
+    let mut total = 0.0;
+    for &(c, weighting) in X_WIDTH_AVG_WEIGHTINGS {
+        total += glyph_width(c).or(fallback_width)? * weighting;
     }
 
-    Some(
-        font.map_glyphs(
-            AVG_CHARACTERS,
-            allsorts::tag::LATN,
-            allsorts::font::MatchingPresentation::NotRequired,
-        )
-        .iter()
-        .map(|g| font.horizontal_advance(g.glyph_index).unwrap())
-        .sum::<u16>() as f32
-            / AVG_CHARACTERS.len() as f32,
-    )
+    Some(total.round())
 }
 
 /// From [implementation](https://github.com/vercel/next.js/blob/dbdf47cf617b8d7213ffe1ff28318ea8eb88c623/packages/font/src/local/pick-font-file-for-fallback-generation.ts#L59)
@@ -256,9 +316,12 @@ mod tests {
     use anyhow::Result;
     use turbo_tasks::RcStr;
 
-    use crate::next_font::local::{
-        font_fallback::pick_font_for_fallback_generation,
-        options::{FontDescriptor, FontDescriptors, FontWeight},
+    use crate::next_font::{
+        font_fallback::{FontAdjustment, FontMetrics, DEFAULT_SANS_SERIF_FONT},
+        local::{
+            font_fallback::{pick_font_for_fallback_generation, weighted_average_width},
+            options::{FontDescriptor, FontDescriptors, FontWeight},
+        },
     };
 
     fn generate_font_descriptor(weight: &FontWeight, style: &Option<String>) -> FontDescriptor {
@@ -382,4 +445,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_x_width_avg_of_monospace_font() {
+        assert_eq!(weighted_average_width(|_| Some(1229.0), None), Some(1229.0));
+    }
+
+    #[test]
+    fn test_x_width_avg_falls_back_for_missing_glyphs() {
+        assert_eq!(weighted_average_width(|_| None, Some(1000.0)), Some(1000.0));
+        assert_eq!(
+            weighted_average_width(|c| (c == ' ').then_some(500.0), Some(1000.0)),
+            Some(909.0)
+        );
+        assert_eq!(weighted_average_width(|_| None, None), None);
+    }
+
+    #[test]
+    fn test_adjustment_matches_google_fonts() {
+        // Same inputs as `test_fallback_from_metrics_sans_serif` in
+        // `next_font::google::font_fallback`.
+        let inter = FontMetrics {
+            ascent: 2728.0,
+            descent: -680.0,
+            line_gap: 0.0,
+            units_per_em: 2816,
+            x_width_avg: 1335.0,
+        };
+
+        assert_eq!(
+            inter.adjustment_for(&DEFAULT_SANS_SERIF_FONT),
+            FontAdjustment {
+                ascent: 0.901_989_700_374_532,
+                descent: -0.224_836_142_322_097_4,
+                line_gap: 0.0,
+                size_adjust: 1.074_014_481_094_127
+            }
+        );
+    }
 }
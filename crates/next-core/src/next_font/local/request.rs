@@ -51,6 +51,7 @@ pub(super) struct SrcDescriptor {
 pub(super) enum AdjustFontFallback {
     Arial,
     TimesNewRoman,
+    CourierNew,
     None,
 }
 
@@ -59,7 +60,7 @@ fn default_adjust_font_fallback() -> AdjustFontFallback {
 }
 
 /// Deserializes and validates JS (bool | string) into [[AdjustFontFallback]]'s
-/// None, Arial, TimesNewRoman
+/// None, Arial, TimesNewRoman, CourierNew
 fn deserialize_adjust_font_fallback<'de, D>(
     de: D,
 ) -> std::result::Result<AdjustFontFallback, D::Error>
@@ -77,9 +78,10 @@ where
         AdjustFontFallbackInner::Named(name) => match name.as_str() {
             "Arial" => Ok(AdjustFontFallback::Arial),
             "Times New Roman" => Ok(AdjustFontFallback::TimesNewRoman),
+            "Courier New" => Ok(AdjustFontFallback::CourierNew),
             _ => Err(serde::de::Error::invalid_value(
                 serde::de::Unexpected::Other("adjust_font_fallback"),
-                &"Expected either \"Arial\" or \"Times New Roman\"",
+                &"Expected one of \"Arial\", \"Times New Roman\" or \"Courier New\"",
             )),
         },
         AdjustFontFallbackInner::None(val) => {
@@ -139,7 +141,7 @@ mod tests {
             Ok(_) => panic!("Should fail"),
             Err(error) => assert!(
                 error.to_string().contains(
-                    r#"invalid value: adjust_font_fallback, expected Expected either "Arial" or "Times New Roman""#
+                    r#"invalid value: adjust_font_fallback, expected Expected one of "Arial", "Times New Roman" or "Courier New""#
                 )
             ),
         };
@@ -168,4 +170,16 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deserializes_courier_new() -> Result<()> {
+        assert_eq!(
+            serde_json::from_str::<TestFallback>(r#"{"adjustFontFallback": "Courier New"}"#)?,
+            TestFallback {
+                adjust_font_fallback: AdjustFontFallback::CourierNew
+            }
+        );
+
+        Ok(())
+    }
 }
@@ -7,7 +7,10 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, FxIndexSet, RcStr, TaskInput};
 
-use crate::next_config::{CrossOriginConfig, Rewrites, RouteHas};
+use crate::{
+    next_config::{CrossOriginConfig, Rewrites, RouteHas},
+    next_font::font_fallback::FontMetrics,
+};
 
 #[derive(Serialize, Default, Debug)]
 pub struct PagesManifest {
@@ -152,6 +155,10 @@ pub struct NextFontManifest {
     pub app: HashMap<RcStr, Vec<RcStr>>,
     pub app_using_size_adjust: bool,
     pub pages_using_size_adjust: bool,
+    /// The metrics of size-adjusted font files, keyed by font path. Only used
+    /// to debug the generated fallback font overrides.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub font_metrics: HashMap<RcStr, FontMetrics>,
 }
 
 #[derive(Serialize, Default, Debug)]
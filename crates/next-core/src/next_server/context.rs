@@ -60,6 +60,7 @@ use crate::{
             styled_jsx::get_styled_jsx_transform_rule,
             swc_ecma_transform_plugins::get_swc_ecma_transform_plugin_rule,
         },
+        url_imports::get_url_imports_options,
         webpack_rules::webpack_loader_options,
     },
     transform_options::{
@@ -316,6 +317,9 @@ pub async fn get_server_resolve_options_context(
         import_map: Some(next_server_import_map),
         before_resolve_plugins,
         after_resolve_plugins,
+        url_imports: *get_url_imports_options(project_path, next_config).await?,
+        extension_alias: next_config.extension_alias().await?.clone_value(),
+        esm_fully_specified: *next_config.fully_specified().await?,
         ..Default::default()
     };
 
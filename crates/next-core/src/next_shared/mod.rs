@@ -1,3 +1,4 @@
 pub(crate) mod resolve;
 pub(crate) mod transforms;
+pub(crate) mod url_imports;
 pub(crate) mod webpack_rules;
@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use turbo_tasks::Vc;
+use turbo_tasks_fetch::UrlFileSystem;
+use turbo_tasks_fs::{to_sys_path, FileSystemPath};
+use turbopack_core::resolve::url_imports::{OptionUrlImportsOptions, UrlImportsOptions};
+
+use crate::next_config::NextConfig;
+
+/// Returns the options for `experimental.urlImports`, if enabled. The URL
+/// file system is shared by all contexts of a project, so URL modules resolve
+/// to the same paths on the client and the server.
+#[turbo_tasks::function]
+pub async fn get_url_imports_options(
+    project_path: Vc<FileSystemPath>,
+    next_config: Vc<NextConfig>,
+) -> Result<Vc<OptionUrlImportsOptions>> {
+    let Some(url_imports) = &*next_config.url_imports().await? else {
+        return Ok(Vc::cell(None));
+    };
+
+    let lock_dir = to_sys_path(project_path)
+        .await?
+        .context("experimental.urlImports requires the project to be on disk")?
+        .join("next.lock");
+
+    Ok(Vc::cell(Some(
+        UrlImportsOptions {
+            allowed_uris: url_imports.allowed_uris.clone(),
+            file_system: Vc::upcast(UrlFileSystem::new(
+                lock_dir.to_string_lossy().into(),
+                url_imports.frozen,
+            ))
+            .to_resolved()
+            .await?,
+        }
+        .resolved_cell(),
+    )))
+}
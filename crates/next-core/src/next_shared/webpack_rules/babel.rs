@@ -96,6 +96,7 @@ pub async fn maybe_add_babel_loader(
                         LoaderRuleItem {
                             loaders: Vc::cell(vec![loader]),
                             rename_as: Some("*".into()),
+                            fully_specified: None,
                         },
                     );
                 }
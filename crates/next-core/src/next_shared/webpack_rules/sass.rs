@@ -78,6 +78,7 @@ pub async fn maybe_add_sass_loader(
                 LoaderRuleItem {
                     loaders: Vc::cell(vec![resolve_url_loader, sass_loader]),
                     rename_as: Some(format!("*{rename}").into()),
+                    fully_specified: None,
                 },
             );
         }
@@ -0,0 +1,585 @@
+//! Generates the route type definitions for `experimental.typedRoutes`.
+//!
+//! The output matches the `types/link.d.ts` file written by the webpack
+//! `NextTypesPlugin`, so `<Link href>`, `useRouter().push` and `<Form action>`
+//! are type checked the same way regardless of the bundler. Like webpack, the
+//! routes are the pages and route handlers plus the sources of the redirects
+//! and rewrites, but not the metadata routes.
+
+/// Everything before the `StaticRoutes` and `DynamicRoutes` declarations.
+const LINK_TYPES_PREFIX: &str = r#"// Type definitions for Next.js routes
+
+/**
+ * Internal types used by the Next.js router and Link component.
+ * These types are not meant to be used directly.
+ * @internal
+ */
+declare namespace __next_route_internal_types__ {
+  type SearchOrHash = `?${string}` | `#${string}`
+  type WithProtocol = `${string}:${string}`
+
+  type Suffix = '' | SearchOrHash
+
+  type SafeSlug<S extends string> = S extends `${string}/${string}`
+    ? never
+    : S extends `${string}${SearchOrHash}`
+    ? never
+    : S extends ''
+    ? never
+    : S
+
+  type CatchAllSlug<S extends string> = S extends `${string}${SearchOrHash}`
+    ? never
+    : S extends ''
+    ? never
+    : S
+
+  type OptionalCatchAllSlug<S extends string> =
+    S extends `${string}${SearchOrHash}` ? never : S
+
+"#;
+
+/// Everything after the `RouteImpl` declaration.
+const LINK_TYPES_SUFFIX: &str = r#"}
+
+declare module 'next' {
+  export { default } from 'next/types.js'
+  export * from 'next/types.js'
+
+  export type Route<T extends string = string> =
+    __next_route_internal_types__.RouteImpl<T>
+}
+
+declare module 'next/link' {
+  import type { LinkProps as OriginalLinkProps } from 'next/dist/client/link.js'
+  import type { AnchorHTMLAttributes, DetailedHTMLProps } from 'react'
+  import type { UrlObject } from 'url'
+
+  type LinkRestProps = Omit<
+    Omit<
+      DetailedHTMLProps<
+        AnchorHTMLAttributes<HTMLAnchorElement>,
+        HTMLAnchorElement
+      >,
+      keyof OriginalLinkProps
+    > &
+      OriginalLinkProps,
+    'href'
+  >
+
+  export type LinkProps<RouteInferType> = LinkRestProps & {
+    /**
+     * The path or URL to navigate to. This is the only required prop. It can also be an object.
+     * @see https://nextjs.org/docs/api-reference/next/link
+     */
+    href: __next_route_internal_types__.RouteImpl<RouteInferType> | UrlObject
+  }
+
+  export default function Link<RouteType>(props: LinkProps<RouteType>): JSX.Element
+}
+
+declare module 'next/navigation' {
+  export * from 'next/dist/client/components/navigation.js'
+
+  import type { NavigateOptions, AppRouterInstance as OriginalAppRouterInstance } from 'next/dist/shared/lib/app-router-context.shared-runtime.js'
+  interface AppRouterInstance extends OriginalAppRouterInstance {
+    /**
+     * Navigate to the provided href.
+     * Pushes a new history entry.
+     */
+    push<RouteType>(href: __next_route_internal_types__.RouteImpl<RouteType>, options?: NavigateOptions): void
+    /**
+     * Navigate to the provided href.
+     * Replaces the current history entry.
+     */
+    replace<RouteType>(href: __next_route_internal_types__.RouteImpl<RouteType>, options?: NavigateOptions): void
+    /**
+     * Prefetch the provided href.
+     */
+    prefetch<RouteType>(href: __next_route_internal_types__.RouteImpl<RouteType>): void
+  }
+
+  export function useRouter(): AppRouterInstance;
+}
+
+declare module 'next/form' {
+  import type { FormProps as OriginalFormProps } from 'next/dist/client/form.js'
+
+  type FormRestProps = Omit<OriginalFormProps, 'action'>
+
+  export type FormProps<RouteInferType> = {
+    /**
+     * `action` can be either a `string` or a function.
+     * - If `action` is a string, it will be interpreted as a path or URL to navigate to when the form is submitted.
+     *   The path will be prefetched when the form becomes visible.
+     * - If `action` is a function, it will be called when the form is submitted. See the [React docs](https://react.dev/reference/react-dom/components/form#props) for more.
+     */
+    action: __next_route_internal_types__.RouteImpl<RouteInferType> | ((formData: FormData) => void)
+  } & FormRestProps
+
+  export default function Form<RouteType>(props: FormProps<RouteType>): JSX.Element
+}
+"#;
+
+/// Used for `RouteImpl` when there are static or dynamic routes.
+const ROUTE_IMPL: &str = r#"
+    | StaticRoutes
+    | SearchOrHash
+    | WithProtocol
+    | `${StaticRoutes}${SearchOrHash}`
+    | (T extends `${DynamicRoutes<infer _>}${Suffix}` ? T : never)
+    "#;
+
+/// Routes that are part of the route tree but can't be navigated to.
+fn is_internal_route(pathname: &str) -> bool {
+    matches!(
+        pathname,
+        "/_app" | "/_document" | "/_error" | "/404" | "/500" | "/_not-found"
+    )
+}
+
+fn is_dynamic_segment(segment: &str) -> bool {
+    segment.len() > 2 && segment.starts_with('[') && segment.ends_with(']')
+}
+
+/// Converts a pathname like `/blog/[slug]/[...rest]` into a template literal
+/// type. Returns whether the route is dynamic along with the union member.
+fn format_route_to_route_type(pathname: &str) -> (bool, String) {
+    let is_dynamic = pathname.split('/').any(is_dynamic_segment);
+    let route = if is_dynamic {
+        pathname
+            .split('/')
+            .map(|segment| {
+                if !is_dynamic_segment(segment) {
+                    segment
+                } else if segment.starts_with("[[...") && segment.ends_with("]]") {
+                    "${OptionalCatchAllSlug<T>}"
+                } else if segment.starts_with("[...") {
+                    "${CatchAllSlug<T>}"
+                } else {
+                    "${SafeSlug<T>}"
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    } else {
+        pathname.to_string()
+    };
+
+    (is_dynamic, format!("\n    | `{route}`"))
+}
+
+/// A token of a `path-to-regexp` path, as returned by its `parse` function.
+#[derive(Debug, PartialEq)]
+enum PathToken {
+    Text(String),
+    Key {
+        /// The name of the parameter. Unnamed groups are numbered from 0.
+        name: String,
+        prefix: String,
+        pattern: String,
+        modifier: Option<char>,
+    },
+}
+
+/// The pattern `path-to-regexp` uses for parameters without one.
+const DEFAULT_PATTERN: &str = "[^\\/#\\?]+?";
+
+#[derive(Debug, PartialEq)]
+enum LexToken {
+    Modifier(char),
+    EscapedChar(char),
+    Char(char),
+    Open,
+    Close,
+    Name(String),
+    Pattern(String),
+}
+
+/// Port of the lexer of `path-to-regexp` 6. Returns `None` for invalid paths.
+fn lex_path(path: &str) -> Option<Vec<LexToken>> {
+    let chars: Vec<char> = path.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let char = chars[i];
+        match char {
+            '*' | '+' | '?' => {
+                tokens.push(LexToken::Modifier(char));
+                i += 1;
+            }
+            '\\' => {
+                tokens.push(LexToken::EscapedChar(*chars.get(i + 1)?));
+                i += 2;
+            }
+            '{' => {
+                tokens.push(LexToken::Open);
+                i += 1;
+            }
+            '}' => {
+                tokens.push(LexToken::Close);
+                i += 1;
+            }
+            ':' => {
+                let mut j = i + 1;
+                while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_') {
+                    j += 1;
+                }
+                if j == i + 1 {
+                    return None;
+                }
+                tokens.push(LexToken::Name(chars[i + 1..j].iter().collect()));
+                i = j;
+            }
+            '(' => {
+                let mut count = 1;
+                let mut pattern = String::new();
+                let mut j = i + 1;
+                if chars.get(j) == Some(&'?') {
+                    return None;
+                }
+                while j < chars.len() {
+                    if chars[j] == '\\' {
+                        pattern.push(chars[j]);
+                        pattern.push(*chars.get(j + 1)?);
+                        j += 2;
+                        continue;
+                    }
+                    if chars[j] == ')' {
+                        count -= 1;
+                        if count == 0 {
+                            j += 1;
+                            break;
+                        }
+                    } else if chars[j] == '(' {
+                        count += 1;
+                        if chars.get(j + 1) != Some(&'?') {
+                            return None;
+                        }
+                    }
+                    pattern.push(chars[j]);
+                    j += 1;
+                }
+                if count != 0 || pattern.is_empty() {
+                    return None;
+                }
+                tokens.push(LexToken::Pattern(pattern));
+                i = j;
+            }
+            _ => {
+                tokens.push(LexToken::Char(char));
+                i += 1;
+            }
+        }
+    }
+    Some(tokens)
+}
+
+/// Port of `parse` of `path-to-regexp` 6 with the default options. Returns
+/// `None` for invalid paths.
+fn parse_path(path: &str) -> Option<Vec<PathToken>> {
+    let mut tokens = lex_path(path)?.into_iter().peekable();
+    let mut result = Vec::new();
+    let mut key = 0;
+    let mut text = String::new();
+
+    macro_rules! try_consume {
+        ($variant:ident) => {
+            match tokens.peek() {
+                Some(LexToken::$variant(_)) => match tokens.next() {
+                    Some(LexToken::$variant(value)) => Some(value),
+                    _ => unreachable!(),
+                },
+                _ => None,
+            }
+        };
+    }
+
+    loop {
+        let char = try_consume!(Char);
+        let name = try_consume!(Name);
+        let pattern = try_consume!(Pattern);
+        if name.is_some() || pattern.is_some() {
+            let mut prefix = char.map(String::from).unwrap_or_default();
+            if prefix != "." && prefix != "/" {
+                text.push_str(&prefix);
+                prefix = String::new();
+            }
+            if !text.is_empty() {
+                result.push(PathToken::Text(std::mem::take(&mut text)));
+            }
+            let name = name.unwrap_or_else(|| {
+                key += 1;
+                (key - 1).to_string()
+            });
+            result.push(PathToken::Key {
+                name,
+                prefix,
+                pattern: pattern.unwrap_or_else(|| DEFAULT_PATTERN.to_string()),
+                modifier: try_consume!(Modifier),
+            });
+            continue;
+        }
+
+        if let Some(char) = char.or_else(|| try_consume!(EscapedChar)) {
+            text.push(char);
+            continue;
+        }
+
+        if !text.is_empty() {
+            result.push(PathToken::Text(std::mem::take(&mut text)));
+        }
+
+        match tokens.next() {
+            None => return Some(result),
+            Some(LexToken::Open) => {
+                macro_rules! consume_text {
+                    () => {{
+                        let mut text = String::new();
+                        while let Some(char) =
+                            try_consume!(Char).or_else(|| try_consume!(EscapedChar))
+                        {
+                            text.push(char);
+                        }
+                        text
+                    }};
+                }
+                let prefix = consume_text!();
+                let name = try_consume!(Name).unwrap_or_default();
+                let pattern = try_consume!(Pattern).unwrap_or_default();
+                // The suffix isn't used for the route types.
+                let _suffix = consume_text!();
+                if tokens.next() != Some(LexToken::Close) {
+                    return None;
+                }
+                let (name, pattern) = match (name.is_empty(), pattern.is_empty()) {
+                    (false, true) => (name, DEFAULT_PATTERN.to_string()),
+                    (true, false) => {
+                        key += 1;
+                        ((key - 1).to_string(), pattern)
+                    }
+                    _ => (name, pattern),
+                };
+                result.push(PathToken::Key {
+                    name,
+                    prefix,
+                    pattern,
+                    modifier: try_consume!(Modifier),
+                });
+            }
+            Some(_) => return None,
+        }
+    }
+}
+
+/// Converts the source of a redirect or rewrite into the route pathnames it
+/// matches, like `addRedirectsRewritesRouteTypes` of the webpack plugin. An
+/// optional plain text parameter forks the route. Sources with patterns that
+/// can't be expressed as route types are skipped.
+fn redirect_or_rewrite_pathnames(source: &str) -> Vec<String> {
+    // Invalid sources are reported elsewhere.
+    let Some(tokens) = parse_path(source) else {
+        return Vec::new();
+    };
+
+    let mut routes = vec![String::new()];
+    let mut slug_count = 1;
+    let append = |routes: &mut Vec<String>, suffix: &str| {
+        for route in routes.iter_mut() {
+            route.push_str(suffix);
+        }
+    };
+
+    for token in tokens {
+        match token {
+            PathToken::Text(text) => append(&mut routes, &text),
+            PathToken::Key {
+                name,
+                prefix,
+                pattern,
+                modifier,
+            } => {
+                // Make sure the slug is always named. Like in JavaScript, the unnamed group 0 is
+                // falsy.
+                let slug = if name.is_empty() || name == "0" {
+                    slug_count += 1;
+                    if slug_count == 2 {
+                        "slug".to_string()
+                    } else {
+                        format!("slug{slug_count}")
+                    }
+                } else {
+                    name
+                };
+                match modifier {
+                    Some('*') => append(&mut routes, &format!("{prefix}[[...{slug}]]")),
+                    Some('+') => append(&mut routes, &format!("{prefix}[...{slug}]")),
+                    None if pattern == DEFAULT_PATTERN => {
+                        append(&mut routes, &format!("{prefix}[{slug}]"))
+                    }
+                    None if pattern == ".*" => {
+                        append(&mut routes, &format!("{prefix}[[...{slug}]]"))
+                    }
+                    None if pattern == ".+" => append(&mut routes, &format!("{prefix}[...{slug}]")),
+                    Some('?')
+                        if pattern
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '/') =>
+                    {
+                        append(&mut routes, &prefix);
+                        let forked: Vec<_> = routes
+                            .iter()
+                            .map(|route| format!("{route}{pattern}"))
+                            .collect();
+                        routes.extend(forked);
+                    }
+                    // Other regex patterns and optional parameters with them are not supported.
+                    _ => return Vec::new(),
+                }
+            }
+        }
+    }
+    routes
+}
+
+/// Creates the contents of `types/link.d.ts` for the given route pathnames
+/// and the sources of the redirects and rewrites.
+pub fn route_type_definitions<'a>(
+    pathnames: impl IntoIterator<Item = &'a str>,
+    redirect_and_rewrite_sources: impl IntoIterator<Item = &'a str>,
+) -> String {
+    let mut static_route_types = String::new();
+    let mut dynamic_route_types = String::new();
+
+    let extra_pathnames = redirect_and_rewrite_sources
+        .into_iter()
+        .flat_map(redirect_or_rewrite_pathnames);
+    for (pathname, is_extra) in pathnames
+        .into_iter()
+        .map(|pathname| (pathname.to_string(), false))
+        .chain(extra_pathnames.map(|pathname| (pathname, true)))
+    {
+        if !is_extra && is_internal_route(&pathname) {
+            continue;
+        }
+        let (is_dynamic, route_type) = format_route_to_route_type(&pathname);
+        if is_dynamic {
+            dynamic_route_types.push_str(&route_type);
+        } else {
+            static_route_types.push_str(&route_type);
+        }
+    }
+
+    // If both StaticRoutes and DynamicRoutes are empty, fallback to type 'string'.
+    let route_impl = if static_route_types.is_empty() && dynamic_route_types.is_empty() {
+        "string"
+    } else {
+        ROUTE_IMPL
+    };
+
+    let or_never = |types: &str| {
+        if types.is_empty() {
+            "never".to_string()
+        } else {
+            types.to_string()
+        }
+    };
+
+    format!(
+        "{LINK_TYPES_PREFIX}  type StaticRoutes = {}\n  type DynamicRoutes<T extends string = \
+         string> = {}\n\n  type RouteImpl<T> = {route_impl}\n{LINK_TYPES_SUFFIX}",
+        or_never(&static_route_types),
+        or_never(&dynamic_route_types),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_route_to_route_type() {
+        assert_eq!(
+            format_route_to_route_type("/about"),
+            (false, "\n    | `/about`".to_string())
+        );
+        assert_eq!(
+            format_route_to_route_type("/blog/[slug]/[...rest]"),
+            (
+                true,
+                "\n    | `/blog/${SafeSlug<T>}/${CatchAllSlug<T>}`".to_string()
+            )
+        );
+        assert_eq!(
+            format_route_to_route_type("/docs/[[...path]]"),
+            (
+                true,
+                "\n    | `/docs/${OptionalCatchAllSlug<T>}`".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_route_type_definitions() {
+        let definitions = route_type_definitions(
+            ["/", "/_not-found", "/404", "/blog/[slug]", "/api/hello"],
+            [],
+        );
+        assert!(definitions.contains("type StaticRoutes = \n    | `/`\n    | `/api/hello`\n"));
+        assert!(definitions.contains(
+            "type DynamicRoutes<T extends string = string> = \n    | `/blog/${SafeSlug<T>}`\n"
+        ));
+        assert!(definitions.contains("| StaticRoutes"));
+        assert!(!definitions.contains("_not-found"));
+    }
+
+    #[test]
+    fn test_route_type_definitions_without_routes() {
+        let definitions = route_type_definitions([], []);
+        assert!(definitions.contains("type StaticRoutes = never\n"));
+        assert!(definitions.contains("type DynamicRoutes<T extends string = string> = never\n"));
+        assert!(definitions.contains("type RouteImpl<T> = string\n}"));
+    }
+
+    #[test]
+    fn test_redirect_or_rewrite_pathnames() {
+        assert_eq!(redirect_or_rewrite_pathnames("/about"), vec!["/about"]);
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/blog/:slug"),
+            vec!["/blog/[slug]"]
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/docs/:path*"),
+            vec!["/docs/[[...path]]"]
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/docs/:path+"),
+            vec!["/docs/[...path]"]
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/old/(.*)"),
+            vec!["/old/[[...slug]]"]
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/:locale(en|fr)/shop"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/shop/:kind(shoes)?"),
+            vec!["/shop/", "/shop/shoes"]
+        );
+        assert_eq!(
+            redirect_or_rewrite_pathnames("/broken/(unclosed"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_route_type_definitions_with_redirects_and_rewrites() {
+        let definitions = route_type_definitions(["/"], ["/old-blog/:slug", "/about-us"]);
+        assert!(definitions.contains("type StaticRoutes = \n    | `/`\n    | `/about-us`\n"));
+        assert!(definitions.contains(
+            "type DynamicRoutes<T extends string = string> = \n    | `/old-blog/${SafeSlug<T>}`\n"
+        ));
+    }
+}
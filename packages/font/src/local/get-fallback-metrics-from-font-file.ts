@@ -1,50 +1,81 @@
 import type { Font } from 'fontkit'
 import type { AdjustFontFallback } from 'next/font'
 
-// The font metadata of the fallback fonts, retrieved with fontkit on system font files
-// The average width is calculated with the calcAverageWidth function below
+// The metrics of the fallback fonts, from
+// https://github.com/seek-oss/capsize/tree/master/packages/metrics
+// `xWidthAvg` is the average character width weighted by letter frequency, as
+// calculated by calcAverageWidth below. Keep in sync with
+// crates/next-core/src/next_font/font_fallback.rs
 const DEFAULT_SANS_SERIF_FONT = {
   name: 'Arial',
-  azAvgWidth: 934.5116279069767,
+  xWidthAvg: 904,
   unitsPerEm: 2048,
 }
 const DEFAULT_SERIF_FONT = {
   name: 'Times New Roman',
-  azAvgWidth: 854.3953488372093,
+  xWidthAvg: 819,
   unitsPerEm: 2048,
 }
+const DEFAULT_MONOSPACE_FONT = {
+  name: 'Courier New',
+  xWidthAvg: 1229,
+  unitsPerEm: 2048,
+}
+
+// Letter frequencies used to weight the advance width of each character, from
+// https://github.com/seek-oss/capsize/blob/42d6dc39d58247bc6b9e013a4b1c4463bf287dca/packages/unpack/src/weightings.ts
+const X_WIDTH_AVG_WEIGHTINGS: [string, number][] = [
+  ['a', 0.0668],
+  ['b', 0.0122],
+  ['c', 0.0228],
+  ['d', 0.0348],
+  ['e', 0.1039],
+  ['f', 0.0182],
+  ['g', 0.0165],
+  ['h', 0.0499],
+  ['i', 0.057],
+  ['j', 0.0013],
+  ['k', 0.0063],
+  ['l', 0.0329],
+  ['m', 0.0197],
+  ['n', 0.0552],
+  ['o', 0.0614],
+  ['p', 0.0158],
+  ['q', 0.0008],
+  ['r', 0.049],
+  ['s', 0.0518],
+  ['t', 0.0741],
+  ['u', 0.0226],
+  ['v', 0.008],
+  ['w', 0.0193],
+  ['x', 0.0012],
+  ['y', 0.0162],
+  ['z', 0.0006],
+  [' ', 0.1818],
+]
 
 /**
- * Calculate the average character width of a font file.
+ * Calculate the average character width of a font file, weighted by letter frequency the same way capsize's `xWidthAvg` is.
  * Used to calculate the size-adjust property by comparing the fallback average with the loaded font average.
+ *
+ * Icon and CJK fonts usually lack some of the latin glyphs. For those, the font-wide average from the OS/2 table is used,
+ * or the width of `.notdef` if there is no such table.
  */
 function calcAverageWidth(font: Font): number | undefined {
   try {
-    /**
-     * Finding the right characters to use when calculating the average width is tricky.
-     * We can't just use the average width of all characters, because we have to take letter frequency into account.
-     * We also have to take word length into account, because the font's space width usually differ a lot from other characters.
-     * The goal is to find a string that'll give you a good average width, given most texts in most languages.
-     *
-     * TODO: Currently only works for the latin alphabet. Support more languages by finding the right characters for additional languages.
-     *
-     * The used characters were decided through trial and error with letter frequency and word length tables as a guideline.
-     * E.g. https://en.wikipedia.org/wiki/Letter_frequency
-     */
-    const avgCharacters = 'aaabcdeeeefghiijklmnnoopqrrssttuvwxyz      '
-    // Check if the font file has all the characters we need to calculate the average width
-    const hasAllChars = font
-      .glyphsForString(avgCharacters)
-      .flatMap((glyph) => glyph.codePoints)
-      .every((codePoint) => font.hasGlyphForCodePoint(codePoint))
-
-    if (!hasAllChars) return undefined
+    const fallbackWidth: number | undefined =
+      (font as any)['OS/2']?.xAvgCharWidth ?? font.getGlyph(0)?.advanceWidth
 
-    const widths = font
-      .glyphsForString(avgCharacters)
-      .map((glyph) => glyph.advanceWidth)
-    const totalWidth = widths.reduce((sum, width) => sum + width, 0)
-    return totalWidth / widths.length
+    let total = 0
+    for (const [char, weighting] of X_WIDTH_AVG_WEIGHTINGS) {
+      const codePoint = char.codePointAt(0)!
+      const width = font.hasGlyphForCodePoint(codePoint)
+        ? font.glyphForCodePoint(codePoint).advanceWidth
+        : fallbackWidth
+      if (width === undefined) return undefined
+      total += width * weighting
+    }
+    return Math.round(total)
   } catch {
     // Could not calculate average width from the font file, skip size-adjust
     return undefined
@@ -75,14 +106,18 @@ export function getFallbackMetricsFromFontFile(
   category = 'serif'
 ): AdjustFontFallback {
   const fallbackFont =
-    category === 'serif' ? DEFAULT_SERIF_FONT : DEFAULT_SANS_SERIF_FONT
+    category === 'serif'
+      ? DEFAULT_SERIF_FONT
+      : category === 'monospace'
+        ? DEFAULT_MONOSPACE_FONT
+        : DEFAULT_SANS_SERIF_FONT
 
-  const azAvgWidth = calcAverageWidth(font)
+  const xWidthAvg = calcAverageWidth(font)
   const { ascent, descent, lineGap, unitsPerEm } = font
 
-  const fallbackFontAvgWidth = fallbackFont.azAvgWidth / fallbackFont.unitsPerEm
-  let sizeAdjust = azAvgWidth
-    ? azAvgWidth / unitsPerEm / fallbackFontAvgWidth
+  const fallbackFontAvgWidth = fallbackFont.xWidthAvg / fallbackFont.unitsPerEm
+  let sizeAdjust = xWidthAvg
+    ? xWidthAvg / unitsPerEm / fallbackFontAvgWidth
     : 1
 
   return {
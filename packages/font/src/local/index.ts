@@ -17,7 +17,7 @@ type LocalFont<T extends CssVariable | undefined = undefined> = {
   display?: Display
   weight?: string
   style?: string
-  adjustFontFallback?: 'Arial' | 'Times New Roman' | false
+  adjustFontFallback?: 'Arial' | 'Times New Roman' | 'Courier New' | false
   fallback?: string[]
   preload?: boolean
   variable?: T
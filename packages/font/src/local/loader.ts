@@ -91,7 +91,11 @@ const nextFontLocalFontLoader: FontLoader = async ({
     if (fallbackFontFile.fontMetadata) {
       adjustFontFallbackMetrics = getFallbackMetricsFromFontFile(
         fallbackFontFile.fontMetadata,
-        adjustFontFallback === 'Times New Roman' ? 'serif' : 'sans-serif'
+        adjustFontFallback === 'Times New Roman'
+          ? 'serif'
+          : adjustFontFallback === 'Courier New'
+            ? 'monospace'
+            : 'sans-serif'
       )
     }
   }
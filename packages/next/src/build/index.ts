@@ -149,6 +149,7 @@ import {
   teardownHeapProfiler,
   createDefineEnv,
 } from './swc'
+import type { Issue } from './swc/types'
 import { getNamedRouteRegex } from '../shared/lib/router/utils/route-regex'
 import { getFilesInDir } from '../lib/get-files-in-dir'
 import { eventSwcPlugins } from '../telemetry/events/swc-plugins'
@@ -1641,6 +1642,67 @@ export default async function build(
           }
         }
 
+        const clientBundleIssues: Issue[] = []
+        if (config.experimental.turbo?.bundleSizeBudgets) {
+          const bundleSizeReport = await project.bundleSizeReport()
+          console.log(bundleSizeReport.result)
+          clientBundleIssues.push(...bundleSizeReport.issues)
+        }
+        const cssOrder = await project.checkCssOrder()
+        clientBundleIssues.push(...cssOrder.issues)
+        for (const issue of clientBundleIssues) {
+          if (
+            issue.severity === 'bug' ||
+            issue.severity === 'fatal' ||
+            issue.severity === 'error'
+          ) {
+            errors.push({
+              page: 'client bundles',
+              message: formatIssue(issue),
+            })
+          } else if (issue.severity === 'warning' && isRelevantWarning(issue)) {
+            warnings.push({
+              page: 'client bundles',
+              message: formatIssue(issue),
+            })
+          }
+        }
+
+        if (
+          config.experimental.turbo?.typeCheck ||
+          config.experimental.turbo?.lint
+        ) {
+          const checks = project.checksSubscribe()
+          const checksResult = (await checks.next()).value
+          await checks.return?.()
+          for (const issue of checksResult?.issues ?? []) {
+            if (issue.stage === 'lint') {
+              if (config.eslint.ignoreDuringBuilds) {
+                continue
+              }
+              if (issue.severity !== 'warning') {
+                errors.push({
+                  page: 'lint',
+                  message: formatIssue(issue),
+                })
+              } else if (isRelevantWarning(issue)) {
+                warnings.push({
+                  page: 'lint',
+                  message: formatIssue(issue),
+                })
+              }
+            } else if (
+              !config.typescript.ignoreBuildErrors &&
+              issue.severity !== 'warning'
+            ) {
+              errors.push({
+                page: 'type check',
+                message: formatIssue(issue),
+              })
+            }
+          }
+        }
+
         const shutdownPromise = project.shutdown()
 
         if (warnings.length > 0) {
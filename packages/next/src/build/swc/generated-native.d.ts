@@ -265,6 +265,39 @@ export function projectUpdateInfoSubscribe(
   aggregationMs: number,
   func: (...args: any[]) => any
 ): void
+export interface NapiTaskGraphOptions {
+  /** Only returns the tasks at most `depth` edges away from this task. */
+  task?: number
+  /** Defaults to 2 when `task` is set. */
+  depth?: number
+  /** Either `json` (default) or `dot`. */
+  format?: string
+}
+/**
+ * Returns a snapshot of the task graph as JSON or in the DOT format. Returns
+ * `null` when the backend doesn't support inspecting the task graph.
+ */
+export function projectTaskGraph(
+  project: { __napiType: 'Project' },
+  options: NapiTaskGraphOptions
+): string | null
+/**
+ * Enables recording why tasks are invalidated, which is needed by
+ * [project_explain_invalidation]. Disabling it drops all records.
+ */
+export function projectSetTrackInvalidationCauses(
+  project: { __napiType: 'Project' },
+  enabled: boolean
+): void
+/**
+ * Returns the chain of invalidations, as JSON, that caused `task` to be
+ * recomputed the last time, starting with `task` and ending with the
+ * originating change.
+ */
+export function projectExplainInvalidation(
+  project: { __napiType: 'Project' },
+  task: number
+): string
 export interface StackFrame {
   isServer: boolean
   isInternal?: boolean
@@ -285,6 +318,28 @@ export function projectGetSourceMap(
   project: { __napiType: 'Project' },
   filePath: string
 ): Promise<string | null>
+/**
+ * Computes the bundle sizes of all routes and returns them as a table. Routes
+ * exceeding their budget are reported as issues.
+ */
+export function projectBundleSizeReport(project: {
+  __napiType: 'Project'
+}): Promise<TurbopackResult>
+/**
+ * Checks that all pages load their CSS modules in the same order. Modules
+ * loaded in conflicting orders are reported as issues.
+ */
+export function projectCheckCssOrder(project: {
+  __napiType: 'Project'
+}): Promise<TurbopackResult>
+/**
+ * Type checks and lints the module graph and calls `func` with the problems as
+ * issues, again whenever they change.
+ */
+export function projectChecksSubscribe(
+  project: { __napiType: 'Project' },
+  func: (...args: any[]) => any
+): { __napiType: 'RootTask' }
 /** Runs exit handlers for the project registered using the [`ExitHandler`] API. */
 export function projectOnExit(project: { __napiType: 'Project' }): Promise<void>
 export function rootTaskDispose(rootTask: { __napiType: 'RootTask' }): void
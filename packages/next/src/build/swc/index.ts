@@ -31,9 +31,11 @@ import type {
   DefineEnv,
   Endpoint,
   HmrIdentifiers,
+  InvalidationStep,
   Project,
   ProjectOptions,
   Route,
+  TaskGraphOptions,
   TurboEngineOptions,
   TurbopackResult,
   TurbopackStackFrame,
@@ -759,6 +761,34 @@ function bindingToApi(
       return binding.projectGetSourceMap(this._nativeProject, filePath)
     }
 
+    bundleSizeReport(): Promise<TurbopackResult<string>> {
+      return binding.projectBundleSizeReport(this._nativeProject)
+    }
+
+    checkCssOrder(): Promise<TurbopackResult<void>> {
+      return binding.projectCheckCssOrder(this._nativeProject)
+    }
+
+    checksSubscribe() {
+      return subscribe<TurbopackResult<void>>(false, async (callback) =>
+        binding.projectChecksSubscribe(this._nativeProject, callback)
+      )
+    }
+
+    taskGraph(options: TaskGraphOptions): string | null {
+      return binding.projectTaskGraph(this._nativeProject, options)
+    }
+
+    setTrackInvalidationCauses(enabled: boolean): void {
+      binding.projectSetTrackInvalidationCauses(this._nativeProject, enabled)
+    }
+
+    explainInvalidation(task: number): InvalidationStep[] {
+      return JSON.parse(
+        binding.projectExplainInvalidation(this._nativeProject, task)
+      )
+    }
+
     updateInfoSubscribe(aggregationMs: number) {
       return subscribe<TurbopackResult<UpdateMessage>>(true, async (callback) =>
         binding.projectUpdateInfoSubscribe(
@@ -190,6 +190,23 @@ export interface UpdateInfo {
   tasks: number
 }
 
+export interface TaskGraphOptions {
+  /** Only returns the tasks at most `depth` edges away from this task. */
+  task?: number
+  /** Defaults to 2 when `task` is set. */
+  depth?: number
+  format?: 'json' | 'dot'
+}
+
+export interface InvalidationStep {
+  task: number
+  description: string
+  cause:
+    | { type: 'reason'; reason: string }
+    | { type: 'external' }
+    | { type: 'dependency'; task: number }
+}
+
 export interface Project {
   update(options: Partial<ProjectOptions>): Promise<void>
 
@@ -205,6 +222,12 @@ export interface Project {
 
   getSourceMap(filePath: string): Promise<string | null>
 
+  bundleSizeReport(): Promise<TurbopackResult<string>>
+
+  checkCssOrder(): Promise<TurbopackResult<void>>
+
+  checksSubscribe(): AsyncIterableIterator<TurbopackResult<void>>
+
   traceSource(
     stackFrame: TurbopackStackFrame
   ): Promise<TurbopackStackFrame | null>
@@ -213,6 +236,20 @@ export interface Project {
     aggregationMs: number
   ): AsyncIterableIterator<TurbopackResult<UpdateMessage>>
 
+  /**
+   * Returns a snapshot of the task graph as JSON or DOT, or `null` when the
+   * backend doesn't support it.
+   */
+  taskGraph(options: TaskGraphOptions): string | null
+
+  setTrackInvalidationCauses(enabled: boolean): void
+
+  /**
+   * Returns the chain of invalidations that caused the task to be recomputed
+   * the last time, back to the originating change.
+   */
+  explainInvalidation(task: number): InvalidationStep[]
+
   shutdown(): Promise<void>
 
   onExit(): Promise<void>
@@ -30,12 +30,10 @@ const unsupportedTurbopackNextConfigOptions = [
   // 'experimental.serverSourceMaps',
 
   'experimental.allowedRevalidateHeaderKeys',
-  'experimental.extensionAlias',
   'experimental.fallbackNodePolyfills',
 
   'experimental.sri.algorithm',
   'experimental.swcTraceProfiling',
-  'experimental.typedRoutes',
 
   // Left to be implemented (Might not be needed for Turbopack)
   'experimental.craCompat',
@@ -43,14 +41,14 @@ const unsupportedTurbopackNextConfigOptions = [
   'experimental.esmExternals',
   // This is used to force swc-loader to run regardless of finding Babel.
   'experimental.forceSwcTransforms',
-  'experimental.fullySpecified',
-  'experimental.urlImports',
 ]
 
 // The following will need to be supported by `next build --turbopack`
 const unsupportedProductionSpecificTurbopackNextConfigOptions: string[] = [
   // TODO: Support disabling sourcemaps, currently they're always enabled.
   // 'productionBrowserSourceMaps',
+  // TODO: Extract and inline the critical CSS of prerendered pages.
+  'experimental.optimizeCss',
 ]
 
 // check for babelrc, swc plugins
@@ -112,6 +112,7 @@ const zTurboRuleConfigItemOptions: zod.ZodType<TurboRuleConfigItemOptions> =
   z.object({
     loaders: z.array(zTurboLoaderItem),
     as: z.string().optional(),
+    fullySpecified: z.boolean().optional(),
   })
 
 const zTurboRuleConfigItem: zod.ZodType<TurboRuleConfigItem> = z.union([
@@ -412,6 +413,35 @@ export const configSchema: zod.ZodType<NextConfig> = z.lazy(() =>
             memoryLimit: z.number().optional(),
             moduleIdStrategy: z.enum(['named', 'deterministic']).optional(),
             minify: z.boolean().optional(),
+            bundleSizeBudgets: z
+              .record(
+                z.string(),
+                z.object({
+                  firstLoadJs: z.number().int().optional(),
+                  firstLoadCss: z.number().int().optional(),
+                })
+              )
+              .optional(),
+            typeCheck: z.boolean().optional(),
+            lint: z.boolean().optional(),
+            lintFailOn: z.enum(['error', 'warning']).optional(),
+            nodePool: z
+              .strictObject({
+                nodeBinary: z.string().optional(),
+                maxOldSpaceSize: z.number().int().positive().optional(),
+                maxWorkers: z.number().int().positive().optional(),
+                maxOperationsPerWorker: z.number().int().positive().optional(),
+                maxWorkerMemory: z.number().int().positive().optional(),
+                idleTimeout: z.number().int().nonnegative().optional(),
+                operationTimeout: z.number().int().positive().optional(),
+              })
+              .optional(),
+            watchOptions: z
+              .strictObject({
+                ignored: z.array(z.string()).optional(),
+                respectGitignore: z.boolean().optional(),
+              })
+              .optional(),
           })
           .optional(),
         optimizePackageImports: z.array(z.string()).optional(),
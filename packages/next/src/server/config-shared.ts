@@ -106,6 +106,11 @@ export type TurboRuleConfigItemOrShortcut =
 export type TurboRuleConfigItemOptions = {
   loaders: TurboLoaderItem[]
   as?: string
+  /**
+   * Require ESM imports in the matching files to include the file extension,
+   * like `resolve.fullySpecified` of a webpack module rule.
+   */
+  fullySpecified?: boolean
 }
 
 export type TurboRuleConfigItem =
@@ -184,7 +189,70 @@ export interface ExperimentalTurboOptions {
    * Enable minification. Defaults to true in build mode and false in dev mode.
    */
   minify?: boolean
-}
+
+  /**
+   * The maximum gzipped first load JS and CSS of routes in bytes. Keys are
+   * route pathnames, `'*'` applies to all other routes. The build fails when
+   * a route exceeds its budget.
+   */
+  bundleSizeBudgets?: Record<
+    string,
+    {
+      firstLoadJs?: number
+      firstLoadCss?: number
+    }
+  >
+
+  /**
+   * Type check the TypeScript files of the module graph with the `typescript`
+   * package of the project and report the errors like compilation errors.
+   * Builds fail on type errors unless `typescript.ignoreBuildErrors` is set.
+   */
+  typeCheck?: boolean
+
+  /**
+   * Lint the modules of the module graph with the `eslint` package of the
+   * project and report the problems like compilation errors. Only the files in
+   * `eslint.dirs` are linted when it's set.
+   */
+  lint?: boolean
+
+  /**
+   * The lowest ESLint severity that fails the build. Defaults to `error`.
+   * Warnings are still reported as warnings in development.
+   */
+  lintFailOn?: 'error' | 'warning'
+
+  /**
+   * Limits for the Node.js workers that run webpack loaders, PostCSS, the type
+   * checker and the linter.
+   */
+  nodePool?: {
+    /** The Node.js binary to run the workers with. Defaults to `node`. */
+    nodeBinary?: string
+    /** Passed to the workers as `--max-old-space-size`, in megabytes. */
+    maxOldSpaceSize?: number
+    /** The maximum number of workers per pool. Defaults to the number of CPUs. */
+    maxWorkers?: number
+    /** Workers are replaced after this many operations. */
+    maxOperationsPerWorker?: number
+    /** Workers are replaced once their memory usage exceeds this, in megabytes. */
+    maxWorkerMemory?: number
+    /** Idle workers are stopped after this many milliseconds. */
+    idleTimeout?: number
+    /** Operations taking longer than this many milliseconds fail. */
+    operationTimeout?: number
+  }
+
+  /**
+   * Changes to ignored files don't trigger recompilation.
+   */
+  watchOptions?: {
+    /** Glob patterns relative to the project directory, e.g. `**\/*.log`. */
+    ignored?: string[]
+    /** Also ignores the files excluded by `.gitignore` files. */
+    respectGitignore?: boolean
+  }
 
 export interface WebpackConfigContext {
   /** Next.js root directory */
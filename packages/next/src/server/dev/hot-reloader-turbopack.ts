@@ -71,6 +71,7 @@ import {
   type SetupOpts,
 } from '../lib/router-utils/setup-dev-bundler'
 import { TurbopackManifestLoader } from './turbopack/manifest-loader'
+import { getTurbopackDebugMiddleware } from './turbopack/debug-middleware'
 import type { Entrypoints } from './turbopack/types'
 import { findPagePathData } from './on-demand-entry-handler'
 import type { RouteDefinition } from '../route-definitions/route-definition'
@@ -573,6 +574,7 @@ export async function createHotReloaderTurbopack(
       assetPrefix: nextConfig.assetPrefix.replace(/\/$/, ''),
       distDir,
     }),
+    getTurbopackDebugMiddleware(project),
   ]
 
   const versionInfoPromise = getVersionInfo(
@@ -1009,6 +1011,23 @@ export async function createHotReloaderTurbopack(
     process.exit(1)
   })
 
+  // Type checks and lints the pages that have been compiled.
+  async function handleChecksSubscription() {
+    const key = getEntryKey('root', 'server', 'checks')
+    for await (const result of project.checksSubscribe()) {
+      processIssues(currentEntryIssues, key, result, false, true)
+    }
+  }
+
+  if (
+    opts.nextConfig.experimental.turbo?.typeCheck ||
+    opts.nextConfig.experimental.turbo?.lint
+  ) {
+    handleChecksSubscription().catch((err) => {
+      console.error(err)
+    })
+  }
+
   // Write empty manifests
   await currentEntriesHandling
   await manifestLoader.writeManifests({
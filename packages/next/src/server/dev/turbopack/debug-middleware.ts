@@ -0,0 +1,74 @@
+import type { IncomingMessage, ServerResponse } from 'http'
+import type { Project } from '../../../build/swc/types'
+import {
+  badRequest,
+  json,
+  jsonString,
+} from '../../../client/components/react-dev-overlay/server/shared'
+
+function parseTaskId(value: string | null): number | undefined {
+  if (value === null) return undefined
+  const task = Number(value)
+  return Number.isInteger(task) && task > 0 ? task : NaN
+}
+
+/**
+ * Exposes the Turbopack task graph for debugging why changes recompute
+ * tasks:
+ *
+ * - `/__nextjs_turbopack/task-graph?task=<id>&depth=<n>&format=json|dot`
+ *   returns a snapshot of the task graph, optionally only around a task.
+ * - `/__nextjs_turbopack/invalidation-causes?enabled=true|false` enables or
+ *   disables recording why tasks are invalidated.
+ * - `/__nextjs_turbopack/explain-invalidation?task=<id>` returns the chain of
+ *   invalidations that recomputed the task the last time.
+ */
+export function getTurbopackDebugMiddleware(project: Project) {
+  return async function (
+    req: IncomingMessage,
+    res: ServerResponse,
+    next: () => void
+  ): Promise<void> {
+    const { pathname, searchParams } = new URL(req.url!, 'http://n')
+
+    if (pathname === '/__nextjs_turbopack/task-graph') {
+      const task = parseTaskId(searchParams.get('task'))
+      const depth = searchParams.has('depth')
+        ? Number(searchParams.get('depth'))
+        : undefined
+      const format = searchParams.get('format') ?? 'json'
+      if (
+        Number.isNaN(task) ||
+        (depth !== undefined && !(Number.isInteger(depth) && depth >= 0)) ||
+        (format !== 'json' && format !== 'dot')
+      ) {
+        return badRequest(res)
+      }
+
+      const graph = project.taskGraph({ task, depth, format })
+      if (graph === null) {
+        res.statusCode = 404
+        res.end('The task graph is not available for this backend')
+        return
+      }
+      if (format === 'dot') {
+        res.setHeader('Content-Type', 'text/vnd.graphviz').end(graph)
+        return
+      }
+      return jsonString(res, graph)
+    } else if (pathname === '/__nextjs_turbopack/invalidation-causes') {
+      const enabled = searchParams.get('enabled')
+      if (enabled !== 'true' && enabled !== 'false') return badRequest(res)
+
+      project.setTrackInvalidationCauses(enabled === 'true')
+      return json(res, { enabled: enabled === 'true' })
+    } else if (pathname === '/__nextjs_turbopack/explain-invalidation') {
+      const task = parseTaskId(searchParams.get('task'))
+      if (task === undefined || Number.isNaN(task)) return badRequest(res)
+
+      return json(res, project.explainInvalidation(task))
+    }
+
+    return next()
+  }
+}
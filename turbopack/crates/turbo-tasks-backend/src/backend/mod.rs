@@ -30,6 +30,10 @@ use turbo_tasks::{
     },
     event::{Event, EventListener},
     registry,
+    task_graph::{
+        TaskGraph, TaskGraphCells, TaskGraphCollectibles, TaskGraphEdge, TaskGraphEdgeKind,
+        TaskGraphNode,
+    },
     util::IdFactoryWithReuse,
     CellId, FunctionId, RawVc, ReadConsistency, SessionId, TaskId, TraitTypeId,
     TurboTasksBackendApi, ValueTypeId, TRANSIENT_TASK_BIT,
@@ -839,6 +843,103 @@ impl<B: BackingStorage> TurboTasksBackendInner<B> {
         task.invalidate_serialization();
     }
 
+    /// Returns the tasks that are in memory. Tasks that are only stored in the
+    /// backing storage are not restored for this.
+    fn task_graph(&self) -> TaskGraph {
+        let mut tasks = Vec::new();
+        for (task_id, task) in self.storage.iter() {
+            let state = match get!(task, InProgress) {
+                Some(InProgressState::Scheduled { .. }) => "scheduled",
+                Some(InProgressState::InProgress { stale: true, .. }) => "in progress (stale)",
+                Some(InProgressState::InProgress { .. }) => "in progress",
+                None if get!(task, Dirty).is_some_and(|dirty| dirty.get(self.session_id)) => {
+                    "dirty"
+                }
+                None if task.has_key(&CachedDataItemKey::Output {}) => "done",
+                None => "not executed",
+            };
+            let mut cells = AutoMap::<ValueTypeId, usize>::new();
+            let mut collectibles = AutoMap::<TraitTypeId, i32>::new();
+            let mut dependencies = Vec::new();
+            for (key, value) in task.iter_all() {
+                match (key, value) {
+                    (CachedDataItemKey::CellData { cell }, _) => {
+                        *cells.entry(cell.type_id).or_default() += 1;
+                    }
+                    (
+                        CachedDataItemKey::Collectible { collectible },
+                        CachedDataItemValue::Collectible { value },
+                    ) => {
+                        *collectibles
+                            .entry(collectible.collectible_type)
+                            .or_default() += *value;
+                    }
+                    (CachedDataItemKey::OutputDependency { target }, _) => {
+                        dependencies.push(TaskGraphEdge {
+                            task: *target,
+                            kind: TaskGraphEdgeKind::Output,
+                        });
+                    }
+                    (CachedDataItemKey::CellDependency { target }, _) => {
+                        dependencies.push(TaskGraphEdge {
+                            task: target.task,
+                            kind: TaskGraphEdgeKind::Cell {
+                                value_type: registry::get_value_type(target.cell.type_id)
+                                    .name
+                                    .clone(),
+                                index: target.cell.index,
+                            },
+                        });
+                    }
+                    (CachedDataItemKey::CollectiblesDependency { target }, _) => {
+                        dependencies.push(TaskGraphEdge {
+                            task: target.task,
+                            kind: TaskGraphEdgeKind::Collectibles {
+                                trait_type: registry::get_trait(target.collectible_type)
+                                    .name
+                                    .clone(),
+                            },
+                        });
+                    }
+                    (CachedDataItemKey::Child { task }, _) => {
+                        dependencies.push(TaskGraphEdge {
+                            task: *task,
+                            kind: TaskGraphEdgeKind::Child,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            tasks.push(TaskGraphNode {
+                id: task_id,
+                // Filled in below, as looking up the description must not happen while the
+                // storage is locked.
+                description: String::new(),
+                state: state.to_string(),
+                cells: cells
+                    .into_iter()
+                    .map(|(value_type, count)| TaskGraphCells {
+                        value_type: registry::get_value_type(value_type).name.clone(),
+                        count,
+                    })
+                    .collect(),
+                dependencies,
+                collectibles: collectibles
+                    .into_iter()
+                    .map(|(trait_type, count)| TaskGraphCollectibles {
+                        trait_type: registry::get_trait(trait_type).name.clone(),
+                        count,
+                    })
+                    .collect(),
+            });
+        }
+        for node in &mut tasks {
+            node.description = self.get_task_description(node.id);
+        }
+        tasks.sort_by_key(|node| node.id);
+        TaskGraph { tasks }
+    }
+
     fn get_task_description(&self, task_id: TaskId) -> std::string::String {
         self.lookup_task_type(task_id).map_or_else(
             || format!("{task_id:?} transient"),
@@ -1679,6 +1780,10 @@ impl<B: BackingStorage> Backend for TurboTasksBackend<B> {
         self.0.get_task_description(task)
     }
 
+    fn task_graph(&self) -> Option<TaskGraph> {
+        Some(self.0.task_graph())
+    }
+
     fn try_get_function_id(&self, task_id: TaskId) -> Option<FunctionId> {
         self.0.try_get_function_id(task_id)
     }
@@ -341,6 +341,12 @@ where
         }
     }
 
+    /// Iterates over all tasks in memory. A shard is read locked while its tasks
+    /// are iterated, so tasks must not be accessed meanwhile.
+    pub fn iter(&self) -> impl Iterator<Item = (K, impl Deref<Target = InnerStorage<T>> + '_)> {
+        self.map.iter().map(|entry| (entry.key().clone(), entry))
+    }
+
     pub fn access_pair_mut(
         &self,
         key1: K,
@@ -0,0 +1,179 @@
+//! Inspects and maintains the persistent cache directory, e.g. `.next/cache/turbopack`.
+//!
+//! The commands that open a database must not be run while a build is using it.
+
+use std::{path::PathBuf, process::exit, time::SystemTime};
+
+use anyhow::{bail, Result};
+use clap::{Args, Parser};
+use turbo_tasks::util::{FormatBytes, FormatDuration};
+use turbo_tasks_backend::maintenance::{
+    compact, key_space_statistics, largest_task_types, list_versions, prune_versions, verify,
+};
+
+#[derive(Debug, Parser)]
+#[clap(author, version, about, long_about = None)]
+enum Arguments {
+    /// List the database versions in the cache directory with their sizes.
+    Versions(CacheDirArguments),
+    /// Show the number of entries and their sizes per key space.
+    Stats(DatabaseArguments),
+    /// Show the task types that take the most space.
+    TaskTypes(TaskTypesArguments),
+    /// Check the database for inconsistencies and entries that can't be decoded.
+    Verify(DatabaseArguments),
+    /// Rewrite the database without its free pages or outdated entries. Fails when the database
+    /// is in use.
+    Compact(DatabaseArguments),
+    /// Remove the databases of other versions.
+    Prune(PruneArguments),
+}
+
+#[derive(Debug, Args)]
+struct CacheDirArguments {
+    /// The persistent cache directory, e.g. `.next/cache/turbopack`.
+    #[clap(value_parser)]
+    cache_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+struct DatabaseArguments {
+    #[clap(flatten)]
+    cache_dir: CacheDirArguments,
+
+    /// The database version to use. Defaults to the most recently used one.
+    #[clap(long)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct TaskTypesArguments {
+    #[clap(flatten)]
+    database: DatabaseArguments,
+
+    /// The number of task types to show.
+    #[clap(long, default_value_t = 20)]
+    limit: usize,
+}
+
+#[derive(Debug, Args)]
+struct PruneArguments {
+    #[clap(flatten)]
+    cache_dir: CacheDirArguments,
+
+    /// The version that is in use, which is never removed. Defaults to the most recently used one.
+    #[clap(long)]
+    current: Option<String>,
+
+    /// The number of other versions to keep besides the current one.
+    #[clap(long, default_value_t = 0)]
+    keep: usize,
+}
+
+impl DatabaseArguments {
+    fn db_path(&self) -> Result<PathBuf> {
+        let cache_dir = &self.cache_dir.cache_dir;
+        if let Some(version) = &self.version {
+            return Ok(cache_dir.join(version));
+        }
+        let Some(version) = list_versions(cache_dir, None)?.into_iter().next() else {
+            bail!("{} doesn't contain any database", cache_dir.display());
+        };
+        Ok(version.path)
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Arguments::parse()) {
+        eprintln!("{:?}", err);
+        exit(1);
+    }
+}
+
+fn run(args: Arguments) -> Result<()> {
+    match args {
+        Arguments::Versions(args) => print_versions(&args.cache_dir)?,
+        Arguments::Stats(args) => {
+            let db_path = args.db_path()?;
+            println!("{}", db_path.display());
+            let statistics = key_space_statistics(&db_path)?;
+            for key_space in statistics {
+                println!(
+                    "  {:<20} {:>10} entries {:>12} keys {:>12} values",
+                    key_space.key_space.name(),
+                    key_space.entries,
+                    FormatBytes(key_space.key_bytes as usize).to_string(),
+                    FormatBytes(key_space.value_bytes as usize).to_string(),
+                );
+            }
+        }
+        Arguments::TaskTypes(args) => {
+            let db_path = args.database.db_path()?;
+            println!("{}", db_path.display());
+            for task_type in largest_task_types(&db_path, args.limit)? {
+                println!(
+                    "  {:>12} {:>10} tasks  {}",
+                    FormatBytes(task_type.bytes as usize).to_string(),
+                    task_type.tasks,
+                    task_type.name
+                );
+            }
+        }
+        Arguments::Verify(args) => {
+            let db_path = args.db_path()?;
+            let issues = verify(&db_path)?;
+            for issue in &issues {
+                println!("{issue}");
+            }
+            if !issues.is_empty() {
+                bail!(
+                    "{} integrity issues found in {}",
+                    issues.len(),
+                    db_path.display()
+                );
+            }
+            println!("no integrity issues found in {}", db_path.display());
+        }
+        Arguments::Compact(args) => {
+            let db_path = args.db_path()?;
+            let result = compact(&db_path)?;
+            println!(
+                "compacted {} from {} to {}",
+                db_path.display(),
+                FormatBytes(result.size_before as usize),
+                FormatBytes(result.size_after as usize)
+            );
+        }
+        Arguments::Prune(args) => {
+            for path in prune_versions(
+                &args.cache_dir.cache_dir,
+                args.current.as_deref(),
+                args.keep,
+            )? {
+                println!("removed {}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_versions(args: &CacheDirArguments) -> Result<()> {
+    let now = SystemTime::now();
+    for version in list_versions(&args.cache_dir, None)? {
+        let last_used = version
+            .last_used
+            .and_then(|last_used| now.duration_since(last_used).ok())
+            .map_or_else(
+                || "unknown".to_string(),
+                |age| format!("{} ago", FormatDuration(age)),
+            );
+        println!(
+            "{} {:<40} {:>12}  last used {}",
+            if version.current { "*" } else { " " },
+            version.name,
+            FormatBytes(version.size as usize).to_string(),
+            last_used
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,618 @@
+use std::{
+    borrow::Cow,
+    fs::{self, create_dir_all, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use parking_lot::{Mutex, RwLock};
+use rustc_hash::FxHashMap;
+use turbo_tasks_hash::{DeterministicHasher, Xxh3Hash64Hasher};
+
+use crate::database::{
+    by_key_space::ByKeySpace,
+    file_lock::{lock_file, LockMode},
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+};
+
+pub const LOG_FILE_NAME: &str = "data.log";
+/// Locked while the database is open. The log itself can't be locked, because compaction replaces
+/// it.
+pub const LOCK_FILE_NAME: &str = "data.log.lock";
+
+/// The log is rewritten on startup when it's larger than this and most of it is overwritten or
+/// deleted entries.
+const MIN_COMPACTION_SIZE: u64 = 64 * 1024 * 1024;
+/// Maximum payload size of the frames written by compaction.
+const COMPACTION_FRAME_SIZE: usize = 16 * 1024 * 1024;
+/// Payload length (u32) and checksum (u64).
+const FRAME_HEADER_SIZE: u64 = 12;
+/// Key space (u8), key length (u32) and value length (u32).
+const ENTRY_HEADER_SIZE: usize = 9;
+/// Value length marking a deleted entry.
+const DELETED: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+struct ValueLocation {
+    offset: u64,
+    len: u32,
+}
+
+type Index = ByKeySpace<RwLock<FxHashMap<Box<[u8]>, ValueLocation>>>;
+
+/// A [KeyValueDatabase] that appends every committed write batch to a single log file and keeps
+/// an index of the values in memory. Values are read from the file on demand.
+///
+/// In contrast to LMDB there is no size limit for the database and write batches can be prepared
+/// concurrently. Only committing them is serialized. The log is compacted on startup when most of
+/// it is outdated. A partially written batch at the end of the log, e.g. after a crash, is
+/// discarded when opening the database.
+///
+/// Read transactions don't isolate from concurrent commits, they see the latest committed state.
+/// The database can only be opened by one process at a time.
+pub struct AppendLogKeyValueDatabase {
+    /// Holds the exclusive lock until the database is dropped.
+    _lock: File,
+    file: File,
+    /// The length of the log. Locked while committing.
+    len: Mutex<u64>,
+    index: Index,
+}
+
+impl AppendLogKeyValueDatabase {
+    pub fn new(path: &Path) -> Result<Self> {
+        Self::open(path, MIN_COMPACTION_SIZE)
+    }
+
+    /// Opens the database and rewrites the log with only the current values, regardless of how
+    /// much of it is outdated.
+    pub fn new_compacted(path: &Path) -> Result<Self> {
+        Self::open_with(path, |_, _| true)
+    }
+
+    fn open(path: &Path, min_compaction_size: u64) -> Result<Self> {
+        Self::open_with(path, |len, live_size| {
+            len > min_compaction_size && live_size < len / 2
+        })
+    }
+
+    /// `should_compact` is called with the length of the log and the size of its current
+    /// entries.
+    fn open_with(path: &Path, should_compact: impl FnOnce(u64, u64) -> bool) -> Result<Self> {
+        create_dir_all(path).context("Creating database directory failed")?;
+        let lock =
+            lock_file(&path.join(LOCK_FILE_NAME), LockMode::Exclusive).with_context(|| {
+                format!(
+                    "Locking the database in {} failed, it might be used by another process",
+                    path.display()
+                )
+            })?;
+        let path = path.join(LOG_FILE_NAME);
+        let file = open_log(&path)?;
+        let index = ByKeySpace::new(|_| RwLock::new(FxHashMap::default()));
+        let (len, live_size) = read_log(&file, &index)?;
+        if file.metadata()?.len() != len {
+            // A batch has not been written completely
+            file.set_len(len)
+                .context("Truncating the incomplete end of the log failed")?;
+        }
+        let (file, index, len) = if should_compact(len, live_size) {
+            compact(&path, file, &index)?
+        } else {
+            (file, index, len)
+        };
+        Ok(Self {
+            _lock: lock,
+            file,
+            len: Mutex::new(len),
+            index,
+        })
+    }
+
+    fn get_value(&self, key_space: KeySpace, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let location = self.index.get(key_space).read().get(key).copied();
+        location
+            .map(|location| read_value(&self.file, location))
+            .transpose()
+    }
+
+    /// Calls `f` with every entry of a key space, ordered by key.
+    pub fn for_each_raw_entry(
+        &self,
+        key_space: KeySpace,
+        mut f: impl FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let mut entries = self
+            .index
+            .get(key_space)
+            .read()
+            .iter()
+            .map(|(key, &location)| (key.clone(), location))
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        for (key, location) in entries {
+            f(&key, &read_value(&self.file, location)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_value(file: &File, location: ValueLocation) -> Result<Vec<u8>> {
+    let mut buffer = vec![0; location.len as usize];
+    read_exact_at(file, &mut buffer, location.offset)
+        .context("Reading a value from the log failed")?;
+    Ok(buffer)
+}
+
+/// Rewrites the log with only the current values. Returns the new log, its index and length.
+fn compact(path: &Path, file: File, index: &Index) -> Result<(File, Index, u64)> {
+    let temp_path = path.with_extension("log.tmp");
+    let mut writer = BufWriter::new(File::create(&temp_path)?);
+    let new_index: Index = ByKeySpace::new(|_| RwLock::new(FxHashMap::default()));
+    let mut len = 0;
+    let mut frame = Vec::new();
+    let mut frame_locations = Vec::new();
+    for (key_space, index) in index.iter() {
+        for (key, &location) in index.read().iter() {
+            let value = read_value(&file, location)?;
+            let value_offset = write_entry(&mut frame, key_space, key, Some(&value));
+            frame_locations.push((key_space, key.clone(), value_offset, value.len()));
+            if frame.len() >= COMPACTION_FRAME_SIZE {
+                len += write_frame(&mut writer, &frame)?;
+                add_locations(&new_index, len, &frame, frame_locations.drain(..));
+                frame.clear();
+            }
+        }
+    }
+    if !frame.is_empty() {
+        len += write_frame(&mut writer, &frame)?;
+        add_locations(&new_index, len, &frame, frame_locations.drain(..));
+    }
+    writer.into_inner()?.sync_all()?;
+    drop(file);
+    fs::rename(&temp_path, path).context("Replacing the log failed")?;
+    Ok((open_log(path)?, new_index, len))
+}
+
+fn open_log(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+        .with_context(|| format!("Opening {} failed", path.display()))
+}
+
+/// Adds the locations of the entries of a frame that ends at `end` to the index.
+fn add_locations(
+    index: &Index,
+    end: u64,
+    frame: &[u8],
+    locations: impl Iterator<Item = (KeySpace, Box<[u8]>, usize, usize)>,
+) {
+    let frame_start = end - frame.len() as u64;
+    for (key_space, key, value_offset, value_len) in locations {
+        index.get(key_space).write().insert(
+            key,
+            ValueLocation {
+                offset: frame_start + value_offset as u64,
+                len: value_len as u32,
+            },
+        );
+    }
+}
+
+/// Reads all complete frames of the log into `index`. Returns the length of the complete frames
+/// and the size of the entries that are still current.
+fn read_log(file: &File, index: &Index) -> Result<(u64, u64)> {
+    let file_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut len = 0;
+    let mut header = [0; FRAME_HEADER_SIZE as usize];
+    let mut payload = Vec::new();
+    loop {
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let payload_len = u32::from_be_bytes(header[0..4].try_into()?) as usize;
+        let checksum = u64::from_be_bytes(header[4..12].try_into()?);
+        let payload_start = len + FRAME_HEADER_SIZE;
+        if payload_start + payload_len as u64 > file_len {
+            // The frame is incomplete or its header is corrupted
+            break;
+        }
+        payload.resize(payload_len, 0);
+        match reader.read_exact(&mut payload) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        if frame_checksum(&payload) != checksum {
+            break;
+        }
+        let mut pos = 0;
+        while pos < payload.len() {
+            let (key_space, key, value) = read_entry(&payload, &mut pos)?;
+            let index = &mut *index.get(key_space).write();
+            match value {
+                Some((value_offset, value_len)) => {
+                    index.insert(
+                        key.into(),
+                        ValueLocation {
+                            offset: payload_start + value_offset as u64,
+                            len: value_len,
+                        },
+                    );
+                }
+                None => {
+                    index.remove(key);
+                }
+            }
+        }
+        len = payload_start + payload_len as u64;
+    }
+    let live_size = index
+        .iter()
+        .map(|(_, index)| {
+            index
+                .read()
+                .iter()
+                .map(|(key, location)| (ENTRY_HEADER_SIZE + key.len()) as u64 + location.len as u64)
+                .sum::<u64>()
+        })
+        .sum();
+    Ok((len, live_size))
+}
+
+fn frame_checksum(payload: &[u8]) -> u64 {
+    let mut hasher = Xxh3Hash64Hasher::new();
+    hasher.write_bytes(payload);
+    hasher.finish()
+}
+
+/// Writes a frame and returns its size.
+fn write_frame(writer: &mut impl Write, payload: &[u8]) -> Result<u64> {
+    let mut header = [0; FRAME_HEADER_SIZE as usize];
+    header[0..4].copy_from_slice(&u32::try_from(payload.len())?.to_be_bytes());
+    header[4..12].copy_from_slice(&frame_checksum(payload).to_be_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    Ok(FRAME_HEADER_SIZE + payload.len() as u64)
+}
+
+fn key_space_tag(key_space: KeySpace) -> u8 {
+    match key_space {
+        KeySpace::Infra => 0,
+        KeySpace::TaskMeta => 1,
+        KeySpace::TaskData => 2,
+        KeySpace::ForwardTaskCache => 3,
+        KeySpace::ReverseTaskCache => 4,
+    }
+}
+
+/// Appends an entry to `buffer` and returns the offset of the value in the buffer.
+fn write_entry(
+    buffer: &mut Vec<u8>,
+    key_space: KeySpace,
+    key: &[u8],
+    value: Option<&[u8]>,
+) -> usize {
+    buffer.push(key_space_tag(key_space));
+    buffer.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(
+        &value
+            .map_or(DELETED, |value| value.len() as u32)
+            .to_be_bytes(),
+    );
+    buffer.extend_from_slice(key);
+    let value_offset = buffer.len();
+    if let Some(value) = value {
+        buffer.extend_from_slice(value);
+    }
+    value_offset
+}
+
+/// Reads an entry and returns the key and the offset and length of the value, or `None` when the
+/// entry has been deleted.
+fn read_entry<'l>(
+    buffer: &'l [u8],
+    pos: &mut usize,
+) -> Result<(KeySpace, &'l [u8], Option<(usize, u32)>)> {
+    let Some(header) = buffer.get(*pos..*pos + ENTRY_HEADER_SIZE) else {
+        bail!("Truncated entry in log");
+    };
+    let key_space = match header[0] {
+        0 => KeySpace::Infra,
+        1 => KeySpace::TaskMeta,
+        2 => KeySpace::TaskData,
+        3 => KeySpace::ForwardTaskCache,
+        4 => KeySpace::ReverseTaskCache,
+        _ => bail!("Invalid key space in log"),
+    };
+    let key_len = u32::from_be_bytes(header[1..5].try_into()?) as usize;
+    let value_len = u32::from_be_bytes(header[5..9].try_into()?);
+    *pos += ENTRY_HEADER_SIZE;
+    let Some(key) = buffer.get(*pos..*pos + key_len) else {
+        bail!("Truncated key in log");
+    };
+    *pos += key_len;
+    if value_len == DELETED {
+        return Ok((key_space, key, None));
+    }
+    let value_offset = *pos;
+    *pos += value_len as usize;
+    if *pos > buffer.len() {
+        bail!("Truncated value in log");
+    }
+    Ok((key_space, key, Some((value_offset, value_len))))
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buffer: &mut [u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buffer: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    while !buffer.is_empty() {
+        match std::os::windows::fs::FileExt::seek_read(file, buffer, offset) {
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                buffer = &mut buffer[n..];
+                offset += n as u64;
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+impl KeyValueDatabase for AppendLogKeyValueDatabase {
+    type ReadTransaction<'l>
+        = ()
+    where
+        Self: 'l;
+
+    fn lower_read_transaction<'l: 'i + 'r, 'i: 'r, 'r>(
+        tx: &'r Self::ReadTransaction<'l>,
+    ) -> &'r Self::ReadTransaction<'i> {
+        tx
+    }
+
+    fn begin_read_transaction(&self) -> Result<Self::ReadTransaction<'_>> {
+        Ok(())
+    }
+
+    type ValueBuffer<'l>
+        = Cow<'l, [u8]>
+    where
+        Self: 'l;
+
+    fn get<'l, 'db: 'l>(
+        &'l self,
+        _transaction: &'l Self::ReadTransaction<'db>,
+        key_space: KeySpace,
+        key: &[u8],
+    ) -> Result<Option<Self::ValueBuffer<'l>>> {
+        Ok(self.get_value(key_space, key)?.map(Cow::Owned))
+    }
+
+    type WriteBatch<'l>
+        = AppendLogWriteBatch<'l>
+    where
+        Self: 'l;
+
+    fn write_batch(&self) -> Result<Self::WriteBatch<'_>> {
+        Ok(AppendLogWriteBatch {
+            this: self,
+            changes: ByKeySpace::new(|_| FxHashMap::default()),
+        })
+    }
+}
+
+pub struct AppendLogWriteBatch<'a> {
+    this: &'a AppendLogKeyValueDatabase,
+    /// The new values, `None` for deleted entries.
+    changes: ByKeySpace<FxHashMap<Vec<u8>, Option<Vec<u8>>>>,
+}
+
+impl<'a> WriteBatch<'a> for AppendLogWriteBatch<'a> {
+    type ValueBuffer<'l>
+        = Cow<'l, [u8]>
+    where
+        Self: 'l,
+        'a: 'l;
+
+    fn get<'l>(&'l self, key_space: KeySpace, key: &[u8]) -> Result<Option<Self::ValueBuffer<'l>>>
+    where
+        'a: 'l,
+    {
+        if let Some(change) = self.changes.get(key_space).get(key) {
+            return Ok(change.as_deref().map(Cow::Borrowed));
+        }
+        Ok(self.this.get_value(key_space, key)?.map(Cow::Owned))
+    }
+
+    fn put(&mut self, key_space: KeySpace, key: Cow<[u8]>, value: Cow<[u8]>) -> Result<()> {
+        self.changes
+            .get_mut(key_space)
+            .insert(key.into_owned(), Some(value.into_owned()));
+        Ok(())
+    }
+
+    fn delete(&mut self, key_space: KeySpace, key: Cow<[u8]>) -> Result<()> {
+        self.changes
+            .get_mut(key_space)
+            .insert(key.into_owned(), None);
+        Ok(())
+    }
+
+    fn commit(self) -> Result<()> {
+        let mut payload = Vec::new();
+        let mut locations = Vec::new();
+        for (key_space, changes) in self.changes.iter() {
+            for (key, value) in changes.iter() {
+                let value_offset = write_entry(&mut payload, key_space, key, value.as_deref());
+                locations.push((
+                    key_space,
+                    key,
+                    value.as_ref().map(|value| (value_offset, value.len())),
+                ));
+            }
+        }
+        if locations.is_empty() {
+            return Ok(());
+        }
+
+        let mut len = self.this.len.lock();
+        let mut file = &self.this.file;
+        let frame_size = match write_frame(&mut file, &payload)
+            .and_then(|size| Ok(file.sync_data().map(|_| size)?))
+        {
+            Ok(size) => size,
+            Err(err) => {
+                // Remove the partially written frame, later frames would be unreadable otherwise
+                let _ = file.set_len(*len);
+                return Err(err.context("Writing to the log failed"));
+            }
+        };
+        let payload_start = *len + FRAME_HEADER_SIZE;
+        for (key_space, key, value) in locations {
+            let mut index = self.this.index.get(key_space).write();
+            match value {
+                Some((value_offset, value_len)) => {
+                    index.insert(
+                        key.as_slice().into(),
+                        ValueLocation {
+                            offset: payload_start + value_offset as u64,
+                            len: value_len as u32,
+                        },
+                    );
+                }
+                None => {
+                    index.remove(key.as_slice());
+                }
+            }
+        }
+        *len += frame_size;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Borrow;
+
+    use super::*;
+
+    fn get(database: &AppendLogKeyValueDatabase, key: &[u8]) -> Option<Vec<u8>> {
+        database
+            .get(&(), KeySpace::TaskData, key)
+            .unwrap()
+            .map(|value| Borrow::<[u8]>::borrow(&value).to_vec())
+    }
+
+    fn put(database: &AppendLogKeyValueDatabase, key: &[u8], value: Option<&[u8]>) {
+        let mut batch = database.write_batch().unwrap();
+        match value {
+            Some(value) => batch
+                .put(KeySpace::TaskData, key.into(), value.into())
+                .unwrap(),
+            None => batch.delete(KeySpace::TaskData, key.into()).unwrap(),
+        }
+        batch.commit().unwrap();
+    }
+
+    #[test]
+    fn discards_incomplete_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+            put(&database, b"a", Some(b"1"));
+            put(&database, b"b", Some(b"2"));
+        }
+        // Simulate a crash while writing the second batch
+        let log_path = dir.path().join(LOG_FILE_NAME);
+        let log_len = fs::metadata(&log_path).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&log_path).unwrap();
+        file.set_len(log_len - 1).unwrap();
+        drop(file);
+
+        let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+        assert_eq!(get(&database, b"a").as_deref(), Some(&b"1"[..]));
+        assert_eq!(get(&database, b"b"), None);
+
+        put(&database, b"c", Some(b"3"));
+        drop(database);
+        let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+        assert_eq!(get(&database, b"a").as_deref(), Some(&b"1"[..]));
+        assert_eq!(get(&database, b"c").as_deref(), Some(&b"3"[..]));
+    }
+
+    #[test]
+    fn discards_frame_with_invalid_length() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+            put(&database, b"a", Some(b"1"));
+        }
+        // A corrupted header that claims a payload larger than the log
+        let log_path = dir.path().join(LOG_FILE_NAME);
+        let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+        file.write_all(&u32::MAX.to_be_bytes()).unwrap();
+        file.write_all(&[0; 8]).unwrap();
+        file.write_all(b"payload").unwrap();
+        drop(file);
+        let log_len = fs::metadata(&log_path).unwrap().len();
+
+        let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+        assert_eq!(get(&database, b"a").as_deref(), Some(&b"1"[..]));
+        assert_eq!(
+            fs::metadata(&log_path).unwrap().len(),
+            log_len - FRAME_HEADER_SIZE - 7
+        );
+    }
+
+    #[test]
+    fn is_locked_while_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+        assert!(AppendLogKeyValueDatabase::new(dir.path()).is_err());
+        drop(database);
+        AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn compacts_outdated_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+            for i in 0..100u32 {
+                put(&database, b"counter", Some(&i.to_be_bytes()));
+            }
+            put(&database, b"kept", Some(b"value"));
+            put(&database, b"deleted", Some(b"value"));
+            put(&database, b"deleted", None);
+        }
+        let log_path = dir.path().join(LOG_FILE_NAME);
+        let size_before = fs::metadata(&log_path).unwrap().len();
+
+        let database = AppendLogKeyValueDatabase::open(dir.path(), 0).unwrap();
+        assert!(fs::metadata(&log_path).unwrap().len() < size_before / 10);
+        assert_eq!(
+            get(&database, b"counter").as_deref(),
+            Some(&99u32.to_be_bytes()[..])
+        );
+        assert_eq!(get(&database, b"kept").as_deref(), Some(&b"value"[..]));
+        assert_eq!(get(&database, b"deleted"), None);
+
+        put(&database, b"kept", Some(b"new value"));
+        drop(database);
+        let database = AppendLogKeyValueDatabase::new(dir.path()).unwrap();
+        assert_eq!(get(&database, b"kept").as_deref(), Some(&b"new value"[..]));
+    }
+}
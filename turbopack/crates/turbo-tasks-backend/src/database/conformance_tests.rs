@@ -0,0 +1,177 @@
+//! Tests that every [KeyValueDatabase] implementation has to pass.
+
+use std::{borrow::Borrow, path::Path};
+
+use anyhow::Result;
+
+use crate::database::{
+    append_log::AppendLogKeyValueDatabase,
+    dynamic::{DynamicKeyValueDatabase, KeyValueDatabaseKind},
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    lmdb::LmbdKeyValueDatabase,
+};
+
+fn get<T: KeyValueDatabase>(database: &T, key_space: KeySpace, key: &[u8]) -> Option<Vec<u8>> {
+    let tx = database.begin_read_transaction().unwrap();
+    let value = database
+        .get(&tx, key_space, key)
+        .unwrap()
+        .map(|value| value.borrow().to_vec());
+    value
+}
+
+fn get_in_batch<'a, B: WriteBatch<'a>>(
+    batch: &B,
+    key_space: KeySpace,
+    key: &[u8],
+) -> Option<Vec<u8>> {
+    batch
+        .get(key_space, key)
+        .unwrap()
+        .map(|value| value.borrow().to_vec())
+}
+
+fn commit<T: KeyValueDatabase>(
+    database: &T,
+    changes: &[(KeySpace, &[u8], Option<&[u8]>)],
+) -> Result<()> {
+    let mut batch = database.write_batch()?;
+    for &(key_space, key, value) in changes {
+        match value {
+            Some(value) => batch.put(key_space, key.into(), value.into())?,
+            None => batch.delete(key_space, key.into())?,
+        }
+    }
+    batch.commit()
+}
+
+fn check_conformance<T: KeyValueDatabase>(open: impl Fn(&Path) -> Result<T>) {
+    let dir = tempfile::tempdir().unwrap();
+    let long_key = vec![7; 1000];
+    let mut long_key_2 = long_key.clone();
+    *long_key_2.last_mut().unwrap() = 8;
+    {
+        let database = open(dir.path()).unwrap();
+        for key_space in KeySpace::ALL {
+            assert_eq!(get(&database, key_space, b"key"), None);
+        }
+
+        // Writes are visible in the batch, but not outside until committed
+        let mut batch = database.write_batch().unwrap();
+        for key_space in KeySpace::ALL {
+            batch
+                .put(key_space, b"key".into(), key_space.name().as_bytes().into())
+                .unwrap();
+        }
+        assert_eq!(
+            get_in_batch(&batch, KeySpace::TaskData, b"key").as_deref(),
+            Some(&b"data"[..])
+        );
+        assert_eq!(get(&database, KeySpace::TaskData, b"key"), None);
+        batch.commit().unwrap();
+        for key_space in KeySpace::ALL {
+            assert_eq!(
+                get(&database, key_space, b"key").as_deref(),
+                Some(key_space.name().as_bytes()),
+                "key space {}",
+                key_space.name()
+            );
+        }
+
+        // Dropped batches are discarded
+        let mut batch = database.write_batch().unwrap();
+        batch
+            .put(KeySpace::TaskMeta, b"key".into(), b"dropped"[..].into())
+            .unwrap();
+        batch.delete(KeySpace::TaskData, b"key".into()).unwrap();
+        drop(batch);
+        assert_eq!(
+            get(&database, KeySpace::TaskMeta, b"key").as_deref(),
+            Some(&b"meta"[..])
+        );
+        assert_eq!(
+            get(&database, KeySpace::TaskData, b"key").as_deref(),
+            Some(&b"data"[..])
+        );
+
+        // Overwrites, deletes, empty values and keys longer than LMDB's key size limit
+        commit(
+            &database,
+            &[
+                (KeySpace::TaskMeta, &b"key"[..], Some(&b"overwritten"[..])),
+                (KeySpace::TaskData, &b"key"[..], None),
+                (KeySpace::TaskData, &b"missing"[..], None),
+                (KeySpace::Infra, &b"empty"[..], Some(&b""[..])),
+                (KeySpace::ForwardTaskCache, &long_key, Some(&b"long 1"[..])),
+                (
+                    KeySpace::ForwardTaskCache,
+                    &long_key_2,
+                    Some(&b"long 2"[..]),
+                ),
+            ],
+        )
+        .unwrap();
+        let batch = database.write_batch().unwrap();
+        assert_eq!(get_in_batch(&batch, KeySpace::TaskData, b"key"), None);
+        assert_eq!(
+            get_in_batch(&batch, KeySpace::TaskMeta, b"key").as_deref(),
+            Some(&b"overwritten"[..])
+        );
+        drop(batch);
+        assert_eq!(get(&database, KeySpace::TaskData, b"key"), None);
+        assert_eq!(get(&database, KeySpace::TaskData, b"missing"), None);
+        assert_eq!(
+            get(&database, KeySpace::Infra, b"empty").as_deref(),
+            Some(&b""[..])
+        );
+        assert_eq!(
+            get(&database, KeySpace::ForwardTaskCache, &long_key).as_deref(),
+            Some(&b"long 1"[..])
+        );
+
+        commit(
+            &database,
+            &[(KeySpace::ForwardTaskCache, &long_key[..], None)],
+        )
+        .unwrap();
+        assert_eq!(get(&database, KeySpace::ForwardTaskCache, &long_key), None);
+        assert_eq!(
+            get(&database, KeySpace::ForwardTaskCache, &long_key_2).as_deref(),
+            Some(&b"long 2"[..])
+        );
+    }
+
+    // Committed data is persisted
+    let database = open(dir.path()).unwrap();
+    assert_eq!(
+        get(&database, KeySpace::TaskMeta, b"key").as_deref(),
+        Some(&b"overwritten"[..])
+    );
+    assert_eq!(get(&database, KeySpace::TaskData, b"key"), None);
+    assert_eq!(
+        get(&database, KeySpace::ReverseTaskCache, b"key").as_deref(),
+        Some(&b"reverse_task_cache"[..])
+    );
+    assert_eq!(get(&database, KeySpace::ForwardTaskCache, &long_key), None);
+    assert_eq!(
+        get(&database, KeySpace::ForwardTaskCache, &long_key_2).as_deref(),
+        Some(&b"long 2"[..])
+    );
+}
+
+#[test]
+fn lmdb() {
+    check_conformance(LmbdKeyValueDatabase::new);
+}
+
+#[test]
+fn append_log() {
+    check_conformance(AppendLogKeyValueDatabase::new);
+}
+
+#[test]
+fn dynamic() {
+    for kind in [KeyValueDatabaseKind::Lmdb, KeyValueDatabaseKind::AppendLog] {
+        check_conformance(|path| DynamicKeyValueDatabase::new(kind, path));
+    }
+}
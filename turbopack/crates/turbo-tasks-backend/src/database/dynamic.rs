@@ -0,0 +1,276 @@
+use std::{
+    borrow::{Borrow, Cow},
+    env,
+    fs::remove_file,
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use lmdb::RoTransaction;
+
+use crate::database::{
+    append_log::{self, AppendLogKeyValueDatabase, AppendLogWriteBatch, LOG_FILE_NAME},
+    file_lock::is_locked,
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+    lmdb::{self, LmbdKeyValueDatabase, LmbdWriteBatch},
+    startup_cache::STARTUP_CACHE_FILE_NAME,
+};
+
+const KINDS: [KeyValueDatabaseKind; 2] =
+    [KeyValueDatabaseKind::Lmdb, KeyValueDatabaseKind::AppendLog];
+
+/// The embedded database used for persistent caching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyValueDatabaseKind {
+    #[default]
+    Lmdb,
+    AppendLog,
+}
+
+impl KeyValueDatabaseKind {
+    /// Reads the kind from the `TURBO_ENGINE_DATABASE` environment variable, which can be `lmdb`
+    /// or `append-log`.
+    pub fn from_env() -> Result<Self> {
+        match env::var("TURBO_ENGINE_DATABASE").ok().as_deref() {
+            None | Some("") | Some("lmdb") => Ok(KeyValueDatabaseKind::Lmdb),
+            Some("append-log") => Ok(KeyValueDatabaseKind::AppendLog),
+            Some(other) => bail!(
+                "Unknown TURBO_ENGINE_DATABASE {other:?}, expected \"lmdb\" or \"append-log\""
+            ),
+        }
+    }
+
+    /// Detects the kind of the existing database in `path`.
+    pub fn detect(path: &Path) -> Result<Self> {
+        let kinds = KINDS
+            .into_iter()
+            .filter(|kind| kind.exists_in(path))
+            .collect::<Vec<_>>();
+        match &kinds[..] {
+            [kind] => Ok(*kind),
+            [] => bail!("{} doesn't contain a database", path.display()),
+            _ => bail!("{} contains databases of multiple kinds", path.display()),
+        }
+    }
+
+    /// Whether there is a database of this kind in `path`. Lock files alone don't count.
+    fn exists_in(&self, path: &Path) -> bool {
+        let data_file = match self {
+            KeyValueDatabaseKind::Lmdb => "data.mdb",
+            KeyValueDatabaseKind::AppendLog => LOG_FILE_NAME,
+        };
+        path.join(data_file).is_file()
+    }
+
+    fn files(&self) -> &'static [&'static str] {
+        match self {
+            KeyValueDatabaseKind::Lmdb => &["data.mdb", "lock.mdb", lmdb::LOCK_FILE_NAME],
+            KeyValueDatabaseKind::AppendLog => &[LOG_FILE_NAME, append_log::LOCK_FILE_NAME],
+        }
+    }
+
+    fn lock_file(&self) -> &'static str {
+        match self {
+            KeyValueDatabaseKind::Lmdb => lmdb::LOCK_FILE_NAME,
+            KeyValueDatabaseKind::AppendLog => append_log::LOCK_FILE_NAME,
+        }
+    }
+
+    /// Returns `true` when a process has a database in `path` open.
+    pub fn is_in_use(path: &Path) -> bool {
+        KINDS
+            .into_iter()
+            .any(|kind| is_locked(&path.join(kind.lock_file())))
+    }
+
+    /// Removes the databases of the other kinds from `path`. They would be outdated when switching
+    /// back to them later.
+    ///
+    /// Returns `true` when there was a database of another kind. In that case the database of
+    /// this kind and the startup cache are removed as well, since they are older than the removed
+    /// database, and the database needs to be treated as fresh.
+    pub fn remove_other_databases(&self, path: &Path) -> bool {
+        let others = KINDS.into_iter().filter(|kind| kind != self);
+        if !others.clone().any(|kind| kind.exists_in(path)) {
+            return false;
+        }
+        for file in others
+            .flat_map(|kind| kind.files())
+            .map(|file| path.join(file))
+            .chain(self.files().iter().map(|file| path.join(file)))
+            .chain([path.join(STARTUP_CACHE_FILE_NAME)])
+        {
+            let _ = remove_file(file);
+        }
+        true
+    }
+}
+
+/// A [KeyValueDatabase] of a [KeyValueDatabaseKind] selected at runtime.
+pub enum DynamicKeyValueDatabase {
+    Lmdb(LmbdKeyValueDatabase),
+    AppendLog(AppendLogKeyValueDatabase),
+}
+
+impl DynamicKeyValueDatabase {
+    pub fn new(kind: KeyValueDatabaseKind, path: &Path) -> Result<Self> {
+        Ok(match kind {
+            KeyValueDatabaseKind::Lmdb => {
+                DynamicKeyValueDatabase::Lmdb(LmbdKeyValueDatabase::new(path)?)
+            }
+            KeyValueDatabaseKind::AppendLog => {
+                DynamicKeyValueDatabase::AppendLog(AppendLogKeyValueDatabase::new(path)?)
+            }
+        })
+    }
+
+    /// Opens the existing database in `path`, whatever its kind is. Never creates a database of
+    /// another kind next to it, which would make the application discard the existing one.
+    pub fn open_existing(path: &Path) -> Result<Self> {
+        Self::new(KeyValueDatabaseKind::detect(path)?, path)
+    }
+
+    /// Calls `f` with every raw entry of a key space, see
+    /// [LmbdKeyValueDatabase::for_each_raw_entry].
+    pub fn for_each_raw_entry(
+        &self,
+        key_space: KeySpace,
+        f: impl FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        match self {
+            DynamicKeyValueDatabase::Lmdb(database) => database.for_each_raw_entry(key_space, f),
+            DynamicKeyValueDatabase::AppendLog(database) => {
+                database.for_each_raw_entry(key_space, f)
+            }
+        }
+    }
+}
+
+pub enum DynamicReadTransaction<'l> {
+    Lmdb(RoTransaction<'l>),
+    AppendLog,
+}
+
+pub enum DynamicValueBuffer<'l> {
+    Lmdb(&'l [u8]),
+    AppendLog(Cow<'l, [u8]>),
+}
+
+impl Borrow<[u8]> for DynamicValueBuffer<'_> {
+    fn borrow(&self) -> &[u8] {
+        match self {
+            DynamicValueBuffer::Lmdb(value) => value,
+            DynamicValueBuffer::AppendLog(value) => value,
+        }
+    }
+}
+
+impl KeyValueDatabase for DynamicKeyValueDatabase {
+    type ReadTransaction<'l>
+        = DynamicReadTransaction<'l>
+    where
+        Self: 'l;
+
+    fn lower_read_transaction<'l: 'i + 'r, 'i: 'r, 'r>(
+        tx: &'r Self::ReadTransaction<'l>,
+    ) -> &'r Self::ReadTransaction<'i> {
+        tx
+    }
+
+    fn begin_read_transaction(&self) -> Result<Self::ReadTransaction<'_>> {
+        Ok(match self {
+            DynamicKeyValueDatabase::Lmdb(database) => {
+                DynamicReadTransaction::Lmdb(database.begin_read_transaction()?)
+            }
+            DynamicKeyValueDatabase::AppendLog(_) => DynamicReadTransaction::AppendLog,
+        })
+    }
+
+    type ValueBuffer<'l>
+        = DynamicValueBuffer<'l>
+    where
+        Self: 'l;
+
+    fn get<'l, 'db: 'l>(
+        &'l self,
+        transaction: &'l Self::ReadTransaction<'db>,
+        key_space: KeySpace,
+        key: &[u8],
+    ) -> Result<Option<Self::ValueBuffer<'l>>> {
+        Ok(match (self, transaction) {
+            (DynamicKeyValueDatabase::Lmdb(database), DynamicReadTransaction::Lmdb(tx)) => database
+                .get(tx, key_space, key)?
+                .map(DynamicValueBuffer::Lmdb),
+            (DynamicKeyValueDatabase::AppendLog(database), DynamicReadTransaction::AppendLog) => {
+                database
+                    .get(&(), key_space, key)?
+                    .map(DynamicValueBuffer::AppendLog)
+            }
+            _ => bail!("Read transaction of a different database"),
+        })
+    }
+
+    type WriteBatch<'l>
+        = DynamicWriteBatch<'l>
+    where
+        Self: 'l;
+
+    fn write_batch(&self) -> Result<Self::WriteBatch<'_>> {
+        Ok(match self {
+            DynamicKeyValueDatabase::Lmdb(database) => {
+                DynamicWriteBatch::Lmdb(database.write_batch()?)
+            }
+            DynamicKeyValueDatabase::AppendLog(database) => {
+                DynamicWriteBatch::AppendLog(database.write_batch()?)
+            }
+        })
+    }
+}
+
+pub enum DynamicWriteBatch<'l> {
+    Lmdb(LmbdWriteBatch<'l>),
+    AppendLog(AppendLogWriteBatch<'l>),
+}
+
+impl<'a> WriteBatch<'a> for DynamicWriteBatch<'a> {
+    type ValueBuffer<'l>
+        = DynamicValueBuffer<'l>
+    where
+        Self: 'l,
+        'a: 'l;
+
+    fn get<'l>(&'l self, key_space: KeySpace, key: &[u8]) -> Result<Option<Self::ValueBuffer<'l>>>
+    where
+        'a: 'l,
+    {
+        Ok(match self {
+            DynamicWriteBatch::Lmdb(batch) => {
+                batch.get(key_space, key)?.map(DynamicValueBuffer::Lmdb)
+            }
+            DynamicWriteBatch::AppendLog(batch) => batch
+                .get(key_space, key)?
+                .map(DynamicValueBuffer::AppendLog),
+        })
+    }
+
+    fn put(&mut self, key_space: KeySpace, key: Cow<[u8]>, value: Cow<[u8]>) -> Result<()> {
+        match self {
+            DynamicWriteBatch::Lmdb(batch) => batch.put(key_space, key, value),
+            DynamicWriteBatch::AppendLog(batch) => batch.put(key_space, key, value),
+        }
+    }
+
+    fn delete(&mut self, key_space: KeySpace, key: Cow<[u8]>) -> Result<()> {
+        match self {
+            DynamicWriteBatch::Lmdb(batch) => batch.delete(key_space, key),
+            DynamicWriteBatch::AppendLog(batch) => batch.delete(key_space, key),
+        }
+    }
+
+    fn commit(self) -> Result<()> {
+        match self {
+            DynamicWriteBatch::Lmdb(batch) => batch.commit(),
+            DynamicWriteBatch::AppendLog(batch) => batch.commit(),
+        }
+    }
+}
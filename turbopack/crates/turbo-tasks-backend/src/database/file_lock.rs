@@ -0,0 +1,107 @@
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    path::Path,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Can be held by multiple processes at the same time, e.g. by all processes that use a
+    /// database.
+    Shared,
+    /// Can only be held by one process, and not while any shared lock is held.
+    Exclusive,
+}
+
+/// Opens or creates the lock file at `path` and locks it without waiting. The lock is released
+/// when the returned file is closed, which also happens when the process crashes.
+pub fn lock_file(path: &Path, mode: LockMode) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    lock(&file, mode)?;
+    Ok(file)
+}
+
+/// Returns `true` when a process holds a lock on the lock file at `path`. A missing lock file is
+/// not created.
+pub fn is_locked(path: &Path) -> bool {
+    let Ok(file) = OpenOptions::new().read(true).write(true).open(path) else {
+        return false;
+    };
+    lock(&file, LockMode::Exclusive).is_err()
+}
+
+#[cfg(unix)]
+fn lock(file: &File, mode: LockMode) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let operation = match mode {
+        LockMode::Shared => libc::LOCK_SH,
+        LockMode::Exclusive => libc::LOCK_EX,
+    };
+    // SAFETY: The file descriptor is valid while `file` is borrowed.
+    if unsafe { libc::flock(file.as_raw_fd(), operation | libc::LOCK_NB) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn lock(file: &File, mode: LockMode) -> io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::{
+        Storage::FileSystem::{LockFileEx, LOCKFILE_EXCLUSIVE_LOCK, LOCKFILE_FAIL_IMMEDIATELY},
+        System::IO::OVERLAPPED,
+    };
+
+    let flags = match mode {
+        LockMode::Shared => LOCKFILE_FAIL_IMMEDIATELY,
+        LockMode::Exclusive => LOCKFILE_EXCLUSIVE_LOCK | LOCKFILE_FAIL_IMMEDIATELY,
+    };
+    // SAFETY: The handle is valid while `file` is borrowed and `overlapped` is only used during
+    // the call.
+    let result = unsafe {
+        let mut overlapped: OVERLAPPED = std::mem::zeroed();
+        LockFileEx(
+            file.as_raw_handle(),
+            flags,
+            0,
+            u32::MAX,
+            u32::MAX,
+            &mut overlapped,
+        )
+    };
+    if result == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exclusive_lock_excludes_shared_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lock");
+        assert!(!is_locked(&path));
+        assert!(!path.exists());
+
+        let first = lock_file(&path, LockMode::Shared).unwrap();
+        let second = lock_file(&path, LockMode::Shared).unwrap();
+        assert!(lock_file(&path, LockMode::Exclusive).is_err());
+        assert!(is_locked(&path));
+
+        drop((first, second));
+        assert!(!is_locked(&path));
+        let exclusive = lock_file(&path, LockMode::Exclusive).unwrap();
+        assert!(lock_file(&path, LockMode::Shared).is_err());
+        drop(exclusive);
+    }
+}
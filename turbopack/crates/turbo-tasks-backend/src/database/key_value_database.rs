@@ -2,7 +2,7 @@ use std::borrow::Cow;
 
 use anyhow::Result;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum KeySpace {
     Infra,
     TaskMeta,
@@ -11,6 +11,26 @@ pub enum KeySpace {
     ReverseTaskCache,
 }
 
+impl KeySpace {
+    pub const ALL: [KeySpace; 5] = [
+        KeySpace::Infra,
+        KeySpace::TaskMeta,
+        KeySpace::TaskData,
+        KeySpace::ForwardTaskCache,
+        KeySpace::ReverseTaskCache,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeySpace::Infra => "infra",
+            KeySpace::TaskMeta => "meta",
+            KeySpace::TaskData => "data",
+            KeySpace::ForwardTaskCache => "forward_task_cache",
+            KeySpace::ReverseTaskCache => "reverse_task_cache",
+        }
+    }
+}
+
 pub trait WriteBatch<'a> {
     type ValueBuffer<'l>: std::borrow::Borrow<[u8]>
     where
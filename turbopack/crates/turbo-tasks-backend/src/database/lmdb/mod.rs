@@ -1,16 +1,29 @@
-use std::{borrow::Cow, fs::create_dir_all, path::Path, thread::available_parallelism};
+use std::{
+    borrow::Cow,
+    fs::{create_dir_all, File},
+    path::Path,
+    thread::available_parallelism,
+};
 
 use anyhow::{Context, Result};
 use lmdb::{
-    Database, DatabaseFlags, Environment, EnvironmentFlags, RoTransaction, RwTransaction,
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RoTransaction, RwTransaction,
     Transaction, WriteFlags,
 };
 
-use crate::database::key_value_database::{KeySpace, KeyValueDatabase, WriteBatch};
+use crate::database::{
+    file_lock::{lock_file, LockMode},
+    key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
+};
 
 mod extended_key;
 
+/// Locked shared while the database is open, and exclusively while it's maintained, e.g.
+/// compacted. LMDB's own lock file doesn't tell whether another process has the database open.
+pub const LOCK_FILE_NAME: &str = "data.mdb.lock";
+
 pub struct LmbdKeyValueDatabase {
+    lock: File,
     env: Environment,
     infra_db: Database,
     data_db: Database,
@@ -21,7 +34,23 @@ pub struct LmbdKeyValueDatabase {
 
 impl LmbdKeyValueDatabase {
     pub fn new(path: &Path) -> Result<Self> {
+        Self::open(path, LockMode::Shared)
+    }
+
+    /// Opens the database only when no other process has it open, and prevents other processes
+    /// from opening it until the lock returned by [LmbdKeyValueDatabase::into_lock] is dropped.
+    pub fn new_exclusive(path: &Path) -> Result<Self> {
+        Self::open(path, LockMode::Exclusive)
+    }
+
+    fn open(path: &Path, lock_mode: LockMode) -> Result<Self> {
         create_dir_all(path).context("Creating database directory failed")?;
+        let lock = lock_file(&path.join(LOCK_FILE_NAME), lock_mode).with_context(|| {
+            format!(
+                "Locking the database in {} failed, it might be used by another process",
+                path.display()
+            )
+        })?;
 
         #[cfg(target_arch = "x86")]
         const MAP_SIZE: usize = usize::MAX;
@@ -46,6 +75,7 @@ impl LmbdKeyValueDatabase {
         let reverse_task_cache_db =
             env.create_db(Some("reverse_task_cache"), DatabaseFlags::INTEGER_KEY)?;
         Ok(LmbdKeyValueDatabase {
+            lock,
             env,
             infra_db,
             data_db,
@@ -64,6 +94,42 @@ impl LmbdKeyValueDatabase {
             KeySpace::ReverseTaskCache => self.reverse_task_cache_db,
         }
     }
+
+    /// Calls `f` with every raw entry of a key space. Keys longer than the maximum LMDB key
+    /// size are stored hashed with multiple entries packed into the value.
+    pub fn for_each_raw_entry(
+        &self,
+        key_space: KeySpace,
+        mut f: impl FnMut(&[u8], &[u8]) -> Result<()>,
+    ) -> Result<()> {
+        let tx = self.env.begin_ro_txn()?;
+        let mut cursor = tx.open_ro_cursor(self.db(key_space))?;
+        for entry in cursor.iter_start() {
+            let (key, value) = entry?;
+            f(key, value)?;
+        }
+        Ok(())
+    }
+
+    /// Closes the database and returns its lock.
+    pub fn into_lock(self) -> File {
+        self.lock
+    }
+
+    /// Copies all entries into `target`. Used to compact the database, as a copy doesn't contain
+    /// the free pages of the original.
+    pub fn copy_into(&self, target: &LmbdKeyValueDatabase) -> Result<()> {
+        for key_space in KeySpace::ALL {
+            let mut tx = target.env.begin_rw_txn()?;
+            self.for_each_raw_entry(key_space, |key, value| {
+                tx.put(target.db(key_space), &key, &value, WriteFlags::empty())?;
+                Ok(())
+            })
+            .with_context(|| format!("Copying the {} key space failed", key_space.name()))?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
 }
 
 impl KeyValueDatabase for LmbdKeyValueDatabase {
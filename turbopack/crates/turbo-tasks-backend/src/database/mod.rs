@@ -1,5 +1,10 @@
+pub mod append_log;
 mod by_key_space;
+#[cfg(test)]
+mod conformance_tests;
 pub mod db_versioning;
+pub mod dynamic;
+mod file_lock;
 pub mod fresh_db_optimization;
 pub mod key_value_database;
 pub mod lmdb;
@@ -8,8 +13,9 @@ pub mod read_transaction_cache;
 mod startup_cache;
 
 pub use db_versioning::handle_db_versioning;
+pub use dynamic::{DynamicKeyValueDatabase, KeyValueDatabaseKind};
 pub use fresh_db_optimization::{is_fresh, FreshDbOptimization};
 #[allow(unused_imports)]
 pub use noop_kv::NoopKvDb;
 pub use read_transaction_cache::ReadTransactionCache;
-pub use startup_cache::StartupCacheLayer;
+pub use startup_cache::{StartupCacheLayer, STARTUP_CACHE_FILE_NAME};
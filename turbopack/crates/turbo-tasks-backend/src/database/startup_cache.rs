@@ -18,6 +18,9 @@ use crate::database::{
     key_value_database::{KeySpace, KeyValueDatabase, WriteBatch},
 };
 
+/// The name of the file the startup cache is stored in, next to the database.
+pub const STARTUP_CACHE_FILE_NAME: &str = "startup.cache";
+
 const CACHE_SIZE_LIMIT: usize = 100 * 1024 * 1024;
 const PAIR_HEADER_SIZE: usize = 9;
 
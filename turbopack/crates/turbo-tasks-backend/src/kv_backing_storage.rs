@@ -19,13 +19,13 @@ use crate::{
 };
 
 const META_KEY_OPERATIONS: u32 = 0;
-const META_KEY_NEXT_FREE_TASK_ID: u32 = 1;
+pub(crate) const META_KEY_NEXT_FREE_TASK_ID: u32 = 1;
 const META_KEY_SESSION_ID: u32 = 2;
 
-struct IntKey([u8; 4]);
+pub(crate) struct IntKey([u8; 4]);
 
 impl IntKey {
-    fn new(value: u32) -> Self {
+    pub(crate) fn new(value: u32) -> Self {
         Self(value.to_be_bytes())
     }
 }
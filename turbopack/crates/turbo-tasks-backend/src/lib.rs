@@ -5,40 +5,79 @@ mod backing_storage;
 mod data;
 mod database;
 mod kv_backing_storage;
+pub mod maintenance;
 mod utils;
 
 use std::path::Path;
 
 use anyhow::Result;
 
-pub use self::{backend::TurboTasksBackend, kv_backing_storage::KeyValueDatabaseBackingStorage};
+pub use self::{
+    backend::TurboTasksBackend, database::KeyValueDatabaseKind,
+    kv_backing_storage::KeyValueDatabaseBackingStorage,
+};
 use crate::database::{
-    handle_db_versioning, is_fresh, lmdb::LmbdKeyValueDatabase, FreshDbOptimization, NoopKvDb,
-    ReadTransactionCache, StartupCacheLayer,
+    append_log::AppendLogKeyValueDatabase, handle_db_versioning, is_fresh,
+    key_value_database::KeyValueDatabase, lmdb::LmbdKeyValueDatabase, DynamicKeyValueDatabase,
+    FreshDbOptimization, NoopKvDb, ReadTransactionCache, StartupCacheLayer,
+    STARTUP_CACHE_FILE_NAME,
 };
 
-pub type LmdbBackingStorage = KeyValueDatabaseBackingStorage<
-    ReadTransactionCache<StartupCacheLayer<FreshDbOptimization<LmbdKeyValueDatabase>>>,
->;
+pub type PersistentBackingStorage<T> =
+    KeyValueDatabaseBackingStorage<ReadTransactionCache<StartupCacheLayer<FreshDbOptimization<T>>>>;
 
-pub fn lmdb_backing_storage(path: &Path) -> Result<LmdbBackingStorage> {
+fn persistent_backing_storage<T: KeyValueDatabase + 'static>(
+    path: &Path,
+    kind: KeyValueDatabaseKind,
+    open: impl FnOnce(&Path) -> Result<T>,
+) -> Result<PersistentBackingStorage<T>> {
     let path = handle_db_versioning(path)?;
     let fresh_db = is_fresh(&path);
-    let database = LmbdKeyValueDatabase::new(&path)?;
+    // The database is also fresh when it was created by another kind of database before.
+    let fresh_db = kind.remove_other_databases(&path) || fresh_db;
+    let database = open(&path)?;
     let database = FreshDbOptimization::new(database, fresh_db);
-    let database = StartupCacheLayer::new(database, path.join("startup.cache"), fresh_db)?;
+    let database = StartupCacheLayer::new(database, path.join(STARTUP_CACHE_FILE_NAME), fresh_db)?;
     let database = ReadTransactionCache::new(database);
     Ok(KeyValueDatabaseBackingStorage::new(database))
 }
 
+pub type LmdbBackingStorage = PersistentBackingStorage<LmbdKeyValueDatabase>;
+
+pub fn lmdb_backing_storage(path: &Path) -> Result<LmdbBackingStorage> {
+    persistent_backing_storage(path, KeyValueDatabaseKind::Lmdb, LmbdKeyValueDatabase::new)
+}
+
+pub type AppendLogBackingStorage = PersistentBackingStorage<AppendLogKeyValueDatabase>;
+
+pub fn append_log_backing_storage(path: &Path) -> Result<AppendLogBackingStorage> {
+    persistent_backing_storage(
+        path,
+        KeyValueDatabaseKind::AppendLog,
+        AppendLogKeyValueDatabase::new,
+    )
+}
+
+pub type DynamicBackingStorage = PersistentBackingStorage<DynamicKeyValueDatabase>;
+
+/// Creates a backing storage with a database of the given kind.
+pub fn dynamic_backing_storage(
+    path: &Path,
+    kind: KeyValueDatabaseKind,
+) -> Result<DynamicBackingStorage> {
+    persistent_backing_storage(path, kind, |path| DynamicKeyValueDatabase::new(kind, path))
+}
+
 pub type NoopBackingStorage = KeyValueDatabaseBackingStorage<NoopKvDb>;
 
 pub fn noop_backing_storage(_path: &Path) -> Result<NoopBackingStorage> {
     Ok(KeyValueDatabaseBackingStorage::new(NoopKvDb))
 }
 
-pub type DefaultBackingStorage = LmdbBackingStorage;
+pub type DefaultBackingStorage = DynamicBackingStorage;
 
+/// Creates a backing storage with the database selected by the `TURBO_ENGINE_DATABASE`
+/// environment variable, see [KeyValueDatabaseKind::from_env].
 pub fn default_backing_storage(path: &Path) -> Result<DefaultBackingStorage> {
-    lmdb_backing_storage(path)
+    dynamic_backing_storage(path, KeyValueDatabaseKind::from_env()?)
 }
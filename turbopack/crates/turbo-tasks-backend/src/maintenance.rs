@@ -0,0 +1,743 @@
+//! Inspection and maintenance of the persistent cache, see the `turbo-tasks-cache` binary.
+//!
+//! Task types and task data are decoded structurally, without the registry of the application
+//! that wrote them. Task types are identified by their function name.
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    fs::{self, read_dir},
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use pot::{format::Integer, Value};
+use turbo_tasks::TRANSIENT_TASK_BIT;
+
+use crate::{
+    database::{
+        append_log::AppendLogKeyValueDatabase,
+        db_versioning::{db_age, remove_other_db_versions},
+        key_value_database::{KeySpace, KeyValueDatabase},
+        lmdb::LmbdKeyValueDatabase,
+        DynamicKeyValueDatabase, KeyValueDatabaseKind,
+    },
+    kv_backing_storage::{IntKey, META_KEY_NEXT_FREE_TASK_ID},
+};
+
+/// A database directory inside of the cache directory. There is one per version of the
+/// application that used the cache.
+#[derive(Debug, Clone)]
+pub struct CacheVersion {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub last_used: Option<SystemTime>,
+    /// Whether this is the version that is in use.
+    pub current: bool,
+}
+
+/// Lists the database versions in `base_path`, most recently used first. `current` is the version
+/// that is in use, which defaults to the most recently used one.
+pub fn list_versions(base_path: &Path, current: Option<&str>) -> Result<Vec<CacheVersion>> {
+    let mut versions = Vec::new();
+    for entry in read_dir(base_path)
+        .with_context(|| format!("Unable to read the cache directory {}", base_path.display()))?
+    {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+        // Reading the directory to compute its size can update its access time.
+        let last_used = db_age(&path)
+            .ok()
+            .and_then(|age| SystemTime::now().checked_sub(age));
+        versions.push(CacheVersion {
+            current: false,
+            size: dir_size(&path)?,
+            last_used,
+            name,
+            path,
+        });
+    }
+    versions.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    let current = match current {
+        Some(current) => versions.iter_mut().find(|version| version.name == current),
+        None => versions.first_mut(),
+    };
+    if let Some(current) = current {
+        current.current = true;
+    }
+    Ok(versions)
+}
+
+/// Removes all database versions except for `current` and the `keep` most recently used other
+/// versions. `current` is the version that is in use, which defaults to the most recently used
+/// one. Versions that another process has open are kept as well. Returns the removed directories.
+pub fn prune_versions(
+    base_path: &Path,
+    current: Option<&str>,
+    keep: usize,
+) -> Result<Vec<PathBuf>> {
+    let current = match current {
+        Some(current) => current.to_string(),
+        None => match most_recently_used_version(base_path)? {
+            Some(current) => current,
+            None => return Ok(Vec::new()),
+        },
+    };
+    Ok(remove_other_db_versions(base_path, Some(&current), keep))
+}
+
+fn most_recently_used_version(base_path: &Path) -> Result<Option<String>> {
+    let mut newest: Option<(Duration, String)> = None;
+    for entry in read_dir(base_path)
+        .with_context(|| format!("Unable to read the cache directory {}", base_path.display()))?
+    {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let Ok(age) = db_age(&entry.path()) else {
+            continue;
+        };
+        match &newest {
+            Some((newest_age, _)) if *newest_age <= age => {}
+            _ => newest = Some((age, entry.file_name().to_string_lossy().into_owned())),
+        }
+    }
+    Ok(newest.map(|(_, name)| name))
+}
+
+fn dir_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+fn open_database(db_path: &Path) -> Result<DynamicKeyValueDatabase> {
+    DynamicKeyValueDatabase::open_existing(db_path)
+}
+
+fn task_id_from_key(key: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(key.try_into().ok()?))
+}
+
+#[derive(Debug, Clone)]
+pub struct KeySpaceStatistics {
+    pub key_space: KeySpace,
+    pub entries: usize,
+    pub key_bytes: u64,
+    pub value_bytes: u64,
+}
+
+/// Counts the entries and their sizes per [KeySpace] of the database in `db_path`.
+pub fn key_space_statistics(db_path: &Path) -> Result<Vec<KeySpaceStatistics>> {
+    let database = open_database(db_path)?;
+    KeySpace::ALL
+        .into_iter()
+        .map(|key_space| {
+            let mut statistics = KeySpaceStatistics {
+                key_space,
+                entries: 0,
+                key_bytes: 0,
+                value_bytes: 0,
+            };
+            database.for_each_raw_entry(key_space, |key, value| {
+                statistics.entries += 1;
+                statistics.key_bytes += key.len() as u64;
+                statistics.value_bytes += value.len() as u64;
+                Ok(())
+            })?;
+            Ok(statistics)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskTypeStatistics {
+    /// The function name of the task type, or `<unknown>` when it can't be decoded.
+    pub name: String,
+    pub tasks: usize,
+    /// The bytes used by the task cache entries, meta and data of these tasks.
+    pub bytes: u64,
+}
+
+/// Returns the `limit` task types that take the most space in the database in `db_path`.
+pub fn largest_task_types(db_path: &Path, limit: usize) -> Result<Vec<TaskTypeStatistics>> {
+    let database = open_database(db_path)?;
+    let mut task_sizes = HashMap::<u32, u64>::new();
+    for key_space in [KeySpace::TaskMeta, KeySpace::TaskData] {
+        database.for_each_raw_entry(key_space, |key, value| {
+            if let Some(task) = task_id_from_key(key) {
+                *task_sizes.entry(task).or_default() += value.len() as u64;
+            }
+            Ok(())
+        })?;
+    }
+
+    let mut by_name = HashMap::<String, TaskTypeStatistics>::new();
+    database.for_each_raw_entry(KeySpace::ReverseTaskCache, |key, value| {
+        let name = pot::from_slice::<Value>(value)
+            .ok()
+            .and_then(|task_type| task_type_name(&task_type))
+            .unwrap_or_else(|| "<unknown>".to_string());
+        let statistics = by_name
+            .entry(name.clone())
+            .or_insert_with(|| TaskTypeStatistics {
+                name,
+                tasks: 0,
+                bytes: 0,
+            });
+        statistics.tasks += 1;
+        // The task type is stored in the forward and the reverse task cache.
+        statistics.bytes += 2 * value.len() as u64 + key.len() as u64;
+        if let Some(task) = task_id_from_key(key) {
+            statistics.bytes += task_sizes.get(&task).copied().unwrap_or_default();
+        }
+        Ok(())
+    })?;
+
+    let mut task_types = by_name.into_values().collect::<Vec<_>>();
+    task_types.sort_by(|a, b| b.bytes.cmp(&a.bytes));
+    task_types.truncate(limit);
+    Ok(task_types)
+}
+
+/// Extracts the function name from a serialized `CachedTaskType`.
+fn task_type_name(task_type: &Value) -> Option<String> {
+    let Value::Sequence(items) = task_type else {
+        return None;
+    };
+    match items.as_slice() {
+        [Value::Integer(kind), Value::Sequence(function_and_arg), ..] => {
+            let Some(Value::String(name)) = function_and_arg.first() else {
+                return None;
+            };
+            Some(if kind.as_u8().ok() == Some(1) {
+                format!("{name} (resolve)")
+            } else {
+                name.to_string()
+            })
+        }
+        [Value::Integer(_), Value::String(trait_type), Value::String(method), ..] => {
+            Some(format!("{trait_type}::{method} (resolve trait)"))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// A key that should be a task id has an unexpected length.
+    InvalidKey {
+        key_space: KeySpace,
+        key: Vec<u8>,
+    },
+    UndecodableTaskType {
+        task: u32,
+        error: String,
+    },
+    /// The forward task cache doesn't map the task type back to the task.
+    TaskCacheMismatch {
+        task: u32,
+    },
+    TaskIdOutOfRange {
+        task: u32,
+        next_free_task_id: u32,
+    },
+    /// Meta or data is stored for a task without a task type.
+    MissingTaskType {
+        key_space: KeySpace,
+        task: u32,
+    },
+    UndecodableData {
+        key_space: KeySpace,
+        task: u32,
+        error: String,
+    },
+    /// The data of `task` references a persistent task that doesn't exist.
+    DanglingReference {
+        task: u32,
+        referenced: u32,
+    },
+}
+
+impl Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::InvalidKey { key_space, key } => {
+                write!(f, "invalid key {key:?} in {}", key_space.name())
+            }
+            IntegrityIssue::UndecodableTaskType { task, error } => {
+                write!(f, "task type of task {task} can't be decoded: {error}")
+            }
+            IntegrityIssue::TaskCacheMismatch { task } => write!(
+                f,
+                "the forward task cache doesn't map the task type of task {task} to it"
+            ),
+            IntegrityIssue::TaskIdOutOfRange {
+                task,
+                next_free_task_id,
+            } => write!(
+                f,
+                "task {task} is not below the next free task id {next_free_task_id}"
+            ),
+            IntegrityIssue::MissingTaskType { key_space, task } => write!(
+                f,
+                "{} is stored for task {task}, which has no task type",
+                key_space.name()
+            ),
+            IntegrityIssue::UndecodableData {
+                key_space,
+                task,
+                error,
+            } => write!(
+                f,
+                "{} of task {task} can't be decoded: {error}",
+                key_space.name()
+            ),
+            IntegrityIssue::DanglingReference { task, referenced } => write!(
+                f,
+                "task {task} references task {referenced}, which doesn't exist"
+            ),
+        }
+    }
+}
+
+/// Checks the database in `db_path` for inconsistencies between the task caches and the task
+/// data, and for entries that can't be decoded.
+pub fn verify(db_path: &Path) -> Result<Vec<IntegrityIssue>> {
+    let database = open_database(db_path)?;
+    let tx = database.begin_read_transaction()?;
+    let next_free_task_id = database
+        .get(
+            &tx,
+            KeySpace::Infra,
+            IntKey::new(META_KEY_NEXT_FREE_TASK_ID).as_ref(),
+        )?
+        .and_then(|bytes| task_id_from_key(bytes.borrow()))
+        .unwrap_or(1);
+
+    let mut issues = Vec::new();
+    let mut tasks = HashSet::new();
+    database.for_each_raw_entry(KeySpace::ReverseTaskCache, |key, value| {
+        let Some(task) = task_id_from_key(key) else {
+            issues.push(IntegrityIssue::InvalidKey {
+                key_space: KeySpace::ReverseTaskCache,
+                key: key.to_vec(),
+            });
+            return Ok(());
+        };
+        tasks.insert(task);
+        if task >= next_free_task_id {
+            issues.push(IntegrityIssue::TaskIdOutOfRange {
+                task,
+                next_free_task_id,
+            });
+        }
+        if let Err(err) = pot::from_slice::<Value>(value) {
+            issues.push(IntegrityIssue::UndecodableTaskType {
+                task,
+                error: err.to_string(),
+            });
+        }
+        let forward = database.get(&tx, KeySpace::ForwardTaskCache, value)?;
+        if forward.and_then(|bytes| task_id_from_key(bytes.borrow())) != Some(task) {
+            issues.push(IntegrityIssue::TaskCacheMismatch { task });
+        }
+        Ok(())
+    })?;
+
+    for key_space in [KeySpace::TaskMeta, KeySpace::TaskData] {
+        database.for_each_raw_entry(key_space, |key, value| {
+            let Some(task) = task_id_from_key(key) else {
+                issues.push(IntegrityIssue::InvalidKey {
+                    key_space,
+                    key: key.to_vec(),
+                });
+                return Ok(());
+            };
+            if !tasks.contains(&task) {
+                issues.push(IntegrityIssue::MissingTaskType { key_space, task });
+            }
+            match pot::from_slice::<Value>(value) {
+                Ok(items) => {
+                    let mut referenced = Vec::new();
+                    collect_task_references(&items, None, &mut referenced);
+                    referenced.sort_unstable();
+                    referenced.dedup();
+                    for referenced in referenced {
+                        if referenced & TRANSIENT_TASK_BIT == 0 && !tasks.contains(&referenced) {
+                            issues.push(IntegrityIssue::DanglingReference { task, referenced });
+                        }
+                    }
+                }
+                Err(err) => issues.push(IntegrityIssue::UndecodableData {
+                    key_space,
+                    task,
+                    error: err.to_string(),
+                }),
+            }
+            Ok(())
+        })?;
+    }
+    Ok(issues)
+}
+
+/// Collects the task ids in the `task` and `target` fields of serialized `CachedDataItem`s.
+fn collect_task_references(value: &Value, field: Option<&str>, referenced: &mut Vec<u32>) {
+    match value {
+        Value::Integer(integer) if matches!(field, Some("task" | "target")) => {
+            if let Ok(task) = Integer::as_u32(integer) {
+                referenced.push(task);
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                collect_task_references(item, None, referenced);
+            }
+        }
+        Value::Mappings(mappings) => {
+            for (key, value) in mappings {
+                let key = match key {
+                    Value::String(key) => Some(&**key),
+                    _ => None,
+                };
+                // Cell contents are values of the application, their fields are not task ids.
+                if key != Some("CellData") {
+                    collect_task_references(value, key, referenced);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CompactionResult {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+/// Rewrites the database in `db_path` without its free pages, or for an append log without its
+/// outdated entries. Fails when the database is used by another process, and keeps other
+/// processes from opening it until it's done.
+pub fn compact(db_path: &Path) -> Result<CompactionResult> {
+    let size_before = dir_size(db_path)?;
+    match KeyValueDatabaseKind::detect(db_path)? {
+        KeyValueDatabaseKind::Lmdb => compact_lmdb(db_path)?,
+        KeyValueDatabaseKind::AppendLog => {
+            AppendLogKeyValueDatabase::new_compacted(db_path)?;
+        }
+    }
+    Ok(CompactionResult {
+        size_before,
+        size_after: dir_size(db_path)?,
+    })
+}
+
+fn compact_lmdb(db_path: &Path) -> Result<()> {
+    let database = LmbdKeyValueDatabase::new_exclusive(db_path)?;
+    let compact_path = db_path.join("compact");
+    if compact_path.exists() {
+        fs::remove_dir_all(&compact_path)?;
+    }
+    let _lock = {
+        let compacted = LmbdKeyValueDatabase::new(&compact_path)?;
+        database.copy_into(&compacted)?;
+        database.into_lock()
+    };
+    fs::rename(compact_path.join("data.mdb"), db_path.join("data.mdb"))
+        .context("Replacing the database with the compacted copy failed")?;
+    fs::remove_dir_all(&compact_path)?;
+    // The lock file belongs to the replaced database, LMDB creates a new one when it's opened.
+    match fs::remove_file(db_path.join("lock.mdb")) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, fs::FileTimes};
+
+    use super::*;
+    use crate::database::key_value_database::WriteBatch;
+
+    /// Creates a version directory that was last used `minutes` ago.
+    fn create_version(base_path: &Path, name: &str, minutes: u64) {
+        let path = base_path.join(name);
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("data.mdb"), [0; 16]).unwrap();
+        let last_used = SystemTime::now() - Duration::from_secs(minutes * 60);
+        fs::File::open(&path)
+            .unwrap()
+            .set_times(
+                FileTimes::new()
+                    .set_accessed(last_used)
+                    .set_modified(last_used),
+            )
+            .unwrap();
+    }
+
+    fn version_names(base_path: &Path) -> Vec<String> {
+        let mut names = read_dir(base_path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn lists_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        create_version(dir.path(), "a", 30);
+        create_version(dir.path(), "b", 10);
+        create_version(dir.path(), "c", 20);
+
+        let versions = list_versions(dir.path(), None).unwrap();
+        let names = versions
+            .iter()
+            .map(|version| (&*version.name, version.current, version.size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![("b", true, 16), ("c", false, 16), ("a", false, 16)]
+        );
+
+        let versions = list_versions(dir.path(), Some("a")).unwrap();
+        let current = versions
+            .iter()
+            .filter(|version| version.current)
+            .map(|version| &*version.name)
+            .collect::<Vec<_>>();
+        assert_eq!(current, vec!["a"]);
+    }
+
+    #[test]
+    fn prunes_all_but_the_most_recently_used_version() {
+        let dir = tempfile::tempdir().unwrap();
+        create_version(dir.path(), "a", 30);
+        create_version(dir.path(), "b", 10);
+        create_version(dir.path(), "c", 20);
+        create_version(dir.path(), "d", 40);
+
+        let mut removed = prune_versions(dir.path(), None, 1).unwrap();
+        removed.sort();
+        assert_eq!(removed, vec![dir.path().join("a"), dir.path().join("d")]);
+        assert_eq!(version_names(dir.path()), vec!["b", "c"]);
+
+        prune_versions(dir.path(), None, 0).unwrap();
+        assert_eq!(version_names(dir.path()), vec!["b"]);
+    }
+
+    #[test]
+    fn prunes_all_but_the_current_version() {
+        let dir = tempfile::tempdir().unwrap();
+        create_version(dir.path(), "a", 30);
+        create_version(dir.path(), "b", 10);
+        create_version(dir.path(), "c", 20);
+
+        prune_versions(dir.path(), Some("a"), 0).unwrap();
+        assert_eq!(version_names(dir.path()), vec!["a"]);
+    }
+
+    #[test]
+    fn keeps_versions_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        create_version(dir.path(), "a", 30);
+        create_version(dir.path(), "b", 10);
+        create_version(dir.path(), "c", 20);
+
+        // Used by another version of the application.
+        let database = AppendLogKeyValueDatabase::new(&dir.path().join("a")).unwrap();
+        prune_versions(dir.path(), Some("b"), 0).unwrap();
+        assert_eq!(version_names(dir.path()), vec!["a", "b"]);
+
+        drop(database);
+        prune_versions(dir.path(), Some("b"), 0).unwrap();
+        assert_eq!(version_names(dir.path()), vec!["b"]);
+    }
+
+    #[test]
+    fn refuses_to_compact_database_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = LmbdKeyValueDatabase::new(dir.path()).unwrap();
+        assert!(compact(dir.path()).is_err());
+        assert!(!dir.path().join("compact").exists());
+
+        drop(database);
+        compact(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn verifies_and_compacts_lmdb_database() {
+        let dir = tempfile::tempdir().unwrap();
+        verify_and_compact(KeyValueDatabaseKind::Lmdb, dir.path());
+        assert!(!dir.path().join("compact").exists());
+        assert!(!dir.path().join("lock.mdb").exists());
+    }
+
+    #[test]
+    fn verifies_and_compacts_append_log_database() {
+        let dir = tempfile::tempdir().unwrap();
+        verify_and_compact(KeyValueDatabaseKind::AppendLog, dir.path());
+        // Inspecting the cache must not make the application discard it.
+        assert!(!dir.path().join("data.mdb").exists());
+        assert!(!KeyValueDatabaseKind::AppendLog.remove_other_databases(dir.path()));
+    }
+
+    fn verify_and_compact(kind: KeyValueDatabaseKind, path: &Path) {
+        let task_type = |name: &str| {
+            pot::to_vec(&Value::Sequence(vec![
+                Value::Integer(0u8.into()),
+                Value::Sequence(vec![Value::String(name.to_string().into()), Value::Unit]),
+            ]))
+            .unwrap()
+        };
+        let task_key = |task: u32| IntKey::new(task).as_ref().to_vec();
+        {
+            let database = DynamicKeyValueDatabase::new(kind, path).unwrap();
+            let mut batch = database.write_batch().unwrap();
+            let mut put = |key_space, key: Vec<u8>, value: Vec<u8>| {
+                batch
+                    .put(key_space, Cow::Owned(key), Cow::Owned(value))
+                    .unwrap()
+            };
+            put(
+                KeySpace::Infra,
+                task_key(META_KEY_NEXT_FREE_TASK_ID),
+                3u32.to_be_bytes().to_vec(),
+            );
+            put(KeySpace::ReverseTaskCache, task_key(1), task_type("one"));
+            put(KeySpace::ForwardTaskCache, task_type("one"), task_key(1));
+            // Task 2 is missing in the forward task cache.
+            put(KeySpace::ReverseTaskCache, task_key(2), task_type("two"));
+            // Task 4 has no task type.
+            put(
+                KeySpace::TaskMeta,
+                task_key(4),
+                pot::to_vec(&Value::Sequence(vec![])).unwrap(),
+            );
+            // Task 1 references task 5, which doesn't exist.
+            let data = Value::Sequence(vec![mapping(vec![(
+                "Child",
+                mapping(vec![("task", Value::Integer(5u32.into()))]),
+            )])]);
+            put(KeySpace::TaskData, task_key(1), pot::to_vec(&data).unwrap());
+            batch.commit().unwrap();
+        }
+
+        let expected = vec![
+            IntegrityIssue::TaskCacheMismatch { task: 2 },
+            IntegrityIssue::MissingTaskType {
+                key_space: KeySpace::TaskMeta,
+                task: 4,
+            },
+            IntegrityIssue::DanglingReference {
+                task: 1,
+                referenced: 5,
+            },
+        ];
+        assert_eq!(verify(path).unwrap(), expected);
+        assert_eq!(
+            key_space_statistics(path)
+                .unwrap()
+                .iter()
+                .map(|statistics| statistics.entries)
+                .sum::<usize>(),
+            6
+        );
+
+        compact(path).unwrap();
+        assert_eq!(verify(path).unwrap(), expected);
+    }
+
+    fn mapping(entries: Vec<(&str, Value<'static>)>) -> Value<'static> {
+        Value::Mappings(
+            entries
+                .into_iter()
+                .map(|(key, value)| (Value::String(key.to_string().into()), value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn task_type_names() {
+        let native = |kind: u8| {
+            Value::Sequence(vec![
+                Value::Integer(kind.into()),
+                Value::Sequence(vec![
+                    Value::String("turbopack::module_asset".into()),
+                    Value::Unit,
+                ]),
+                Value::None,
+                Value::Unit,
+                Value::Unit,
+            ])
+        };
+        assert_eq!(
+            task_type_name(&native(0)).as_deref(),
+            Some("turbopack::module_asset")
+        );
+        assert_eq!(
+            task_type_name(&native(1)).as_deref(),
+            Some("turbopack::module_asset (resolve)")
+        );
+        let resolve_trait = Value::Sequence(vec![
+            Value::Integer(2u8.into()),
+            Value::String("Module".into()),
+            Value::String("ident".into()),
+            Value::None,
+            Value::Unit,
+        ]);
+        assert_eq!(
+            task_type_name(&resolve_trait).as_deref(),
+            Some("Module::ident (resolve trait)")
+        );
+        assert_eq!(task_type_name(&Value::Unit), None);
+    }
+
+    #[test]
+    fn collects_references_outside_of_cells() {
+        let items = Value::Sequence(vec![
+            mapping(vec![(
+                "Child",
+                mapping(vec![("task", Value::Integer(3u32.into()))]),
+            )]),
+            mapping(vec![(
+                "CellDependency",
+                mapping(vec![(
+                    "target",
+                    mapping(vec![("task", Value::Integer(4u32.into()))]),
+                )]),
+            )]),
+            mapping(vec![(
+                "CellData",
+                mapping(vec![("task", Value::Integer(5u32.into()))]),
+            )]),
+            mapping(vec![(
+                "ChildrenCount",
+                mapping(vec![("value", Value::Integer(6u32.into()))]),
+            )]),
+        ]);
+        let mut referenced = Vec::new();
+        collect_task_references(&items, None, &mut referenced);
+        assert_eq!(referenced, vec![3, 4]);
+    }
+}
@@ -2,11 +2,15 @@
 #![feature(arbitrary_self_types)]
 #![feature(arbitrary_self_types_pointers)]
 
+mod url_fs;
+
 use anyhow::Result;
 use turbo_tasks::{mark_session_dependent, RcStr, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::issue::{Issue, IssueSeverity, IssueStage, OptionStyledString, StyledString};
 
+pub use crate::url_fs::UrlFileSystem;
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
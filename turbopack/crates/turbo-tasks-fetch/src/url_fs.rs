@@ -0,0 +1,265 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use tokio::sync::Mutex;
+use turbo_tasks::{Completion, RcStr, ValueToString, Vc};
+use turbo_tasks_fs::{
+    DirectoryContent, File, FileContent, FileMeta, FileSystem, FileSystemPath, LinkContent,
+};
+use turbopack_core::{
+    issue::{IssueExt, IssueSeverity},
+    resolve::url_imports::fs_path_to_url,
+};
+
+use crate::fetch;
+
+const LOCKFILE_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Lockfile {
+    version: u32,
+    #[serde(flatten)]
+    entries: BTreeMap<String, LockfileEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockfileEntry {
+    integrity: String,
+}
+
+/// A read-only [FileSystem] serving modules that are imported by URL. Paths
+/// map to URLs as described in [turbopack_core::resolve::url_imports].
+///
+/// Every response is pinned by its integrity hash in `<lock_dir>/lock.json`
+/// and cached in `<lock_dir>/data`. URLs that are in the lockfile are served
+/// from the cache, so builds don't need network access once all URL imports
+/// are locked.
+#[turbo_tasks::value(cell = "new", eq = "manual")]
+pub struct UrlFileSystem {
+    lock_dir: RcStr,
+    /// When set, URLs that are not in the lockfile are an error instead of
+    /// being fetched and added to it.
+    frozen: bool,
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    #[serde(skip)]
+    lockfile: Arc<Mutex<Option<Lockfile>>>,
+}
+
+impl UrlFileSystem {
+    /// Creates a new [`Vc<UrlFileSystem>`] that keeps its lockfile and cache in
+    /// `lock_dir`, e.g. `<project>/next.lock`.
+    pub fn new(lock_dir: RcStr, frozen: bool) -> Vc<Self> {
+        Self::cell(UrlFileSystem {
+            lock_dir,
+            frozen,
+            lockfile: Default::default(),
+        })
+    }
+
+    fn lockfile_path(&self) -> PathBuf {
+        PathBuf::from(self.lock_dir.as_str()).join("lock.json")
+    }
+
+    fn cache_path(&self, integrity: &str) -> PathBuf {
+        // The integrity is base64 encoded, which isn't safe to use as a file name.
+        let digest = STANDARD
+            .decode(integrity.trim_start_matches("sha512-"))
+            .unwrap_or_default();
+        let name = digest
+            .iter()
+            .take(20)
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        PathBuf::from(self.lock_dir.as_str())
+            .join("data")
+            .join(name)
+    }
+
+    async fn locked_entry(&self, url: &str) -> Result<Option<LockfileEntry>> {
+        let mut lockfile = self.lockfile.lock().await;
+        if lockfile.is_none() {
+            *lockfile = Some(read_lockfile(&self.lockfile_path()).await?);
+        }
+        Ok(lockfile
+            .as_ref()
+            .and_then(|lockfile| lockfile.entries.get(url))
+            .cloned())
+    }
+
+    async fn lock(&self, url: &str, content: &[u8]) -> Result<()> {
+        let integrity = integrity(content);
+        let cache_path = self.cache_path(&integrity);
+        if let Some(parent) = cache_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&cache_path, content)
+            .await
+            .with_context(|| format!("failed to write {}", cache_path.display()))?;
+
+        let mut lockfile = self.lockfile.lock().await;
+        let lockfile = lockfile.get_or_insert_with(Default::default);
+        lockfile.version = LOCKFILE_VERSION;
+        lockfile
+            .entries
+            .insert(url.to_string(), LockfileEntry { integrity });
+
+        let lockfile_path = self.lockfile_path();
+        let mut json = serde_json::to_string_pretty(&*lockfile)?;
+        json.push('\n');
+        tokio::fs::write(&lockfile_path, json)
+            .await
+            .with_context(|| format!("failed to write {}", lockfile_path.display()))?;
+        Ok(())
+    }
+}
+
+async fn read_lockfile(path: &PathBuf) -> Result<Lockfile> {
+    match tokio::fs::read(path).await {
+        Ok(content) => serde_json::from_slice(&content)
+            .with_context(|| format!("failed to parse {}", path.display())),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Lockfile::default()),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+fn integrity(content: &[u8]) -> String {
+    format!("sha512-{}", STANDARD.encode(Sha512::digest(content)))
+}
+
+#[turbo_tasks::value_impl]
+impl FileSystem for UrlFileSystem {
+    #[turbo_tasks::function(fs)]
+    async fn read(&self, fs_path: Vc<FileSystemPath>) -> Result<Vc<FileContent>> {
+        let path = fs_path.await?;
+        let Some(url) = fs_path_to_url(&path.path) else {
+            return Ok(FileContent::NotFound.cell());
+        };
+
+        let locked = self.locked_entry(&url).await?;
+        if let Some(LockfileEntry {
+            integrity: expected,
+        }) = &locked
+        {
+            if let Ok(content) = tokio::fs::read(self.cache_path(expected)).await {
+                if integrity(&content) == *expected {
+                    return Ok(File::from(content).into());
+                }
+            }
+        } else if self.frozen {
+            bail!(
+                "{url} is not in the lockfile at {}, and the lockfile is frozen",
+                self.lockfile_path().display()
+            );
+        }
+
+        // Not cached yet, or the cache entry is corrupted.
+        let response = fetch(Vc::cell(url.clone()), Vc::cell(None), Vc::cell(None)).await?;
+        let response = match &*response {
+            Ok(response) => response.await?,
+            Err(err) => {
+                err.to_issue(IssueSeverity::Error.into(), fs_path).emit();
+                return Ok(FileContent::NotFound.cell());
+            }
+        };
+        let content = response.body.await?.0.clone();
+
+        match locked {
+            Some(LockfileEntry {
+                integrity: expected,
+            }) => {
+                let actual = integrity(&content);
+                if actual != expected {
+                    bail!(
+                        "The content of {url} changed since it was locked (expected {expected}, \
+                         got {actual}). Remove its entry from {} to update it.",
+                        self.lockfile_path().display()
+                    );
+                }
+            }
+            None => self.lock(&url, &content).await?,
+        }
+
+        Ok(File::from(content).into())
+    }
+
+    #[turbo_tasks::function]
+    fn read_link(&self, _fs_path: Vc<FileSystemPath>) -> Vc<LinkContent> {
+        LinkContent::NotFound.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn read_dir(&self, _fs_path: Vc<FileSystemPath>) -> Result<Vc<DirectoryContent>> {
+        bail!("Listing directories is not possible on the URL file system")
+    }
+
+    #[turbo_tasks::function]
+    fn track(&self, _fs_path: Vc<FileSystemPath>) -> Vc<Completion> {
+        // URL contents are pinned by the lockfile, there is nothing to watch.
+        Completion::immutable()
+    }
+
+    #[turbo_tasks::function]
+    fn write(
+        &self,
+        _fs_path: Vc<FileSystemPath>,
+        _content: Vc<FileContent>,
+    ) -> Result<Vc<Completion>> {
+        bail!("Writing is not possible on the URL file system")
+    }
+
+    #[turbo_tasks::function]
+    fn write_link(
+        &self,
+        _fs_path: Vc<FileSystemPath>,
+        _target: Vc<LinkContent>,
+    ) -> Result<Vc<Completion>> {
+        bail!("Writing is not possible on the URL file system")
+    }
+
+    #[turbo_tasks::function]
+    fn metadata(&self, _fs_path: Vc<FileSystemPath>) -> Result<Vc<FileMeta>> {
+        bail!("Reading metadata is not possible on the URL file system")
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ValueToString for UrlFileSystem {
+    #[turbo_tasks::function]
+    fn to_string(&self) -> Vc<RcStr> {
+        Vc::cell("url imports".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockfile_roundtrip() {
+        let json = r#"{
+  "version": 1,
+  "https://esm.sh/react": {
+    "integrity": "sha512-abc"
+  }
+}"#;
+        let lockfile: Lockfile = serde_json::from_str(json).unwrap();
+        assert_eq!(lockfile.version, 1);
+        assert_eq!(
+            lockfile.entries["https://esm.sh/react"].integrity,
+            "sha512-abc"
+        );
+        assert_eq!(serde_json::to_string_pretty(&lockfile).unwrap(), json);
+    }
+
+    #[test]
+    fn integrity_is_sri_formatted() {
+        assert_eq!(
+            integrity(b""),
+            "sha512-z4PhNX7vuL3xVChQ1m2AB9Yg5AULVxXcg/\
+             SpIdNs6c5H0NE8XYXysP+DGNKHfuwvY7kxvUdBeoGlODJ6+SfaPg=="
+        );
+    }
+}
@@ -22,18 +22,24 @@ pub mod rope;
 pub mod source_context;
 pub mod util;
 pub(crate) mod virtual_fs;
+mod watch_ignore;
 mod watcher;
 
 use std::{
     borrow::Cow,
     cmp::min,
-    collections::HashSet,
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap, HashSet},
+    ffi::OsString,
     fmt::{self, Debug, Display, Formatter, Write as _},
     fs::FileType,
+    hash::{Hash, Hasher},
     io::{self, BufRead, ErrorKind},
     mem::take,
-    path::{Path, PathBuf, MAIN_SEPARATOR},
-    sync::Arc,
+    path::{Component, Path, PathBuf, MAIN_SEPARATOR},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
     time::Duration,
 };
 
@@ -66,6 +72,8 @@ use turbo_tasks_hash::{
 };
 use util::{extract_disk_access, join_path, normalize_path, sys_to_unix, unix_to_sys};
 pub use virtual_fs::VirtualFileSystem;
+pub use watch_ignore::WatchIgnoreOptions;
+use watch_ignore::WatchIgnores;
 use watcher::DiskWatcher;
 
 use self::{invalidation::Write, json::UnparseableJson, mutex_map::MutexMap};
@@ -215,9 +223,33 @@ pub struct DiskFileSystem {
     invalidation_lock: Arc<RwLock<()>>,
     #[turbo_tasks(debug_ignore, trace_ignore)]
     watcher: Arc<DiskWatcher>,
+    /// The paths of the latest emit of every emitter, when this filesystem
+    /// owns its output directory. See [DiskFileSystem::new_owned_output].
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    #[serde(skip)]
+    emitted_paths: Option<Arc<Mutex<EmittedPaths>>>,
 }
 
 impl DiskFileSystem {
+    fn new_instance(
+        name: RcStr,
+        root: RcStr,
+        watch_ignores: &WatchIgnoreOptions,
+        emitted_paths: Option<Arc<Mutex<EmittedPaths>>>,
+    ) -> Result<Self> {
+        let ignores = WatchIgnores::new(simplified(Path::new(&*root)), watch_ignores)?;
+        Ok(DiskFileSystem {
+            name,
+            root,
+            mutex_map: Default::default(),
+            invalidation_lock: Default::default(),
+            invalidator_map: Arc::new(InvalidatorMap::new()),
+            dir_invalidator_map: Arc::new(InvalidatorMap::new()),
+            watcher: Arc::new(DiskWatcher::new(ignores)),
+            emitted_paths,
+        })
+    }
+
     /// Returns the root as Path
     fn root_path(&self) -> &Path {
         simplified(Path::new(&*self.root))
@@ -370,6 +402,99 @@ impl DiskFileSystem {
         })
     }
 
+    fn owned_emitted_paths(&self) -> Result<Arc<Mutex<EmittedPaths>>> {
+        let Some(emitted_paths) = self.emitted_paths.clone() else {
+            bail!(
+                "the {} filesystem doesn't own its output directory, stale output can't be removed",
+                self.name
+            );
+        };
+        Ok(emitted_paths)
+    }
+
+    /// Records the `paths` of the latest emit of `emitter`, replacing the
+    /// paths of its previous emit. Must be called on every emit, as the paths
+    /// are not derived from the writes: a write that is cached doesn't run
+    /// again.
+    ///
+    /// Files of the previous emit that are neither part of the latest one nor
+    /// emitted by another emitter are deleted. Only available for filesystems
+    /// created with [DiskFileSystem::new_owned_output]. Returns the paths of
+    /// the deleted files.
+    pub async fn update_emitted_paths(
+        &self,
+        emitter: impl Hash,
+        paths: impl IntoIterator<Item = Vc<FileSystemPath>>,
+    ) -> Result<Vec<PathBuf>> {
+        let emitted_paths = self.owned_emitted_paths()?;
+        let mut hasher = DefaultHasher::new();
+        emitter.hash(&mut hasher);
+        let emitter = hasher.finish();
+        let mut full_paths = HashSet::new();
+        for path in paths {
+            full_paths.insert(self.to_sys_path(path).await?);
+        }
+        let _lock = self.invalidation_lock.read().await;
+        tokio::task::spawn_blocking(move || {
+            let mut emitted_paths = emitted_paths.lock().unwrap();
+            let previous = emitted_paths
+                .by_emitter
+                .insert(emitter, full_paths)
+                .unwrap_or_default();
+            let mut removed = Vec::new();
+            for path in previous {
+                if emitted_paths.contains(&path) {
+                    continue;
+                }
+                match std::fs::remove_file(&path) {
+                    Ok(()) => removed.push(path),
+                    Err(err) if err.kind() == ErrorKind::NotFound => {}
+                    Err(err) => {
+                        return Err(err)
+                            .with_context(|| format!("removing stale output {}", path.display()))
+                    }
+                }
+            }
+            Ok(removed)
+        })
+        .instrument(tracing::info_span!(
+            "remove stale output",
+            path = &*self.root
+        ))
+        .await?
+    }
+
+    /// Deletes the files below `dir` that an earlier emit into `dir`, possibly
+    /// of an earlier process, recorded but that are not part of the latest
+    /// emit of any emitter (see [DiskFileSystem::update_emitted_paths]), and
+    /// records the latest emit for the next call. Other files are never
+    /// deleted. Only available for filesystems created with
+    /// [DiskFileSystem::new_owned_output], and only for subdirectories of its
+    /// root. Must be called after the paths of all emitters writing to `dir`
+    /// have been recorded. Returns the paths of the deleted files.
+    pub async fn remove_stale_output(&self, dir: Vc<FileSystemPath>) -> Result<Vec<PathBuf>> {
+        let emitted_paths = self.owned_emitted_paths()?;
+        let full_path = self.to_sys_path(dir).await?;
+        if full_path == self.root_path() || !full_path.starts_with(self.root_path()) {
+            bail!(
+                "refusing to remove stale output in {}, only subdirectories of {} can be cleaned",
+                full_path.display(),
+                self.root_path().display()
+            );
+        }
+        let _lock = self.invalidation_lock.read().await;
+        tokio::task::spawn_blocking(move || {
+            let emitted = emitted_paths.lock().unwrap().relative_to(&full_path);
+            remove_unemitted_files(&full_path, &emitted)
+                .with_context(|| format!("removing stale output in {}", full_path.display()))
+        })
+        .instrument(tracing::info_span!(
+            "remove stale output",
+            path = &*self.root
+        ))
+        .await?
+    }
+
     fn invalidate_from_write(&self, full_path: &Path, invalidators: HashSet<Invalidator>) {
         if !invalidators.is_empty() {
             if let Some(path) = format_absolute_fs_path(full_path, &self.name, self.root_path()) {
@@ -415,6 +540,121 @@ pub fn path_to_key(path: impl AsRef<Path>) -> String {
     path.as_ref().to_string_lossy().to_string()
 }
 
+/// Returns a unique path next to `path` for writing its new content before
+/// renaming it into place.
+fn temp_path_for(path: &Path) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let mut file_name = OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(file_name)
+}
+
+/// Writes `file` to a temporary file and renames it to `full_path`, so that
+/// concurrent readers never observe a partially written file.
+async fn write_file_atomically(full_path: &Path, file: &File) -> io::Result<()> {
+    let temp_path = temp_path_for(full_path);
+    let result = async {
+        let mut f = fs::File::create(&temp_path).await?;
+        tokio::io::copy(&mut file.read(), &mut f).await?;
+        #[cfg(target_family = "unix")]
+        f.set_permissions(file.meta.permissions.into()).await?;
+        drop(f);
+        fs::rename(&temp_path, full_path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path).await;
+    }
+    result
+}
+
+/// The paths of the latest emit of every emitter, keyed by a hash of the
+/// emitter.
+#[derive(Default)]
+struct EmittedPaths {
+    by_emitter: HashMap<u64, HashSet<PathBuf>>,
+}
+
+impl EmittedPaths {
+    fn contains(&self, path: &Path) -> bool {
+        self.by_emitter.values().any(|paths| paths.contains(path))
+    }
+
+    /// The emitted paths below `dir`, relative to it.
+    fn relative_to(&self, dir: &Path) -> BTreeSet<PathBuf> {
+        self.by_emitter
+            .values()
+            .flatten()
+            .filter_map(|path| path.strip_prefix(dir).ok())
+            .map(Path::to_path_buf)
+            .collect()
+    }
+}
+
+/// The file in an owned output directory that lists the files emitted into
+/// it, relative to it. A later process only deletes the files listed there.
+const EMITTED_PATHS_MANIFEST: &str = ".turbopack-emitted";
+
+/// Deletes the files of `dir` that are listed in its manifest of emitted paths
+/// but are not `emitted` anymore, removes directories that end up empty, and
+/// replaces the manifest with the `emitted` paths. Files that are not listed
+/// are never deleted. Returns the paths of the deleted files.
+fn remove_unemitted_files(dir: &Path, emitted: &BTreeSet<PathBuf>) -> io::Result<Vec<PathBuf>> {
+    let manifest_path = dir.join(EMITTED_PATHS_MANIFEST);
+    let manifest = match std::fs::read_to_string(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let mut removed = Vec::new();
+    for line in manifest.lines() {
+        let path = Path::new(line);
+        // Only plain relative paths are listed, anything else has been edited
+        // by hand and is ignored.
+        if line.is_empty()
+            || !path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            || emitted.contains(path)
+        {
+            continue;
+        }
+        let full_path = dir.join(path);
+        match std::fs::remove_file(&full_path) {
+            Ok(()) => removed.push(full_path.clone()),
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        // Removing a directory fails while it contains other files.
+        for parent in full_path.ancestors().skip(1) {
+            if parent == dir || std::fs::remove_dir(parent).is_err() {
+                break;
+            }
+        }
+    }
+    if emitted.is_empty() {
+        if let Err(err) = std::fs::remove_file(&manifest_path) {
+            if err.kind() != ErrorKind::NotFound {
+                return Err(err);
+            }
+        }
+    } else {
+        std::fs::create_dir_all(dir)?;
+        let mut manifest = String::new();
+        for path in emitted {
+            manifest.push_str(&sys_to_unix(&path.to_string_lossy()));
+            manifest.push('\n');
+        }
+        std::fs::write(&manifest_path, manifest)?;
+    }
+    Ok(removed)
+}
+
 #[turbo_tasks::value_impl]
 impl DiskFileSystem {
     /// Create a new instance of `DiskFileSystem`.
@@ -429,19 +669,62 @@ impl DiskFileSystem {
     pub async fn new(name: RcStr, root: RcStr, ignored_subpaths: Vec<RcStr>) -> Result<Vc<Self>> {
         mark_stateful();
 
-        let instance = DiskFileSystem {
+        Ok(Self::cell(Self::new_instance(
             name,
             root,
-            mutex_map: Default::default(),
-            invalidation_lock: Default::default(),
-            invalidator_map: Arc::new(InvalidatorMap::new()),
-            dir_invalidator_map: Arc::new(InvalidatorMap::new()),
-            watcher: Arc::new(DiskWatcher::new(
-                ignored_subpaths.into_iter().map(PathBuf::from).collect(),
-            )),
-        };
+            &WatchIgnoreOptions {
+                ignored_subpaths,
+                ..Default::default()
+            },
+            None,
+        )?))
+    }
+
+    /// Create a new instance of `DiskFileSystem` whose watcher ignores changes
+    /// to the paths selected by `watch_ignores`, e.g. glob patterns or the
+    /// `.gitignore` files in the root.
+    ///
+    /// * `name` - Name of the filesystem.
+    /// * `root` - Path to the given filesystem's root.
+    #[turbo_tasks::function]
+    pub async fn new_with_watch_ignores(
+        name: RcStr,
+        root: RcStr,
+        watch_ignores: Vc<WatchIgnoreOptions>,
+    ) -> Result<Vc<Self>> {
+        mark_stateful();
+
+        Ok(Self::cell(Self::new_instance(
+            name,
+            root,
+            &*watch_ignores.await?,
+            None,
+        )?))
+    }
+
+    /// Create a new instance of `DiskFileSystem` that owns its output
+    /// directory. The paths emitted into it are recorded with
+    /// [DiskFileSystem::update_emitted_paths], so that other files can be
+    /// deleted as stale output.
+    ///
+    /// Takes the same arguments as [DiskFileSystem::new].
+    #[turbo_tasks::function]
+    pub async fn new_owned_output(
+        name: RcStr,
+        root: RcStr,
+        ignored_subpaths: Vec<RcStr>,
+    ) -> Result<Vc<Self>> {
+        mark_stateful();
 
-        Ok(Self::cell(instance))
+        Ok(Self::cell(Self::new_instance(
+            name,
+            root,
+            &WatchIgnoreOptions {
+                ignored_subpaths,
+                ..Default::default()
+            },
+            Some(Default::default()),
+        )?))
     }
 
     #[turbo_tasks::function(fs)]
@@ -729,10 +1012,7 @@ impl FileSystem for DiskFileSystem {
                 retry_future(move || {
                     let full_path = full_path_to_write.clone();
                     async move {
-                        let mut f = fs::File::create(&full_path).await?;
-                        tokio::io::copy(&mut file.read(), &mut f).await?;
-                        #[cfg(target_family = "unix")]
-                        f.set_permissions(file.meta.permissions.into()).await?;
+                        write_file_atomically(&full_path, file).await?;
                         #[cfg(feature = "write_version")]
                         {
                             let mut full_path = full_path;
@@ -744,10 +1024,7 @@ impl FileSystem for DiskFileSystem {
                                 format!("{:016x}", hash)
                             };
                             full_path.set_extension(ext);
-                            let mut f = fs::File::create(&full_path).await?;
-                            tokio::io::copy(&mut file.read(), &mut f).await?;
-                            #[cfg(target_family = "unix")]
-                            f.set_permissions(file.meta.permissions.into()).await?;
+                            write_file_atomically(&full_path, file).await?;
                         }
                         Ok::<(), io::Error>(())
                     }
@@ -2396,4 +2673,79 @@ mod tests {
         .await
         .unwrap()
     }
+
+    #[tokio::test]
+    async fn write_file_atomically_replaces_content() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("chunk.js");
+        std::fs::write(&path, "old")?;
+
+        write_file_atomically(&path, &File::from("new")).await?;
+
+        assert_eq!(std::fs::read_to_string(&path)?, "new");
+        // the temporary file has been renamed into place
+        assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn temp_path_is_unique_sibling() {
+        let path = Path::new("dist").join("chunk.js");
+        let a = temp_path_for(&path);
+        let b = temp_path_for(&path);
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), path.parent());
+        let name = a.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with(".chunk.js.") && name.ends_with(".tmp"));
+    }
+
+    #[test]
+    fn remove_unemitted_files_only_removes_listed_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("chunks"))?;
+        std::fs::create_dir_all(root.join("old/nested"))?;
+        std::fs::write(root.join("index.js"), "")?;
+        std::fs::write(root.join("chunks/a.js"), "")?;
+        std::fs::write(root.join("chunks/stale.js"), "")?;
+        std::fs::write(root.join("old/nested/stale.js"), "")?;
+        std::fs::write(root.join("untracked.txt"), "")?;
+        std::fs::write(
+            root.join(EMITTED_PATHS_MANIFEST),
+            "index.js\nchunks/a.js\nchunks/stale.js\nold/nested/stale.js\n../outside.js\n",
+        )?;
+
+        let emitted = BTreeSet::from([PathBuf::from("index.js"), PathBuf::from("chunks/a.js")]);
+        let mut removed = remove_unemitted_files(root, &emitted)?;
+
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![
+                root.join("chunks/stale.js"),
+                root.join("old/nested/stale.js")
+            ]
+        );
+        assert!(root.join("index.js").exists());
+        assert!(root.join("chunks/a.js").exists());
+        assert!(root.join("untracked.txt").exists());
+        assert!(!root.join("old").exists());
+        assert_eq!(
+            std::fs::read_to_string(root.join(EMITTED_PATHS_MANIFEST))?,
+            "chunks/a.js\nindex.js\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_unemitted_files_without_manifest_removes_nothing() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::write(root.join("stale.js"), "")?;
+
+        assert!(remove_unemitted_files(root, &BTreeSet::new())?.is_empty());
+        assert!(root.join("stale.js").exists());
+        assert!(!root.join(EMITTED_PATHS_MANIFEST).exists());
+        Ok(())
+    }
 }
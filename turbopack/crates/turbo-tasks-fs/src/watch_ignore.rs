@@ -0,0 +1,208 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use ignore::{gitignore::Gitignore, Match};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::RcStr;
+
+use crate::{glob::Glob, util::sys_to_unix};
+
+/// Configures which changes on disk are ignored by the watcher of a
+/// [crate::DiskFileSystem]. Ignored paths don't invalidate any reads, and on
+/// Linux ignored directories are not watched at all.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default, Clone)]
+pub struct WatchIgnoreOptions {
+    /// Full paths of directories to ignore. Since it is possible that the
+    /// root and the project dir are different, these are not relative to the
+    /// root.
+    pub ignored_subpaths: Vec<RcStr>,
+    /// Glob patterns relative to the root, e.g. `**/*.swp` or `**/.turbo`. A
+    /// directory matching a pattern is ignored with everything inside it.
+    pub ignored_globs: Vec<RcStr>,
+    /// Also ignores everything excluded by the `.gitignore` files inside the
+    /// root, and the `.git` directory.
+    pub respect_gitignore: bool,
+}
+
+/// The compiled form of [WatchIgnoreOptions].
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct WatchIgnores {
+    root: PathBuf,
+    subpaths: Vec<PathBuf>,
+    globs: Vec<Glob>,
+    respect_gitignore: bool,
+    /// The parsed `.gitignore` file of every directory checked so far, `None`
+    /// when the directory has none.
+    #[serde(skip)]
+    gitignores: DashMap<PathBuf, Option<Arc<Gitignore>>>,
+}
+
+impl WatchIgnores {
+    pub(crate) fn new(root: &Path, options: &WatchIgnoreOptions) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            subpaths: options
+                .ignored_subpaths
+                .iter()
+                .map(|path| PathBuf::from(&**path))
+                .collect(),
+            globs: options
+                .ignored_globs
+                .iter()
+                .map(|glob| {
+                    // `dir/**` is equivalent to `dir`, as everything inside an ignored directory
+                    // is ignored anyway.
+                    let glob = glob.strip_suffix("/**").unwrap_or(glob);
+                    Glob::parse(glob.trim_start_matches("./"))
+                })
+                .collect::<Result<_>>()?,
+            respect_gitignore: options.respect_gitignore,
+            gitignores: Default::default(),
+        })
+    }
+
+    /// Returns whether changes to `path` should be ignored. `is_dir` is only
+    /// relevant for `.gitignore` patterns that match directories only.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self
+            .subpaths
+            .iter()
+            .any(|ignored| path.starts_with(ignored))
+        {
+            return true;
+        }
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+        if !self.globs.is_empty() {
+            let relative = sys_to_unix(&relative.to_string_lossy()).into_owned();
+            // Check the path and all its ancestors, so that matching a directory ignores its
+            // contents.
+            let mut current = relative.as_str();
+            loop {
+                if self.globs.iter().any(|glob| glob.execute(current)) {
+                    return true;
+                }
+                match current.rsplit_once('/') {
+                    Some((parent, _)) => current = parent,
+                    None => break,
+                }
+            }
+        }
+        if self.respect_gitignore {
+            if relative.starts_with(".git") {
+                return true;
+            }
+            return self.is_gitignored(path, is_dir);
+        }
+        false
+    }
+
+    /// Drops the cached `.gitignore` of a directory after it has changed.
+    pub(crate) fn invalidate_gitignore(&self, path: &Path) {
+        if self.respect_gitignore && path.file_name().is_some_and(|name| name == ".gitignore") {
+            if let Some(dir) = path.parent() {
+                self.gitignores.remove(dir);
+            }
+        }
+    }
+
+    fn is_gitignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Deeper `.gitignore` files take precedence over the ones in their parents.
+        for dir in path.ancestors().skip(1) {
+            if let Some(gitignore) = self.gitignore(dir) {
+                match gitignore.matched_path_or_any_parents(path, is_dir) {
+                    Match::None => {}
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                }
+            }
+            if dir == self.root {
+                break;
+            }
+        }
+        false
+    }
+
+    fn gitignore(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        if let Some(gitignore) = self.gitignores.get(dir) {
+            return gitignore.clone();
+        }
+        let file = dir.join(".gitignore");
+        let gitignore = if file.is_file() {
+            // Invalid lines are skipped, the remaining patterns still apply.
+            let (gitignore, _err) = Gitignore::new(&file);
+            Some(Arc::new(gitignore))
+        } else {
+            None
+        };
+        self.gitignores.insert(dir.to_path_buf(), gitignore.clone());
+        gitignore
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignores(root: &Path, globs: &[&str], respect_gitignore: bool) -> WatchIgnores {
+        WatchIgnores::new(
+            root,
+            &WatchIgnoreOptions {
+                ignored_subpaths: vec![root.join("generated").to_string_lossy().into()],
+                ignored_globs: globs.iter().map(|glob| (*glob).into()).collect(),
+                respect_gitignore,
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn subpaths_and_globs() {
+        let root = Path::new("/project");
+        let ignores = ignores(root, &["**/*.swp", "**/.turbo", "coverage/**"], false);
+
+        assert!(ignores.is_ignored(&root.join("generated/types.ts"), false));
+        assert!(ignores.is_ignored(&root.join("src/.index.ts.swp"), false));
+        assert!(ignores.is_ignored(&root.join("apps/web/.turbo"), true));
+        assert!(ignores.is_ignored(&root.join("apps/web/.turbo/cache/log"), false));
+        assert!(ignores.is_ignored(&root.join("coverage"), true));
+        assert!(ignores.is_ignored(&root.join("coverage/lcov.info"), false));
+        assert!(!ignores.is_ignored(&root.join("src/index.ts"), false));
+        assert!(!ignores.is_ignored(root, true));
+        assert!(!ignores.is_ignored(Path::new("/other/index.swp"), false));
+    }
+
+    #[test]
+    fn gitignore() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("packages/a"))?;
+        std::fs::write(root.join(".gitignore"), "dist/\n*.log\n")?;
+        std::fs::write(root.join("packages/a/.gitignore"), "!keep.log\nout\n")?;
+
+        let ignores = ignores(root, &[], true);
+        assert!(ignores.is_ignored(&root.join(".git/index"), false));
+        assert!(ignores.is_ignored(&root.join("dist"), true));
+        assert!(ignores.is_ignored(&root.join("dist/index.js"), false));
+        assert!(ignores.is_ignored(&root.join("packages/a/debug.log"), false));
+        assert!(!ignores.is_ignored(&root.join("packages/a/keep.log"), false));
+        assert!(ignores.is_ignored(&root.join("packages/a/out/index.js"), false));
+        assert!(!ignores.is_ignored(&root.join("packages/b/out/index.js"), false));
+        assert!(!ignores.is_ignored(&root.join("src/index.ts"), false));
+
+        // changes to a `.gitignore` are picked up once it's invalidated
+        std::fs::write(root.join(".gitignore"), "*.log\n")?;
+        ignores.invalidate_gitignore(&root.join(".gitignore"));
+        assert!(!ignores.is_ignored(&root.join("dist/index.js"), false));
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@ use crate::{
     invalidation::{WatchChange, WatchStart},
     invalidator_map::InvalidatorMap,
     path_to_key,
+    watch_ignore::WatchIgnores,
 };
 
 enum DiskWatcherInternal {
@@ -46,11 +47,11 @@ pub(crate) struct DiskWatcher {
     #[serde(skip)]
     watcher: Mutex<Option<DiskWatcherInternal>>,
 
-    /// Array of paths that should not notify invalidations.
-    /// `notify` currently doesn't support unwatching subpaths from the root,
-    /// so underlying we still watches filesystem event but only skips to
-    /// invalidate.
-    ignored_subpaths: Vec<PathBuf>,
+    /// Paths that should not notify invalidations. On Linux ignored
+    /// directories are not watched at all. Elsewhere `notify` watches the root
+    /// recursively and doesn't support unwatching subpaths, so underlying we
+    /// still receive their events but skip invalidating.
+    ignores: WatchIgnores,
 
     /// Keeps track of which directories are currently watched. This is only
     /// used on OSs that doesn't support recursive watching.
@@ -60,9 +61,9 @@ pub(crate) struct DiskWatcher {
 }
 
 impl DiskWatcher {
-    pub(crate) fn new(ignored_subpaths: Vec<PathBuf>) -> Self {
+    pub(crate) fn new(ignores: WatchIgnores) -> Self {
         Self {
-            ignored_subpaths,
+            ignores,
             ..Default::default()
         }
     }
@@ -78,7 +79,7 @@ impl DiskWatcher {
 
     #[cfg(not(any(target_os = "macos", target_os = "windows")))]
     pub(crate) fn ensure_watching(&self, dir_path: &Path, root_path: &Path) -> Result<()> {
-        if self.watching.contains(dir_path) {
+        if self.watching.contains(dir_path) || self.ignores.is_ignored(dir_path, true) {
             return Ok(());
         }
         let mut watcher = self.watcher.lock().unwrap();
@@ -266,19 +267,29 @@ impl DiskWatcher {
             loop {
                 match event {
                     Ok(Ok(notify::Event { kind, paths, .. })) => {
-                        let paths: Vec<PathBuf> = paths
+                        paths
                             .iter()
-                            .filter(|p| {
-                                !self
-                                    .ignored_subpaths
-                                    .iter()
-                                    .any(|ignored| p.starts_with(ignored))
-                            })
-                            .cloned()
-                            .collect();
+                            .for_each(|path| self.ignores.invalidate_gitignore(path));
+                        let is_ignored =
+                            |path: &PathBuf| self.ignores.is_ignored(path, path.is_dir());
+                        let paths: Vec<PathBuf> = if matches!(
+                            kind,
+                            EventKind::Modify(ModifyKind::Name(RenameMode::Both))
+                        ) {
+                            // Keep both sides of a rename, e.g. a temporary file that is
+                            // renamed into place, unless both of them are ignored.
+                            if paths.iter().all(is_ignored) {
+                                Vec::new()
+                            } else {
+                                paths
+                            }
+                        } else {
+                            paths.into_iter().filter(|path| !is_ignored(path)).collect()
+                        };
 
                         if paths.is_empty() {
-                            return;
+                            event = rx.try_recv();
+                            continue;
                         }
 
                         // [NOTE] there is attrs in the `Event` struct, which contains few
@@ -55,6 +55,10 @@ pub enum ReadContentError {
 }
 
 impl Cell {
+    pub fn dependent_tasks(&self) -> &TaskIdSet {
+        &self.dependent_tasks
+    }
+
     /// Removes a task from the list of dependent tasks.
     pub fn remove_dependent_task(&mut self, task: TaskId) {
         self.dependent_tasks.remove(&task);
@@ -1,5 +1,6 @@
 use std::{
     borrow::{Borrow, Cow},
+    collections::HashSet,
     future::Future,
     hash::{BuildHasher, BuildHasherDefault, Hash},
     num::NonZeroU32,
@@ -23,6 +24,7 @@ use turbo_tasks::{
         TransientTaskType, TypedCellContent,
     },
     event::EventListener,
+    task_graph::TaskGraph,
     util::{IdFactoryWithReuse, NoMoveVec},
     CellId, FunctionId, RawVc, ReadConsistency, TaskId, TaskIdSet, TraitTypeId,
     TurboTasksBackendApi, Unused, ValueTypeId, TRANSIENT_TASK_BIT,
@@ -450,6 +452,30 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| task.get_description())
     }
 
+    fn task_graph(&self) -> Option<TaskGraph> {
+        // Root and once tasks are not cached, they are found as dependents of other tasks.
+        let mut queue = Vec::new();
+        self.with_all_cached_tasks(|task| queue.push(task));
+        let mut visited: HashSet<TaskId> = queue.iter().copied().collect();
+        let mut graph = TaskGraph::default();
+        while let Some(id) = queue.pop() {
+            let (node, dependents) = self.with_task(id, |task| task.graph_node());
+            for task in node
+                .dependencies
+                .iter()
+                .map(|edge| edge.task)
+                .chain(dependents)
+            {
+                if visited.insert(task) {
+                    queue.push(task);
+                }
+            }
+            graph.tasks.push(node);
+        }
+        graph.tasks.sort_by_key(|node| node.id);
+        Some(graph)
+    }
+
     type TaskState = TaskState;
     fn new_task_state(&self, _task: TaskId) -> Self::TaskState {
         TaskState {
@@ -21,8 +21,12 @@ use turbo_prehash::PreHashed;
 use turbo_tasks::{
     backend::{CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec},
     event::{Event, EventListener},
-    get_invalidator, registry, CellId, Invalidator, RawVc, ReadConsistency, TaskId, TaskIdSet,
-    TraitTypeId, TurboTasksBackendApi, TurboTasksBackendApiExt, ValueTypeId,
+    get_invalidator, registry,
+    task_graph::{
+        TaskGraphCells, TaskGraphCollectibles, TaskGraphEdge, TaskGraphEdgeKind, TaskGraphNode,
+    },
+    CellId, Invalidator, RawVc, ReadConsistency, TaskId, TaskIdSet, TraitTypeId,
+    TurboTasksBackendApi, TurboTasksBackendApiExt, ValueTypeId,
 };
 
 use crate::{
@@ -1524,6 +1528,87 @@ impl Task {
         }
     }
 
+    /// Describes the task for [MemoryBackend::task_graph]. Also returns the
+    /// tasks that depend on the output or cells of this task.
+    pub(crate) fn graph_node(&self) -> (TaskGraphNode, Vec<TaskId>) {
+        let mut node = TaskGraphNode {
+            id: self.id,
+            description: self.get_description(),
+            state: "unloaded".to_string(),
+            cells: Vec::new(),
+            dependencies: Vec::new(),
+            collectibles: Vec::new(),
+        };
+        let mut dependents = Vec::new();
+        let TaskMetaStateReadGuard::Full(state) = self.state() else {
+            return (node, dependents);
+        };
+        node.state = Task::state_string(&state).to_string();
+        for (&type_id, cells) in state.cells.iter() {
+            node.cells.push(TaskGraphCells {
+                value_type: registry::get_value_type(type_id).name.clone(),
+                count: cells.len(),
+            });
+            for cell in cells {
+                dependents.extend(cell.dependent_tasks().iter().copied());
+            }
+        }
+        dependents.extend(state.output.dependent_tasks.iter().copied());
+        let edges: Box<dyn Iterator<Item = TaskEdge> + '_> = match &state.state_type {
+            Done { edges, .. } => Box::new(edges.iter()),
+            InProgress(box InProgressState {
+                outdated_edges,
+                new_children,
+                ..
+            }) => Box::new(
+                outdated_edges
+                    .iter()
+                    .chain(new_children.iter().map(|&child| TaskEdge::Child(child))),
+            ),
+            Dirty { outdated_edges } => Box::new(outdated_edges.iter()),
+            Scheduled(box ScheduledState { outdated_edges, .. }) => Box::new(outdated_edges.iter()),
+        };
+        node.dependencies = edges
+            .map(|edge| match edge {
+                TaskEdge::Output(task) => TaskGraphEdge {
+                    task,
+                    kind: TaskGraphEdgeKind::Output,
+                },
+                TaskEdge::Cell(task, CellId { type_id, index }) => TaskGraphEdge {
+                    task,
+                    kind: TaskGraphEdgeKind::Cell {
+                        value_type: registry::get_value_type(type_id).name.clone(),
+                        index,
+                    },
+                },
+                TaskEdge::Collectibles(task, trait_type) => TaskGraphEdge {
+                    task,
+                    kind: TaskGraphEdgeKind::Collectibles {
+                        trait_type: registry::get_trait(trait_type).name.clone(),
+                    },
+                },
+                TaskEdge::Child(task) => TaskGraphEdge {
+                    task,
+                    kind: TaskGraphEdgeKind::Child,
+                },
+            })
+            .collect();
+        if let Some(collectibles) = state.collectibles.as_ref() {
+            let mut counts = AutoMap::<TraitTypeId, i32>::new();
+            for (&(trait_type, _), &count) in collectibles.iter() {
+                *counts.entry(trait_type).or_default() += count;
+            }
+            node.collectibles = counts
+                .into_iter()
+                .map(|(trait_type, count)| TaskGraphCollectibles {
+                    trait_type: registry::get_trait(trait_type).name.clone(),
+                    count,
+                })
+                .collect();
+        }
+        (node, dependents)
+    }
+
     fn state_string(state: &TaskState) -> &'static str {
         match state.state_type {
             Scheduled { .. } => "scheduled",
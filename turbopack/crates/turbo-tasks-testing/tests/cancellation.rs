@@ -0,0 +1,100 @@
+#![feature(arbitrary_self_types)]
+#![feature(arbitrary_self_types_pointers)]
+#![allow(clippy::needless_return)] // tokio macro-generated code doesn't respect this
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use anyhow::Result;
+use turbo_tasks::{check_cancelled, State, Vc};
+use turbo_tasks_testing::{register, run, Registration};
+
+static REGISTRATION: Registration = register!();
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+static COMPLETED: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn invalidate_while_running() {
+    run(&REGISTRATION, || async {
+        let started = STARTED.load(Ordering::SeqCst);
+        let completed = COMPLETED.load(Ordering::SeqCst);
+
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let input_val = input.await?;
+        let output = counted_compute(input);
+        let read = async { Ok(*output.strongly_consistent().await?) };
+        let invalidate = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            input_val.state.set(2);
+            anyhow::Ok(())
+        };
+        let (value, ()) = tokio::try_join!(read, invalidate)?;
+        assert_eq!(value, 2);
+
+        // The first execution stopped early and its result has been dropped.
+        assert_eq!(STARTED.load(Ordering::SeqCst) - started, 2);
+        assert_eq!(COMPLETED.load(Ordering::SeqCst) - completed, 1);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn read_during_cancel() {
+    run(&REGISTRATION, || async {
+        let input = ChangingInput {
+            state: State::new(1),
+        }
+        .cell();
+        let input_val = input.await?;
+        let output = compute(input);
+        // Waits for the execution that is canceled.
+        let first = async { Ok(*output.await?) };
+        let second = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            input_val.state.set(2);
+            // Starts reading while the canceled execution is still running.
+            anyhow::Ok(*output.await?)
+        };
+        let (first, second) = tokio::try_join!(first, second)?;
+        assert_eq!(first, 2);
+        assert_eq!(second, 2);
+        anyhow::Ok(())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value]
+struct ChangingInput {
+    state: State<u32>,
+}
+
+#[turbo_tasks::function]
+async fn counted_compute(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    STARTED.fetch_add(1, Ordering::SeqCst);
+    let value = slow_read(input).await?;
+    COMPLETED.fetch_add(1, Ordering::SeqCst);
+    Ok(Vc::cell(value))
+}
+
+#[turbo_tasks::function]
+async fn compute(input: Vc<ChangingInput>) -> Result<Vc<u32>> {
+    Ok(Vc::cell(slow_read(input).await?))
+}
+
+async fn slow_read(input: Vc<ChangingInput>) -> Result<u32> {
+    let value = *input.await?.state.get();
+    for _ in 0..20 {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        check_cancelled()?;
+    }
+    Ok(value)
+}
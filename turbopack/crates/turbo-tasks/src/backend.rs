@@ -21,6 +21,7 @@ use crate::{
     raw_vc::CellId,
     registry,
     task::shared_reference::TypedSharedReference,
+    task_graph::TaskGraph,
     trait_helpers::{get_trait_method, has_trait, traits},
     triomphe_utils::unchecked_sidecast_triomphe_arc,
     FunctionId, RawVc, ReadRef, SharedReference, TaskId, TaskIdSet, TaskPersistence, TraitRef,
@@ -551,6 +552,12 @@ pub trait Backend: Sync + Send {
 
     fn get_task_description(&self, task: TaskId) -> String;
 
+    /// Returns a snapshot of the task graph for debugging. Backends that can't
+    /// enumerate their tasks return `None`.
+    fn task_graph(&self) -> Option<TaskGraph> {
+        None
+    }
+
     /// Task-local state that stored inside of [`TurboTasksBackendApi`]. Constructed with
     /// [`Self::new_task_state`].
     ///
@@ -0,0 +1,259 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    sync::atomic::{fence, AtomicUsize, Ordering},
+};
+
+use dashmap::{DashMap, DashSet};
+use parking_lot::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::TaskId;
+
+/// The error returned by [crate::check_cancelled] and by task executions that
+/// were canceled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCanceled;
+
+impl Display for TaskCanceled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the computation was canceled because its result is no longer needed"
+        )
+    }
+}
+
+impl std::error::Error for TaskCanceled {}
+
+/// Tracks which executions can be canceled cooperatively.
+///
+/// An execution is canceled when the task is invalidated while it is running,
+/// as the result will be recomputed anyway, or when all root tasks it is
+/// executed for have been canceled or disposed. The result of a canceled
+/// execution is never stored, the task is executed again instead.
+///
+/// Tasks are executed for the root tasks of the tasks that schedule them or
+/// wait for them. Tasks scheduled or read outside of any task, e.g. by the
+/// file watcher, are never canceled for a root task. Tasks that are only
+/// needed by canceled root tasks are parked instead of executed, until
+/// another task reads them.
+#[derive(Default)]
+pub(crate) struct CancellationTracker {
+    /// The root tasks each scheduled task is executed for.
+    owners: DashMap<TaskId, Vec<TaskId>>,
+    roots: DashSet<TaskId>,
+    canceled_roots: DashSet<TaskId>,
+    /// The tokens of the currently running executions that can be canceled.
+    running: DashMap<TaskId, CancellationToken>,
+    /// Scheduled tasks that are not executed, as they are only needed by
+    /// canceled root tasks.
+    parked: Mutex<HashSet<TaskId>>,
+    /// The number of tasks in `parked`, or about to be added to it. Lets
+    /// [CancellationTracker::unpark] skip the lock when nothing is parked.
+    parked_len: AtomicUsize,
+}
+
+impl CancellationTracker {
+    pub(crate) fn add_root(&self, root: TaskId) {
+        self.roots.insert(root);
+        self.canceled_roots.remove(&root);
+        self.owners.insert(root, vec![root]);
+    }
+
+    pub(crate) fn is_root(&self, task: TaskId) -> bool {
+        self.roots.contains(&task)
+    }
+
+    /// Marks `task` as executed for the same root tasks as `from`.
+    pub(crate) fn inherit(&self, task: TaskId, from: TaskId) {
+        if task == from {
+            return;
+        }
+        let Some(roots) = self.owners.get(&from).map(|roots| roots.clone()) else {
+            // `from` is never canceled, so `task` must not be canceled either.
+            self.detach(task);
+            return;
+        };
+        let mut owners = self.owners.entry(task).or_default();
+        for root in roots {
+            if !owners.contains(&root) {
+                owners.push(root);
+            }
+        }
+    }
+
+    /// Marks `task` as needed outside of any root task, so it's never canceled
+    /// for a root task.
+    pub(crate) fn detach(&self, task: TaskId) {
+        if !self.roots.contains(&task) {
+            self.owners.remove(&task);
+        }
+    }
+
+    fn is_canceled(&self, task: TaskId) -> bool {
+        self.owners.get(&task).is_some_and(|roots| {
+            !roots.is_empty() && roots.iter().all(|root| self.canceled_roots.contains(root))
+        })
+    }
+
+    /// Creates the cancellation token for an execution of `task`.
+    pub(crate) fn start(&self, task: TaskId) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.running.insert(task, token.clone());
+        token
+    }
+
+    /// Called when an execution of `task` has finished, or when its result
+    /// is already being read while it's still running, e.g. because of
+    /// [crate::mark_finished]. The execution can't be canceled afterwards.
+    pub(crate) fn finish(&self, task: TaskId) {
+        self.running.remove(&task);
+    }
+
+    /// Called when `task` won't be executed again until it is scheduled again.
+    pub(crate) fn task_completed(&self, task: TaskId) {
+        if !self.roots.contains(&task) {
+            self.owners.remove(&task);
+        }
+    }
+
+    /// Parks `task` instead of executing it when it's only needed by canceled
+    /// root tasks. Returns `true` when it has been parked.
+    pub(crate) fn park(&self, task: TaskId) -> bool {
+        // Most tasks are not canceled, they don't need the lock.
+        if self.roots.contains(&task) || !self.is_canceled(task) {
+            return false;
+        }
+        let mut parked = self.parked.lock();
+        // Counted before checking again, so an `unpark` after an `inherit`
+        // that this check misses takes the lock and finds the task.
+        self.parked_len.fetch_add(1, Ordering::SeqCst);
+        if !self.is_canceled(task) || !parked.insert(task) {
+            self.parked_len.fetch_sub(1, Ordering::SeqCst);
+            return false;
+        }
+        true
+    }
+
+    /// Returns `true` when `task` has been parked and is needed again, so it
+    /// has to be scheduled.
+    pub(crate) fn unpark(&self, task: TaskId) -> bool {
+        // Orders the preceding `inherit` or `detach` before the load, pairing
+        // with the increment in `park`.
+        fence(Ordering::SeqCst);
+        if self.parked_len.load(Ordering::SeqCst) == 0 {
+            return false;
+        }
+        let mut parked = self.parked.lock();
+        if self.is_canceled(task) || !parked.remove(&task) {
+            return false;
+        }
+        self.parked_len.fetch_sub(1, Ordering::SeqCst);
+        true
+    }
+
+    /// Cancels the running execution of `task`, if any.
+    pub(crate) fn cancel_execution(&self, task: TaskId) {
+        if let Some(token) = self.running.get(&task) {
+            token.cancel();
+        }
+    }
+
+    /// Cancels the running executions of all tasks that are only executed for
+    /// canceled root tasks.
+    pub(crate) fn cancel_root(&self, root: TaskId) {
+        if !self.roots.contains(&root) {
+            return;
+        }
+        self.canceled_roots.insert(root);
+        for entry in self.running.iter() {
+            if self.is_canceled(*entry.key()) {
+                entry.value().cancel();
+            }
+        }
+    }
+
+    /// Called when a root task has been disposed.
+    pub(crate) fn remove_root(&self, root: TaskId) {
+        self.roots.remove(&root);
+        self.owners.remove(&root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancels_exclusively_owned_tasks() {
+        let tracker = CancellationTracker::default();
+        let (root_a, root_b) = (TaskId::from(1), TaskId::from(2));
+        let (exclusive, shared, unowned) = (TaskId::from(3), TaskId::from(4), TaskId::from(5));
+        tracker.add_root(root_a);
+        tracker.add_root(root_b);
+        tracker.inherit(exclusive, root_a);
+        tracker.inherit(shared, root_a);
+        tracker.inherit(shared, root_b);
+        tracker.inherit(unowned, TaskId::from(6));
+
+        let exclusive_token = tracker.start(exclusive);
+        let shared_token = tracker.start(shared);
+        let unowned_token = tracker.start(unowned);
+
+        tracker.cancel_root(root_a);
+        assert!(exclusive_token.is_cancelled());
+        assert!(!shared_token.is_cancelled());
+        assert!(!unowned_token.is_cancelled());
+
+        // root tasks are never parked, nothing would schedule them again
+        assert!(!tracker.park(root_a));
+
+        tracker.cancel_root(root_b);
+        assert!(shared_token.is_cancelled());
+        assert!(!unowned_token.is_cancelled());
+    }
+
+    #[test]
+    fn parks_until_needed_again() {
+        let tracker = CancellationTracker::default();
+        let (root_a, root_b) = (TaskId::from(1), TaskId::from(2));
+        let (task, other) = (TaskId::from(3), TaskId::from(4));
+        tracker.add_root(root_a);
+        tracker.add_root(root_b);
+        tracker.inherit(task, root_a);
+        tracker.inherit(other, root_a);
+        tracker.cancel_root(root_a);
+
+        assert!(tracker.park(task));
+        assert!(tracker.park(other));
+        assert!(!tracker.unpark(task));
+
+        // a task of a live root task reads it
+        tracker.inherit(task, root_b);
+        assert!(tracker.unpark(task));
+        assert!(!tracker.park(task));
+        assert!(!tracker.unpark(task));
+
+        // it's read outside of any task
+        tracker.detach(other);
+        assert!(tracker.unpark(other));
+        assert_eq!(tracker.parked_len.load(Ordering::SeqCst), 0);
+        assert!(!tracker.unpark(other));
+    }
+
+    #[test]
+    fn cancels_invalidated_execution() {
+        let tracker = CancellationTracker::default();
+        let task = TaskId::from(1);
+        let token = tracker.start(task);
+        tracker.cancel_execution(task);
+        assert!(token.is_cancelled());
+
+        tracker.finish(task);
+        let token = tracker.start(task);
+        tracker.finish(task);
+        tracker.cancel_execution(task);
+        assert!(!token.is_cancelled());
+    }
+}
@@ -20,7 +20,7 @@ use futures::FutureExt;
 use rustc_hash::FxHasher;
 use serde::{Deserialize, Serialize};
 use tokio::{runtime::Handle, select, task_local};
-use tokio_util::task::TaskTracker;
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 use tracing::{info_span, instrument, trace_span, Instrument, Level};
 use turbo_tasks_malloc::TurboMalloc;
 
@@ -29,6 +29,7 @@ use crate::{
         Backend, CachedTaskType, CellContent, TaskCollectiblesMap, TaskExecutionSpec,
         TransientTaskType, TypedCellContent,
     },
+    cancellation::{CancellationTracker, TaskCanceled},
     capture_future::{self, CaptureFuture},
     event::{Event, EventListener},
     id::{
@@ -37,10 +38,12 @@ use crate::{
     },
     id_factory::{IdFactory, IdFactoryWithReuse},
     magic_any::MagicAny,
+    priority::{PriorityScheduler, TaskPriority},
     raw_vc::{CellId, RawVc},
     registry::{self, get_function},
     serialization_invalidation::SerializationInvalidator,
     task::shared_reference::TypedSharedReference,
+    task_graph::{InvalidationCause, InvalidationLog, InvalidationStep, TaskGraph},
     trace::TraceRawVcs,
     trait_helpers::get_trait_method,
     util::StaticOrArc,
@@ -122,6 +125,12 @@ pub trait TurboTasksApi: TurboTasksCallApi + Sync + Send {
     /// `schedule_notify_tasks_set()`
     fn notify_scheduled_tasks(&self);
 
+    /// Called when the current task, or code outside of any task, waits for
+    /// `task`. Prevents `task` from being canceled while it's still needed,
+    /// schedules it again when it has been parked, and raises it to the
+    /// priority of the current task when it's still waiting to start.
+    fn inherit_from_current_task(&self, _task: TaskId) {}
+
     fn try_read_task_output(
         &self,
         task: TaskId,
@@ -378,6 +387,9 @@ pub struct TurboTasks<B: Backend + 'static> {
     event_foreground: Event,
     event_background: Event,
     program_start: Instant,
+    cancellation: CancellationTracker,
+    invalidation_log: InvalidationLog,
+    priority_scheduler: PriorityScheduler,
 }
 
 /// Information about a "global" task. A global task can contain multiple "local" tasks (see
@@ -391,6 +403,13 @@ pub struct TurboTasks<B: Backend + 'static> {
 struct CurrentGlobalTaskState {
     task_id: TaskId,
 
+    /// The priority the task is executed with. Tasks scheduled or read by this
+    /// task inherit it.
+    priority: TaskPriority,
+
+    /// Canceled when the result of this execution is no longer needed.
+    cancellation_token: CancellationToken,
+
     /// Affected tasks, that are tracked during task execution. These tasks will
     /// be invalidated when the execution finishes or before reading a cell
     /// value.
@@ -417,9 +436,16 @@ struct CurrentGlobalTaskState {
 }
 
 impl CurrentGlobalTaskState {
-    fn new(task_id: TaskId, backend_state: Box<dyn Any + Send + Sync>) -> Self {
+    fn new(
+        task_id: TaskId,
+        priority: TaskPriority,
+        cancellation_token: CancellationToken,
+        backend_state: Box<dyn Any + Send + Sync>,
+    ) -> Self {
         Self {
             task_id,
+            priority,
+            cancellation_token,
             tasks_to_notify: Vec::new(),
             stateful: false,
             cell_counters: Some(AutoMap::default()),
@@ -492,6 +518,9 @@ impl<B: Backend + 'static> TurboTasks<B> {
             event_foreground: Event::new(|| "TurboTasks::event_foreground".to_string()),
             event_background: Event::new(|| "TurboTasks::event_background".to_string()),
             program_start: Instant::now(),
+            cancellation: Default::default(),
+            invalidation_log: Default::default(),
+            priority_scheduler: PriorityScheduler::new(),
         });
         this.backend.startup(&*this);
         this
@@ -503,6 +532,21 @@ impl<B: Backend + 'static> TurboTasks<B> {
 
     /// Creates a new root task
     pub fn spawn_root_task<T, F, Fut>(&self, functor: F) -> TaskId
+    where
+        T: Send,
+        F: Fn() -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<Vc<T>>> + Send,
+    {
+        self.spawn_root_task_with_priority(TaskPriority::Normal, functor)
+    }
+
+    /// Creates a new root task, that is executed with the given priority every
+    /// time it is invalidated. Tasks it depends on inherit the priority.
+    pub fn spawn_root_task_with_priority<T, F, Fut>(
+        &self,
+        priority: TaskPriority,
+        functor: F,
+    ) -> TaskId
     where
         T: Send,
         F: Fn() -> Fut + Send + Sync + Clone + 'static,
@@ -515,12 +559,48 @@ impl<B: Backend + 'static> TurboTasks<B> {
             })),
             self,
         );
+        self.cancellation.add_root(id);
+        self.priority_scheduler.set_root_priority(id, priority);
         self.schedule(id);
         id
     }
 
+    /// Cancels the running executions of all tasks that are only needed by
+    /// this root task or other canceled root tasks, and prevents such tasks
+    /// from executing until another task needs them. The results of canceled
+    /// executions are dropped.
+    fn cancel_root_task(&self, task_id: TaskId) {
+        self.cancellation.cancel_root(task_id);
+    }
+
     pub fn dispose_root_task(&self, task_id: TaskId) {
+        self.cancel_root_task(task_id);
         self.backend.dispose_root_task(task_id, self);
+        self.cancellation.remove_root(task_id);
+        self.priority_scheduler.remove_root_priority(task_id);
+    }
+
+    /// Returns a snapshot of the task graph for debugging, if the backend
+    /// supports it. Use [TaskGraph::subgraph] to focus on a single task.
+    pub fn task_graph(&self) -> Option<TaskGraph> {
+        self.backend.task_graph()
+    }
+
+    /// Enables recording why tasks are invalidated, which is needed by
+    /// [TurboTasks::explain_invalidation]. Disabling it drops all records.
+    pub fn set_track_invalidation_causes(&self, enabled: bool) {
+        self.invalidation_log.set_enabled(enabled);
+    }
+
+    /// Explains why `task` has been recomputed the last time. The first step is
+    /// the invalidation of `task`, every following step is the invalidation of
+    /// the dependency that caused the previous one, and the last step is the
+    /// originating change, e.g. a file change. Empty when the task hasn't been
+    /// invalidated since [TurboTasks::set_track_invalidation_causes] was
+    /// enabled.
+    pub fn explain_invalidation(&self, task: TaskId) -> Vec<InvalidationStep> {
+        self.invalidation_log
+            .explain(task, |task| self.backend.get_task_description(task))
     }
 
     // TODO make sure that all dependencies settle before reading them
@@ -528,6 +608,21 @@ impl<B: Backend + 'static> TurboTasks<B> {
     /// Dependencies will not invalidate the task.
     #[track_caller]
     pub fn spawn_once_task<T, Fut>(&self, future: Fut) -> TaskId
+    where
+        T: Send,
+        Fut: Future<Output = Result<Vc<T>>> + Send + 'static,
+    {
+        self.spawn_once_task_with_priority(TaskPriority::Normal, future)
+    }
+
+    /// Creates a new root task with the given priority, that is only executed
+    /// once. Tasks it depends on inherit the priority.
+    #[track_caller]
+    pub fn spawn_once_task_with_priority<T, Fut>(
+        &self,
+        priority: TaskPriority,
+        future: Fut,
+    ) -> TaskId
     where
         T: Send,
         Fut: Future<Output = Result<Vc<T>>> + Send + 'static,
@@ -536,27 +631,51 @@ impl<B: Backend + 'static> TurboTasks<B> {
             TransientTaskType::Once(Box::pin(async move { Ok(future.await?.node) })),
             self,
         );
+        self.cancellation.add_root(id);
+        self.priority_scheduler.set_root_priority(id, priority);
         self.schedule(id);
         id
     }
 
+    /// Dropping the returned future before it completes cancels the work that
+    /// is only needed by it. That work isn't executed again until another task
+    /// needs it.
     pub async fn run_once<T: TraceRawVcs + Send + 'static>(
         &self,
         future: impl Future<Output = Result<T>> + Send + 'static,
+    ) -> Result<T> {
+        self.run_once_with_priority(TaskPriority::Normal, future)
+            .await
+    }
+
+    /// Like [TurboTasks::run_once], but executes the future and the tasks it
+    /// depends on with the given priority.
+    pub async fn run_once_with_priority<T: TraceRawVcs + Send + 'static>(
+        &self,
+        priority: TaskPriority,
+        future: impl Future<Output = Result<T>> + Send + 'static,
     ) -> Result<T> {
         let (tx, rx) = tokio::sync::oneshot::channel();
-        let task_id = self.spawn_once_task(async move {
+        let task_id = self.spawn_once_task_with_priority(priority, async move {
             let result = future.await?;
             tx.send(result)
                 .map_err(|_| anyhow!("unable to send result"))?;
             Ok(Completion::new())
         });
+        let mut cancel_guard = CancelOnDrop {
+            turbo_tasks: self,
+            task_id,
+            armed: true,
+        };
         // INVALIDATION: A Once task will never invalidate, therefore we don't need to
         // track a dependency
         let raw_result =
             read_task_output_untracked(self, task_id, ReadConsistency::Eventual).await?;
         ReadVcFuture::<Completion>::from(raw_result.into_read_untracked_with_turbo_tasks(self))
             .await?;
+        cancel_guard.armed = false;
+        self.cancellation.remove_root(task_id);
+        self.priority_scheduler.remove_root_priority(task_id);
 
         Ok(rx.await?)
     }
@@ -756,16 +875,44 @@ impl<B: Backend + 'static> TurboTasks<B> {
         self.begin_primary_job();
         self.scheduled_tasks.fetch_add(1, Ordering::AcqRel);
 
+        // Tasks scheduled by another task are executed for its root tasks and
+        // inherit its priority.
+        let current = CURRENT_GLOBAL_TASK_STATE
+            .try_with(|ts| {
+                let ts = ts.read().unwrap();
+                (ts.task_id, ts.priority)
+            })
+            .ok();
+        if let Some((current, _)) = current {
+            self.cancellation.inherit(task_id, current);
+        }
+        let enqueued_priority = self
+            .priority_scheduler
+            .enqueue(task_id, current.map(|(_, priority)| priority));
+
         #[cfg(feature = "tokio_tracing")]
         let description = self.backend.get_task_description(task_id);
 
         let this = self.pin();
         let future = async move {
+            let priority = this
+                .priority_scheduler
+                .wait_for_turn(task_id, enqueued_priority)
+                .await;
             let mut schedule_again = true;
             while schedule_again {
+                // Don't start work that is no longer needed. It's scheduled again when another
+                // task reads it.
+                if this.cancellation.park(task_id) {
+                    this.finish_primary_job();
+                    return anyhow::Ok(());
+                }
                 let backend_state = this.backend.new_task_state(task_id);
+                let cancellation_token = this.cancellation.start(task_id);
                 let global_task_state = Arc::new(RwLock::new(CurrentGlobalTaskState::new(
                     task_id,
+                    priority,
+                    cancellation_token.clone(),
                     Box::new(backend_state),
                 )));
                 let local_task_state = CurrentLocalTaskState::new(
@@ -802,6 +949,12 @@ impl<B: Backend + 'static> TurboTasks<B> {
                                 Err(_) => None,
                             },
                         });
+                        if cancellation_token.is_cancelled() && !this.cancellation.is_root(task_id)
+                        {
+                            // The result of a canceled execution must never be read. Marking the
+                            // task as stale drops it and executes the task again.
+                            this.backend.invalidate_task(task_id, &*this);
+                        }
                         this.backend.task_execution_result(task_id, result, &*this);
                         let stateful = this.finish_current_task_state();
                         let cell_counters = CURRENT_GLOBAL_TASK_STATE
@@ -827,7 +980,9 @@ impl<B: Backend + 'static> TurboTasks<B> {
                         CURRENT_LOCAL_TASK_STATE.scope(local_task_state, single_execution_future),
                     )
                     .await;
+                this.cancellation.finish(task_id);
             }
+            this.cancellation.task_completed(task_id);
             this.finish_primary_job();
             anyhow::Ok(())
         };
@@ -1148,11 +1303,42 @@ impl<B: Backend + 'static> TurboTasks<B> {
         });
 
         if !tasks.is_empty() {
+            self.cancel_invalidated(&tasks);
+            self.record_invalidation_by_current_task(&tasks);
             self.backend.invalidate_tasks(&tasks, self);
         }
         stateful
     }
 
+    /// Records that `tasks` are invalidated because the current task changed
+    /// something they depend on, or by an external change when called outside
+    /// of a task.
+    fn record_invalidation_by_current_task<'a>(&self, tasks: impl IntoIterator<Item = &'a TaskId>) {
+        if !self.invalidation_log.is_enabled() {
+            return;
+        }
+        let cause = match CURRENT_GLOBAL_TASK_STATE.try_with(|ts| ts.read().unwrap().task_id) {
+            Ok(task) => InvalidationCause::Dependency { task },
+            Err(_) => InvalidationCause::External,
+        };
+        self.invalidation_log.record(tasks, || cause.clone());
+    }
+
+    /// Cancels the running executions of invalidated tasks, as they will be
+    /// executed again anyway. The current task is never canceled by itself,
+    /// and root tasks are never canceled for an invalidation, as they report
+    /// their result outside of turbo-tasks.
+    fn cancel_invalidated<'a>(&self, tasks: impl IntoIterator<Item = &'a TaskId>) {
+        let current = CURRENT_GLOBAL_TASK_STATE
+            .try_with(|ts| ts.read().unwrap().task_id)
+            .ok();
+        for &task in tasks {
+            if Some(task) != current && !self.cancellation.is_root(task) {
+                self.cancellation.cancel_execution(task);
+            }
+        }
+    }
+
     pub fn backend(&self) -> &B {
         &self.backend
     }
@@ -1253,15 +1439,23 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
 
     #[instrument(level = Level::INFO, skip_all, name = "invalidate")]
     fn invalidate(&self, task: TaskId) {
+        self.cancel_invalidated([&task]);
+        self.invalidation_log
+            .record([&task], || InvalidationCause::External);
         self.backend.invalidate_task(task, self);
     }
 
     #[instrument(level = Level::INFO, skip_all, name = "invalidate", fields(name = display(&reason)))]
     fn invalidate_with_reason(&self, task: TaskId, reason: StaticOrArc<dyn InvalidationReason>) {
+        self.invalidation_log
+            .record([&task], || InvalidationCause::Reason {
+                reason: reason.to_string(),
+            });
         {
             let (_, reason_set) = &mut *self.aggregated_update.lock().unwrap();
             reason_set.insert(reason);
         }
+        self.cancel_invalidated([&task]);
         self.backend.invalidate_task(task, self);
     }
 
@@ -1280,10 +1474,30 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
             if tasks.is_empty() {
                 return;
             }
+            self.cancel_invalidated(&tasks);
+            self.record_invalidation_by_current_task(&tasks);
             self.backend.invalidate_tasks(&tasks, self);
         });
     }
 
+    fn inherit_from_current_task(&self, task: TaskId) {
+        match CURRENT_GLOBAL_TASK_STATE.try_with(|ts| {
+            let ts = ts.read().unwrap();
+            (ts.task_id, ts.priority)
+        }) {
+            Ok((current, priority)) => {
+                self.cancellation.inherit(task, current);
+                // The task might still wait for its turn behind tasks with a
+                // higher priority than its own.
+                self.priority_scheduler.raise(task, priority);
+            }
+            Err(_) => self.cancellation.detach(task),
+        }
+        if self.cancellation.unpark(task) {
+            self.schedule(task);
+        }
+    }
+
     fn try_read_task_output(
         &self,
         task: TaskId,
@@ -1405,6 +1619,9 @@ impl<B: Backend + 'static> TurboTasksApi for TurboTasks<B> {
     }
 
     fn mark_own_task_as_finished(&self, task: TaskId) {
+        // The result is read while the task is still running, so it can't be
+        // canceled any more.
+        self.cancellation.finish(task);
         self.backend.mark_own_task_as_finished(task, self);
     }
 
@@ -1512,6 +1729,8 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
         });
         if result.is_err() {
             let _guard = trace_span!("schedule_notify_tasks", count = tasks.len()).entered();
+            self.cancel_invalidated(tasks);
+            self.record_invalidation_by_current_task(tasks);
             self.backend.invalidate_tasks(tasks, self);
         }
     }
@@ -1527,6 +1746,8 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
         });
         if result.is_err() {
             let _guard = trace_span!("schedule_notify_tasks_set", count = tasks.len()).entered();
+            self.cancel_invalidated(tasks);
+            self.record_invalidation_by_current_task(tasks);
             self.backend.invalidate_tasks_set(tasks, self);
         };
     }
@@ -1573,6 +1794,47 @@ impl<B: Backend + 'static> TurboTasksBackendApi<B> for TurboTasks<B> {
     }
 }
 
+/// Returns the cancellation token of the current task execution. It is
+/// canceled when the result of the execution is no longer needed, e.g. because
+/// the task has been invalidated while running. Long running work should stop
+/// early when it is canceled. Outside of a task a token that is never canceled
+/// is returned.
+pub fn cancellation_token() -> CancellationToken {
+    CURRENT_GLOBAL_TASK_STATE
+        .try_with(|ts| ts.read().unwrap().cancellation_token.clone())
+        .unwrap_or_default()
+}
+
+/// Fails with [TaskCanceled] when the current task execution has been
+/// canceled, see [cancellation_token].
+pub fn check_cancelled() -> Result<()> {
+    let cancelled = CURRENT_GLOBAL_TASK_STATE
+        .try_with(|ts| ts.read().unwrap().cancellation_token.is_cancelled())
+        .unwrap_or(false);
+    if cancelled {
+        return Err(TaskCanceled.into());
+    }
+    Ok(())
+}
+
+/// Cancels a once task when the future waiting for it is dropped.
+struct CancelOnDrop<'a, B: Backend + 'static> {
+    turbo_tasks: &'a TurboTasks<B>,
+    task_id: TaskId,
+    armed: bool,
+}
+
+impl<B: Backend + 'static> Drop for CancelOnDrop<'_, B> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.turbo_tasks.cancel_root_task(self.task_id);
+            self.turbo_tasks
+                .priority_scheduler
+                .remove_root_priority(self.task_id);
+        }
+    }
+}
+
 pub(crate) fn current_task(from: &str) -> TaskId {
     match CURRENT_GLOBAL_TASK_STATE.try_with(|ts| ts.read().unwrap().task_id) {
         Ok(id) => id,
@@ -1683,6 +1945,8 @@ pub fn with_turbo_tasks_for_testing<T>(
         CURRENT_GLOBAL_TASK_STATE.scope(
             Arc::new(RwLock::new(CurrentGlobalTaskState::new(
                 current_task,
+                TaskPriority::Normal,
+                CancellationToken::new(),
                 Box::new(()),
             ))),
             CURRENT_LOCAL_TASK_STATE.scope(CurrentLocalTaskState::new(execution_id, None), f),
@@ -1782,7 +2046,10 @@ pub(crate) async fn read_task_output(
     loop {
         match this.try_read_task_output(id, consistency)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                this.inherit_from_current_task(id);
+                wait_unless_canceled(listener).await?
+            }
         }
     }
 }
@@ -1797,11 +2064,24 @@ pub(crate) async fn read_task_output_untracked(
     loop {
         match this.try_read_task_output_untracked(id, consistency)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                this.inherit_from_current_task(id);
+                wait_unless_canceled(listener).await?
+            }
         }
     }
 }
 
+/// Waits for `listener`, unless the current task execution is canceled. A
+/// canceled execution may wait for a task that is parked.
+async fn wait_unless_canceled(listener: EventListener) -> Result<()> {
+    let cancellation = cancellation_token();
+    select! {
+        _ = listener => Ok(()),
+        _ = cancellation.cancelled() => Err(TaskCanceled.into()),
+    }
+}
+
 pub(crate) async fn read_task_cell(
     this: &dyn TurboTasksApi,
     id: TaskId,
@@ -1810,7 +2090,10 @@ pub(crate) async fn read_task_cell(
     loop {
         match this.try_read_task_cell(id, index)? {
             Ok(result) => return Ok(result),
-            Err(listener) => listener.await,
+            Err(listener) => {
+                this.inherit_from_current_task(id);
+                wait_unless_canceled(listener).await?
+            }
         }
     }
 }
@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{event::Event, TaskId};
+
+/// A hint in which order scheduled tasks should be executed, e.g. to prefer
+/// the tasks needed to answer a dev server request over a background emit.
+///
+/// Tasks inherit the priority of the task that schedules them or waits for
+/// them.
+#[derive(
+    Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum TaskPriority {
+    /// Work nobody is waiting for, e.g. eager compilation.
+    Background,
+    #[default]
+    Normal,
+    /// Work someone is waiting for, e.g. a request to the dev server.
+    High,
+}
+
+impl TaskPriority {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Orders the start of task executions by [TaskPriority]. A scheduled task
+/// waits before starting as long as tasks with a higher priority are scheduled
+/// but haven't started yet.
+///
+/// Only the start is ordered, a running task is never held back. A waiting
+/// task that a higher priority task waits for is raised to that priority, so
+/// the higher priority task isn't blocked by it.
+///
+/// As long as all tasks have the [TaskPriority::Normal] priority, scheduling a
+/// task only touches atomic counters and never looks up a map.
+pub(crate) struct PriorityScheduler {
+    /// The number of tasks per priority that are scheduled but haven't started
+    /// yet.
+    pending: [AtomicUsize; 3],
+    /// Notified when the pending tasks of a priority are all started, or when
+    /// a waiting task is raised.
+    event: Event,
+    /// Priorities of root tasks that are not [TaskPriority::Normal]. They
+    /// apply to every execution until the root task is disposed.
+    root_priorities: DashMap<TaskId, TaskPriority>,
+    /// The number of entries in `root_priorities`.
+    root_priorities_len: AtomicUsize,
+    /// Tasks that wait for their turn, with the priority they are counted as
+    /// pending with.
+    waiting: DashMap<TaskId, TaskPriority>,
+    /// The number of entries in `waiting`.
+    waiting_len: AtomicUsize,
+}
+
+impl PriorityScheduler {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: Default::default(),
+            event: Event::new(|| "PriorityScheduler::event".to_string()),
+            root_priorities: DashMap::new(),
+            root_priorities_len: AtomicUsize::new(0),
+            waiting: DashMap::new(),
+            waiting_len: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn set_root_priority(&self, task: TaskId, priority: TaskPriority) {
+        let previous = if priority == TaskPriority::Normal {
+            self.root_priorities.remove(&task).map(|(_, p)| p)
+        } else {
+            self.root_priorities.insert(task, priority)
+        };
+        match (previous.is_some(), priority != TaskPriority::Normal) {
+            (false, true) => {
+                self.root_priorities_len.fetch_add(1, Ordering::AcqRel);
+            }
+            (true, false) => {
+                self.root_priorities_len.fetch_sub(1, Ordering::AcqRel);
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn remove_root_priority(&self, task: TaskId) {
+        self.set_root_priority(task, TaskPriority::Normal);
+    }
+
+    fn root_priority(&self, task: TaskId) -> Option<TaskPriority> {
+        if self.root_priorities_len.load(Ordering::Acquire) == 0 {
+            return None;
+        }
+        self.root_priorities.get(&task).map(|p| *p)
+    }
+
+    /// Marks `task` as scheduled. `inherited` is the priority of the task that
+    /// scheduled it, if any. Returns the priority to pass to
+    /// [PriorityScheduler::wait_for_turn].
+    pub(crate) fn enqueue(&self, task: TaskId, inherited: Option<TaskPriority>) -> TaskPriority {
+        let priority = match (self.root_priority(task), inherited) {
+            (Some(root), Some(inherited)) => root.max(inherited),
+            (Some(priority), None) | (None, Some(priority)) => priority,
+            (None, None) => TaskPriority::Normal,
+        };
+        self.pending[priority.index()].fetch_add(1, Ordering::AcqRel);
+        priority
+    }
+
+    /// Raises `task` to at least `priority` if it is waiting for its turn.
+    pub(crate) fn raise(&self, task: TaskId, priority: TaskPriority) {
+        if self.waiting_len.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        let Some(mut entry) = self.waiting.get_mut(&task) else {
+            return;
+        };
+        let previous = *entry;
+        if previous >= priority {
+            return;
+        }
+        // Count it as pending with the new priority before removing the old
+        // one, so it's never missing from the counts.
+        self.pending[priority.index()].fetch_add(1, Ordering::AcqRel);
+        *entry = priority;
+        drop(entry);
+        self.pending[previous.index()].fetch_sub(1, Ordering::AcqRel);
+        self.event.notify(usize::MAX);
+    }
+
+    /// Waits until no task with a higher priority than `task` is pending, and
+    /// marks the task as started. `enqueued` is the priority returned by
+    /// [PriorityScheduler::enqueue]. Returns the priority the task executes
+    /// with, which might have been raised while waiting.
+    pub(crate) async fn wait_for_turn(&self, task: TaskId, enqueued: TaskPriority) -> TaskPriority {
+        if !self.has_pending_above(enqueued) {
+            self.started(enqueued);
+            return enqueued;
+        }
+        self.waiting.insert(task, enqueued);
+        self.waiting_len.fetch_add(1, Ordering::AcqRel);
+        loop {
+            let listener = self.event.listen();
+            // Check after listening, so no notification is missed.
+            let priority = self.waiting.get(&task).map_or(enqueued, |p| *p);
+            if !self.has_pending_above(priority) {
+                break;
+            }
+            listener.await;
+        }
+        // The entry is removed under its lock, so a concurrent raise has either
+        // been applied to the pending counts or isn't applied at all.
+        let priority = self.waiting.remove(&task).map_or(enqueued, |(_, p)| p);
+        self.waiting_len.fetch_sub(1, Ordering::AcqRel);
+        self.started(priority);
+        priority
+    }
+
+    fn started(&self, priority: TaskPriority) {
+        if self.pending[priority.index()].fetch_sub(1, Ordering::AcqRel) == 1
+            && self.waiting_len.load(Ordering::Acquire) > 0
+        {
+            self.event.notify(usize::MAX);
+        }
+    }
+
+    fn has_pending_above(&self, priority: TaskPriority) -> bool {
+        self.pending[priority.index() + 1..]
+            .iter()
+            .any(|pending| pending.load(Ordering::Acquire) > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn enqueue_prefers_the_highest_priority() {
+        let scheduler = PriorityScheduler::new();
+        let (root, task) = (TaskId::from(1), TaskId::from(2));
+        assert_eq!(scheduler.enqueue(task, None), TaskPriority::Normal);
+        assert_eq!(
+            scheduler.enqueue(task, Some(TaskPriority::Background)),
+            TaskPriority::Background
+        );
+
+        scheduler.set_root_priority(root, TaskPriority::High);
+        assert_eq!(scheduler.enqueue(root, None), TaskPriority::High);
+        assert_eq!(
+            scheduler.enqueue(root, Some(TaskPriority::Background)),
+            TaskPriority::High
+        );
+
+        scheduler.set_root_priority(root, TaskPriority::Background);
+        assert_eq!(
+            scheduler.enqueue(root, Some(TaskPriority::Normal)),
+            TaskPriority::Normal
+        );
+
+        scheduler.remove_root_priority(root);
+        assert_eq!(scheduler.enqueue(root, None), TaskPriority::Normal);
+        assert_eq!(scheduler.root_priorities_len.load(Ordering::Acquire), 0);
+    }
+
+    #[tokio::test]
+    async fn lower_priority_waits_for_higher() {
+        let scheduler = PriorityScheduler::new();
+        let (high, normal) = (TaskId::from(1), TaskId::from(2));
+
+        let high_enqueued = scheduler.enqueue(high, Some(TaskPriority::High));
+        let normal_enqueued = scheduler.enqueue(normal, None);
+
+        let normal_turn = scheduler.wait_for_turn(normal, normal_enqueued);
+        tokio::pin!(normal_turn);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), &mut normal_turn)
+                .await
+                .is_err()
+        );
+
+        assert_eq!(
+            scheduler.wait_for_turn(high, high_enqueued).await,
+            TaskPriority::High
+        );
+        assert_eq!(normal_turn.await, TaskPriority::Normal);
+        assert!(!scheduler.has_pending_above(TaskPriority::Background));
+    }
+
+    #[tokio::test]
+    async fn raises_a_waiting_task() {
+        let scheduler = PriorityScheduler::new();
+        let (high, background) = (TaskId::from(1), TaskId::from(2));
+
+        let high_enqueued = scheduler.enqueue(high, Some(TaskPriority::High));
+        let background_enqueued = scheduler.enqueue(background, Some(TaskPriority::Background));
+
+        let background_turn = scheduler.wait_for_turn(background, background_enqueued);
+        tokio::pin!(background_turn);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), &mut background_turn)
+                .await
+                .is_err()
+        );
+
+        // the high priority task needs the queued background task
+        scheduler.raise(background, TaskPriority::High);
+        assert_eq!(background_turn.await, TaskPriority::High);
+
+        // raising a task that isn't waiting has no effect on the counts
+        scheduler.raise(background, TaskPriority::High);
+        assert_eq!(
+            scheduler.wait_for_turn(high, high_enqueued).await,
+            TaskPriority::High
+        );
+        assert!(!scheduler.has_pending_above(TaskPriority::Background));
+        assert_eq!(scheduler.waiting_len.load(Ordering::Acquire), 0);
+    }
+}
@@ -0,0 +1,348 @@
+//! Debugging helpers to inspect the live task graph and to explain why a task
+//! has been recomputed.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::Serialize;
+
+use crate::TaskId;
+
+/// A snapshot of the tasks of a backend, their cells, dependencies and
+/// collectibles. Created by [crate::TurboTasks::task_graph].
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct TaskGraph {
+    pub tasks: Vec<TaskGraphNode>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskGraphNode {
+    pub id: TaskId,
+    pub description: String,
+    /// The execution state, e.g. `done` or `dirty`.
+    pub state: String,
+    pub cells: Vec<TaskGraphCells>,
+    /// The tasks this task has read from, and its children.
+    pub dependencies: Vec<TaskGraphEdge>,
+    pub collectibles: Vec<TaskGraphCollectibles>,
+}
+
+/// The number of cells of a value type a task has.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskGraphCells {
+    pub value_type: String,
+    pub count: usize,
+}
+
+/// The number of collectibles of a trait type a task has emitted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskGraphCollectibles {
+    pub trait_type: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct TaskGraphEdge {
+    pub task: TaskId,
+    pub kind: TaskGraphEdgeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TaskGraphEdgeKind {
+    /// The output of the task has been read.
+    Output,
+    /// A cell of the task has been read.
+    #[serde(rename_all = "camelCase")]
+    Cell { value_type: String, index: u32 },
+    /// The collectibles of the task have been read.
+    #[serde(rename_all = "camelCase")]
+    Collectibles { trait_type: String },
+    /// The task has been called.
+    Child,
+}
+
+impl TaskGraphEdgeKind {
+    fn label(&self) -> String {
+        match self {
+            TaskGraphEdgeKind::Output => "output".to_string(),
+            TaskGraphEdgeKind::Cell { value_type, index } => format!("{value_type} #{index}"),
+            TaskGraphEdgeKind::Collectibles { trait_type } => format!("collectibles {trait_type}"),
+            TaskGraphEdgeKind::Child => "child".to_string(),
+        }
+    }
+}
+
+impl TaskGraph {
+    /// Returns the part of the graph that is at most `depth` edges away from
+    /// `task`, following edges in both directions.
+    pub fn subgraph(&self, task: TaskId, depth: usize) -> TaskGraph {
+        let mut neighbors: HashMap<TaskId, Vec<TaskId>> = HashMap::new();
+        for node in &self.tasks {
+            for edge in &node.dependencies {
+                neighbors.entry(node.id).or_default().push(edge.task);
+                neighbors.entry(edge.task).or_default().push(node.id);
+            }
+        }
+
+        let mut included = HashSet::from([task]);
+        let mut queue = VecDeque::from([(task, 0)]);
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance == depth {
+                continue;
+            }
+            for &neighbor in neighbors.get(&current).into_iter().flatten() {
+                if included.insert(neighbor) {
+                    queue.push_back((neighbor, distance + 1));
+                }
+            }
+        }
+
+        TaskGraph {
+            tasks: self
+                .tasks
+                .iter()
+                .filter(|node| included.contains(&node.id))
+                .map(|node| TaskGraphNode {
+                    dependencies: node
+                        .dependencies
+                        .iter()
+                        .filter(|edge| included.contains(&edge.task))
+                        .cloned()
+                        .collect(),
+                    ..node.clone()
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Renders the graph in the Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph tasks {\n  node [shape=box];\n");
+        for node in &self.tasks {
+            let mut label = format!("{}\\n{}", escape_dot(&node.description), node.state);
+            for cells in &node.cells {
+                write!(
+                    label,
+                    "\\n{} x{}",
+                    escape_dot(&cells.value_type),
+                    cells.count
+                )
+                .unwrap();
+            }
+            for collectibles in &node.collectibles {
+                write!(
+                    label,
+                    "\\ncollectibles {} x{}",
+                    escape_dot(&collectibles.trait_type),
+                    collectibles.count
+                )
+                .unwrap();
+            }
+            writeln!(dot, "  t{} [label=\"{label}\"];", node.id).unwrap();
+        }
+        for node in &self.tasks {
+            for edge in &node.dependencies {
+                let style = if edge.kind == TaskGraphEdgeKind::Child {
+                    ", style=dashed"
+                } else {
+                    ""
+                };
+                writeln!(
+                    dot,
+                    "  t{} -> t{} [label=\"{}\"{style}];",
+                    node.id,
+                    edge.task,
+                    escape_dot(&edge.kind.label())
+                )
+                .unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Why a task has been invalidated the last time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum InvalidationCause {
+    /// Invalidated from outside of the task graph with a reason, e.g. a file
+    /// change.
+    Reason { reason: String },
+    /// Invalidated from outside of the task graph without a reason, e.g. by
+    /// an [crate::Invalidator] or a state update outside of a task.
+    External,
+    /// The output, a cell or the collectibles of `task` that the task depends
+    /// on changed while `task` was executing.
+    Dependency { task: TaskId },
+}
+
+/// One step in the chain returned by [crate::TurboTasks::explain_invalidation].
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidationStep {
+    pub task: TaskId,
+    pub description: String,
+    pub cause: InvalidationCause,
+}
+
+/// Records the last [InvalidationCause] of every task while enabled.
+#[derive(Default)]
+pub(crate) struct InvalidationLog {
+    enabled: AtomicBool,
+    causes: DashMap<TaskId, InvalidationCause>,
+}
+
+impl InvalidationLog {
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Release);
+        if !enabled {
+            self.causes.clear();
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn record<'a>(
+        &self,
+        tasks: impl IntoIterator<Item = &'a TaskId>,
+        cause: impl Fn() -> InvalidationCause,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        for &task in tasks {
+            self.causes.insert(task, cause());
+        }
+    }
+
+    /// Follows the recorded causes from `task` back to the change that
+    /// started the invalidation.
+    pub(crate) fn explain(
+        &self,
+        task: TaskId,
+        describe: impl Fn(TaskId) -> String,
+    ) -> Vec<InvalidationStep> {
+        let mut steps = Vec::new();
+        let mut visited = HashSet::new();
+        let mut current = task;
+        while visited.insert(current) {
+            let Some(cause) = self.causes.get(&current).map(|cause| cause.clone()) else {
+                break;
+            };
+            let next = match cause {
+                InvalidationCause::Dependency { task } => Some(task),
+                _ => None,
+            };
+            steps.push(InvalidationStep {
+                task: current,
+                description: describe(current),
+                cause,
+            });
+            match next {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: u32, dependencies: &[u32]) -> TaskGraphNode {
+        TaskGraphNode {
+            id: TaskId::from(id),
+            description: format!("task {id}"),
+            state: "done".to_string(),
+            cells: Vec::new(),
+            dependencies: dependencies
+                .iter()
+                .map(|&task| TaskGraphEdge {
+                    task: TaskId::from(task),
+                    kind: TaskGraphEdgeKind::Output,
+                })
+                .collect(),
+            collectibles: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn subgraph_follows_both_directions() {
+        // 1 -> 2 -> 3 -> 4, 5 -> 3
+        let graph = TaskGraph {
+            tasks: vec![
+                node(1, &[2]),
+                node(2, &[3]),
+                node(3, &[4]),
+                node(4, &[]),
+                node(5, &[3]),
+            ],
+        };
+        let ids = |graph: &TaskGraph| graph.tasks.iter().map(|n| *n.id).collect::<Vec<_>>();
+
+        let subgraph = graph.subgraph(TaskId::from(3), 1);
+        assert_eq!(ids(&subgraph), vec![2, 3, 4, 5]);
+        assert_eq!(subgraph.tasks[0].dependencies.len(), 1);
+
+        let subgraph = graph.subgraph(TaskId::from(4), 1);
+        assert_eq!(ids(&subgraph), vec![3, 4]);
+
+        let dot = graph.subgraph(TaskId::from(1), 1).to_dot();
+        assert!(dot.contains("t1 -> t2 [label=\"output\"];"));
+        assert!(!dot.contains("t3"));
+    }
+
+    #[test]
+    fn explains_invalidation_chain() {
+        let log = InvalidationLog::default();
+        let (file, parse, chunk) = (TaskId::from(1), TaskId::from(2), TaskId::from(3));
+        log.record([&file], || InvalidationCause::Reason {
+            reason: "src/index.js changed".to_string(),
+        });
+        assert!(log.explain(file, |_| String::new()).is_empty());
+
+        log.set_enabled(true);
+        log.record([&file], || InvalidationCause::Reason {
+            reason: "src/index.js changed".to_string(),
+        });
+        log.record([&parse], || InvalidationCause::Dependency { task: file });
+        log.record([&chunk], || InvalidationCause::Dependency { task: parse });
+
+        let steps = log.explain(chunk, |task| format!("task {task}"));
+        assert_eq!(
+            steps.iter().map(|step| step.task).collect::<Vec<_>>(),
+            vec![chunk, parse, file]
+        );
+        assert_eq!(
+            steps[2].cause,
+            InvalidationCause::Reason {
+                reason: "src/index.js changed".to_string()
+            }
+        );
+
+        // cycles don't loop forever
+        log.record([&file], || InvalidationCause::Dependency { task: chunk });
+        assert_eq!(log.explain(chunk, |_| String::new()).len(), 3);
+    }
+}
@@ -24,7 +24,7 @@ use turbopack_ecmascript_runtime::RuntimeType;
 
 use crate::ecmascript::{
     chunk::EcmascriptDevChunk,
-    evaluate::chunk::EcmascriptDevEvaluateChunk,
+    evaluate::{chunk::EcmascriptDevEvaluateChunk, single_file::EcmascriptSingleFileChunk},
     list::asset::{EcmascriptDevChunkList, EcmascriptDevChunkListSource},
 };
 
@@ -83,6 +83,11 @@ impl BrowserChunkingContextBuilder {
         self
     }
 
+    pub fn ordered_vendors_split(mut self, enable_ordered_vendors_split: bool) -> Self {
+        self.chunking_context.enable_ordered_vendors_split = enable_ordered_vendors_split;
+        self
+    }
+
     pub fn minify_type(mut self, minify_type: MinifyType) -> Self {
         self.chunking_context.minify_type = minify_type;
         self
@@ -143,6 +148,9 @@ pub struct BrowserChunkingContext {
     minify_type: MinifyType,
     /// Whether to use manifest chunks for lazy compilation
     manifest_chunks: bool,
+    /// Whether to split the leading node_modules items of ordered chunk types,
+    /// e.g. CSS, into chunks shared by the chunk groups
+    enable_ordered_vendors_split: bool,
     /// The module id strategy to use
     module_id_strategy: Vc<Box<dyn ModuleIdStrategy>>,
 }
@@ -176,6 +184,7 @@ impl BrowserChunkingContext {
                 runtime_type,
                 minify_type: MinifyType::NoMinify,
                 manifest_chunks: false,
+                enable_ordered_vendors_split: false,
                 module_id_strategy: Vc::upcast(DevModuleIdStrategy::new()),
             },
         }
@@ -366,6 +375,11 @@ impl ChunkingContext for BrowserChunkingContext {
         Vc::cell(self.enable_hot_module_replacement)
     }
 
+    #[turbo_tasks::function]
+    fn is_ordered_vendors_split_enabled(&self) -> Vc<bool> {
+        Vc::cell(self.enable_ordered_vendors_split)
+    }
+
     #[turbo_tasks::function]
     fn should_use_file_source_map_uris(&self) -> Vc<bool> {
         Vc::cell(self.should_use_file_source_map_uris)
@@ -503,6 +517,34 @@ impl ChunkingContext for BrowserChunkingContext {
         .await
     }
 
+    #[turbo_tasks::function]
+    async fn single_file_chunk_group_asset(
+        self: Vc<Self>,
+        path: Vc<FileSystemPath>,
+        evaluatable_assets: Vc<EvaluatableAssets>,
+    ) -> Result<Vc<Box<dyn OutputAsset>>> {
+        let entries = evaluatable_assets
+            .await?
+            .iter()
+            .map(|&evaluatable| Vc::upcast(evaluatable));
+
+        let MakeChunkGroupResult { chunks, .. } =
+            make_chunk_group(Vc::upcast(self), entries, AvailabilityInfo::Root).await?;
+
+        let chunks = chunks
+            .iter()
+            .map(|chunk| self.generate_chunk(**chunk).to_resolved())
+            .try_join()
+            .await?;
+
+        Ok(Vc::upcast(EcmascriptSingleFileChunk::new(
+            self,
+            path,
+            Vc::cell(chunks),
+            evaluatable_assets,
+        )))
+    }
+
     #[turbo_tasks::function]
     fn entry_chunk_group(
         self: Vc<Self>,
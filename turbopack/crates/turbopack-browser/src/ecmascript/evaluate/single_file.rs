@@ -0,0 +1,208 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use indoc::writedoc;
+use serde::Serialize;
+use turbo_tasks::{ReadRef, TryJoinIterExt, Value, Vc};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{ChunkItemExt, ChunkableModule, EvaluatableAssets, MinifyType, ModuleId},
+    code_builder::{Code, CodeBuilder},
+    environment::{EdgeWorkerEnvironment, Environment, ExecutionEnvironment},
+    ident::AssetIdent,
+    output::{OutputAsset, OutputAssets},
+};
+use turbopack_ecmascript::{chunk::EcmascriptChunkPlaceable, minify::minify, utils::StringifyJs};
+use turbopack_ecmascript_runtime::RuntimeType;
+
+use crate::BrowserChunkingContext;
+
+/// An Ecmascript chunk that:
+/// * Contains the code of all chunks of a chunk group;
+/// * Contains the Turbopack runtime code; and
+/// * Evaluates a list of runtime entries.
+///
+/// It never loads other files, so it uses the runtime backend without chunk
+/// loading, which doesn't need `importScripts` or a DOM either. This makes it
+/// usable for worklets.
+#[turbo_tasks::value(shared)]
+pub(crate) struct EcmascriptSingleFileChunk {
+    chunking_context: Vc<BrowserChunkingContext>,
+    path: Vc<FileSystemPath>,
+    chunks: Vc<OutputAssets>,
+    evaluatable_assets: Vc<EvaluatableAssets>,
+}
+
+#[turbo_tasks::value_impl]
+impl EcmascriptSingleFileChunk {
+    /// Creates a new [`Vc<EcmascriptSingleFileChunk>`].
+    #[turbo_tasks::function]
+    pub fn new(
+        chunking_context: Vc<BrowserChunkingContext>,
+        path: Vc<FileSystemPath>,
+        chunks: Vc<OutputAssets>,
+        evaluatable_assets: Vc<EvaluatableAssets>,
+    ) -> Vc<Self> {
+        EcmascriptSingleFileChunk {
+            chunking_context,
+            path,
+            chunks,
+            evaluatable_assets,
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn code(self: Vc<Self>) -> Result<Vc<Code>> {
+        let this = self.await?;
+        let chunking_context = this.chunking_context.await?;
+        let output_root = this.chunking_context.output_root().await?;
+        let path = this.path.await?;
+        let chunk_public_path = output_root
+            .get_path_to(&path)
+            .with_context(|| format!("chunk path {} is not in output root", path.path))?;
+
+        let mut code = CodeBuilder::default();
+
+        // The chunks register themselves in the `TURBOPACK` global, which the
+        // runtime below picks up.
+        let mut other_chunks = Vec::new();
+        for chunk in this.chunks.await?.iter() {
+            let chunk_path = chunk.ident().path().await?;
+            if chunk_path.extension_ref() != Some("js") {
+                continue;
+            }
+            let FileContent::Content(file) = &*chunk.content().file_content().await? else {
+                continue;
+            };
+            // The source maps of the chunks are not emitted next to this file.
+            for line in file
+                .content()
+                .to_str()?
+                .lines()
+                .filter(|line| !line.starts_with("//# sourceMappingURL="))
+            {
+                writeln!(code, "{line}")?;
+            }
+            other_chunks.push(
+                output_root
+                    .get_path_to(&chunk_path)
+                    .with_context(|| {
+                        format!("chunk path {} is not in output root", chunk_path.path)
+                    })?
+                    .to_string(),
+            );
+        }
+
+        let runtime_module_ids: Vec<ReadRef<ModuleId>> = this
+            .evaluatable_assets
+            .await?
+            .iter()
+            .map({
+                let chunking_context = this.chunking_context;
+                move |entry| async move {
+                    if let Some(placeable) =
+                        Vc::try_resolve_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(*entry)
+                            .await?
+                    {
+                        Ok(Some(
+                            placeable
+                                .as_chunk_item(Vc::upcast(chunking_context))
+                                .id()
+                                .await?,
+                        ))
+                    } else {
+                        Ok(None)
+                    }
+                }
+            })
+            .try_join()
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let params = EcmascriptSingleFileChunkRuntimeParams {
+            other_chunks: &other_chunks,
+            runtime_module_ids,
+        };
+
+        writedoc!(
+            code,
+            r#"
+                (globalThis.TURBOPACK = globalThis.TURBOPACK || []).push([
+                    {},
+                    {{}},
+                    {}
+                ]);
+            "#,
+            StringifyJs(&chunk_public_path),
+            StringifyJs(&params),
+        )?;
+
+        match chunking_context.runtime_type() {
+            #[cfg(feature = "test")]
+            RuntimeType::Dummy => {
+                let runtime_code = turbopack_ecmascript_runtime::get_dummy_runtime_code();
+                code.push_code(&runtime_code);
+            }
+            runtime_type => {
+                let environment = Environment::new(Value::new(ExecutionEnvironment::EdgeWorker(
+                    EdgeWorkerEnvironment {}.cell(),
+                )));
+                let runtime_code = turbopack_ecmascript_runtime::get_browser_runtime_code(
+                    environment,
+                    chunking_context.chunk_base_path(),
+                    Value::new(runtime_type),
+                    Vc::cell(output_root.to_string().into()),
+                );
+                code.push_code(&*runtime_code.await?);
+            }
+        }
+
+        let code = code.build().cell();
+        if matches!(chunking_context.minify_type(), MinifyType::Minify) {
+            return Ok(minify(this.path, code));
+        }
+
+        Ok(code)
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for EcmascriptSingleFileChunk {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        AssetIdent::from_path(self.path)
+    }
+
+    #[turbo_tasks::function]
+    async fn references(&self) -> Result<Vc<OutputAssets>> {
+        // The chunks themselves are inlined, but the assets they reference are
+        // still loaded from their own files.
+        let mut references = Vec::new();
+        for chunk in self.chunks.await?.iter() {
+            references.extend(chunk.references().await?.iter().copied());
+        }
+        Ok(Vc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for EcmascriptSingleFileChunk {
+    #[turbo_tasks::function]
+    async fn content(self: Vc<Self>) -> Result<Vc<AssetContent>> {
+        let code = self.code().await?;
+        Ok(AssetContent::file(
+            File::from(code.source_code().clone()).into(),
+        ))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EcmascriptSingleFileChunkRuntimeParams<'a> {
+    other_chunks: &'a [String],
+    runtime_module_ids: Vec<ReadRef<ModuleId>>,
+}
@@ -3,7 +3,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use clap::{Args, Parser};
+use clap::{Args, Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, TaskInput};
 use turbopack_cli_utils::issue::IssueSeverityCliOption;
 
 #[derive(Debug, Parser)]
@@ -11,6 +13,8 @@ use turbopack_cli_utils::issue::IssueSeverityCliOption;
 pub enum Arguments {
     Build(BuildArguments),
     Dev(DevArguments),
+    /// Explain why a module or package is part of the build.
+    Why(WhyArguments),
 }
 
 impl Arguments {
@@ -19,6 +23,7 @@ impl Arguments {
         match self {
             Arguments::Build(args) => args.common.dir.as_deref(),
             Arguments::Dev(args) => args.common.dir.as_deref(),
+            Arguments::Why(args) => args.common.dir.as_deref(),
         }
     }
 }
@@ -61,6 +66,11 @@ pub struct CommonArguments {
     /// MB.
     #[clap(long)]
     pub memory_limit: Option<usize>,
+
+    /// The maximum number of Node.js workers per pool, e.g. for webpack
+    /// loaders. Overrides `nodePool.maxWorkers` of the config file.
+    #[clap(long)]
+    pub node_workers: Option<usize>,
 }
 
 #[derive(Debug, Args)]
@@ -107,4 +117,74 @@ pub struct BuildArguments {
     /// Don't minify build output.
     #[clap(long)]
     pub no_minify: bool,
+
+    /// The environment the build output runs in. Browser builds contain an
+    /// HTML page per entry, `index.html` if there is only one entry and the
+    /// path of the entry relative to the directory shared by all entries
+    /// otherwise, e.g. `admin/index.html` for `src/admin/index.js`, and the
+    /// files of the `public` directory.
+    #[clap(long, value_enum, default_value_t = BuildTarget::Node)]
+    pub target: BuildTarget,
+
+    /// Emit every module of the project that is imported by the entries on
+    /// its own, in the same directory structure. Only for `--target library`.
+    #[clap(long)]
+    pub preserve_modules: bool,
+
+    /// Keep running and rebuild the changed outputs when files change.
+    #[clap(long)]
+    pub watch: bool,
+
+    /// Delete the files that earlier builds wrote to the output directory and
+    /// that this build doesn't write anymore. Other files are kept.
+    #[clap(long)]
+    pub clean: bool,
+}
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    ValueEnum,
+    TaskInput,
+    Serialize,
+    Deserialize,
+    TraceRawVcs,
+)]
+pub enum BuildTarget {
+    /// `*.entry.js` files that can be run with Node.js.
+    #[default]
+    Node,
+    /// A static website.
+    Browser,
+    /// A package that exports the exports of the entries, as `<name>.cjs` and
+    /// `<name>.mjs` with the `<name>.d.ts` next to the entry. The name is the
+    /// path of the entry relative to the directory shared by all entries. The
+    /// dependencies in `package.json` aren't bundled, they are imported by the
+    /// `.mjs` output when the entry imports them with `import`.
+    Library,
+}
+
+#[derive(Debug, Args)]
+#[clap(author, version, about, long_about = None)]
+pub struct WhyArguments {
+    #[clap(flatten)]
+    pub common: CommonArguments,
+
+    /// The path of the module to explain, e.g. `src/utils/date.ts`. Modules
+    /// whose path ends with it are matched.
+    #[clap(long, conflicts_with = "package", required_unless_present = "package")]
+    pub module: Option<String>,
+
+    /// The name of a package in node_modules to explain, e.g. `moment`.
+    #[clap(long)]
+    pub package: Option<String>,
+
+    /// Print the result as JSON.
+    #[clap(long)]
+    pub json: bool,
 }
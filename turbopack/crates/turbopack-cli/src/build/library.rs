@@ -0,0 +1,814 @@
+//! Library builds: every entry is emitted as a CommonJS module (`.cjs`) and an
+//! ES module (`.mjs`) that export the exports of the entry, and the
+//! dependencies of the package are kept external. Both are built on their own,
+//! so the ES module imports the dependencies that are imported with `import`
+//! instead of requiring them.
+
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{
+    trace::TraceRawVcs, FxIndexSet, RcStr, ResolvedVc, TaskInput, TryJoinIterExt, Value,
+    ValueToString, Vc,
+};
+use turbo_tasks_fs::{glob::Glob, File, FileContent, FileSystemPath};
+use turbopack::{
+    ecmascript::{
+        chunk::{EcmascriptChunkPlaceable, EcmascriptExports},
+        utils::StringifyJs,
+    },
+    ModuleAssetContext,
+};
+use turbopack_core::{
+    asset::{Asset, AssetContent},
+    chunk::{availability_info::AvailabilityInfo, EvaluatableAsset, EvaluatableAssets},
+    compile_time_info::CompileTimeInfo,
+    context::AssetContext,
+    file_source::FileSource,
+    ident::AssetIdent,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    module::Module,
+    output::{OutputAsset, OutputAssets},
+    package_json::read_package_json,
+    reference::{all_assets_from_entries, all_modules_and_affecting_sources},
+    reference_type::{EntryReferenceSubType, ReferenceType},
+    resolve::{
+        parse::Request,
+        plugin::{
+            AfterResolvePlugin, AfterResolvePluginCondition, BeforeResolvePlugin,
+            BeforeResolvePluginCondition,
+        },
+        ExternalType, ResolveResult, ResolveResultItem, ResolveResultOption,
+    },
+    virtual_output::VirtualOutputAsset,
+};
+use turbopack_node::execution_context::ExecutionContext;
+use turbopack_nodejs::NodeJsChunkingContext;
+
+use super::{entry_names, project_relative_path};
+use crate::{
+    config::ProjectConfig,
+    contexts::{get_client_module_options_context, get_client_resolve_options_context, NodeEnv},
+};
+
+/// The extensions of the modules that get their own output with
+/// `--preserve-modules`.
+const MODULE_EXTENSIONS: [&str; 8] = ["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"];
+
+/// The `package.json` fields listing the packages that are kept external.
+const DEPENDENCY_FIELDS: [&str; 3] = ["dependencies", "peerDependencies", "optionalDependencies"];
+
+/// The module format of library outputs.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, TaskInput, Serialize, Deserialize, TraceRawVcs,
+)]
+pub(crate) enum LibraryFormat {
+    /// `.cjs` files, which require their imports.
+    CommonJs,
+    /// `.mjs` files, which import the modules that are imported with `import`
+    /// and require the ones that are required.
+    EcmaScript,
+}
+
+impl LibraryFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            LibraryFormat::CommonJs => "cjs",
+            LibraryFormat::EcmaScript => "mjs",
+        }
+    }
+
+    /// The type of the external that replaces a request with `reference_type`.
+    fn external_type(self, reference_type: &ReferenceType) -> ExternalType {
+        match (self, reference_type) {
+            (LibraryFormat::EcmaScript, ReferenceType::EcmaScriptModules(_)) => {
+                ExternalType::EcmaScriptModule
+            }
+            _ => ExternalType::CommonJs,
+        }
+    }
+}
+
+/// The names of the packages the library depends on, which are imported at
+/// runtime instead of being bundled.
+#[turbo_tasks::function]
+async fn package_dependencies(project_path: Vc<FileSystemPath>) -> Result<Vc<Vec<RcStr>>> {
+    let package_json = read_package_json(project_path.join("package.json".into())).await?;
+    let Some(package_json) = &*package_json else {
+        return Ok(Vc::cell(vec![]));
+    };
+    let dependencies: FxIndexSet<RcStr> = DEPENDENCY_FIELDS
+        .iter()
+        .filter_map(|field| package_json[*field].as_object())
+        .flat_map(|dependencies| dependencies.keys())
+        .map(|name| RcStr::from(name.as_str()))
+        .collect();
+    Ok(Vc::cell(dependencies.into_iter().collect()))
+}
+
+/// Keeps requests for the dependencies of the package, including subpaths like
+/// `lodash/fp`, as externals of the [LibraryFormat].
+#[turbo_tasks::value]
+struct PackageExternalsPlugin {
+    dependencies: ResolvedVc<Vec<RcStr>>,
+    format: LibraryFormat,
+}
+
+#[turbo_tasks::value_impl]
+impl PackageExternalsPlugin {
+    #[turbo_tasks::function]
+    fn new(dependencies: ResolvedVc<Vec<RcStr>>, format: LibraryFormat) -> Vc<Self> {
+        PackageExternalsPlugin {
+            dependencies,
+            format,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl BeforeResolvePlugin for PackageExternalsPlugin {
+    #[turbo_tasks::function]
+    fn before_resolve_condition(&self) -> Vc<BeforeResolvePluginCondition> {
+        BeforeResolvePluginCondition::from_modules(*self.dependencies)
+    }
+
+    #[turbo_tasks::function]
+    async fn before_resolve(
+        &self,
+        _lookup_path: Vc<FileSystemPath>,
+        reference_type: Value<ReferenceType>,
+        request: Vc<Request>,
+    ) -> Result<Vc<ResolveResultOption>> {
+        let Some(request) = request.await?.request() else {
+            return Ok(ResolveResultOption::none());
+        };
+        let external_type = self.format.external_type(&reference_type);
+        Ok(ResolveResultOption::some(
+            ResolveResult::primary(ResolveResultItem::External(request, external_type)).cell(),
+        ))
+    }
+}
+
+/// Turns imports between the modules of the project into imports of their
+/// outputs, so every module is emitted on its own with `--preserve-modules`.
+#[turbo_tasks::value]
+struct PreserveModulesPlugin {
+    project_path: ResolvedVc<FileSystemPath>,
+    format: LibraryFormat,
+}
+
+#[turbo_tasks::value_impl]
+impl PreserveModulesPlugin {
+    #[turbo_tasks::function]
+    fn new(project_path: ResolvedVc<FileSystemPath>, format: LibraryFormat) -> Vc<Self> {
+        PreserveModulesPlugin {
+            project_path,
+            format,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl AfterResolvePlugin for PreserveModulesPlugin {
+    #[turbo_tasks::function]
+    fn after_resolve_condition(&self) -> Vc<AfterResolvePluginCondition> {
+        AfterResolvePluginCondition::new(
+            *self.project_path,
+            Glob::new(format!("**/*.{{{}}}", MODULE_EXTENSIONS.join(",")).into()),
+        )
+    }
+
+    #[turbo_tasks::function]
+    async fn after_resolve(
+        &self,
+        fs_path: Vc<FileSystemPath>,
+        lookup_path: Vc<FileSystemPath>,
+        reference_type: Value<ReferenceType>,
+        _request: Vc<Request>,
+    ) -> Result<Vc<ResolveResultOption>> {
+        let reference_type = reference_type.into_value();
+        if matches!(reference_type, ReferenceType::Entry(_)) {
+            return Ok(ResolveResultOption::none());
+        }
+        let fs_path = fs_path.await?;
+        let Some(project_relative) = self.project_path.await?.get_path_to(&fs_path) else {
+            return Ok(ResolveResultOption::none());
+        };
+        if project_relative
+            .split('/')
+            .any(|segment| segment == "node_modules")
+        {
+            return Ok(ResolveResultOption::none());
+        }
+        // The outputs have the same directory structure as the modules.
+        let Some(relative) = lookup_path.await?.get_relative_path_to(&fs_path) else {
+            return Ok(ResolveResultOption::none());
+        };
+        let relative = match fs_path.extension_ref() {
+            Some(extension) => &relative[..relative.len() - extension.len() - 1],
+            None => &*relative,
+        };
+        // Required modules are always loaded from the CommonJS output.
+        let external_type = self.format.external_type(&reference_type);
+        let extension = match external_type {
+            ExternalType::EcmaScriptModule => LibraryFormat::EcmaScript.extension(),
+            _ => LibraryFormat::CommonJs.extension(),
+        };
+        let request = if relative.starts_with("../") {
+            format!("{relative}.{extension}")
+        } else {
+            format!("./{relative}.{extension}")
+        };
+        Ok(ResolveResultOption::some(
+            ResolveResult::primary(ResolveResultItem::External(request.into(), external_type))
+                .cell(),
+        ))
+    }
+}
+
+/// The asset context of library builds of the `format`, which keeps the
+/// dependencies of the package external and, when `preserve_modules` is set,
+/// all imports between the modules of the project.
+#[turbo_tasks::function]
+pub(crate) async fn get_library_asset_context(
+    project_path: ResolvedVc<FileSystemPath>,
+    execution_context: Vc<ExecutionContext>,
+    compile_time_info: Vc<CompileTimeInfo>,
+    config: Vc<ProjectConfig>,
+    preserve_modules: bool,
+    format: LibraryFormat,
+) -> Result<Vc<Box<dyn AssetContext>>> {
+    let mut resolve_options_context = get_client_resolve_options_context(*project_path, config)
+        .await?
+        .clone_value();
+    resolve_options_context
+        .before_resolve_plugins
+        .push(ResolvedVc::upcast(
+            PackageExternalsPlugin::new(package_dependencies(*project_path), format)
+                .to_resolved()
+                .await?,
+        ));
+    if preserve_modules {
+        resolve_options_context
+            .after_resolve_plugins
+            .push(ResolvedVc::upcast(
+                PreserveModulesPlugin::new(*project_path, format)
+                    .to_resolved()
+                    .await?,
+            ));
+    }
+    let module_options_context = get_client_module_options_context(
+        *project_path,
+        execution_context,
+        compile_time_info.environment(),
+        NodeEnv::Production.cell(),
+        config,
+    );
+
+    // The modules of the formats differ in their externals, so they need to be
+    // in different layers.
+    let layer = match format {
+        LibraryFormat::CommonJs => "library",
+        LibraryFormat::EcmaScript => "library-esm",
+    };
+    Ok(Vc::upcast(ModuleAssetContext::new(
+        Default::default(),
+        compile_time_info,
+        module_options_context,
+        resolve_options_context.cell(),
+        Vc::cell(layer.into()),
+    )))
+}
+
+/// The `.cjs`, `.mjs` and `.d.ts` files of the entries, or of all modules of
+/// the project they import when `preserve_modules` is set, and the chunks they
+/// load. The outputs are named after the path of the module, see
+/// [entry_names]. `asset_context` returns the asset context of a format.
+pub(crate) async fn library_output_assets(
+    entries: &[ResolvedVc<Box<dyn Module>>],
+    chunking_context: Vc<NodeJsChunkingContext>,
+    project_path: Vc<FileSystemPath>,
+    build_output_root: Vc<FileSystemPath>,
+    preserve_modules: bool,
+    asset_context: impl Fn(LibraryFormat) -> Vc<Box<dyn AssetContext>>,
+) -> Result<ResolvedVc<OutputAssets>> {
+    // The source files to emit, with their path relative to the project.
+    let sources: Vec<(Vc<FileSystemPath>, RcStr)> = if preserve_modules {
+        let project_path_ref = project_path.await?;
+        let mut paths = FxIndexSet::default();
+        for &entry in entries {
+            for module in all_modules_and_affecting_sources(*entry).await?.iter() {
+                let path = module.ident().path().await?;
+                let Some(project_relative) = project_path_ref.get_path_to(&path) else {
+                    continue;
+                };
+                if project_relative
+                    .split('/')
+                    .any(|segment| segment == "node_modules")
+                    || !path
+                        .extension_ref()
+                        .is_some_and(|extension| MODULE_EXTENSIONS.contains(&extension))
+                {
+                    continue;
+                }
+                paths.insert(RcStr::from(project_relative));
+            }
+        }
+        paths
+            .into_iter()
+            .map(|path| (project_path.join(path.clone()), path))
+            .collect()
+    } else {
+        entries
+            .iter()
+            .map(|&entry| async move {
+                Ok((
+                    entry.ident().path(),
+                    project_relative_path(project_path, entry).await?,
+                ))
+            })
+            .try_join()
+            .await?
+    };
+    let paths = sources
+        .iter()
+        .map(|(_, path)| path.clone())
+        .collect::<Vec<_>>();
+    let names = entry_names(&paths)?;
+    let asset_context = &asset_context;
+
+    let entry_assets: Vec<ResolvedVc<Box<dyn OutputAsset>>> = sources
+        .into_iter()
+        .zip(names)
+        .map(|((source_path, _), name)| async move {
+            let mut assets = Vec::new();
+            for format in [LibraryFormat::CommonJs, LibraryFormat::EcmaScript] {
+                let module = asset_context(format)
+                    .process(
+                        Vc::upcast(FileSource::new(source_path)),
+                        Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined)),
+                    )
+                    .module()
+                    .to_resolved()
+                    .await?;
+                let Some(evaluatable) =
+                    ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(module).await?
+                else {
+                    bail!(
+                        "{} can't be evaluated, so it can't be used as an entry of a library",
+                        module.ident().to_string().await?
+                    )
+                };
+                let entry_chunk = chunking_context
+                    .entry_chunk_group(
+                        build_output_root.join(format!("{name}.{}", format.extension()).into()),
+                        *module,
+                        EvaluatableAssets::one(*evaluatable),
+                        OutputAssets::empty(),
+                        Value::new(AvailabilityInfo::Root),
+                    )
+                    .await?
+                    .asset;
+                assets.push(match format {
+                    LibraryFormat::CommonJs => entry_chunk,
+                    LibraryFormat::EcmaScript => ResolvedVc::upcast(
+                        EsmLibraryEntry::new(*entry_chunk, *module)
+                            .to_resolved()
+                            .await?,
+                    ),
+                });
+            }
+
+            let declarations = source_path.parent().join(
+                format!(
+                    "{}.d.ts",
+                    source_path
+                        .file_stem()
+                        .await?
+                        .as_deref()
+                        .unwrap_or_default()
+                )
+                .into(),
+            );
+            if let FileContent::Content(_) = &*declarations.read().await? {
+                assets.push(ResolvedVc::upcast(
+                    VirtualOutputAsset::new(
+                        build_output_root.join(format!("{name}.d.ts").into()),
+                        AssetContent::file(declarations.read()),
+                    )
+                    .to_resolved()
+                    .await?,
+                ));
+            }
+            Ok(assets)
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    Ok(all_assets_from_entries(Vc::cell(entry_assets))
+        .to_resolved()
+        .await?)
+}
+
+/// Makes `require` and `module` available to the CommonJS code of the entry
+/// chunk.
+const ESM_PRELUDE: &str =
+    "import { createRequire as __turbopack_create_require__ } from \"node:module\";\nconst \
+     require = __turbopack_create_require__(import.meta.url);\nconst module = { exports: {} };\n";
+
+/// The `.mjs` output of an entry. The entry chunk of the
+/// [LibraryFormat::EcmaScript] build is CommonJS code that loads the Turbopack
+/// runtime and the other chunks, so it's wrapped into an ES module that
+/// provides `require` and exports the exports of the entry.
+///
+/// The source map of the entry chunk is shifted by the lines of the
+/// [ESM_PRELUDE], see [OffsetSourceMap].
+#[turbo_tasks::value]
+struct EsmLibraryEntry {
+    entry_chunk: ResolvedVc<Box<dyn OutputAsset>>,
+    module: ResolvedVc<Box<dyn Module>>,
+}
+
+#[turbo_tasks::value_impl]
+impl EsmLibraryEntry {
+    #[turbo_tasks::function]
+    fn new(
+        entry_chunk: ResolvedVc<Box<dyn OutputAsset>>,
+        module: ResolvedVc<Box<dyn Module>>,
+    ) -> Vc<Self> {
+        EsmLibraryEntry {
+            entry_chunk,
+            module,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for EsmLibraryEntry {
+    /// The entry chunk itself is not emitted, so the wrapper takes its place.
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.entry_chunk.ident()
+    }
+
+    #[turbo_tasks::function]
+    async fn references(&self) -> Result<Vc<OutputAssets>> {
+        let source_map_path = self
+            .entry_chunk
+            .ident()
+            .path()
+            .append(".map".into())
+            .await?;
+        let lines = ESM_PRELUDE.lines().count();
+        let references = self
+            .entry_chunk
+            .references()
+            .await?
+            .iter()
+            .map(|&reference| {
+                let source_map_path = &source_map_path;
+                async move {
+                    Ok(if *reference.ident().path().await? == *source_map_path {
+                        ResolvedVc::upcast(
+                            OffsetSourceMap {
+                                source_map: reference,
+                                lines,
+                            }
+                            .resolved_cell(),
+                        )
+                    } else {
+                        reference
+                    })
+                }
+            })
+            .try_join()
+            .await?;
+        Ok(Vc::cell(references))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for EsmLibraryEntry {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let FileContent::Content(file) = &*self.entry_chunk.content().file_content().await? else {
+            bail!(
+                "The entry chunk of {} has no content",
+                self.module.ident().to_string().await?
+            );
+        };
+        let mut code = ESM_PRELUDE.to_string();
+        // The source map comment needs to stay at the end.
+        let mut source_mapping_url = None;
+        for line in file.content().to_str()?.lines() {
+            if line.starts_with("//# sourceMappingURL=") {
+                source_mapping_url = Some(line);
+            } else {
+                writeln!(code, "{line}")?;
+            }
+        }
+        // The exports of async modules, e.g. when they import ES module
+        // dependencies, are a promise.
+        writeln!(
+            code,
+            "const __turbopack_library__ = module.exports instanceof Promise ? await \
+             module.exports : module.exports;"
+        )?;
+
+        let exports =
+            match ResolvedVc::try_sidecast::<Box<dyn EcmascriptChunkPlaceable>>(self.module).await?
+            {
+                Some(placeable) => Some(placeable.get_exports().await?),
+                None => None,
+            };
+        match exports.as_deref() {
+            Some(EcmascriptExports::EsmExports(exports)) => {
+                let exports = exports.expand_exports().await?;
+                let names = exports.exports.keys().cloned().collect::<Vec<_>>();
+                // The names of these exports are only known at runtime, they are
+                // still available from the default export when there is none.
+                let export_default =
+                    !exports.dynamic_exports.is_empty() && !exports.exports.contains_key("default");
+                code.push_str(&esm_exports(&names, export_default));
+                if !exports.dynamic_exports.is_empty() {
+                    DynamicLibraryExportsIssue {
+                        entry: self.module.ident().to_resolved().await?,
+                        modules: exports
+                            .dynamic_exports
+                            .iter()
+                            .map(|module| async move {
+                                Ok((*module.ident().to_string().await?).clone())
+                            })
+                            .try_join()
+                            .await?,
+                        export_default,
+                    }
+                    .cell()
+                    .emit();
+                }
+            }
+            Some(EcmascriptExports::None | EcmascriptExports::EmptyCommonJs) => {}
+            Some(
+                EcmascriptExports::CommonJs
+                | EcmascriptExports::DynamicNamespace
+                | EcmascriptExports::Value,
+            )
+            | None => {
+                code.push_str(&esm_exports(&[], true));
+            }
+        }
+        if let Some(source_mapping_url) = source_mapping_url {
+            writeln!(code, "{source_mapping_url}")?;
+        }
+        Ok(AssetContent::file(File::from(code).into()))
+    }
+}
+
+/// The source map of an entry chunk that is emitted with `lines` more lines
+/// in front of its code.
+#[turbo_tasks::value]
+struct OffsetSourceMap {
+    source_map: ResolvedVc<Box<dyn OutputAsset>>,
+    lines: usize,
+}
+
+#[turbo_tasks::value_impl]
+impl OutputAsset for OffsetSourceMap {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source_map.ident()
+    }
+
+    #[turbo_tasks::function]
+    fn references(&self) -> Vc<OutputAssets> {
+        self.source_map.references()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for OffsetSourceMap {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let FileContent::Content(file) = &*self.source_map.content().file_content().await? else {
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        };
+        let source_map = offset_source_map(&file.content().to_str()?, self.lines)?;
+        Ok(AssetContent::file(File::from(source_map).into()))
+    }
+}
+
+/// Shifts the mappings of a source map, either a regular or an index map, by
+/// `lines` lines.
+fn offset_source_map(source_map: &str, lines: usize) -> Result<String> {
+    let mut source_map: serde_json::Value = serde_json::from_str(source_map)?;
+    if let Some(sections) = source_map
+        .get_mut("sections")
+        .and_then(|sections| sections.as_array_mut())
+    {
+        for section in sections {
+            let Some(line) = section.pointer_mut("/offset/line") else {
+                bail!("A section of the source map has no offset");
+            };
+            *line = (line.as_u64().unwrap_or_default() + lines as u64).into();
+        }
+    } else if let Some(mappings) = source_map.get_mut("mappings") {
+        let Some(shifted) = mappings
+            .as_str()
+            .map(|mappings| format!("{}{mappings}", ";".repeat(lines)))
+        else {
+            bail!("The mappings of the source map are not a string");
+        };
+        *mappings = shifted.into();
+    } else {
+        bail!("The source map has neither sections nor mappings");
+    }
+    Ok(serde_json::to_string(&source_map)?)
+}
+
+/// The export statements of an ES module that exports the `names` of
+/// `__turbopack_library__` and, when `export_default` is set,
+/// `__turbopack_library__` itself as the default export.
+fn esm_exports(names: &[RcStr], export_default: bool) -> String {
+    let mut code = String::new();
+    let mut specifiers = Vec::with_capacity(names.len());
+    for (i, name) in names.iter().enumerate() {
+        // Writing to a `String` can't fail.
+        let _ = writeln!(
+            code,
+            "const __turbopack_export_{i}__ = __turbopack_library__[{}];",
+            StringifyJs(name)
+        );
+        specifiers.push(format!("__turbopack_export_{i}__ as {}", StringifyJs(name)));
+    }
+    if !specifiers.is_empty() {
+        let _ = writeln!(code, "export {{ {} }};", specifiers.join(", "));
+    }
+    if export_default {
+        code.push_str("export default __turbopack_library__;\n");
+    }
+    code
+}
+
+/// The `.mjs` output of a library entry can't export the exports of modules
+/// whose exports are only known at runtime, e.g. `export * from` a CommonJS
+/// module.
+#[turbo_tasks::value(shared)]
+struct DynamicLibraryExportsIssue {
+    entry: ResolvedVc<AssetIdent>,
+    modules: Vec<RcStr>,
+    export_default: bool,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for DynamicLibraryExportsIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(
+            "The ES module output can't re-export the exports of some modules by name".into(),
+        )
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::CodeGen.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.entry.path()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        let mut description = format!(
+            "The exports of these modules are only known at runtime: {}.",
+            self.modules.join(", ")
+        );
+        description.push_str(if self.export_default {
+            " They are available from the default export of the `.mjs` output and as named exports \
+             of the `.cjs` output."
+        } else {
+            " They are available as named exports of the `.cjs` output."
+        });
+        Vc::cell(Some(StyledString::Text(description.into()).cell()))
+    }
+}
+
+/// The longest directory prefix, including the trailing `/`, shared by all
+/// `paths`.
+pub(super) fn common_directory<'a>(paths: impl Iterator<Item = &'a str>) -> String {
+    let mut common: Option<Vec<&str>> = None;
+    for path in paths {
+        let directory = path.rsplit_once('/').map_or("", |(directory, _)| directory);
+        let segments: Vec<&str> = directory.split('/').filter(|s| !s.is_empty()).collect();
+        common = Some(match common {
+            None => segments,
+            Some(common) => common
+                .into_iter()
+                .zip(segments)
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    match common {
+        Some(common) if !common.is_empty() => format!("{}/", common.join("/")),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbopack_core::reference_type::CommonJsReferenceSubType;
+
+    use super::*;
+
+    #[test]
+    fn common_directory_of_paths() {
+        let common = |paths: &[&str]| common_directory(paths.iter().copied());
+        assert_eq!(common(&[]), "");
+        assert_eq!(common(&["index.js"]), "");
+        assert_eq!(common(&["src/index.js"]), "src/");
+        assert_eq!(common(&["src/a/index.js", "src/a/b/c.js"]), "src/a/");
+        assert_eq!(common(&["src/a/index.js", "src/b/index.js"]), "src/");
+        assert_eq!(common(&["src/index.js", "lib/index.js"]), "");
+        // Only whole segments are shared.
+        assert_eq!(common(&["src/app/a.js", "src/apps/b.js"]), "src/");
+    }
+
+    #[test]
+    fn externals_of_formats() {
+        let import = ReferenceType::EcmaScriptModules(Default::default());
+        let require = ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined);
+        assert_eq!(
+            LibraryFormat::CommonJs.external_type(&import),
+            ExternalType::CommonJs
+        );
+        assert_eq!(
+            LibraryFormat::EcmaScript.external_type(&import),
+            ExternalType::EcmaScriptModule
+        );
+        assert_eq!(
+            LibraryFormat::EcmaScript.external_type(&require),
+            ExternalType::CommonJs
+        );
+    }
+
+    #[test]
+    fn offsets_source_maps() {
+        let lines = ESM_PRELUDE.lines().count();
+        assert_eq!(lines, 3);
+        let regular = offset_source_map(r#"{"version":3,"mappings":"AAAA;AACA"}"#, lines).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&regular).unwrap(),
+            serde_json::json!({ "version": 3, "mappings": ";;;AAAA;AACA" })
+        );
+        let sectioned = offset_source_map(
+            r#"{"version":3,"sections":[{"offset":{"line":0,"column":0},"map":{}},{"offset":{"line":10,"column":2},"map":{}}]}"#,
+            lines,
+        )
+        .unwrap();
+        let sectioned: serde_json::Value = serde_json::from_str(&sectioned).unwrap();
+        assert_eq!(
+            sectioned.pointer("/sections/0/offset/line"),
+            Some(&3.into())
+        );
+        assert_eq!(
+            sectioned.pointer("/sections/1/offset/line"),
+            Some(&13.into())
+        );
+        assert_eq!(
+            sectioned.pointer("/sections/1/offset/column"),
+            Some(&2.into())
+        );
+        assert!(offset_source_map(r#"{"version":3}"#, lines).is_err());
+    }
+
+    #[test]
+    fn esm_export_statements() {
+        assert_eq!(esm_exports(&[], false), "");
+        assert_eq!(
+            esm_exports(&[], true),
+            "export default __turbopack_library__;\n"
+        );
+        assert_eq!(
+            esm_exports(&["a".into(), "default".into()], false),
+            "const __turbopack_export_0__ = __turbopack_library__[\"a\"];\nconst \
+             __turbopack_export_1__ = __turbopack_library__[\"default\"];\nexport { \
+             __turbopack_export_0__ as \"a\", __turbopack_export_1__ as \"default\" };\n"
+        );
+    }
+}
@@ -1,26 +1,31 @@
 use std::{
-    collections::HashSet,
     env::current_dir,
-    path::{PathBuf, MAIN_SEPARATOR},
-    sync::Arc,
+    fmt::{self, Display},
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+    sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use anyhow::{bail, Context, Result};
+use owo_colors::OwoColorize;
 use turbo_tasks::{
-    RcStr, ReadConsistency, ResolvedVc, TransientInstance, TryJoinIterExt, TurboTasks, Value, Vc,
+    util::FormatDuration, FxIndexMap, FxIndexSet, RcStr, ReadConsistency, ResolvedVc,
+    TransientInstance, TransientValue, TryJoinIterExt, TurboTasks, Value, Vc,
 };
-use turbo_tasks_fs::FileSystem;
+use turbo_tasks_fs::{DirectoryContent, DirectoryEntry, FileSystem, FileSystemPath};
 use turbo_tasks_memory::MemoryBackend;
+use turbopack::{css::write_css_module_declarations, evaluate_context::node_build_environment};
+use turbopack_browser::BrowserChunkingContext;
 use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
 use turbopack_core::{
-    asset::Asset,
+    asset::{Asset, AssetContent},
     chunk::{
         availability_info::AvailabilityInfo, ChunkableModule, ChunkingContext, ChunkingContextExt,
         EvaluatableAsset, EvaluatableAssets, MinifyType,
     },
     environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
-    issue::{handle_issues, IssueReporter, IssueSeverity},
-    module::Module,
+    issue::{handle_issues, Issue, IssueDescriptionExt, IssueReporter, IssueSeverity},
+    module::{Module, Modules},
     output::{OutputAsset, OutputAssets},
     reference::all_assets_from_entries,
     reference_type::{EntryReferenceSubType, ReferenceType},
@@ -28,21 +33,29 @@ use turbopack_core::{
         origin::{PlainResolveOrigin, ResolveOriginExt},
         parse::Request,
     },
+    virtual_output::VirtualOutputAsset,
 };
+use turbopack_dev_server::html::DevHtmlAsset;
 use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_env::dotenv::load_env;
 use turbopack_node::execution_context::ExecutionContext;
 use turbopack_nodejs::NodeJsChunkingContext;
 
+use self::library::{
+    common_directory, get_library_asset_context, library_output_assets, LibraryFormat,
+};
 use crate::{
-    arguments::BuildArguments,
+    arguments::{BuildArguments, BuildTarget},
+    config::ProjectConfig,
     contexts::{get_client_asset_context, get_client_compile_time_info, NodeEnv},
     util::{
-        normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest, EntryRequests,
-        NormalizedDirs,
+        normalize_dirs, normalize_entries, output_disk_fs, output_fs, project_fs, EntryRequest,
+        EntryRequests, NormalizedDirs,
     },
 };
 
+pub(crate) mod library;
+
 pub fn register() {
     turbopack::register();
     include!(concat!(env!("OUT_DIR"), "/register.rs"));
@@ -58,6 +71,11 @@ pub struct TurbopackBuildBuilder {
     show_all: bool,
     log_detail: bool,
     minify_type: MinifyType,
+    target: BuildTarget,
+    preserve_modules: bool,
+    watch: bool,
+    clean: bool,
+    config: ProjectConfig,
 }
 
 impl TurbopackBuildBuilder {
@@ -76,6 +94,11 @@ impl TurbopackBuildBuilder {
             show_all: false,
             log_detail: false,
             minify_type: MinifyType::Minify,
+            target: BuildTarget::Node,
+            preserve_modules: false,
+            watch: false,
+            clean: false,
+            config: ProjectConfig::default(),
         }
     }
 
@@ -109,7 +132,41 @@ impl TurbopackBuildBuilder {
         self
     }
 
+    pub fn target(mut self, target: BuildTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Emits every module of the project imported by the entries on its own
+    /// with [BuildTarget::Library].
+    pub fn preserve_modules(mut self, preserve_modules: bool) -> Self {
+        self.preserve_modules = preserve_modules;
+        self
+    }
+
+    /// Keeps running after the build and rebuilds when files change.
+    pub fn watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    /// Deletes the files that earlier builds wrote to the output directory
+    /// and that the build doesn't write anymore.
+    pub fn clean(mut self, clean: bool) -> Self {
+        self.clean = clean;
+        self
+    }
+
+    pub fn config(mut self, config: ProjectConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     pub async fn build(self) -> Result<()> {
+        if self.watch {
+            return self.build_and_watch().await;
+        }
+
         let task = self.turbo_tasks.spawn_once_task::<(), _>(async move {
             let build_result = build_internal(
                 self.project_dir.clone(),
@@ -124,6 +181,10 @@ impl TurbopackBuildBuilder {
                 .cell(),
                 self.browserslist_query,
                 self.minify_type,
+                self.target,
+                self.preserve_modules,
+                self.clean,
+                self.config.cell(),
             );
 
             // Await the result to propagate any errors.
@@ -156,6 +217,225 @@ impl TurbopackBuildBuilder {
 
         Ok(())
     }
+
+    /// Builds in a root task, which is re-executed when files change, and
+    /// prints a summary after every rebuild. Never returns.
+    async fn build_and_watch(self) -> Result<()> {
+        let TurbopackBuildBuilder {
+            turbo_tasks,
+            project_dir,
+            root_dir,
+            entry_requests,
+            browserslist_query,
+            log_level,
+            show_all,
+            log_detail,
+            minify_type,
+            target,
+            preserve_modules,
+            watch: _,
+            clean,
+            config,
+        } = self;
+        let state = Arc::new(Mutex::new(WatchState::default()));
+        let log_options = TransientInstance::new(LogOptions {
+            project_dir: PathBuf::from(&*project_dir),
+            current_dir: current_dir().unwrap(),
+            show_all,
+            log_detail,
+            log_level,
+        });
+
+        turbo_tasks.spawn_root_task({
+            let state = state.clone();
+            move || {
+                let state = state.clone();
+                let project_dir = project_dir.clone();
+                let root_dir = root_dir.clone();
+                let entry_requests = entry_requests.clone();
+                let browserslist_query = browserslist_query.clone();
+                let config = config.clone();
+                let log_options = log_options.clone();
+                async move {
+                    let build_result = build_internal(
+                        project_dir,
+                        root_dir,
+                        EntryRequests(
+                            entry_requests
+                                .into_iter()
+                                .map(EntryRequest::resolved_cell)
+                                .collect(),
+                        )
+                        .cell(),
+                        browserslist_query,
+                        minify_type,
+                        target,
+                        preserve_modules,
+                        clean,
+                        config.cell(),
+                    );
+                    let summary = summarize_rebuild(build_result, log_options, &state).await;
+                    state.lock().unwrap().summary = Some(summary);
+                    Ok(Vc::<()>::default())
+                }
+            }
+        });
+
+        loop {
+            let update = turbo_tasks
+                .get_or_wait_aggregated_update_info(Duration::from_millis(100))
+                .await;
+            let Some(summary) = state.lock().unwrap().summary.take() else {
+                continue;
+            };
+            let reasons = if update.reasons.is_empty() {
+                String::new()
+            } else {
+                format!("{} ", update.reasons)
+            };
+            match summary {
+                Ok(summary) => println!(
+                    "{event_type} - {reasons}built in {duration}, {summary}",
+                    event_type = "event".purple(),
+                    duration = FormatDuration(update.duration),
+                ),
+                Err(err) => println!(
+                    "{event_type} - {reasons}build failed after {duration}: {err:?}",
+                    event_type = "error".red(),
+                    duration = FormatDuration(update.duration),
+                ),
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    /// The content hashes of the output assets of the last successful build.
+    outputs: FxIndexMap<RcStr, u64>,
+    /// The summary of the last build, until it's printed.
+    summary: Option<Result<RebuildSummary>>,
+}
+
+struct RebuildSummary {
+    changed_outputs: Vec<RcStr>,
+    errors: usize,
+    warnings: usize,
+}
+
+impl Display for RebuildSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// The number of changed outputs listed by name.
+        const MAX_LISTED_OUTPUTS: usize = 3;
+
+        match self.changed_outputs.len() {
+            0 => write!(f, "no outputs changed")?,
+            1 => write!(f, "1 output changed")?,
+            count => write!(f, "{count} outputs changed")?,
+        }
+        if !self.changed_outputs.is_empty() {
+            let listed = self
+                .changed_outputs
+                .iter()
+                .take(MAX_LISTED_OUTPUTS)
+                .map(|path| &**path)
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " ({listed}")?;
+            if self.changed_outputs.len() > MAX_LISTED_OUTPUTS {
+                write!(
+                    f,
+                    " and {} more",
+                    self.changed_outputs.len() - MAX_LISTED_OUTPUTS
+                )?;
+            }
+            write!(f, ")")?;
+        }
+        write!(
+            f,
+            ", {} error{}, {} warning{}",
+            self.errors,
+            if self.errors == 1 { "" } else { "s" },
+            self.warnings,
+            if self.warnings == 1 { "" } else { "s" },
+        )
+    }
+}
+
+/// Reports the new issues of `build_result` to the console and compares its
+/// outputs with the ones of the previous build.
+async fn summarize_rebuild(
+    build_result: Vc<OutputAssets>,
+    log_options: TransientInstance<LogOptions>,
+    state: &Mutex<WatchState>,
+) -> Result<RebuildSummary> {
+    let output_assets = build_result.strongly_consistent().await?;
+
+    let issues = build_result.peek_issues_with_path().await?;
+    let mut errors = 0;
+    let mut warnings = 0;
+    for issue in issues.iter() {
+        match *issue.severity().await? {
+            severity if severity <= IssueSeverity::Error => errors += 1,
+            IssueSeverity::Warning => warnings += 1,
+            _ => {}
+        }
+    }
+    // Fatal issues are counted as errors instead of failing the build, so the
+    // watcher keeps running.
+    let issue_reporter: Vc<Box<dyn IssueReporter>> = Vc::upcast(ConsoleUi::new(log_options));
+    issue_reporter
+        .report_issues(
+            TransientInstance::new(issues),
+            TransientValue::new(Vc::into_raw(build_result)),
+            IssueSeverity::Error.into(),
+        )
+        .await?;
+
+    let outputs = output_assets
+        .iter()
+        .map(|asset| async move {
+            Ok((
+                asset.ident().path().await?.path.clone(),
+                *asset.content().file_content().hash().await?,
+            ))
+        })
+        .try_join()
+        .await?
+        .into_iter()
+        .collect::<FxIndexMap<_, _>>();
+
+    let mut state = state.lock().unwrap();
+    let changed_outputs = changed_outputs(&state.outputs, &outputs);
+    state.outputs = outputs;
+
+    Ok(RebuildSummary {
+        changed_outputs,
+        errors,
+        warnings,
+    })
+}
+
+/// The sorted paths of the `outputs` that are new or have another content hash
+/// than in `previous`.
+fn changed_outputs(
+    previous: &FxIndexMap<RcStr, u64>,
+    outputs: &FxIndexMap<RcStr, u64>,
+) -> Vec<RcStr> {
+    let mut changed_outputs = outputs
+        .iter()
+        .filter(|(path, hash)| previous.get(*path) != Some(*hash))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    changed_outputs.sort();
+    changed_outputs
+}
+
+/// The entry modules of a build and all output assets generated for them.
+#[turbo_tasks::value(shared)]
+pub(crate) struct BuildGraph {
+    pub entries: ResolvedVc<Modules>,
+    pub output_assets: ResolvedVc<OutputAssets>,
 }
 
 #[turbo_tasks::function]
@@ -165,7 +445,90 @@ async fn build_internal(
     entry_requests: Vc<EntryRequests>,
     browserslist_query: RcStr,
     minify_type: MinifyType,
-) -> Result<Vc<()>> {
+    target: BuildTarget,
+    preserve_modules: bool,
+    clean: bool,
+    config: Vc<ProjectConfig>,
+) -> Result<Vc<OutputAssets>> {
+    let graph = build_graph(
+        project_dir.clone(),
+        root_dir.clone(),
+        entry_requests,
+        browserslist_query,
+        minify_type,
+        target,
+        preserve_modules,
+        config,
+    )
+    .await?;
+    if config.await?.css_modules.generate_declarations {
+        write_css_module_declarations(*graph.entries).await?;
+    }
+
+    let output_assets = graph.output_assets;
+    output_assets
+        .await?
+        .iter()
+        .map(|c| c.content().write(c.ident().path()))
+        .try_join()
+        .await?;
+
+    if !clean {
+        return Ok(*output_assets);
+    }
+
+    // All outputs have been written, so the files that earlier builds wrote and
+    // this one didn't are left over.
+    let output_dir = config.await?.output_dir();
+    check_cleanable_output_dir(
+        &Path::new(&*project_dir).join(&*output_dir),
+        Path::new(&*project_dir),
+        Path::new(&*root_dir),
+    )?;
+    let output_disk_fs = output_disk_fs(project_dir.clone()).await?;
+    output_disk_fs
+        .update_emitted_paths(
+            "build",
+            output_assets
+                .await?
+                .iter()
+                .map(|asset| asset.ident().path()),
+        )
+        .await?;
+    output_disk_fs
+        .remove_stale_output(output_fs(project_dir).root().join(output_dir))
+        .await?;
+
+    Ok(*output_assets)
+}
+
+/// Stale output is only removed from directories that contain neither the
+/// project nor the root directory.
+fn check_cleanable_output_dir(
+    output_dir: &Path,
+    project_dir: &Path,
+    root_dir: &Path,
+) -> Result<()> {
+    if project_dir.starts_with(output_dir) || root_dir.starts_with(output_dir) {
+        bail!(
+            "refusing to clean the output directory {}, it contains the project",
+            output_dir.display()
+        );
+    }
+    Ok(())
+}
+
+#[turbo_tasks::function]
+pub(crate) async fn build_graph(
+    project_dir: RcStr,
+    root_dir: RcStr,
+    entry_requests: Vc<EntryRequests>,
+    browserslist_query: RcStr,
+    minify_type: MinifyType,
+    target: BuildTarget,
+    preserve_modules: bool,
+    config: Vc<ProjectConfig>,
+) -> Result<Vc<BuildGraph>> {
     let env = Environment::new(Value::new(ExecutionEnvironment::Browser(
         BrowserEnvironment {
             dom: true,
@@ -184,32 +547,65 @@ async fn build_internal(
         .replace(MAIN_SEPARATOR, "/")
         .into();
     let project_path = project_fs.root().join(project_relative);
-    let build_output_root = output_fs.root().join("dist".into());
+    let build_output_root = output_fs.root().join(config.await?.output_dir());
 
     let node_env = NodeEnv::Production.cell();
+    let runtime_type = match *node_env.await? {
+        NodeEnv::Development => RuntimeType::Development,
+        NodeEnv::Production => RuntimeType::Production,
+    };
 
-    let chunking_context = Vc::upcast(
-        NodeJsChunkingContext::builder(
+    let node_chunking_context = match target {
+        BuildTarget::Node | BuildTarget::Library => NodeJsChunkingContext::builder(
             project_path,
             build_output_root,
             build_output_root,
             build_output_root,
             build_output_root,
             env,
-            match *node_env.await? {
-                NodeEnv::Development => RuntimeType::Development,
-                NodeEnv::Production => RuntimeType::Production,
-            },
+            runtime_type,
         )
         .minify_type(minify_type)
         .build(),
-    );
+        // Only used to run webpack loaders and other build time code, so it's
+        // written next to the build output instead of into it.
+        BuildTarget::Browser => {
+            let build_root = output_fs.root().join(".turbopack/build".into());
+            NodeJsChunkingContext::builder(
+                project_path,
+                build_root,
+                build_root,
+                build_root.join("chunks".into()),
+                build_root.join("assets".into()),
+                node_build_environment(),
+                RuntimeType::Development,
+            )
+            .build()
+        }
+    };
 
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
+    let env = load_env(project_path);
+    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env, config, env);
     let execution_context =
-        ExecutionContext::new(project_path, chunking_context, load_env(project_path));
-    let asset_context =
-        get_client_asset_context(project_path, execution_context, compile_time_info, node_env);
+        ExecutionContext::new(project_path, Vc::upcast(node_chunking_context), env)
+            .with_pool_options(config.node_pool_options());
+    let asset_context = match target {
+        BuildTarget::Node | BuildTarget::Browser => get_client_asset_context(
+            project_path,
+            execution_context,
+            compile_time_info,
+            node_env,
+            config,
+        ),
+        BuildTarget::Library => get_library_asset_context(
+            project_path,
+            execution_context,
+            compile_time_info,
+            config,
+            false,
+            LibraryFormat::CommonJs,
+        ),
+    };
 
     let entry_requests = (*entry_requests
         .await?
@@ -237,10 +633,15 @@ async fn build_internal(
 
     let origin = PlainResolveOrigin::new(asset_context, output_fs.root().join("_".into()));
     let project_dir = &project_dir;
+    let entry_subtype = match target {
+        BuildTarget::Node | BuildTarget::Library => EntryReferenceSubType::Undefined,
+        BuildTarget::Browser => EntryReferenceSubType::Web,
+    };
+    let entry_subtype = &entry_subtype;
     let entries = entry_requests
         .into_iter()
         .map(|request_vc| async move {
-            let ty = Value::new(ReferenceType::Entry(EntryReferenceSubType::Undefined));
+            let ty = Value::new(ReferenceType::Entry(entry_subtype.clone()));
             let request = request_vc.await?;
             origin
                 .resolve_asset(request_vc, origin.resolve_options(ty.clone()), ty)
@@ -257,17 +658,78 @@ async fn build_internal(
         .try_join()
         .await?;
 
+    let output_assets = match target {
+        BuildTarget::Node => {
+            node_output_assets(&entries, node_chunking_context, build_output_root).await?
+        }
+        BuildTarget::Browser => {
+            let chunking_context = Vc::upcast(
+                BrowserChunkingContext::builder(
+                    project_path,
+                    build_output_root,
+                    build_output_root,
+                    build_output_root.join("_chunks".into()),
+                    build_output_root.join("_assets".into()),
+                    compile_time_info.environment(),
+                    runtime_type,
+                )
+                .minify_type(minify_type)
+                .build(),
+            );
+            browser_output_assets(
+                &entries,
+                chunking_context,
+                project_path,
+                project_path.join("public".into()),
+                build_output_root,
+            )
+            .await?
+        }
+        BuildTarget::Library => {
+            library_output_assets(
+                &entries,
+                node_chunking_context,
+                project_path,
+                build_output_root,
+                preserve_modules,
+                |format| {
+                    get_library_asset_context(
+                        project_path,
+                        execution_context,
+                        compile_time_info,
+                        config,
+                        preserve_modules,
+                        format,
+                    )
+                },
+            )
+            .await?
+        }
+    };
+
+    Ok(BuildGraph {
+        entries: ResolvedVc::cell(entries),
+        output_assets,
+    }
+    .cell())
+}
+
+/// The `*.entry.js` files of the entries and the chunks they load.
+async fn node_output_assets(
+    entries: &[ResolvedVc<Box<dyn Module>>],
+    chunking_context: Vc<NodeJsChunkingContext>,
+    build_output_root: Vc<FileSystemPath>,
+) -> Result<ResolvedVc<OutputAssets>> {
     let entry_chunk_groups = entries
-        .into_iter()
+        .iter()
+        .copied()
         .map(|entry_module| async move {
             Ok(
                 if let Some(ecmascript) =
                     ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module).await?
                 {
                     Vc::cell(vec![
-                        Vc::try_resolve_downcast_type::<NodeJsChunkingContext>(chunking_context)
-                            .await?
-                            .unwrap()
+                        chunking_context
                             .entry_chunk_group(
                                 build_output_root
                                     .join(
@@ -305,25 +767,166 @@ async fn build_internal(
         .try_join()
         .await?;
 
-    let mut chunks: HashSet<ResolvedVc<Box<dyn OutputAsset>>> = HashSet::new();
+    let mut chunks: FxIndexSet<ResolvedVc<Box<dyn OutputAsset>>> = FxIndexSet::default();
     for chunk_group in entry_chunk_groups {
         chunks.extend(&*all_assets_from_entries(chunk_group).await?);
     }
 
-    chunks
+    Ok(ResolvedVc::cell(chunks.into_iter().collect()))
+}
+
+/// The names of the outputs of the entries with the project relative `paths`.
+/// A name is the path without extension, relative to the directory shared by
+/// all entries. Fails when two entries would get the same name, e.g. `a.ts`
+/// and `a.js`.
+fn entry_names(paths: &[RcStr]) -> Result<Vec<RcStr>> {
+    let root = common_directory(paths.iter().map(|path| &**path));
+    let mut entries_by_name: FxIndexMap<RcStr, &RcStr> = FxIndexMap::default();
+    paths
         .iter()
-        .map(|c| c.content().write(c.ident().path()))
+        .map(|path| {
+            let relative = path.strip_prefix(root.as_str()).unwrap_or(path);
+            let file_name_start = relative.rfind('/').map_or(0, |index| index + 1);
+            let name: RcStr = match relative[file_name_start..].rfind('.') {
+                Some(dot) if dot > 0 => relative[..file_name_start + dot].into(),
+                _ => relative.into(),
+            };
+            if let Some(other) = entries_by_name.insert(name.clone(), path) {
+                bail!(
+                    "The entries {other} and {path} would both be written as {name}, rename one \
+                     of them"
+                );
+            }
+            Ok(name)
+        })
+        .collect()
+}
+
+/// The path of an entry relative to the project directory, or to the root of
+/// the filesystem when it's outside of the project.
+async fn project_relative_path(
+    project_path: Vc<FileSystemPath>,
+    module: ResolvedVc<Box<dyn Module>>,
+) -> Result<RcStr> {
+    let path = module.ident().path().await?;
+    Ok(match project_path.await?.get_path_to(&path) {
+        Some(relative) => relative.into(),
+        None => path.path.clone(),
+    })
+}
+
+/// An HTML page per entry, the chunks they load and the files of the
+/// `public_dir`. The pages are named after the path of the entry, see
+/// [entry_names], or `index.html` for a single entry.
+async fn browser_output_assets(
+    entries: &[ResolvedVc<Box<dyn Module>>],
+    chunking_context: Vc<Box<dyn ChunkingContext>>,
+    project_path: Vc<FileSystemPath>,
+    public_dir: Vc<FileSystemPath>,
+    build_output_root: Vc<FileSystemPath>,
+) -> Result<ResolvedVc<OutputAssets>> {
+    let names = if let [_] = entries {
+        vec![RcStr::from("index")]
+    } else {
+        let paths = entries
+            .iter()
+            .map(|&entry| project_relative_path(project_path, entry))
+            .try_join()
+            .await?;
+        entry_names(&paths)?
+    };
+    let pages = entries
+        .iter()
+        .copied()
+        .zip(names)
+        .map(|(entry_module, name)| async move {
+            let Some(chunkable) =
+                ResolvedVc::try_sidecast::<Box<dyn ChunkableModule>>(entry_module).await?
+            else {
+                bail!(
+                    "Entry module is not chunkable, so it can't be used to bootstrap the \
+                     application"
+                )
+            };
+            // The HTML page evaluates the entry itself, so there are no other
+            // runtime entries.
+            let runtime_entries =
+                if ResolvedVc::try_sidecast::<Box<dyn EvaluatableAsset>>(entry_module)
+                    .await?
+                    .is_some()
+                {
+                    Some(EvaluatableAssets::empty())
+                } else {
+                    None
+                };
+            let path = build_output_root.join(format!("{name}.html").into());
+            Ok(Vc::upcast::<Box<dyn OutputAsset>>(DevHtmlAsset::new(
+                path,
+                vec![(chunkable, chunking_context, runtime_entries)],
+            )))
+        })
         .try_join()
         .await?;
 
-    Ok(Default::default())
+    // Generated assets take precedence over public files with the same path.
+    let mut assets: FxIndexMap<RcStr, ResolvedVc<Box<dyn OutputAsset>>> = FxIndexMap::default();
+    let public_assets = copied_assets(public_dir, build_output_root).await?;
+    let generated_assets = all_assets_from_entries(Vc::cell(
+        pages
+            .into_iter()
+            .map(|page| page.to_resolved())
+            .try_join()
+            .await?,
+    ))
+    .await?;
+    for &asset in public_assets.iter().chain(generated_assets.iter()) {
+        assets.insert(asset.ident().path().await?.path.clone(), asset);
+    }
+
+    Ok(ResolvedVc::cell(assets.into_values().collect()))
+}
+
+/// Copies of the files in `dir` and its subdirectories to `output_dir`.
+#[turbo_tasks::function]
+async fn copied_assets(
+    dir: Vc<FileSystemPath>,
+    output_dir: Vc<FileSystemPath>,
+) -> Result<Vc<OutputAssets>> {
+    let DirectoryContent::Entries(entries) = &*dir.read_dir().await? else {
+        return Ok(OutputAssets::empty());
+    };
+    let mut assets = Vec::new();
+    for (name, entry) in entries.iter() {
+        match entry {
+            DirectoryEntry::File(path) | DirectoryEntry::Symlink(path) => {
+                assets.push(ResolvedVc::upcast(
+                    VirtualOutputAsset::new(
+                        output_dir.join(name.clone()),
+                        AssetContent::file(path.read()),
+                    )
+                    .to_resolved()
+                    .await?,
+                ))
+            }
+            DirectoryEntry::Directory(path) => assets.extend(
+                copied_assets(**path, output_dir.join(name.clone()))
+                    .await?
+                    .iter()
+                    .copied(),
+            ),
+            DirectoryEntry::Other(_) | DirectoryEntry::Error => {}
+        }
+    }
+    Ok(Vc::cell(assets))
 }
 
 pub async fn build(args: &BuildArguments) -> Result<()> {
     let NormalizedDirs {
         project_dir,
         root_dir,
+        mut config,
     } = normalize_dirs(&args.common.dir, &args.common.root)?;
+    config.apply_arguments(&args.common);
 
     let tt = TurboTasks::new(MemoryBackend::new(
         args.common
@@ -343,8 +946,17 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
         } else {
             MinifyType::Minify
         })
+        .target(args.target)
+        .preserve_modules(args.preserve_modules)
+        .watch(args.watch)
+        .clean(args.clean)
         .show_all(args.common.show_all);
 
+    if let Some(browserslist) = &config.browserslist {
+        builder = builder.browserslist_query(browserslist.clone());
+    }
+    builder = builder.config(config);
+
     for entry in normalize_entries(&args.common.entries) {
         builder = builder.entry_request(EntryRequest::Relative(entry));
     }
@@ -353,3 +965,95 @@ pub async fn build(args: &BuildArguments) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(paths: &[&str]) -> Result<Vec<RcStr>> {
+        entry_names(
+            &paths
+                .iter()
+                .map(|&path| RcStr::from(path))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn only_cleans_output_dirs_inside_the_project() {
+        let project_dir = Path::new("/repo/app");
+        let root_dir = Path::new("/repo");
+        let check = |output_dir: &str| {
+            check_cleanable_output_dir(&project_dir.join(output_dir), project_dir, root_dir)
+        };
+        assert!(check("dist").is_ok());
+        assert!(check("build/client").is_ok());
+        assert!(check(".").is_err());
+        assert!(check("/repo").is_err());
+        assert!(check("/").is_err());
+    }
+
+    #[test]
+    fn entry_names_are_relative_to_the_shared_directory() {
+        assert_eq!(names(&["src/a.js", "src/b.tsx"]).unwrap(), vec!["a", "b"]);
+        assert_eq!(
+            names(&["src/a/index.js", "src/b/index.js"]).unwrap(),
+            vec!["a/index", "b/index"]
+        );
+        assert_eq!(
+            names(&["src/index.js", "src/admin/index.js"]).unwrap(),
+            vec!["index", "admin/index"]
+        );
+        assert_eq!(
+            names(&["lib/v1.2/a", "lib/b.js"]).unwrap(),
+            vec!["v1.2/a", "b"]
+        );
+    }
+
+    #[test]
+    fn changed_outputs_are_new_or_have_another_hash() {
+        let outputs = |entries: &[(&str, u64)]| {
+            entries
+                .iter()
+                .map(|&(path, hash)| (RcStr::from(path), hash))
+                .collect::<FxIndexMap<_, _>>()
+        };
+        let previous = outputs(&[("dist/b.js", 1), ("dist/a.js", 2), ("dist/removed.js", 3)]);
+        let current = outputs(&[("dist/b.js", 4), ("dist/a.js", 2), ("dist/c.js", 5)]);
+        assert_eq!(
+            changed_outputs(&previous, &current),
+            vec!["dist/b.js", "dist/c.js"]
+        );
+        assert!(changed_outputs(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn rebuild_summary() {
+        let summary = |changed_outputs: &[&str], errors, warnings| {
+            RebuildSummary {
+                changed_outputs: changed_outputs.iter().map(|&path| path.into()).collect(),
+                errors,
+                warnings,
+            }
+            .to_string()
+        };
+        assert_eq!(
+            summary(&[], 0, 1),
+            "no outputs changed, 0 errors, 1 warning"
+        );
+        assert_eq!(
+            summary(&["a.js"], 1, 0),
+            "1 output changed (a.js), 1 error, 0 warnings"
+        );
+        assert_eq!(
+            summary(&["a.js", "b.js", "c.js", "d.js", "e.js"], 0, 0),
+            "5 outputs changed (a.js, b.js, c.js and 2 more), 0 errors, 0 warnings"
+        );
+    }
+
+    #[test]
+    fn entry_names_must_be_unique() {
+        let err = names(&["src/a.js", "src/a.ts"]).unwrap_err();
+        assert!(err.to_string().contains("src/a.js and src/a.ts"), "{err}");
+    }
+}
@@ -0,0 +1,464 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, FxIndexMap, RcStr, Vc};
+use turbopack::{
+    css::{CssModulesExportConvention, CssModulesOptions},
+    module_options::{LoaderRuleItem, WebpackLoadersOptions},
+};
+use turbopack_node::{
+    transforms::webpack::{WebpackLoaderItem, WebpackLoaderItems},
+    NodeJsPoolConfig, NodeJsPoolOptions,
+};
+
+use crate::arguments::CommonArguments;
+
+/// The configuration files looked up in the project directory, in order of
+/// precedence.
+pub const CONFIG_FILE_NAMES: [&str; 2] = ["turbopack.config.json", "turbopack.config.toml"];
+
+/// The project configuration from `turbopack.config.json` or
+/// `turbopack.config.toml`. Changes are picked up on restart.
+#[turbo_tasks::value(serialization = "custom", eq = "manual")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Aliases for import requests, e.g. `"@/*": "./src/*"` or
+    /// `"lodash": "lodash-es"`. A `*` in the alias matches any string, which
+    /// replaces the `*` in the target. Relative targets are resolved from the
+    /// project directory.
+    pub resolve_alias: FxIndexMap<RcStr, RcStr>,
+    /// The extensions tried for requests without one, e.g. `[".ts", ".js"]`.
+    pub resolve_extensions: Option<Vec<RcStr>>,
+    /// Expressions replaced at compile time, e.g. `"process.env.API":
+    /// "https://example.com"` or `"__DEV__": false`. Strings are inserted as
+    /// string literals, other values as JSON.
+    #[turbo_tasks(trace_ignore)]
+    pub define: FxIndexMap<RcStr, serde_json::Value>,
+    /// Environment variables, also from `.env` files, that are inlined as
+    /// `process.env.NAME`.
+    pub env: Vec<RcStr>,
+    /// Webpack loaders applied to files matching a glob, e.g. `"*.svg": {
+    /// "loaders": ["@svgr/webpack"], "as": "*.js" }`.
+    pub rules: FxIndexMap<RcStr, RuleConfig>,
+    /// The browserslist query of the targeted browsers.
+    pub browserslist: Option<RcStr>,
+    /// The directory builds are written to, relative to the project directory.
+    /// Defaults to `dist`. It must be inside of the project directory, since
+    /// stale files in it are removed.
+    pub output_dir: Option<RcStr>,
+    pub plugins: PluginsConfig,
+    pub css_modules: CssModulesConfig,
+    /// Limits for the Node.js workers that run webpack loaders, e.g.
+    /// `{ "maxWorkers": 2, "idleTimeout": 30000 }`. Memory is in megabytes,
+    /// timeouts in milliseconds.
+    pub node_pool: NodeJsPoolConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RuleConfig {
+    #[serde(default)]
+    pub loaders: Vec<LoaderConfig>,
+    /// Treats the result of the loaders as a file with this name, where `*`
+    /// is replaced with the original name, e.g. `*.js`.
+    #[serde(rename = "as")]
+    pub rename_as: Option<RcStr>,
+    /// Whether ESM imports in the matching files need to include the file
+    /// extension.
+    pub fully_specified: Option<bool>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(untagged)]
+pub enum LoaderConfig {
+    Name(RcStr),
+    WithOptions {
+        loader: RcStr,
+        #[turbo_tasks(trace_ignore)]
+        #[serde(default)]
+        options: serde_json::Map<String, serde_json::Value>,
+    },
+}
+
+/// The ECMAScript transforms applied to the project's code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct PluginsConfig {
+    pub emotion: bool,
+    pub styled_components: bool,
+    pub styled_jsx: bool,
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        PluginsConfig {
+            emotion: true,
+            styled_components: true,
+            styled_jsx: true,
+        }
+    }
+}
+
+/// Options for CSS modules (`*.module.css`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CssModulesConfig {
+    /// The pattern of the generated class names, using the `[name]`,
+    /// `[local]` and `[hash]` placeholders, e.g. `[local]_[hash]`.
+    pub pattern: Option<RcStr>,
+    /// Scopes CSS variables, e.g. `--color`, to the module.
+    pub dashed_idents: bool,
+    /// Scopes grid line and area names to the module.
+    pub grid: bool,
+    /// How class names are exported: `asIs`, `camelCase`, `camelCaseOnly`,
+    /// `dashes` or `dashesOnly`.
+    pub export_convention: CssModulesExportConvention,
+    /// Writes a `.module.css.d.ts` file next to each CSS module.
+    pub generate_declarations: bool,
+}
+
+impl ProjectConfig {
+    /// Reads the configuration file from `project_dir`. Returns the default
+    /// configuration when there is none.
+    pub fn load(project_dir: &Path) -> Result<ProjectConfig> {
+        for name in CONFIG_FILE_NAMES {
+            let path = project_dir.join(name);
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(err).with_context(|| format!("Unable to read {}", path.display()))
+                }
+            };
+            let config = if name.ends_with(".toml") {
+                toml::from_str(&content).map_err(anyhow::Error::from)
+            } else {
+                serde_json::from_str(&content).map_err(anyhow::Error::from)
+            };
+            return config
+                .and_then(|config: ProjectConfig| {
+                    config.validate()?;
+                    Ok(config)
+                })
+                .with_context(|| format!("Invalid configuration in {}", path.display()));
+        }
+        Ok(ProjectConfig::default())
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(output_dir) = &self.output_dir {
+            validate_output_dir(output_dir)
+                .with_context(|| format!("Invalid outputDir {output_dir:?}"))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the command line arguments, which take precedence over the
+    /// configuration file.
+    pub fn apply_arguments(&mut self, args: &CommonArguments) {
+        if let Some(max_workers) = args.node_workers {
+            self.node_pool.max_workers = Some(max_workers);
+        }
+    }
+
+    pub fn output_dir(&self) -> RcStr {
+        self.output_dir.clone().unwrap_or_else(|| "dist".into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ProjectConfig {
+    /// The webpack loaders configured by `rules`, if any.
+    #[turbo_tasks::function]
+    pub fn webpack_loaders_options(&self) -> Vc<OptionWebpackLoadersOptions> {
+        if self.rules.is_empty() {
+            return Vc::cell(None);
+        }
+        let rules = self
+            .rules
+            .iter()
+            .map(|(glob, rule)| {
+                let loaders = rule
+                    .loaders
+                    .iter()
+                    .map(|loader| match loader {
+                        LoaderConfig::Name(loader) => WebpackLoaderItem {
+                            loader: loader.clone(),
+                            options: Default::default(),
+                        },
+                        LoaderConfig::WithOptions { loader, options } => WebpackLoaderItem {
+                            loader: loader.clone(),
+                            options: options.clone(),
+                        },
+                    })
+                    .collect();
+                (
+                    glob.clone(),
+                    LoaderRuleItem {
+                        loaders: WebpackLoaderItems(loaders).cell(),
+                        rename_as: rule.rename_as.clone(),
+                        fully_specified: rule.fully_specified,
+                    },
+                )
+            })
+            .collect();
+        Vc::cell(Some(
+            WebpackLoadersOptions {
+                rules: Vc::cell(rules),
+                loader_runner_package: None,
+            }
+            .cell(),
+        ))
+    }
+
+    /// The options for CSS modules configured by `cssModules`.
+    #[turbo_tasks::function]
+    pub fn css_modules_options(&self) -> Vc<CssModulesOptions> {
+        let CssModulesConfig {
+            pattern,
+            dashed_idents,
+            grid,
+            export_convention,
+            generate_declarations,
+        } = self.css_modules.clone();
+        CssModulesOptions {
+            pattern,
+            dashed_idents,
+            grid,
+            export_convention,
+            generate_declarations,
+        }
+        .cell()
+    }
+
+    /// The options for the Node.js pools configured by `nodePool`.
+    #[turbo_tasks::function]
+    pub fn node_pool_options(&self) -> Vc<NodeJsPoolOptions> {
+        self.node_pool.to_options().cell()
+    }
+}
+
+/// Checks that `output_dir` is a directory inside of the project directory.
+/// Both `/` and `\` are treated as separators, so the configuration is
+/// rejected the same way on every platform.
+fn validate_output_dir(output_dir: &str) -> Result<()> {
+    if output_dir.trim().is_empty() {
+        bail!("The output directory must not be empty");
+    }
+    let has_drive = output_dir.as_bytes().get(1) == Some(&b':')
+        && output_dir.as_bytes()[0].is_ascii_alphabetic();
+    if output_dir.starts_with(['/', '\\']) || has_drive || Path::new(output_dir).is_absolute() {
+        bail!("The output directory must be relative to the project directory");
+    }
+    let mut segments = output_dir
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .peekable();
+    if segments.peek().is_none() {
+        bail!("The output directory must not be the project directory");
+    }
+    if segments.any(|segment| segment == "..") {
+        bail!("The output directory must not contain `..`");
+    }
+    Ok(())
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionWebpackLoadersOptions(Option<Vc<WebpackLoadersOptions>>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(files: &[(&str, &str)]) -> Result<ProjectConfig> {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        ProjectConfig::load(dir.path())
+    }
+
+    fn common_arguments(node_workers: Option<usize>) -> CommonArguments {
+        CommonArguments {
+            entries: None,
+            dir: None,
+            root: None,
+            log_level: None,
+            show_all: false,
+            log_detail: false,
+            full_stats: false,
+            memory_limit: None,
+            node_workers,
+        }
+    }
+
+    #[test]
+    fn defaults_without_config_file() {
+        let config = load(&[]).unwrap();
+        assert_eq!(config, ProjectConfig::default());
+        assert_eq!(config.output_dir(), "dist");
+        assert!(config.plugins.emotion);
+    }
+
+    #[test]
+    fn parses_json() {
+        let config = load(&[(
+            "turbopack.config.json",
+            r#"{
+                "resolveAlias": { "@/*": "./src/*" },
+                "define": { "__DEV__": false },
+                "rules": {
+                    "*.svg": { "loaders": ["@svgr/webpack"], "as": "*.js" },
+                    "*.md": { "loaders": [{ "loader": "md-loader", "options": { "gfm": true } }] }
+                },
+                "outputDir": "build",
+                "plugins": { "emotion": false },
+                "cssModules": { "exportConvention": "dashesOnly" },
+                "nodePool": { "maxWorkers": 4 }
+            }"#,
+        )])
+        .unwrap();
+        assert_eq!(config.resolve_alias.get("@/*").unwrap(), "./src/*");
+        assert_eq!(
+            config.define.get("__DEV__"),
+            Some(&serde_json::Value::Bool(false))
+        );
+        let svg = config.rules.get("*.svg").unwrap();
+        assert_eq!(
+            svg.loaders,
+            vec![LoaderConfig::Name("@svgr/webpack".into())]
+        );
+        assert_eq!(svg.rename_as.as_deref(), Some("*.js"));
+        let LoaderConfig::WithOptions { loader, options } =
+            &config.rules.get("*.md").unwrap().loaders[0]
+        else {
+            panic!("expected a loader with options");
+        };
+        assert_eq!(loader, "md-loader");
+        assert_eq!(options.get("gfm"), Some(&serde_json::Value::Bool(true)));
+        assert_eq!(config.output_dir(), "build");
+        assert!(!config.plugins.emotion);
+        assert!(config.plugins.styled_jsx);
+        assert_eq!(
+            config.css_modules.export_convention,
+            CssModulesExportConvention::DashesOnly
+        );
+        assert_eq!(config.node_pool.max_workers, Some(4));
+    }
+
+    #[test]
+    fn parses_toml() {
+        let config = load(&[(
+            "turbopack.config.toml",
+            r#"
+                browserslist = "last 1 Chrome versions"
+                env = ["API_URL"]
+
+                [resolveAlias]
+                lodash = "lodash-es"
+
+                [cssModules]
+                dashedIdents = true
+            "#,
+        )])
+        .unwrap();
+        assert_eq!(
+            config.browserslist.as_deref(),
+            Some("last 1 Chrome versions")
+        );
+        assert_eq!(config.env, vec![RcStr::from("API_URL")]);
+        assert_eq!(config.resolve_alias.get("lodash").unwrap(), "lodash-es");
+        assert!(config.css_modules.dashed_idents);
+    }
+
+    #[test]
+    fn prefers_json_over_toml() {
+        let config = load(&[
+            ("turbopack.config.json", r#"{ "outputDir": "json" }"#),
+            ("turbopack.config.toml", r#"outputDir = "toml""#),
+        ])
+        .unwrap();
+        assert_eq!(config.output_dir(), "json");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        for content in [
+            r#"{ "resolveAliases": {} }"#,
+            r#"{ "plugins": { "emotions": true } }"#,
+            r#"{ "cssModules": { "exportConventions": "camelCase" } }"#,
+            r#"{ "nodePool": { "workers": 2 } }"#,
+            r#"{ "rules": { "*.svg": { "loader": "@svgr/webpack" } } }"#,
+        ] {
+            let err = load(&[("turbopack.config.json", content)]).unwrap_err();
+            assert!(
+                format!("{err:#}").contains("unknown field"),
+                "{content}: {err:#}"
+            );
+        }
+        assert!(load(&[("turbopack.config.toml", "outDir = \"build\"")]).is_err());
+    }
+
+    fn output_dir_error(output_dir: &str) -> String {
+        let content = serde_json::json!({ "outputDir": output_dir }).to_string();
+        let err = load(&[("turbopack.config.json", content.as_str())]).unwrap_err();
+        format!("{err:#}")
+    }
+
+    #[test]
+    fn rejects_empty_output_dir() {
+        assert!(output_dir_error("").contains("must not be empty"));
+        assert!(output_dir_error(" ").contains("must not be empty"));
+    }
+
+    #[test]
+    fn rejects_output_dir_of_the_project_directory() {
+        assert!(output_dir_error(".").contains("must not be the project directory"));
+        assert!(output_dir_error("./").contains("must not be the project directory"));
+    }
+
+    #[test]
+    fn rejects_output_dir_with_parent_segments() {
+        assert!(output_dir_error("..").contains("must not contain `..`"));
+        assert!(output_dir_error("../dist").contains("must not contain `..`"));
+        assert!(output_dir_error("build/../../dist").contains("must not contain `..`"));
+        assert!(output_dir_error("build\\..\\..").contains("must not contain `..`"));
+    }
+
+    #[test]
+    fn rejects_absolute_output_dir() {
+        for output_dir in ["/tmp/dist", "\\dist", "C:\\dist", "C:/dist"] {
+            assert!(
+                output_dir_error(output_dir).contains("must be relative"),
+                "{output_dir}"
+            );
+        }
+        let err = load(&[("turbopack.config.toml", r#"outputDir = "/dist""#)]).unwrap_err();
+        assert!(format!("{err:#}").contains("Invalid outputDir"));
+    }
+
+    #[test]
+    fn accepts_nested_output_dir() {
+        let config = load(&[(
+            "turbopack.config.json",
+            r#"{ "outputDir": "./build/dist" }"#,
+        )])
+        .unwrap();
+        assert_eq!(config.output_dir(), "./build/dist");
+    }
+
+    #[test]
+    fn command_line_takes_precedence() {
+        let mut config = load(&[(
+            "turbopack.config.json",
+            r#"{ "nodePool": { "maxWorkers": 4 } }"#,
+        )])
+        .unwrap();
+        config.apply_arguments(&common_arguments(None));
+        assert_eq!(config.node_pool.max_workers, Some(4));
+        config.apply_arguments(&common_arguments(Some(2)));
+        assert_eq!(config.node_pool.max_workers, Some(2));
+    }
+}
@@ -2,19 +2,22 @@ use std::fmt;
 
 use anyhow::Result;
 use turbo_tasks::{RcStr, ResolvedVc, Value, Vc};
+use turbo_tasks_env::ProcessEnv;
 use turbo_tasks_fs::{FileSystem, FileSystemPath};
 use turbopack::{
     ecmascript::{EcmascriptInputTransform, TreeShakingMode},
     module_options::{
-        EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext, ModuleRule,
-        ModuleRuleEffect, RuleCondition,
+        CssOptionsContext, EcmascriptOptionsContext, JsxTransformOptions, ModuleOptionsContext,
+        ModuleRule, ModuleRuleEffect, RuleCondition,
     },
     ModuleAssetContext,
 };
 use turbopack_browser::react_refresh::assert_can_resolve_react_refresh;
 use turbopack_core::{
     compile_time_defines,
-    compile_time_info::{CompileTimeDefines, CompileTimeInfo},
+    compile_time_info::{
+        CompileTimeDefineValue, CompileTimeDefines, CompileTimeInfo, DefineableNameSegment,
+    },
     condition::ContextCondition,
     context::AssetContext,
     environment::{BrowserEnvironment, Environment, ExecutionEnvironment},
@@ -30,6 +33,8 @@ use turbopack_node::{
 };
 use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
 
+use crate::config::ProjectConfig;
+
 #[turbo_tasks::value(shared)]
 pub enum NodeEnv {
     Development,
@@ -52,9 +57,21 @@ async fn foreign_code_context_condition() -> Result<ContextCondition> {
 #[turbo_tasks::function]
 pub async fn get_client_import_map(
     project_path: ResolvedVc<FileSystemPath>,
+    config: Vc<ProjectConfig>,
 ) -> Result<Vc<ImportMap>> {
     let mut import_map = ImportMap::empty();
 
+    for (alias, target) in &config.await?.resolve_alias {
+        let mapping =
+            ImportMapping::PrimaryAlternative(target.clone(), Some(project_path)).resolved_cell();
+        match alias.split_once('*') {
+            Some((prefix, suffix)) => {
+                import_map.insert_wildcard_alias_with_suffix(prefix, suffix, mapping)
+            }
+            None => import_map.insert_exact_alias(alias.clone(), mapping),
+        }
+    }
+
     import_map.insert_singleton_alias("@swc/helpers", project_path);
     import_map.insert_singleton_alias("styled-jsx", project_path);
     import_map.insert_singleton_alias("react", project_path);
@@ -80,11 +97,15 @@ pub async fn get_client_import_map(
 #[turbo_tasks::function]
 pub async fn get_client_resolve_options_context(
     project_path: Vc<FileSystemPath>,
+    config: Vc<ProjectConfig>,
 ) -> Result<Vc<ResolveOptionsContext>> {
-    let next_client_import_map = get_client_import_map(project_path).to_resolved().await?;
+    let next_client_import_map = get_client_import_map(project_path, config)
+        .to_resolved()
+        .await?;
     let module_options_context = ResolveOptionsContext {
         enable_node_modules: Some(project_path.root().to_resolved().await?),
         custom_conditions: vec!["development".into()],
+        custom_extensions: config.await?.resolve_extensions.clone(),
         import_map: Some(next_client_import_map),
         browser: true,
         module: true,
@@ -103,11 +124,12 @@ pub async fn get_client_resolve_options_context(
 }
 
 #[turbo_tasks::function]
-async fn get_client_module_options_context(
+pub(crate) async fn get_client_module_options_context(
     project_path: Vc<FileSystemPath>,
     execution_context: ResolvedVc<ExecutionContext>,
     env: ResolvedVc<Environment>,
     node_env: Vc<NodeEnv>,
+    config: Vc<ProjectConfig>,
 ) -> Result<Vc<ModuleOptionsContext>> {
     let module_options_context = ModuleOptionsContext {
         preset_env_versions: Some(env),
@@ -116,7 +138,7 @@ async fn get_client_module_options_context(
         ..Default::default()
     };
 
-    let resolve_options_context = get_client_resolve_options_context(project_path);
+    let resolve_options_context = get_client_resolve_options_context(project_path, config);
 
     let enable_react_refresh = matches!(*node_env.await?, NodeEnv::Development)
         && assert_can_resolve_react_refresh(project_path, resolve_options_context)
@@ -140,33 +162,45 @@ async fn get_client_module_options_context(
         RuleCondition::ResourcePathEndsWith(".tsx".to_string()),
     ]);
 
+    let plugins = config.await?.plugins.clone();
+    let mut transforms = vec![];
+    if plugins.emotion {
+        transforms.push(EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
+            EmotionTransformer::new(&EmotionTransformConfig::default())
+                .expect("Should be able to create emotion transformer"),
+        ) as _)));
+    }
+    if plugins.styled_components {
+        transforms.push(EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
+            StyledComponentsTransformer::new(&StyledComponentsTransformConfig::default()),
+        ) as _)));
+    }
+    if plugins.styled_jsx {
+        transforms.push(EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
+            StyledJsxTransformer::new(!module_options_context.css.use_swc_css, versions),
+        ) as _)));
+    }
+
     let module_rules = ModuleRule::new(
         conditions,
         vec![ModuleRuleEffect::ExtendEcmascriptTransforms {
-            prepend: Vc::cell(vec![
-                EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
-                    EmotionTransformer::new(&EmotionTransformConfig::default())
-                        .expect("Should be able to create emotion transformer"),
-                ) as _)),
-                EcmascriptInputTransform::Plugin(Vc::cell(Box::new(
-                    StyledComponentsTransformer::new(&StyledComponentsTransformConfig::default()),
-                ) as _)),
-                EcmascriptInputTransform::Plugin(Vc::cell(Box::new(StyledJsxTransformer::new(
-                    !module_options_context.css.use_swc_css,
-                    versions,
-                )) as _)),
-            ]),
+            prepend: Vc::cell(transforms),
             append: Vc::cell(vec![]),
         }],
     );
 
     let module_options_context = ModuleOptionsContext {
+        enable_webpack_loaders: *config.webpack_loaders_options().await?,
         ecmascript: EcmascriptOptionsContext {
             enable_jsx,
             enable_typescript_transform: Some(Default::default()),
             ..Default::default()
         },
         enable_postcss_transform: Some(PostCssTransformOptions::default().cell()),
+        css: CssOptionsContext {
+            modules: Some(config.css_modules_options().to_resolved().await?),
+            ..Default::default()
+        },
         rules: vec![(
             foreign_code_context_condition().await?,
             module_options_context.clone().cell(),
@@ -185,13 +219,15 @@ pub fn get_client_asset_context(
     execution_context: Vc<ExecutionContext>,
     compile_time_info: Vc<CompileTimeInfo>,
     node_env: Vc<NodeEnv>,
+    config: Vc<ProjectConfig>,
 ) -> Vc<Box<dyn AssetContext>> {
-    let resolve_options_context = get_client_resolve_options_context(project_path);
+    let resolve_options_context = get_client_resolve_options_context(project_path, config);
     let module_options_context = get_client_module_options_context(
         project_path,
         execution_context,
         compile_time_info.environment(),
         node_env,
+        config,
     );
 
     let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
@@ -205,31 +241,54 @@ pub fn get_client_asset_context(
     asset_context
 }
 
-fn client_defines(node_env: &NodeEnv) -> Vc<CompileTimeDefines> {
-    compile_time_defines!(
+#[turbo_tasks::function]
+async fn client_defines(
+    node_env: Vc<NodeEnv>,
+    config: Vc<ProjectConfig>,
+    env: Vc<Box<dyn ProcessEnv>>,
+) -> Result<Vc<CompileTimeDefines>> {
+    let mut defines = compile_time_defines!(
         process.turbopack = true,
         process.env.TURBOPACK = true,
-        process.env.NODE_ENV = node_env.to_string()
-    )
-    .cell()
+        process.env.NODE_ENV = node_env.await?.to_string()
+    );
+    let config = config.await?;
+    for name in &config.env {
+        if let Some(value) = &*env.read(name.clone()).await? {
+            defines.0.insert(
+                vec!["process".into(), "env".into(), name.clone().into()],
+                value.clone().into(),
+            );
+        }
+    }
+    for (name, value) in &config.define {
+        let name: Vec<DefineableNameSegment> = name.split('.').map(Into::into).collect();
+        let value = match value {
+            serde_json::Value::String(value) => CompileTimeDefineValue::from(value.as_str()),
+            serde_json::Value::Bool(value) => CompileTimeDefineValue::from(*value),
+            value => CompileTimeDefineValue::from(value.clone()),
+        };
+        defines.0.insert(name, value);
+    }
+    Ok(defines.cell())
 }
 
 #[turbo_tasks::function]
-pub async fn get_client_compile_time_info(
+pub fn get_client_compile_time_info(
     browserslist_query: RcStr,
     node_env: Vc<NodeEnv>,
-) -> Result<Vc<CompileTimeInfo>> {
-    Ok(
-        CompileTimeInfo::builder(Environment::new(Value::new(ExecutionEnvironment::Browser(
-            BrowserEnvironment {
-                dom: true,
-                web_worker: false,
-                service_worker: false,
-                browserslist_query,
-            }
-            .into(),
-        ))))
-        .defines(client_defines(&*node_env.await?))
-        .cell(),
-    )
+    config: Vc<ProjectConfig>,
+    env: Vc<Box<dyn ProcessEnv>>,
+) -> Vc<CompileTimeInfo> {
+    CompileTimeInfo::builder(Environment::new(Value::new(ExecutionEnvironment::Browser(
+        BrowserEnvironment {
+            dom: true,
+            web_worker: false,
+            service_worker: false,
+            browserslist_query,
+        }
+        .into(),
+    ))))
+    .defines(client_defines(node_env, config, env))
+    .cell()
 }
@@ -18,7 +18,7 @@ use turbo_tasks::{
 use turbo_tasks_fs::FileSystem;
 use turbo_tasks_malloc::TurboMalloc;
 use turbo_tasks_memory::MemoryBackend;
-use turbopack::evaluate_context::node_build_environment;
+use turbopack::{css::write_css_module_declarations, evaluate_context::node_build_environment};
 use turbopack_cli_utils::issue::{ConsoleUi, LogOptions};
 use turbopack_core::{
     issue::{IssueReporter, IssueSeverity},
@@ -26,7 +26,7 @@ use turbopack_core::{
     server_fs::ServerFileSystem,
 };
 use turbopack_dev_server::{
-    introspect::IntrospectionSource,
+    introspect::{module_graph_query::ModuleGraphQuerySource, IntrospectionSource},
     source::{
         combined::CombinedContentSource, router::PrefixedRouterContentSource,
         static_assets::StaticAssetsContentSource, ContentSource,
@@ -41,6 +41,7 @@ use turbopack_nodejs::NodeJsChunkingContext;
 use self::web_entry_source::create_web_entry_source;
 use crate::{
     arguments::DevArguments,
+    config::ProjectConfig,
     contexts::NodeEnv,
     util::{
         normalize_dirs, normalize_entries, output_fs, project_fs, EntryRequest, NormalizedDirs,
@@ -63,6 +64,7 @@ pub struct TurbopackDevServerBuilder {
     show_all: bool,
     log_detail: bool,
     allow_retry: bool,
+    config: ProjectConfig,
 }
 
 impl TurbopackDevServerBuilder {
@@ -87,6 +89,7 @@ impl TurbopackDevServerBuilder {
             show_all: false,
             log_detail: false,
             allow_retry: false,
+            config: ProjectConfig::default(),
         }
     }
 
@@ -135,6 +138,11 @@ impl TurbopackDevServerBuilder {
         self
     }
 
+    pub fn config(mut self, config: ProjectConfig) -> TurbopackDevServerBuilder {
+        self.config = config;
+        self
+    }
+
     pub fn issue_reporter(
         mut self,
         issue_reporter: Box<dyn IssueReporterProvider>,
@@ -204,6 +212,7 @@ impl TurbopackDevServerBuilder {
             log_level: self.log_level,
         });
         let entry_requests = TransientInstance::new(self.entry_requests);
+        let config = TransientInstance::new(self.config);
         let tasks = turbo_tasks.clone();
         let issue_provider = self.issue_reporter.unwrap_or_else(|| {
             // Initialize a ConsoleUi reporter if no custom reporter was provided
@@ -217,6 +226,7 @@ impl TurbopackDevServerBuilder {
                 entry_requests.clone(),
                 eager_compile,
                 browserslist_query.clone(),
+                config.clone(),
             )
         };
 
@@ -232,7 +242,9 @@ async fn source(
     entry_requests: TransientInstance<Vec<EntryRequest>>,
     eager_compile: bool,
     browserslist_query: RcStr,
-) -> Vc<Box<dyn ContentSource>> {
+    config: TransientInstance<ProjectConfig>,
+) -> Result<Vc<Box<dyn ContentSource>>> {
+    let config = (*config).clone().cell();
     let project_relative = project_dir.strip_prefix(&*root_dir).unwrap();
     let project_relative: RcStr = project_relative
         .strip_prefix(MAIN_SEPARATOR)
@@ -259,7 +271,8 @@ async fn source(
     .build();
 
     let execution_context =
-        ExecutionContext::new(project_path, Vc::upcast(build_chunking_context), env);
+        ExecutionContext::new(project_path, Vc::upcast(build_chunking_context), env)
+            .with_pool_options(config.node_pool_options());
 
     let server_fs = Vc::upcast::<Box<dyn FileSystem>>(ServerFileSystem::new());
     let server_root = server_fs.root();
@@ -281,7 +294,7 @@ async fn source(
         })
         .collect();
 
-    let web_source = create_web_entry_source(
+    let web_entry = create_web_entry_source(
         project_path,
         execution_context,
         entry_requests,
@@ -290,7 +303,13 @@ async fn source(
         eager_compile,
         NodeEnv::Development.cell(),
         browserslist_query,
-    );
+        config,
+    )
+    .await?;
+    if config.await?.css_modules.generate_declarations {
+        write_css_module_declarations(web_entry.entries).await?;
+    }
+    let web_source = web_entry.source;
     let static_source = Vc::upcast(StaticAssetsContentSource::new(
         Default::default(),
         project_path.join("public".into()),
@@ -302,12 +321,19 @@ async fn source(
         }
         .cell(),
     );
+    let why = Vc::upcast(ModuleGraphQuerySource::new(
+        web_entry.entries,
+        web_entry.html,
+    ));
     let main_source = Vc::upcast(main_source);
-    Vc::upcast(PrefixedRouterContentSource::new(
+    Ok(Vc::upcast(PrefixedRouterContentSource::new(
         Default::default(),
-        vec![("__turbopack__".into(), introspect)],
+        vec![
+            ("__turbopack__/why".into(), why),
+            ("__turbopack__".into(), introspect),
+        ],
         main_source,
-    ))
+    )))
 }
 
 pub fn register() {
@@ -326,7 +352,9 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
     let NormalizedDirs {
         project_dir,
         root_dir,
+        mut config,
     } = normalize_dirs(&args.common.dir, &args.common.root)?;
+    config.apply_arguments(&args.common);
 
     let tt = TurboTasks::new(MemoryBackend::new(
         args.common
@@ -348,6 +376,11 @@ pub async fn start_server(args: &DevArguments) -> Result<()> {
                 .map_or_else(|| IssueSeverity::Warning, |l| l.0),
         );
 
+    if let Some(browserslist) = &config.browserslist {
+        server = server.browserslist_query(browserslist.clone());
+    }
+    server = server.config(config);
+
     for entry in normalize_entries(&args.common.entries) {
         server = server.entry_request(EntryRequest::Relative(entry))
     }
@@ -8,6 +8,8 @@ use turbopack_core::{
     chunk::{ChunkableModule, ChunkingContext, EvaluatableAsset},
     environment::Environment,
     file_source::FileSource,
+    module::{Module, Modules},
+    output::{OutputAsset, OutputAssets},
     reference_type::{EntryReferenceSubType, ReferenceType},
     resolve::{
         origin::{PlainResolveOrigin, ResolveOriginExt},
@@ -22,6 +24,7 @@ use turbopack_ecmascript_runtime::RuntimeType;
 use turbopack_node::execution_context::ExecutionContext;
 
 use crate::{
+    config::ProjectConfig,
     contexts::{
         get_client_asset_context, get_client_compile_time_info, get_client_resolve_options_context,
         NodeEnv,
@@ -54,8 +57,9 @@ pub fn get_client_chunking_context(
 #[turbo_tasks::function]
 pub async fn get_client_runtime_entries(
     project_path: ResolvedVc<FileSystemPath>,
+    config: Vc<ProjectConfig>,
 ) -> Result<Vc<RuntimeEntries>> {
-    let resolve_options_context = get_client_resolve_options_context(*project_path);
+    let resolve_options_context = get_client_resolve_options_context(*project_path, config);
 
     let mut runtime_entries = Vec::new();
 
@@ -88,23 +92,38 @@ pub async fn get_client_runtime_entries(
     Ok(Vc::cell(runtime_entries))
 }
 
+/// The content source serving a web entry, together with the resolved entry
+/// modules and the HTML page bootstrapping them.
+#[turbo_tasks::value(shared)]
+pub struct WebEntrySource {
+    pub source: Vc<Box<dyn ContentSource>>,
+    pub entries: Vc<Modules>,
+    pub html: Vc<OutputAssets>,
+}
+
 #[turbo_tasks::function]
 pub async fn create_web_entry_source(
     project_path: Vc<FileSystemPath>,
     execution_context: Vc<ExecutionContext>,
     entry_requests: Vec<Vc<Request>>,
     server_root: Vc<FileSystemPath>,
-    _env: Vc<Box<dyn ProcessEnv>>,
+    env: Vc<Box<dyn ProcessEnv>>,
     eager_compile: bool,
     node_env: Vc<NodeEnv>,
     browserslist_query: RcStr,
-) -> Result<Vc<Box<dyn ContentSource>>> {
-    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env);
-    let asset_context =
-        get_client_asset_context(project_path, execution_context, compile_time_info, node_env);
+    config: Vc<ProjectConfig>,
+) -> Result<Vc<WebEntrySource>> {
+    let compile_time_info = get_client_compile_time_info(browserslist_query, node_env, config, env);
+    let asset_context = get_client_asset_context(
+        project_path,
+        execution_context,
+        compile_time_info,
+        node_env,
+        config,
+    );
     let chunking_context =
         get_client_chunking_context(project_path, server_root, compile_time_info.environment());
-    let entries = get_client_runtime_entries(project_path);
+    let entries = get_client_runtime_entries(project_path, config);
 
     let runtime_entries = entries.resolve_entries(asset_context);
 
@@ -125,9 +144,11 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entries: Vec<_> = entries
-        .into_iter()
-        .flatten()
+    let entry_modules: Vec<ResolvedVc<Box<dyn Module>>> = entries.into_iter().flatten().collect();
+
+    let entries: Vec<_> = entry_modules
+        .iter()
+        .copied()
         .map(|module| async move {
             if let (Some(chnkable), Some(entry)) = (
                 ResolvedVc::try_sidecast::<Box<dyn ChunkableModule>>(module).await?,
@@ -155,7 +176,7 @@ pub async fn create_web_entry_source(
         .try_join()
         .await?;
 
-    let entry_asset = Vc::upcast(DevHtmlAsset::new(
+    let entry_asset: Vc<Box<dyn OutputAsset>> = Vc::upcast(DevHtmlAsset::new(
         server_root.join("index.html".into()),
         entries,
     ));
@@ -165,5 +186,10 @@ pub async fn create_web_entry_source(
     } else {
         AssetGraphContentSource::new_lazy(server_root, entry_asset)
     });
-    Ok(graph)
+    Ok(WebEntrySource {
+        source: graph,
+        entries: Vc::cell(entry_modules),
+        html: OutputAssets::new(vec![entry_asset.to_resolved().await?]),
+    }
+    .cell())
 }
@@ -5,10 +5,12 @@
 
 pub mod arguments;
 pub mod build;
+pub mod config;
 pub(crate) mod contexts;
 pub mod dev;
 pub(crate) mod embed_js;
 pub(crate) mod util;
+pub mod why;
 
 pub fn register() {
     turbopack::register();
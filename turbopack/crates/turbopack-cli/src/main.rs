@@ -80,5 +80,6 @@ async fn main_inner(args: Arguments) -> Result<()> {
     match args {
         Arguments::Build(args) => turbopack_cli::build::build(&args).await,
         Arguments::Dev(args) => turbopack_cli::dev::start_server(&args).await,
+        Arguments::Why(args) => turbopack_cli::why::why(&args).await,
     }
 }
@@ -1,10 +1,15 @@
-use std::{env::current_dir, path::PathBuf};
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
 use dunce::canonicalize;
 use turbo_tasks::{RcStr, ResolvedVc, Vc};
 use turbo_tasks_fs::{DiskFileSystem, FileSystem};
 
+use crate::config::ProjectConfig;
+
 #[turbo_tasks::value(transparent)]
 pub struct EntryRequests(pub Vec<ResolvedVc<EntryRequest>>);
 
@@ -20,10 +25,13 @@ pub struct NormalizedDirs {
     pub project_dir: RcStr,
     /// Normalized root directory path as an absolute path
     pub root_dir: RcStr,
+    /// The configuration file of the project directory
+    pub config: ProjectConfig,
 }
 
 /// Normalizes (canonicalizes and represents as an absolute path in a String)
-/// the project and root directories.
+/// the project and root directories, and loads the project's configuration
+/// file.
 pub fn normalize_dirs(
     project_dir: &Option<PathBuf>,
     root_dir: &Option<PathBuf>,
@@ -46,9 +54,12 @@ pub fn normalize_dirs(
         None => project_dir.clone(),
     };
 
+    let config = ProjectConfig::load(Path::new(&*project_dir))?;
+
     Ok(NormalizedDirs {
         project_dir,
         root_dir,
+        config,
     })
 }
 
@@ -66,9 +77,16 @@ pub async fn project_fs(project_dir: RcStr) -> Result<Vc<Box<dyn FileSystem>>> {
     Ok(Vc::upcast(disk_fs))
 }
 
+/// The filesystem build outputs are written to. It owns its output
+/// directories, so stale files from earlier builds can be removed.
 #[turbo_tasks::function]
-pub async fn output_fs(project_dir: RcStr) -> Result<Vc<Box<dyn FileSystem>>> {
-    let disk_fs = DiskFileSystem::new("output".into(), project_dir, vec![]);
+pub async fn output_disk_fs(project_dir: RcStr) -> Result<Vc<DiskFileSystem>> {
+    let disk_fs = DiskFileSystem::new_owned_output("output".into(), project_dir, vec![]);
     disk_fs.await?.start_watching(None).await?;
-    Ok(Vc::upcast(disk_fs))
+    Ok(disk_fs)
+}
+
+#[turbo_tasks::function]
+pub fn output_fs(project_dir: RcStr) -> Vc<Box<dyn FileSystem>> {
+    Vc::upcast(output_disk_fs(project_dir))
 }
@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use turbo_tasks::TurboTasks;
+use turbo_tasks_memory::MemoryBackend;
+use turbopack_core::{
+    chunk::MinifyType,
+    module_graph_query::{query_module_graph, ModuleQuery, ModuleQueryResult},
+};
+
+use crate::{
+    arguments::{BuildTarget, WhyArguments},
+    build::build_graph,
+    util::{normalize_dirs, normalize_entries, EntryRequest, EntryRequests, NormalizedDirs},
+};
+
+/// Explains why a module or package is part of the build, by printing the
+/// shortest import chain from each entry and the chunks containing it.
+pub async fn why(args: &WhyArguments) -> Result<()> {
+    let NormalizedDirs {
+        project_dir,
+        root_dir,
+        config,
+    } = normalize_dirs(&args.common.dir, &args.common.root)?;
+
+    let query = match (&args.module, &args.package) {
+        (Some(module), None) => ModuleQuery::Path(module.as_str().into()),
+        (None, Some(package)) => ModuleQuery::Package(package.as_str().into()),
+        _ => bail!("Exactly one of --module or --package must be provided"),
+    };
+    let entry_requests = normalize_entries(&args.common.entries)
+        .into_iter()
+        .map(EntryRequest::Relative)
+        .collect::<Vec<_>>();
+    let json = args.json;
+
+    let tt = TurboTasks::new(MemoryBackend::new(
+        args.common
+            .memory_limit
+            .map_or(usize::MAX, |l| l * 1024 * 1024),
+    ));
+
+    let output = tt
+        .run_once(async move {
+            let graph = build_graph(
+                project_dir,
+                root_dir,
+                EntryRequests(
+                    entry_requests
+                        .into_iter()
+                        .map(EntryRequest::resolved_cell)
+                        .collect(),
+                )
+                .cell(),
+                config.browserslist.clone().unwrap_or_else(|| {
+                    "chrome 64, edge 79, firefox 67, opera 51, safari 12".into()
+                }),
+                MinifyType::NoMinify,
+                BuildTarget::Node,
+                false,
+                config.clone().cell(),
+            )
+            .await?;
+            let result =
+                query_module_graph(*graph.entries, *graph.output_assets, query.clone().cell())
+                    .await?;
+            if json {
+                Ok(serde_json::to_string_pretty(&*result)?)
+            } else {
+                format_result(&query, &result)
+            }
+        })
+        .await?;
+
+    println!("{output}");
+    Ok(())
+}
+
+fn format_result(query: &ModuleQuery, result: &ModuleQueryResult) -> Result<String> {
+    let mut out = String::new();
+    if result.matches.is_empty() {
+        let name = match query {
+            ModuleQuery::Path(path) => path,
+            ModuleQuery::Package(package) => package,
+        };
+        write!(out, "{name} is not part of the module graph")?;
+        return Ok(out);
+    }
+    for module in &result.matches {
+        writeln!(out, "{}", module.module)?;
+        for chain in &module.chains {
+            writeln!(out, "  from entry {}", chain.entry)?;
+            for edge in &chain.edges {
+                writeln!(out, "    -> {} [{}]", edge.to, edge.reference_type)?;
+                writeln!(out, "       via {}", edge.reference)?;
+            }
+        }
+        if module.chunks.is_empty() {
+            writeln!(out, "  not in any chunk")?;
+        } else {
+            writeln!(out, "  in chunks:")?;
+            for chunk in &module.chunks {
+                writeln!(out, "    {chunk}")?;
+            }
+        }
+    }
+    Ok(out)
+}
@@ -96,6 +96,13 @@ pub async fn make_chunks(
                 &mut split_context,
             )
             .await?;
+        } else if *chunking_context.is_ordered_vendors_split_enabled().await? {
+            ordered_vendors_split(
+                chunk_items,
+                format!("{key_prefix}{ty_name}"),
+                &mut split_context,
+            )
+            .await?;
         } else {
             make_chunk(
                 chunk_items,
@@ -226,6 +233,55 @@ async fn app_vendors_split(
     Ok(())
 }
 
+/// Split the leading vendor chunk items of a chunk type that must keep the
+/// item order into a chunk for each package, in the same order. Libraries are
+/// usually imported before the app code, so chunk groups importing the same
+/// package share its chunk, even when they import other packages as well.
+/// Only whole modules are shared, CSS rules that several modules repeat are
+/// not deduplicated.
+///
+/// Used when [ChunkingContext::is_ordered_vendors_split_enabled].
+#[tracing::instrument(level = Level::TRACE, skip_all, fields(name = display(&name)))]
+async fn ordered_vendors_split(
+    mut chunk_items: Vec<ChunkItemWithInfo>,
+    mut name: String,
+    split_context: &mut SplitContext<'_>,
+) -> Result<()> {
+    let app_start = chunk_items
+        .iter()
+        .position(|(_, _, _, asset_ident)| is_app_code(asset_ident))
+        .unwrap_or(chunk_items.len());
+    if app_start == 0 {
+        return make_chunk(chunk_items, &mut name, split_context).await;
+    }
+    let app_chunk_items = chunk_items.split_off(app_start);
+    for (index, (package_name, list)) in package_runs(chunk_items, |(_, _, _, asset_ident)| {
+        package_name(asset_ident)
+    })
+    .into_iter()
+    .enumerate()
+    {
+        let mut key = format!("{}-vendors-{}-{}", name, index, package_name);
+        make_chunk(list, &mut key, split_context).await?;
+    }
+    if !app_chunk_items.is_empty() {
+        make_chunk(app_chunk_items, &mut format!("{}-app", name), split_context).await?;
+    }
+    Ok(())
+}
+
+/// Groups consecutive items of the same package, keeping their order.
+fn package_runs<T>(items: Vec<T>, package_name: impl Fn(&T) -> &str) -> Vec<(String, Vec<T>)> {
+    let mut runs: Vec<(String, Vec<T>)> = Vec::new();
+    for item in items {
+        match runs.last_mut() {
+            Some((name, list)) if *name == package_name(&item) => list.push(item),
+            _ => runs.push((package_name(&item).to_string(), vec![item])),
+        }
+    }
+    runs
+}
+
 /// Split chunk items by node_modules package name. Continues splitting with
 /// [folder_split] if necessary.
 #[tracing::instrument(level = Level::TRACE, skip_all, fields(name = display(&name)))]
@@ -373,3 +429,47 @@ fn chunk_size(chunk_items: &[ChunkItemWithInfo]) -> ChunkSize {
         ChunkSize::Small
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{package_name, package_runs};
+
+    #[test]
+    fn returns_package_name() {
+        assert_eq!(package_name("[project]/node_modules/a/index.css"), "a");
+        assert_eq!(
+            package_name("[project]/node_modules/@scope/b/index.css"),
+            "@scope/b"
+        );
+        assert_eq!(
+            package_name("[project]/node_modules/a/node_modules/c/index.css"),
+            "c"
+        );
+        assert_eq!(package_name("[project]/src/index.css"), "");
+    }
+
+    #[test]
+    fn groups_consecutive_items_of_a_package() {
+        let items = vec![
+            "[project]/node_modules/a/1.css",
+            "[project]/node_modules/a/2.css",
+            "[project]/node_modules/b/1.css",
+            "[project]/node_modules/a/3.css",
+        ];
+        let runs = package_runs(items, |item| package_name(item));
+        assert_eq!(
+            runs,
+            vec![
+                (
+                    "a".to_string(),
+                    vec![
+                        "[project]/node_modules/a/1.css",
+                        "[project]/node_modules/a/2.css"
+                    ]
+                ),
+                ("b".to_string(), vec!["[project]/node_modules/b/1.css"]),
+                ("a".to_string(), vec!["[project]/node_modules/a/3.css"]),
+            ]
+        );
+    }
+}
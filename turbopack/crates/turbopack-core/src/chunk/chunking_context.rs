@@ -88,6 +88,13 @@ pub trait ChunkingContext {
         Vc::cell(false)
     }
 
+    /// Whether the leading node_modules items of chunk types that must keep
+    /// their item order, e.g. CSS, are put into chunks of their own, so chunk
+    /// groups can share them.
+    fn is_ordered_vendors_split_enabled(self: Vc<Self>) -> Vc<bool> {
+        Vc::cell(false)
+    }
+
     fn async_loader_chunk_item(
         &self,
         module: Vc<Box<dyn ChunkableModule>>,
@@ -109,6 +116,16 @@ pub trait ChunkingContext {
         availability_info: Value<AvailabilityInfo>,
     ) -> Vc<ChunkGroupResult>;
 
+    /// Generates a single output asset at `path` that contains the runtime and
+    /// all chunks needed to evaluate the given assets. It doesn't load any
+    /// other chunks, so it can be used where no further scripts can be
+    /// loaded, e.g. in worklets.
+    fn single_file_chunk_group_asset(
+        self: Vc<Self>,
+        path: Vc<FileSystemPath>,
+        evaluatable_assets: Vc<EvaluatableAssets>,
+    ) -> Result<Vc<Box<dyn OutputAsset>>>;
+
     /// Generates an output chunk that:
     /// * loads the given extra_chunks in addition to the generated chunks; and
     /// * evaluates the given assets; and
@@ -21,6 +21,7 @@ pub mod ident;
 pub mod introspect;
 pub mod issue;
 pub mod module;
+pub mod module_graph_query;
 pub mod output;
 pub mod package_json;
 pub mod proxied_asset;
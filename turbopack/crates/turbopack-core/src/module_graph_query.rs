@@ -0,0 +1,247 @@
+//! Answers "why is this module in my bundle?".
+//!
+//! Given a module path or package name, [query_module_graph] finds the
+//! shortest import chain from each entry to every matching module, including
+//! the [ReferenceType] of every edge, and lists the chunks that contain the
+//! matching modules.
+
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use turbo_tasks::{trace::TraceRawVcs, RcStr, ResolvedVc, ValueToString, Vc};
+
+use crate::{
+    introspect::Introspectable,
+    module::{Module, Modules},
+    output::{OutputAsset, OutputAssets},
+    reference::ModuleReference,
+    reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
+};
+
+/// Selects the modules to explain.
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Clone, Hash)]
+pub enum ModuleQuery {
+    /// Modules whose path is, or ends with, the given path, e.g.
+    /// `src/utils/date.ts`.
+    Path(RcStr),
+    /// All modules of a package in `node_modules`, e.g. `moment` or
+    /// `@scope/pkg`.
+    Package(RcStr),
+}
+
+impl ModuleQuery {
+    pub fn matches(&self, path: &str) -> bool {
+        match self {
+            ModuleQuery::Path(query) => {
+                let query = query.trim_start_matches("./");
+                path == query
+                    || path
+                        .strip_suffix(query)
+                        .is_some_and(|prefix| prefix.ends_with('/'))
+            }
+            ModuleQuery::Package(name) => {
+                let needle = format!("node_modules/{name}/");
+                path.starts_with(&needle) || path.contains(&format!("/{needle}"))
+            }
+        }
+    }
+}
+
+/// One edge of an [ImportChain].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChainEdge {
+    /// The module containing the reference.
+    pub from: RcStr,
+    /// The referenced module.
+    pub to: RcStr,
+    /// A description of the reference, usually including the request.
+    pub reference: RcStr,
+    pub reference_type: RcStr,
+}
+
+/// The shortest chain of references from an entry to a module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChain {
+    pub entry: RcStr,
+    pub edges: Vec<ImportChainEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleQueryMatch {
+    pub module: RcStr,
+    /// One chain for every entry the module is reachable from.
+    pub chains: Vec<ImportChain>,
+    /// The paths of the chunks containing the module.
+    pub chunks: Vec<RcStr>,
+}
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleQueryResult {
+    pub matches: Vec<ModuleQueryMatch>,
+}
+
+type Edge = (
+    ResolvedVc<Box<dyn Module>>,
+    ResolvedVc<Box<dyn ModuleReference>>,
+);
+
+/// Walks the module graph from every entry and explains how the modules
+/// selected by `query` are reached. `output_assets` are searched for chunks
+/// containing the matches. They are inspected via [Introspectable], so only
+/// chunks that list their modules as introspection children are found.
+#[turbo_tasks::function]
+pub async fn query_module_graph(
+    entries: Vc<Modules>,
+    output_assets: Vc<OutputAssets>,
+    query: Vc<ModuleQuery>,
+) -> Result<Vc<ModuleQueryResult>> {
+    let query = query.await?;
+    let mut matches: Vec<(ResolvedVc<Box<dyn Module>>, ModuleQueryMatch)> = Vec::new();
+
+    for &entry in entries.await?.iter() {
+        // Breadth-first, so the first path to a module is a shortest one.
+        let mut parents: HashMap<ResolvedVc<Box<dyn Module>>, Option<Edge>> = HashMap::new();
+        parents.insert(entry, None);
+        let mut queue = VecDeque::from([entry]);
+        while let Some(module) = queue.pop_front() {
+            if query.matches(&module.ident().path().await?.path) {
+                let chain = import_chain(entry, module, &parents).await?;
+                match matches.iter_mut().find(|(m, _)| *m == module) {
+                    Some((_, result)) => result.chains.push(chain),
+                    None => matches.push((
+                        module,
+                        ModuleQueryMatch {
+                            module: module.ident().to_string().await?.clone_value(),
+                            chains: vec![chain],
+                            chunks: Vec::new(),
+                        },
+                    )),
+                }
+            }
+            for &reference in module.references().await?.iter() {
+                let reference = reference.to_resolved().await?;
+                for &target in reference
+                    .resolve_reference()
+                    .resolve()
+                    .await?
+                    .primary_modules()
+                    .await?
+                    .iter()
+                {
+                    if let Entry::Vacant(e) = parents.entry(target) {
+                        e.insert(Some((module, reference)));
+                        queue.push_back(target);
+                    }
+                }
+            }
+        }
+    }
+
+    if !matches.is_empty() {
+        for &asset in output_assets.await?.iter() {
+            let modules = chunk_module_titles(*asset).await?;
+            if modules.is_empty() {
+                continue;
+            }
+            let path = asset.ident().path().to_string().await?;
+            for (_, result) in matches.iter_mut() {
+                if modules.contains(&result.module) {
+                    result.chunks.push(path.clone_value());
+                }
+            }
+        }
+    }
+
+    Ok(ModuleQueryResult {
+        matches: matches.into_iter().map(|(_, result)| result).collect(),
+    }
+    .cell())
+}
+
+async fn import_chain(
+    entry: ResolvedVc<Box<dyn Module>>,
+    module: ResolvedVc<Box<dyn Module>>,
+    parents: &HashMap<ResolvedVc<Box<dyn Module>>, Option<Edge>>,
+) -> Result<ImportChain> {
+    let mut edges = Vec::new();
+    let mut current = module;
+    while let Some(Some((parent, reference))) = parents.get(&current) {
+        edges.push(ImportChainEdge {
+            from: parent.ident().to_string().await?.clone_value(),
+            to: current.ident().to_string().await?.clone_value(),
+            reference: reference.to_string().await?.clone_value(),
+            reference_type: reference_type_to_string(&*reference.reference_type().await?).into(),
+        });
+        current = *parent;
+    }
+    edges.reverse();
+    Ok(ImportChain {
+        entry: entry.ident().to_string().await?.clone_value(),
+        edges,
+    })
+}
+
+/// The titles of the modules in a chunk, which are their idents.
+async fn chunk_module_titles(asset: Vc<Box<dyn OutputAsset>>) -> Result<HashSet<RcStr>> {
+    let mut titles = HashSet::new();
+    let Some(introspectable) = Vc::try_resolve_sidecast::<Box<dyn Introspectable>>(asset).await?
+    else {
+        return Ok(titles);
+    };
+    // Output chunks wrap the actual chunk as a "chunk" child.
+    let mut queue = vec![introspectable];
+    while let Some(introspectable) = queue.pop() {
+        for &(key, child) in introspectable.children().await?.iter() {
+            match key.await?.as_str() {
+                "chunk" => queue.push(child),
+                "module" | "entry module" => {
+                    titles.insert(child.title().await?.clone_value());
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(titles)
+}
+
+fn reference_type_to_string(ty: &ReferenceType) -> String {
+    match ty {
+        ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::DynamicImport) => {
+            "EcmaScript Modules (dynamic import)".to_string()
+        }
+        ReferenceType::Worker(sub_type) => format!("worker ({sub_type:?})"),
+        ReferenceType::Url(sub_type) => format!("url ({sub_type:?})"),
+        ReferenceType::Custom(id) => format!("custom ({id})"),
+        ty => ty.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_query() {
+        let query = ModuleQuery::Path("src/utils/date.ts".into());
+        assert!(query.matches("src/utils/date.ts"));
+        assert!(query.matches("apps/web/src/utils/date.ts"));
+        assert!(!query.matches("apps/web/mysrc/utils/date.ts"));
+        assert!(ModuleQuery::Path("./date.ts".into()).matches("src/date.ts"));
+    }
+
+    #[test]
+    fn package_query() {
+        let query = ModuleQuery::Package("moment".into());
+        assert!(query.matches("node_modules/moment/moment.js"));
+        assert!(query.matches("node_modules/.pnpm/moment@2.30.1/node_modules/moment/locale/de.js"));
+        assert!(!query.matches("node_modules/moment-timezone/index.js"));
+        assert!(ModuleQuery::Package("@scope/pkg".into()).matches("a/node_modules/@scope/pkg/x.js"));
+    }
+}
@@ -11,6 +11,7 @@ use crate::{
     module::{Module, Modules},
     output::{OutputAsset, OutputAssets},
     raw_module::RawModule,
+    reference_type::ReferenceType,
     resolve::{ModuleResolveResult, RequestKey},
 };
 pub mod source_map;
@@ -27,8 +28,12 @@ pub use source_map::SourceMapReference;
 #[turbo_tasks::value_trait]
 pub trait ModuleReference: ValueToString {
     fn resolve_reference(self: Vc<Self>) -> Vc<ModuleResolveResult>;
-    // TODO think about different types
-    // fn kind(&self) -> Vc<AssetReferenceType>;
+
+    /// The kind of this reference, e. g. an ESM import or a CSS `@import`.
+    /// This is informational only and not used for resolving.
+    fn reference_type(self: Vc<Self>) -> Vc<ReferenceType> {
+        ReferenceType::Undefined.cell()
+    }
 }
 
 /// Multiple [ModuleReference]s
@@ -37,6 +37,25 @@ pub enum CommonJsReferenceSubType {
 #[derive(Debug, Clone, Hash)]
 pub enum ImportWithType {
     Json,
+    /// `with { type: "css" }`, a constructable `CSSStyleSheet`.
+    Css,
+    /// `with { type: "text" }`, the content as a string.
+    Text,
+    /// `with { type: "bytes" }`, the content as a `Uint8Array`.
+    Bytes,
+}
+
+impl ImportWithType {
+    /// Parses the value of the `type` import attribute.
+    pub fn from_attribute(ty: &str) -> Option<Self> {
+        Some(match ty {
+            "json" => ImportWithType::Json,
+            "css" => ImportWithType::Css,
+            "text" => ImportWithType::Text,
+            "bytes" => ImportWithType::Bytes,
+            _ => return None,
+        })
+    }
 }
 
 #[turbo_tasks::value(serialization = "auto_for_input")]
@@ -199,6 +218,9 @@ pub enum WorkerReferenceSubType {
     WebWorker,
     SharedWorker,
     ServiceWorker,
+    /// `CSS.paintWorklet.addModule(...)`, `audioContext.audioWorklet.addModule(...)` and other
+    /// worklets.
+    Worklet,
     Custom(u8),
     Undefined,
 }
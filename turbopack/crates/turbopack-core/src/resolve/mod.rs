@@ -14,7 +14,8 @@ use turbo_tasks::{
     TryJoinIterExt, Value, ValueToString, Vc,
 };
 use turbo_tasks_fs::{
-    util::normalize_request, FileSystemEntryType, FileSystemPath, RealPathResult,
+    util::{join_path, normalize_request},
+    FileSystemEntryType, FileSystemPath, RealPathResult,
 };
 
 use self::{
@@ -27,6 +28,7 @@ use self::{
     pattern::Pattern,
     plugin::BeforeResolvePlugin,
     remap::{ExportsField, ImportsField},
+    url_imports::{fs_path_origin, fs_path_to_url, url_to_fs_path, UrlImportsOptions},
 };
 use crate::{
     context::AssetContext,
@@ -54,6 +56,7 @@ pub mod parse;
 pub mod pattern;
 pub mod plugin;
 pub(crate) mod remap;
+pub mod url_imports;
 
 pub use alias_map::{
     AliasMap, AliasMapIntoIter, AliasMapLookupIterator, AliasMatch, AliasPattern, AliasTemplate,
@@ -1658,6 +1661,20 @@ async fn resolve_internal_inline(
             }
         }
 
+        if let Some(url_imports) = &options_value.url_imports {
+            if let Some(result) = resolve_in_url_module(
+                lookup_path,
+                request,
+                &request_value,
+                &*url_imports.await?,
+                options,
+            )
+            .await?
+            {
+                return Ok(result);
+            }
+        }
+
         let result = match &*request_value {
             Request::Dynamic => ResolveResult::unresolvable().into(),
             Request::Alternatives { requests } => {
@@ -1854,11 +1871,24 @@ async fn resolve_internal_inline(
                 fragment: _,
             } => {
                 let uri: RcStr = format!("{}{}", protocol, remainder).into();
-                ResolveResult::primary_with_key(
-                    RequestKey::new(uri.clone()),
-                    ResolveResultItem::External(uri, ExternalType::Url),
-                )
-                .into()
+                match (&options_value.url_imports, url_to_fs_path(&uri)) {
+                    (Some(url_imports), Some(fs_path)) => {
+                        resolve_url(
+                            uri,
+                            fs_path,
+                            lookup_path,
+                            request,
+                            &*url_imports.await?,
+                            options,
+                        )
+                        .await?
+                    }
+                    _ => ResolveResult::primary_with_key(
+                        RequestKey::new(uri.clone()),
+                        ResolveResultItem::External(uri, ExternalType::Url),
+                    )
+                    .into(),
+                }
             }
             Request::Unknown { path } => {
                 if !has_alias {
@@ -1979,6 +2009,88 @@ async fn resolve_into_folder(
     )
 }
 
+/// Resolves a URL request to the matching module in the URL file system.
+async fn resolve_url(
+    url: RcStr,
+    fs_path: RcStr,
+    lookup_path: ResolvedVc<FileSystemPath>,
+    request: ResolvedVc<Request>,
+    url_imports: &UrlImportsOptions,
+    options: Vc<ResolveOptions>,
+) -> Result<Vc<ResolveResult>> {
+    if !url_imports.is_allowed(&url) {
+        ResolvingIssue {
+            severity: error_severity(options).await?,
+            request_type: "url import: not allowed".to_string(),
+            request: *request,
+            file_path: *lookup_path,
+            resolve_options: options,
+            error_message: Some(format!(
+                "{url} does not match any of the allowed URL imports ({}). Add its origin to the \
+                 list of allowed URLs to import it.",
+                url_imports.allowed_uris.join(", ")
+            )),
+            source: None,
+        }
+        .cell()
+        .emit();
+        return Ok(ResolveResult::unresolvable().cell());
+    }
+
+    let path = url_imports.file_system.root().join(fs_path);
+    Ok(ResolveResult::source_with_key(
+        RequestKey::new(url),
+        ResolvedVc::upcast(FileSource::new(path).to_resolved().await?),
+    )
+    .cell())
+}
+
+/// Relative and server-relative requests from within a URL module are
+/// resolved against the URL of that module. The URL file system can't list
+/// directories, so the usual file probing is skipped.
+async fn resolve_in_url_module(
+    lookup_path: ResolvedVc<FileSystemPath>,
+    request: ResolvedVc<Request>,
+    request_value: &Request,
+    url_imports: &UrlImportsOptions,
+    options: Vc<ResolveOptions>,
+) -> Result<Option<Vc<ResolveResult>>> {
+    let lookup_path_value = lookup_path.await?;
+    if lookup_path_value.fs != url_imports.file_system {
+        return Ok(None);
+    }
+
+    let fs_path = match request_value {
+        Request::Relative { path, query, .. } => {
+            let Some(path) = path.as_string() else {
+                return Ok(None);
+            };
+            let Some(joined) =
+                join_path(&lookup_path_value.path, &format!("{path}{}", query.await?))
+            else {
+                return Ok(None);
+            };
+            joined.into()
+        }
+        Request::ServerRelative { path, query, .. } => {
+            let (Some(path), Some(origin)) =
+                (path.as_string(), fs_path_origin(&lookup_path_value.path))
+            else {
+                return Ok(None);
+            };
+            format!("{origin}{path}{}", query.await?).into()
+        }
+        _ => return Ok(None),
+    };
+
+    let Some(url) = fs_path_to_url(&fs_path) else {
+        return Ok(None);
+    };
+    Ok(Some(
+        resolve_url(url, fs_path, lookup_path, request, url_imports, options).await?,
+    ))
+}
+
 #[tracing::instrument(level = Level::TRACE, skip_all)]
 async fn resolve_relative_request(
     lookup_path: ResolvedVc<FileSystemPath>,
@@ -2037,35 +2149,16 @@ async fn resolve_relative_request(
         new_path.normalize();
     };
 
-    if options_value.enable_typescript_with_output_extension {
+    if options_value.enable_typescript_with_output_extension
+        || !options_value.extension_alias.is_empty()
+    {
         new_path.replace_final_constants(&|c: &RcStr| -> Option<Pattern> {
-            let (base, replacement) = match c.rsplit_once(".") {
-                Some((base, "js")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".ts".into()),
-                        Pattern::Constant(".tsx".into()),
-                        Pattern::Constant(".js".into()),
-                    ],
-                ),
-                Some((base, "mjs")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".mts".into()),
-                        Pattern::Constant(".mjs".into()),
-                    ],
-                ),
-                Some((base, "cjs")) => (
-                    base,
-                    vec![
-                        Pattern::Constant(".cts".into()),
-                        Pattern::Constant(".cjs".into()),
-                    ],
-                ),
-                _ => {
-                    return None;
-                }
-            };
+            let (base, extension) = c.split_at(c.rfind('.')?);
+            let replacement = options_value
+                .aliased_extensions(extension)?
+                .into_iter()
+                .map(Pattern::Constant)
+                .collect();
             if base.is_empty() {
                 Some(Pattern::Alternatives(replacement))
             } else {
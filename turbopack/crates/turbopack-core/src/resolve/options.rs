@@ -3,8 +3,8 @@ use std::{collections::BTreeMap, future::Future, pin::Pin};
 use anyhow::{bail, Result};
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, trace::TraceRawVcs, FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt,
-    Value, ValueToString, Vc,
+    debug::ValueDebugFormat, trace::TraceRawVcs, FxIndexMap, FxIndexSet, RcStr, ResolvedVc,
+    TryJoinIterExt, Value, ValueToString, Vc,
 };
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 
@@ -12,6 +12,7 @@ use super::{
     alias_map::{AliasMap, AliasTemplate},
     pattern::Pattern,
     plugin::BeforeResolvePlugin,
+    url_imports::UrlImportsOptions,
     AliasPattern, ExternalType, ResolveResult, ResolveResultItem,
 };
 use crate::resolve::{parse::Request, plugin::AfterResolvePlugin};
@@ -513,12 +514,42 @@ pub struct ResolveOptions {
     pub plugins: Vec<ResolvedVc<Box<dyn AfterResolvePlugin>>>,
     /// Support resolving *.js requests to *.ts files
     pub enable_typescript_with_output_extension: bool,
+    /// Extensions to try instead of the extension of a relative request, in
+    /// order, e.g. `.js` -> [`.ts`, `.tsx`, `.js`]. Keys and values include
+    /// the leading dot.
+    pub extension_alias: FxIndexMap<RcStr, Vec<RcStr>>,
+    /// When set, ESM requests need to be fully specified, like
+    /// [ResolveOptions::fully_specified] for all requests.
+    pub esm_fully_specified: bool,
     /// Warn instead of error for resolve errors
     pub loose_errors: bool,
+    /// When set, `http:` and `https:` requests are resolved to modules fetched
+    /// from the network instead of being treated as externals.
+    pub url_imports: Option<ResolvedVc<UrlImportsOptions>>,
 
     pub placeholder_for_future_extensions: (),
 }
 
+impl ResolveOptions {
+    /// Returns the extensions to try instead of `extension` (including the
+    /// leading dot) when resolving a relative request.
+    pub fn aliased_extensions(&self, extension: &str) -> Option<Vec<RcStr>> {
+        if let Some(aliases) = self.extension_alias.get(extension) {
+            return Some(aliases.clone());
+        }
+        if !self.enable_typescript_with_output_extension {
+            return None;
+        }
+        let aliases: &[&str] = match extension {
+            ".js" => &[".ts", ".tsx", ".js"],
+            ".mjs" => &[".mts", ".mjs"],
+            ".cjs" => &[".cts", ".cjs"],
+            _ => return None,
+        };
+        Some(aliases.iter().map(|&alias| alias.into()).collect())
+    }
+}
+
 #[turbo_tasks::value_impl]
 impl ResolveOptions {
     /// Returns a new [Vc<ResolveOptions>] with its import map extended to
@@ -581,6 +612,21 @@ impl ResolveOptions {
         resolve_options.fully_specified = fully_specified;
         Ok(resolve_options.cell())
     }
+
+    /// Overrides the esm_fully_specified flag for resolving
+    #[turbo_tasks::function]
+    pub async fn with_esm_fully_specified(
+        self: Vc<Self>,
+        esm_fully_specified: bool,
+    ) -> Result<Vc<Self>> {
+        let resolve_options = self.await?;
+        if resolve_options.esm_fully_specified == esm_fully_specified {
+            return Ok(self);
+        }
+        let mut resolve_options = resolve_options.clone_value();
+        resolve_options.esm_fully_specified = esm_fully_specified;
+        Ok(resolve_options.cell())
+    }
 }
 
 #[turbo_tasks::value(shared)]
@@ -0,0 +1,146 @@
+//! Resolving `http:` and `https:` requests to modules fetched from the network.
+//!
+//! URL modules are read from a dedicated [FileSystem] whose paths mirror the
+//! URL, e.g. `https://esm.sh/react@18/index.js` is read from
+//! `https/esm.sh/react@18/index.js`. This way relative and server-relative
+//! requests made from within a URL module are joined against its URL like any
+//! other path.
+
+use turbo_tasks::{RcStr, ResolvedVc};
+use turbo_tasks_fs::FileSystem;
+use url::Url;
+
+const SUPPORTED_SCHEMES: [&str; 2] = ["http", "https"];
+
+#[turbo_tasks::value(shared)]
+#[derive(Debug)]
+pub struct UrlImportsOptions {
+    /// URL prefixes that may be imported. A URL matches a prefix when it has
+    /// the same origin and its path starts with the path segments of the
+    /// prefix. Requests to other URLs are reported as resolve errors.
+    pub allowed_uris: Vec<RcStr>,
+    /// The file system that URL modules are read from.
+    pub file_system: ResolvedVc<Box<dyn FileSystem>>,
+}
+
+#[turbo_tasks::value(transparent)]
+pub struct OptionUrlImportsOptions(Option<ResolvedVc<UrlImportsOptions>>);
+
+impl UrlImportsOptions {
+    pub fn is_allowed(&self, url: &str) -> bool {
+        let Ok(url) = Url::parse(url) else {
+            return false;
+        };
+        self.allowed_uris
+            .iter()
+            .any(|prefix| is_inside_prefix(&url, prefix))
+    }
+}
+
+/// Compares the parsed URLs, so `https://esm.sh` doesn't match
+/// `https://esm.sh.evil.com` and `https://esm.sh/react` doesn't match
+/// `https://esm.sh/react-evil`.
+fn is_inside_prefix(url: &Url, prefix: &str) -> bool {
+    let Ok(prefix) = Url::parse(prefix) else {
+        return false;
+    };
+    if url.origin() != prefix.origin() {
+        return false;
+    }
+    let mut segments = url.path_segments().into_iter().flatten();
+    prefix
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .all(|prefix_segment| segments.next() == Some(prefix_segment))
+}
+
+/// Converts a URL like `https://esm.sh/react` into the matching path in the
+/// URL file system (`https/esm.sh/react`). Returns `None` for unsupported
+/// schemes.
+pub fn url_to_fs_path(url: &str) -> Option<RcStr> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !SUPPORTED_SCHEMES.contains(&scheme) || rest.is_empty() {
+        return None;
+    }
+    Some(format!("{scheme}/{rest}").into())
+}
+
+/// The inverse of [url_to_fs_path].
+pub fn fs_path_to_url(path: &str) -> Option<RcStr> {
+    let (scheme, rest) = path.split_once('/')?;
+    if !SUPPORTED_SCHEMES.contains(&scheme) || rest.is_empty() {
+        return None;
+    }
+    Some(format!("{scheme}://{rest}").into())
+}
+
+/// Returns the `<scheme>/<host>` prefix of a path in the URL file system.
+pub fn fs_path_origin(path: &str) -> Option<&str> {
+    let mut separators = path.match_indices('/').map(|(index, _)| index);
+    let _scheme_end = separators.next()?;
+    Some(match separators.next() {
+        Some(host_end) => &path[..host_end],
+        None => path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_to_fs_path() {
+        assert_eq!(
+            url_to_fs_path("https://esm.sh/react@18?target=es2022").as_deref(),
+            Some("https/esm.sh/react@18?target=es2022")
+        );
+        assert_eq!(
+            url_to_fs_path("http://localhost:3000/a.js").as_deref(),
+            Some("http/localhost:3000/a.js")
+        );
+        assert_eq!(url_to_fs_path("data:text/javascript,1"), None);
+        assert_eq!(url_to_fs_path("ftp://example.com/a.js"), None);
+    }
+
+    #[test]
+    fn test_fs_path_to_url() {
+        assert_eq!(
+            fs_path_to_url("https/esm.sh/react@18/index.js").as_deref(),
+            Some("https://esm.sh/react@18/index.js")
+        );
+        assert_eq!(fs_path_to_url("https"), None);
+        assert_eq!(fs_path_to_url("file/a.js"), None);
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let allowed_uris = ["https://esm.sh", "https://cdn.example.com/libs/"];
+        let is_allowed = |url: &str| {
+            let url = Url::parse(url).unwrap();
+            allowed_uris
+                .iter()
+                .any(|prefix| is_inside_prefix(&url, prefix))
+        };
+
+        assert!(is_allowed("https://esm.sh/react@18"));
+        assert!(is_allowed("https://ESM.sh:443/react@18"));
+        assert!(is_allowed("https://cdn.example.com/libs/a.js"));
+        assert!(is_allowed("https://cdn.example.com/libs"));
+
+        assert!(!is_allowed("https://esm.sh.evil.com/react"));
+        assert!(!is_allowed("https://esm.sh@evil.com/react"));
+        assert!(!is_allowed("http://esm.sh/react"));
+        assert!(!is_allowed("https://esm.sh:8443/react"));
+        assert!(!is_allowed("https://cdn.example.com/libs-evil/a.js"));
+        assert!(!is_allowed("https://cdn.example.com/libs/../secret.js"));
+    }
+
+    #[test]
+    fn test_fs_path_origin() {
+        assert_eq!(fs_path_origin("https/esm.sh/a/b.js"), Some("https/esm.sh"));
+        assert_eq!(fs_path_origin("https/esm.sh"), Some("https/esm.sh"));
+        assert_eq!(fs_path_origin("https"), None);
+    }
+}
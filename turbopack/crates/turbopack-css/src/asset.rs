@@ -21,7 +21,7 @@ use crate::{
         FinalCssResult, ParseCss, ParseCssResult, ProcessCss,
     },
     references::{compose::CssModuleComposeReference, import::ImportAssetReference},
-    CssModuleAssetType,
+    CssModuleAssetType, CssModulesOptions,
 };
 
 #[turbo_tasks::function]
@@ -40,6 +40,7 @@ pub struct CssModuleAsset {
     asset_context: Vc<Box<dyn AssetContext>>,
     import_context: Option<Vc<ImportContext>>,
     ty: CssModuleAssetType,
+    modules_options: Vc<CssModulesOptions>,
     minify_type: MinifyType,
     use_swc_css: bool,
 }
@@ -52,6 +53,7 @@ impl CssModuleAsset {
         source: Vc<Box<dyn Source>>,
         asset_context: Vc<Box<dyn AssetContext>>,
         ty: CssModuleAssetType,
+        modules_options: Vc<CssModulesOptions>,
         minify_type: MinifyType,
         use_swc_css: bool,
         import_context: Option<Vc<ImportContext>>,
@@ -61,6 +63,7 @@ impl CssModuleAsset {
             asset_context,
             import_context,
             ty,
+            modules_options,
             minify_type,
             use_swc_css,
         })
@@ -85,6 +88,7 @@ impl ParseCss for CssModuleAsset {
             this.import_context
                 .unwrap_or_else(|| ImportContext::new(vec![], vec![], vec![])),
             this.ty,
+            this.modules_options,
             this.use_swc_css,
         ))
     }
@@ -1,3 +1,4 @@
+pub mod order;
 pub(crate) mod single_item_chunk;
 pub mod source_map;
 
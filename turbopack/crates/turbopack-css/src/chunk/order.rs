@@ -0,0 +1,213 @@
+use std::hash::Hash;
+
+use anyhow::Result;
+use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, ValueToString, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::{
+    chunk::ChunkItem,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    output::OutputAssets,
+};
+
+use super::{CssChunk, CssChunkItem};
+
+/// Emits a warning for each CSS chunk item that a chunk group orders before an
+/// item which an earlier chunk group ordered before it.
+///
+/// CSS is only loaded once, so when navigating between chunk groups the order
+/// of the rules in the document is the one of the chunk group loaded first and
+/// the cascade can differ from the one the other chunk group was written for.
+pub async fn check_css_order_conflicts(
+    chunk_groups: impl IntoIterator<Item = (RcStr, Vc<OutputAssets>)>,
+) -> Result<()> {
+    let mut chunk_items_by_group = Vec::new();
+    for (name, assets) in chunk_groups {
+        let mut chunk_items = Vec::new();
+        for &asset in assets.await?.iter() {
+            let Some(chunk) = ResolvedVc::try_downcast_type::<CssChunk>(asset).await? else {
+                continue;
+            };
+            chunk_items.extend(chunk.await?.content.await?.chunk_items.iter().copied());
+        }
+        chunk_items_by_group.push((name, chunk_items));
+    }
+
+    for conflict in order_conflicts(&chunk_items_by_group) {
+        CssOrderConflictIssue {
+            path: conflict.item.asset_ident().path(),
+            chunk_group: conflict.chunk_group,
+            other_chunk_group: conflict.other_chunk_group,
+            module: conflict.item.asset_ident().to_string().await?.clone_value(),
+            other_module: conflict
+                .other_item
+                .asset_ident()
+                .to_string()
+                .await?
+                .clone_value(),
+        }
+        .cell()
+        .emit();
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct OrderConflict<T> {
+    item: T,
+    other_item: T,
+    chunk_group: RcStr,
+    other_chunk_group: RcStr,
+}
+
+/// Finds the items that a chunk group orders before another item, which an
+/// earlier chunk group ordered before them.
+fn order_conflicts<T: Copy + Eq + Hash>(chunk_groups: &[(RcStr, Vec<T>)]) -> Vec<OrderConflict<T>> {
+    // The order of all items seen so far, each with the chunk group that added
+    // it.
+    let mut order = FxIndexMap::<T, &RcStr>::default();
+    let mut conflicts = Vec::new();
+
+    for (name, items) in chunk_groups {
+        // The items already in `order` have to appear in increasing order,
+        // otherwise the item conflicts with the latest one before it.
+        let mut latest: Option<(usize, T)> = None;
+        for &item in items {
+            let Some(index) = order.get_index_of(&item) else {
+                continue;
+            };
+            match latest {
+                Some((latest_index, latest_item)) if index < latest_index => {
+                    conflicts.push(OrderConflict {
+                        item,
+                        other_item: latest_item,
+                        chunk_group: name.clone(),
+                        other_chunk_group: order[latest_index].clone(),
+                    });
+                }
+                _ => latest = Some((index, item)),
+            }
+        }
+
+        for &item in items {
+            order.entry(item).or_insert(name);
+        }
+    }
+
+    conflicts
+}
+
+#[turbo_tasks::value(shared)]
+struct CssOrderConflictIssue {
+    path: Vc<FileSystemPath>,
+    chunk_group: RcStr,
+    other_chunk_group: RcStr,
+    module: RcStr,
+    other_module: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for CssOrderConflictIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Other("chunking".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text("Conflicting order of CSS modules".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Stack(vec![
+                StyledString::Line(vec![
+                    StyledString::Code(self.module.clone()),
+                    StyledString::Text(" is loaded before ".into()),
+                    StyledString::Code(self.other_module.clone()),
+                    StyledString::Text(" in ".into()),
+                    StyledString::Strong(self.chunk_group.clone()),
+                    StyledString::Text(", but after it in ".into()),
+                    StyledString::Strong(self.other_chunk_group.clone()),
+                    StyledString::Text(".".into()),
+                ]),
+                StyledString::Text(
+                    "The styles may apply in a different order depending on which of them is \
+                     loaded first. Import the CSS in the same order everywhere to fix this."
+                        .into(),
+                ),
+            ])
+            .cell(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbo_tasks::RcStr;
+
+    use super::{order_conflicts, OrderConflict};
+
+    fn groups(groups: &[(&str, &[&'static str])]) -> Vec<(RcStr, Vec<&'static str>)> {
+        groups
+            .iter()
+            .map(|(name, items)| (RcStr::from(*name), items.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_consistent_order() {
+        let groups = groups(&[
+            ("/a", &["reset", "button", "a"]),
+            ("/b", &["reset", "b", "button"]),
+            ("/c", &["c"]),
+        ]);
+        assert!(order_conflicts(&groups).is_empty());
+    }
+
+    #[test]
+    fn finds_opposite_order() {
+        let groups = groups(&[
+            ("/a", &["reset", "button", "card"]),
+            ("/b", &["card", "button"]),
+        ]);
+        assert_eq!(
+            order_conflicts(&groups),
+            vec![OrderConflict {
+                item: "button",
+                other_item: "card",
+                chunk_group: "/b".into(),
+                other_chunk_group: "/a".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn names_the_chunk_group_that_added_the_item() {
+        let groups = groups(&[
+            ("/a", &["button"]),
+            ("/b", &["button", "card"]),
+            ("/c", &["card", "button"]),
+        ]);
+        assert_eq!(
+            order_conflicts(&groups),
+            vec![OrderConflict {
+                item: "button",
+                other_item: "card",
+                chunk_group: "/c".into(),
+                other_chunk_group: "/b".into(),
+            }]
+        );
+    }
+}
@@ -17,9 +17,9 @@ pub(crate) mod references;
 pub(crate) mod util;
 
 pub use asset::CssModuleAsset;
-pub use module_asset::ModuleCssAsset;
+pub use module_asset::{write_css_module_declarations, ModuleCssAsset};
 use serde::{Deserialize, Serialize};
-use turbo_tasks::{trace::TraceRawVcs, TaskInput};
+use turbo_tasks::{trace::TraceRawVcs, RcStr, TaskInput, ValueDefault, Vc};
 
 pub use self::process::*;
 use crate::references::import::ImportAssetReference;
@@ -47,6 +47,66 @@ pub enum CssModuleAssetType {
     Module,
 }
 
+/// How the class names of a CSS module are exported to JavaScript, following
+/// css-loader's `exportLocalsConvention`.
+#[derive(
+    PartialOrd,
+    Ord,
+    Eq,
+    PartialEq,
+    Hash,
+    Debug,
+    Copy,
+    Clone,
+    Default,
+    Serialize,
+    Deserialize,
+    TaskInput,
+    TraceRawVcs,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum CssModulesExportConvention {
+    /// Class names are exported as written.
+    #[default]
+    AsIs,
+    /// Class names are exported as written and in camel case.
+    CamelCase,
+    /// Class names are only exported in camel case.
+    CamelCaseOnly,
+    /// Class names are exported as written and with dashes converted to camel
+    /// case.
+    Dashes,
+    /// Class names are only exported with dashes converted to camel case.
+    DashesOnly,
+}
+
+/// Options for CSS modules (`*.module.css`).
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+#[serde(default)]
+pub struct CssModulesOptions {
+    /// The pattern of the generated class names, e.g.
+    /// `[name]__[local]___[hash]`. Defaults to `[name]__[hash]__[local]`.
+    /// Only supported by lightningcss.
+    pub pattern: Option<RcStr>,
+    /// Scopes CSS variables and other dashed identifiers.
+    pub dashed_idents: bool,
+    /// Scopes grid line and area names.
+    pub grid: bool,
+    pub export_convention: CssModulesExportConvention,
+    /// Writes a `.module.css.d.ts` file next to each CSS module, declaring its
+    /// exported class names.
+    pub generate_declarations: bool,
+}
+
+#[turbo_tasks::value_impl]
+impl ValueDefault for CssModulesOptions {
+    #[turbo_tasks::function]
+    fn value_default() -> Vc<Self> {
+        Self::default().cell()
+    }
+}
+
 pub fn register() {
     turbo_tasks::register();
     turbo_tasks_fs::register();
@@ -4,16 +4,18 @@ use anyhow::{bail, Context, Result};
 use indoc::formatdoc;
 use lightningcss::css_modules::CssModuleReference;
 use swc_core::common::{BytePos, FileName, LineCol, SourceMap};
-use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, Value, ValueToString, Vc};
-use turbo_tasks_fs::FileSystemPath;
+use turbo_tasks::{
+    Completion, FxIndexMap, FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt, Value, ValueToString, Vc,
+};
+use turbo_tasks_fs::{File, FileContent, FileSystemPath};
 use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{ChunkItem, ChunkItemExt, ChunkType, ChunkableModule, ChunkingContext},
     context::{AssetContext, ProcessResult},
     ident::AssetIdent,
     issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
-    module::Module,
-    reference::{ModuleReference, ModuleReferences},
+    module::{Module, Modules},
+    reference::{all_modules_and_affecting_sources, ModuleReference, ModuleReferences},
     reference_type::{CssReferenceSubType, ReferenceType},
     resolve::{origin::ResolveOrigin, parse::Request},
     source::Source,
@@ -31,6 +33,8 @@ use turbopack_ecmascript::{
 use crate::{
     process::{CssWithPlaceholderResult, ProcessCss},
     references::{compose::CssModuleComposeReference, internal::InternalCssAssetReference},
+    util::export_names,
+    CssModulesOptions,
 };
 
 #[turbo_tasks::function]
@@ -43,15 +47,21 @@ fn modifier() -> Vc<RcStr> {
 pub struct ModuleCssAsset {
     pub source: Vc<Box<dyn Source>>,
     pub asset_context: Vc<Box<dyn AssetContext>>,
+    pub modules_options: Vc<CssModulesOptions>,
 }
 
 #[turbo_tasks::value_impl]
 impl ModuleCssAsset {
     #[turbo_tasks::function]
-    pub fn new(source: Vc<Box<dyn Source>>, asset_context: Vc<Box<dyn AssetContext>>) -> Vc<Self> {
+    pub fn new(
+        source: Vc<Box<dyn Source>>,
+        asset_context: Vc<Box<dyn AssetContext>>,
+        modules_options: Vc<CssModulesOptions>,
+    ) -> Vc<Self> {
         Self::cell(ModuleCssAsset {
             source,
             asset_context,
+            modules_options,
         })
     }
 }
@@ -164,6 +174,7 @@ impl ModuleCssAsset {
             .context("inner asset should be CSS processable")?;
 
         let result = inner.get_css_with_placeholder().await?;
+        let export_convention = self.await?.modules_options.await?.export_convention;
         let mut classes = FxIndexMap::default();
 
         // TODO(alexkirsz) Should we report an error on parse error here?
@@ -201,7 +212,9 @@ impl ModuleCssAsset {
                     })
                 }
 
-                classes.insert(class_name.to_string(), export);
+                for name in export_names(class_name, export_convention) {
+                    classes.insert(name, export.clone());
+                }
             }
         }
 
@@ -225,6 +238,55 @@ impl ModuleCssAsset {
 
         Ok(Vc::cell(references))
     }
+
+    /// Writes a `.module.css.d.ts` file next to the source, declaring the
+    /// exported class names. The file is only rewritten when they change.
+    #[turbo_tasks::function]
+    async fn write_declarations(self: Vc<Self>) -> Result<Vc<Completion>> {
+        let classes = self.classes().await?;
+
+        let mut code = "declare const classes: {\n".to_string();
+        for export_name in classes.keys() {
+            writeln!(code, "  readonly {}: string;", StringifyJs(export_name))?;
+        }
+        code += "};\nexport default classes;\n";
+
+        let path = self.await?.source.ident().path().append(".d.ts".into());
+        Ok(path.write(FileContent::Content(File::from(code)).cell()))
+    }
+}
+
+/// Writes the `.module.css.d.ts` files of the CSS modules in the module graph
+/// of `entries` that have [`CssModulesOptions::generate_declarations`]
+/// enabled.
+#[turbo_tasks::function]
+pub async fn write_css_module_declarations(entries: Vc<Modules>) -> Result<Vc<Completion>> {
+    let mut modules = FxIndexSet::default();
+    for &entry in entries.await?.iter() {
+        modules.extend(
+            all_modules_and_affecting_sources(entry)
+                .await?
+                .iter()
+                .copied(),
+        );
+    }
+
+    modules
+        .into_iter()
+        .map(|module| async move {
+            let Some(module) = ResolvedVc::try_downcast_type::<ModuleCssAsset>(module).await?
+            else {
+                return Ok(());
+            };
+            if module.await?.modules_options.await?.generate_declarations {
+                module.write_declarations().await?;
+            }
+            Ok(())
+        })
+        .try_join()
+        .await?;
+
+    Ok(Completion::new())
 }
 
 #[turbo_tasks::value_impl]
@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock},
 };
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use lightningcss::{
     css_modules::{CssModuleExport, CssModuleExports, CssModuleReference, Pattern, Segment},
     dependencies::{Dependency, ImportDependency, Location, SourceRange},
@@ -41,8 +41,8 @@ use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{ChunkingContext, MinifyType},
     issue::{
-        Issue, IssueExt, IssueSource, IssueStage, OptionIssueSource, OptionStyledString,
-        StyledString,
+        Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource,
+        OptionStyledString, StyledString,
     },
     reference::ModuleReferences,
     reference_type::ImportContext,
@@ -61,7 +61,7 @@ use crate::{
         analyze_references,
         url::{replace_url_references, resolve_url_reference, UrlAssetReference},
     },
-    CssModuleAssetType,
+    CssModuleAssetType, CssModulesOptions,
 };
 
 // Capture up until the first "."
@@ -428,6 +428,7 @@ pub async fn finalize_css(
                 ParseCssResult::Unparseable => return Ok(FinalCssResult::Unparseable.into()),
                 ParseCssResult::NotFound => return Ok(FinalCssResult::NotFound.into()),
             };
+            turbo_tasks::check_cancelled()?;
 
             let url_references = *url_references;
 
@@ -484,6 +485,7 @@ pub async fn parse_css(
     origin: Vc<Box<dyn ResolveOrigin>>,
     import_context: Vc<ImportContext>,
     ty: CssModuleAssetType,
+    modules_options: Vc<CssModulesOptions>,
     use_swc_css: bool,
 ) -> Result<Vc<ParseCssResult>> {
     let span = {
@@ -494,6 +496,7 @@ pub async fn parse_css(
         let content = source.content();
         let fs_path = source.ident().path();
         let ident_str = &*source.ident().to_string().await?;
+        let modules_options = &*modules_options.await?;
         Ok(match &*content.await? {
             AssetContent::Redirect { .. } => ParseCssResult::Unparseable.cell(),
             AssetContent::File(file_content) => match &*file_content.await? {
@@ -510,6 +513,7 @@ pub async fn parse_css(
                             origin,
                             import_context,
                             ty,
+                            modules_options,
                             use_swc_css,
                         )
                         .await?
@@ -522,6 +526,26 @@ pub async fn parse_css(
     .await
 }
 
+/// Parses a CSS modules class name pattern. The parsed pattern borrows from
+/// its source and is kept in the parser options of the result, so each
+/// distinct pattern is leaked once.
+fn css_modules_pattern(pattern: &str) -> Result<Pattern<'static>> {
+    static PATTERNS: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+    let pattern: &'static str = {
+        let mut patterns = PATTERNS.lock().unwrap();
+        match patterns.get(pattern) {
+            Some(pattern) => *pattern,
+            None => {
+                let leaked: &'static str = Box::leak(pattern.to_string().into_boxed_str());
+                patterns.insert(leaked);
+                leaked
+            }
+        }
+    };
+    Pattern::parse(pattern).map_err(|err| anyhow!("Invalid CSS modules pattern {pattern:?}: {err}"))
+}
+
 async fn process_content(
     content_vc: Vc<FileContent>,
     code: String,
@@ -531,6 +555,7 @@ async fn process_content(
     origin: Vc<Box<dyn ResolveOrigin>>,
     import_context: Vc<ImportContext>,
     ty: CssModuleAssetType,
+    modules_options: &CssModulesOptions,
     use_swc_css: bool,
 ) -> Result<Vc<ParseCssResult>> {
     #[allow(clippy::needless_lifetimes)]
@@ -545,20 +570,25 @@ async fn process_content(
         }
     }
 
+    turbo_tasks::check_cancelled()?;
+
     let config = ParserOptions {
         css_modules: match ty {
             CssModuleAssetType::Module => Some(lightningcss::css_modules::Config {
-                pattern: Pattern {
-                    segments: smallvec![
-                        Segment::Name,
-                        Segment::Literal("__"),
-                        Segment::Hash,
-                        Segment::Literal("__"),
-                        Segment::Local,
-                    ],
+                pattern: match &modules_options.pattern {
+                    Some(pattern) => css_modules_pattern(pattern)?,
+                    None => Pattern {
+                        segments: smallvec![
+                            Segment::Name,
+                            Segment::Literal("__"),
+                            Segment::Hash,
+                            Segment::Literal("__"),
+                            Segment::Local,
+                        ],
+                    },
                 },
-                dashed_idents: false,
-                grid: false,
+                dashed_idents: modules_options.dashed_idents,
+                grid: modules_options.grid,
                 ..Default::default()
             }),
 
@@ -646,6 +676,16 @@ async fn process_content(
     } else {
         let fs_path = &*fs_path_vc.await?;
 
+        if ty == CssModuleAssetType::Module
+            && (modules_options.pattern.is_some()
+                || modules_options.dashed_idents
+                || modules_options.grid)
+        {
+            IgnoredCssModulesOptionsIssue { file: fs_path_vc }
+                .cell()
+                .emit();
+        }
+
         let handler = swc_core::common::errors::Handler::with_emitter(
             true,
             false,
@@ -1128,6 +1168,48 @@ impl Issue for ParsingIssue {
     }
 }
 
+/// The `pattern`, `dashedIdents` and `grid` CSS modules options are only
+/// supported by lightningcss.
+#[turbo_tasks::value]
+struct IgnoredCssModulesOptionsIssue {
+    file: Vc<FileSystemPath>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for IgnoredCssModulesOptionsIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Warning.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        self.file
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Parse.cell()
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text("CSS modules options are ignored with swc css".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(Some(
+            StyledString::Text(
+                "The pattern, dashedIdents and grid options are only supported by lightningcss. \
+                 Disable swc css to use them."
+                    .into(),
+            )
+            .cell(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use lightningcss::{
@@ -3,7 +3,7 @@ use turbo_tasks::{RcStr, Value, ValueToString, Vc};
 use turbopack_core::{
     chunk::ChunkableModuleReference,
     reference::ModuleReference,
-    reference_type::CssReferenceSubType,
+    reference_type::{CssReferenceSubType, ReferenceType},
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 
@@ -40,6 +40,11 @@ impl ModuleReference for CssModuleComposeReference {
             None,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::Css(CssReferenceSubType::Compose).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
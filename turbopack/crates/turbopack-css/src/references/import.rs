@@ -16,7 +16,7 @@ use turbopack_core::{
     chunk::{ChunkableModuleReference, ChunkingContext},
     issue::IssueSource,
     reference::ModuleReference,
-    reference_type::{CssReferenceSubType, ImportContext},
+    reference_type::{CssReferenceSubType, ImportContext, ReferenceType},
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 
@@ -241,6 +241,11 @@ impl ModuleReference for ImportAssetReference {
             Some(self.issue_source),
         ))
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::Css(CssReferenceSubType::AtImport(None)).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -96,6 +96,11 @@ impl ModuleReference for UrlAssetReference {
             false,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::Url(UrlReferenceSubType::CssUrl).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -1,3 +1,5 @@
+use crate::CssModulesExportConvention;
+
 pub fn stringify_js(str: &str) -> String {
     let mut escaped = String::with_capacity(str.len());
     for char in str.chars() {
@@ -20,9 +22,77 @@ pub fn stringify_js(str: &str) -> String {
     format!("\"{}\"", escaped)
 }
 
+/// Returns the names a CSS module class is exported as under `convention`.
+pub fn export_names(name: &str, convention: CssModulesExportConvention) -> Vec<String> {
+    let converted = match convention {
+        CssModulesExportConvention::AsIs => return vec![name.to_string()],
+        CssModulesExportConvention::CamelCase | CssModulesExportConvention::CamelCaseOnly => {
+            camel_case(name)
+        }
+        CssModulesExportConvention::Dashes | CssModulesExportConvention::DashesOnly => {
+            dashes_camel_case(name)
+        }
+    };
+    match convention {
+        CssModulesExportConvention::CamelCaseOnly | CssModulesExportConvention::DashesOnly => {
+            vec![converted]
+        }
+        _ if converted == name => vec![converted],
+        _ => vec![name.to_string(), converted],
+    }
+}
+
+/// Removes the dashes and underscores from `name`, uppercasing the character
+/// following each of them unless it starts the name.
+fn camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut uppercase_next = false;
+    for char in name.chars() {
+        if char == '-' || char == '_' {
+            uppercase_next = !result.is_empty();
+        } else if uppercase_next {
+            result.extend(char.to_uppercase());
+            uppercase_next = false;
+        } else {
+            result.push(char);
+        }
+    }
+    if result.is_empty() {
+        name.to_string()
+    } else {
+        result
+    }
+}
+
+/// Replaces each run of dashes followed by a word character with that
+/// character uppercased, like css-loader does for `dashes`. Other dashes, e.g.
+/// trailing ones, are kept.
+fn dashes_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '-' {
+            result.push(char);
+            continue;
+        }
+        let mut dashes = 1;
+        while chars.next_if_eq(&'-').is_some() {
+            dashes += 1;
+        }
+        match chars.next_if(|next| next.is_alphanumeric() || *next == '_') {
+            Some(next) => result.extend(next.to_uppercase()),
+            None => result.push_str(&"-".repeat(dashes)),
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::stringify_js;
+    use crate::{
+        util::{export_names, stringify_js},
+        CssModulesExportConvention,
+    };
 
     #[test]
     fn surrounds_with_double_quotes() {
@@ -50,4 +120,48 @@ mod tests {
     fn escapes_mixed() {
         assert_eq!(stringify_js("\n\r\u{0c}"), r#""\n\r\f""#);
     }
+
+    #[test]
+    fn exports_as_is() {
+        assert_eq!(
+            export_names("foo-bar_baz", CssModulesExportConvention::AsIs),
+            vec!["foo-bar_baz"]
+        );
+    }
+
+    #[test]
+    fn exports_camel_case() {
+        assert_eq!(
+            export_names("foo-bar_baz", CssModulesExportConvention::CamelCase),
+            vec!["foo-bar_baz", "fooBarBaz"]
+        );
+        assert_eq!(
+            export_names("foo-bar_baz", CssModulesExportConvention::CamelCaseOnly),
+            vec!["fooBarBaz"]
+        );
+        assert_eq!(
+            export_names("foo", CssModulesExportConvention::CamelCase),
+            vec!["foo"]
+        );
+        assert_eq!(
+            export_names("-foo--bar", CssModulesExportConvention::CamelCaseOnly),
+            vec!["fooBar"]
+        );
+    }
+
+    #[test]
+    fn exports_dashes() {
+        assert_eq!(
+            export_names("foo-bar_baz", CssModulesExportConvention::Dashes),
+            vec!["foo-bar_baz", "fooBar_baz"]
+        );
+        assert_eq!(
+            export_names("-foo--bar", CssModulesExportConvention::DashesOnly),
+            vec!["FooBar"]
+        );
+        assert_eq!(
+            export_names("foo-", CssModulesExportConvention::Dashes),
+            vec!["foo-"]
+        );
+    }
 }
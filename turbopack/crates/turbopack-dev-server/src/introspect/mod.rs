@@ -1,3 +1,5 @@
+pub mod module_graph_query;
+
 use std::{borrow::Cow, collections::HashSet, fmt::Display};
 
 use anyhow::Result;
@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use turbo_tasks::{RcStr, Value, Vc};
+use turbo_tasks_fs::File;
+use turbopack_core::{
+    asset::AssetContent,
+    module::Modules,
+    module_graph_query::{query_module_graph, ModuleQuery},
+    output::OutputAssets,
+    reference::all_assets_from_entries,
+    version::VersionedContentExt,
+};
+
+use crate::source::{
+    query::QueryValue,
+    route_tree::{RouteTree, RouteType},
+    ContentSource, ContentSourceContent, ContentSourceData, ContentSourceDataFilter,
+    ContentSourceDataVary, GetContentSourceContent,
+};
+
+/// Serves the result of [query_module_graph] as JSON. The module to explain is
+/// selected with the `module` (a path) or the `package` query parameter, e.g.
+/// `/__turbopack__/why?package=moment`.
+#[turbo_tasks::value(shared)]
+pub struct ModuleGraphQuerySource {
+    pub entries: Vc<Modules>,
+    /// The root output assets, e.g. the HTML page. All assets referenced from
+    /// them are searched for chunks containing the queried modules.
+    pub root_assets: Vc<OutputAssets>,
+}
+
+#[turbo_tasks::value_impl]
+impl ModuleGraphQuerySource {
+    #[turbo_tasks::function]
+    pub fn new(entries: Vc<Modules>, root_assets: Vc<OutputAssets>) -> Vc<Self> {
+        ModuleGraphQuerySource {
+            entries,
+            root_assets,
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl ContentSource for ModuleGraphQuerySource {
+    #[turbo_tasks::function]
+    fn get_routes(self: Vc<Self>) -> Vc<RouteTree> {
+        RouteTree::new_route(Vec::new(), RouteType::Exact, Vc::upcast(self))
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl GetContentSourceContent for ModuleGraphQuerySource {
+    #[turbo_tasks::function]
+    fn vary(&self) -> Vc<ContentSourceDataVary> {
+        ContentSourceDataVary {
+            query: Some(ContentSourceDataFilter::Subset(BTreeSet::from([
+                "module".to_string(),
+                "package".to_string(),
+            ]))),
+            ..Default::default()
+        }
+        .cell()
+    }
+
+    #[turbo_tasks::function]
+    async fn get(
+        &self,
+        _path: RcStr,
+        data: Value<ContentSourceData>,
+    ) -> Result<Vc<ContentSourceContent>> {
+        let param = |name: &str| match data.query.as_ref().and_then(|query| query.get(name)) {
+            Some(QueryValue::String(value)) if !value.is_empty() => Some(RcStr::from(&**value)),
+            _ => None,
+        };
+        let query = match (param("module"), param("package")) {
+            (Some(module), _) => ModuleQuery::Path(module),
+            (None, Some(package)) => ModuleQuery::Package(package),
+            (None, None) => return Ok(ContentSourceContent::not_found()),
+        };
+
+        let output_assets = all_assets_from_entries(self.root_assets);
+        let result = query_module_graph(self.entries, output_assets, query.cell()).await?;
+        let json = serde_json::to_string_pretty(&*result)?;
+
+        Ok(ContentSourceContent::static_content(
+            AssetContent::file(
+                File::from(json)
+                    .with_content_type(mime::APPLICATION_JSON)
+                    .into(),
+            )
+            .versioned(),
+        ))
+    }
+}
@@ -1,3 +1,4 @@
+pub mod runtime_error;
 pub mod server;
 pub mod stream;
 
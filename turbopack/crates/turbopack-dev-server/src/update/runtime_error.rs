@@ -0,0 +1,318 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Write},
+};
+
+use anyhow::Result;
+use hyper::{HeaderMap, Uri};
+use turbo_tasks::{trace::TraceRawVcs, Completion, RcStr, State, TransientInstance, Vc};
+use turbo_tasks_fs::{FileSystem, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    issue::{Issue, IssueExt, IssueSeverity, IssueStage, OptionStyledString, StyledString},
+    server_fs::ServerFileSystem,
+    source_map::{SourceMap, Token},
+    version::VersionedContent,
+    SOURCE_MAP_PREFIX,
+};
+
+use crate::source::{
+    request::SourceRequest,
+    resolve::{resolve_source_request, ResolveSourceRequestResult},
+    Body, ContentSource,
+};
+
+/// The number of runtime errors of a client that are kept and reported.
+const MAX_RUNTIME_ERRORS: usize = 16;
+
+/// The last runtime errors thrown in the browser of a client. They are
+/// reported by a single task, so there is no task for each distinct error.
+#[turbo_tasks::value(serialization = "none", eq = "manual", cell = "new")]
+pub(crate) struct RuntimeErrors {
+    errors: State<VecDeque<RuntimeError>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, TraceRawVcs)]
+struct RuntimeError {
+    path: RcStr,
+    message: RcStr,
+    stack: RcStr,
+}
+
+impl RuntimeErrors {
+    pub(crate) fn new() -> Vc<Self> {
+        RuntimeErrors {
+            errors: State::new(VecDeque::new()),
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl RuntimeErrors {
+    /// Emits the kept errors as issues, so they are reported together with the
+    /// compilation issues.
+    #[turbo_tasks::function]
+    pub(crate) fn report(&self) -> Vc<Completion> {
+        for error in self.errors.get().iter() {
+            RuntimeErrorIssue {
+                path: error.path.clone(),
+                message: error.message.clone(),
+                stack: error.stack.clone(),
+            }
+            .cell()
+            .emit();
+        }
+        Completion::new()
+    }
+}
+
+/// Maps the stack of an error thrown in the browser through the source maps
+/// of the served chunks and adds the error to `errors`, dropping the oldest
+/// one when there are too many.
+pub(crate) async fn add_runtime_error(
+    errors: Vc<RuntimeErrors>,
+    source: Vc<Box<dyn ContentSource>>,
+    message: RcStr,
+    stack: &str,
+) -> Result<()> {
+    let mut path = None;
+    let mut traced_stack = String::new();
+    for line in stack.lines() {
+        // V8 repeats the message before the frames.
+        let Some(frame) = StackFrame::parse(line) else {
+            continue;
+        };
+        let frame = match trace_frame(source, &frame).await {
+            Ok(Some(traced)) => traced,
+            // Frames without a source map, e.g. from browser extensions, are
+            // printed as they are.
+            Ok(None) | Err(_) => frame,
+        };
+        path.get_or_insert_with(|| frame.display_path().to_string());
+        writeln!(traced_stack, "    at {frame}")?;
+    }
+
+    let error = RuntimeError {
+        path: path.unwrap_or_default().into(),
+        message,
+        stack: traced_stack.into(),
+    };
+    errors.await?.errors.update_conditionally(|errors| {
+        // The same error is reported once.
+        if errors.contains(&error) {
+            return false;
+        }
+        if errors.len() == MAX_RUNTIME_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(error);
+        true
+    });
+
+    Ok(())
+}
+
+/// Looks up the original position of a frame in the source map served next
+/// to its chunk.
+async fn trace_frame(
+    source: Vc<Box<dyn ContentSource>>,
+    frame: &StackFrame,
+) -> Result<Option<StackFrame>> {
+    let request = SourceRequest {
+        method: "GET".to_string(),
+        uri: Uri::try_from(format!("{}.map", url_path(&frame.file)))?,
+        headers: HeaderMap::new(),
+        body: Body::new(vec![]),
+    };
+    let ResolveSourceRequestResult::Static(content, _) =
+        &*resolve_source_request(source, TransientInstance::new(request)).await?
+    else {
+        return Ok(None);
+    };
+    let AssetContent::File(file) = &*content.await?.content.content().await? else {
+        return Ok(None);
+    };
+    let Some(map) = SourceMap::new_from_file_content(**file).await? else {
+        return Ok(None);
+    };
+
+    // Stack frames are 1-indexed, source map tokens are 0-indexed.
+    let token = map
+        .cell()
+        .lookup_token(frame.line.saturating_sub(1), frame.column.saturating_sub(1))
+        .await?;
+    let Token::Original(token) = &*token else {
+        return Ok(None);
+    };
+    Ok(Some(StackFrame {
+        name: token
+            .name
+            .as_ref()
+            .map(|name| name.to_string())
+            .or_else(|| frame.name.clone()),
+        file: urlencoding::decode(&token.original_file)?.into_owned(),
+        line: token.original_line + 1,
+        column: token.original_column + 1,
+    }))
+}
+
+/// Returns the path of a chunk URL, e.g. `/_chunks/index.js` for
+/// `http://localhost:3000/_chunks/index.js?v=1`.
+fn url_path(url: &str) -> &str {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |index| &rest[index..]),
+        None => url,
+    };
+    path.split(['?', '#']).next().unwrap_or(path)
+}
+
+/// A frame of a browser stack trace.
+struct StackFrame {
+    name: Option<String>,
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+impl StackFrame {
+    /// Parses a frame in the format of V8 (`    at name (file:1:2)`) or of
+    /// Firefox and Safari (`name@file:1:2`).
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        let (name, location) = if let Some(frame) = line.strip_prefix("at ") {
+            match frame
+                .strip_suffix(')')
+                .and_then(|frame| frame.split_once(" ("))
+            {
+                Some((name, location)) => (Some(name), location),
+                None => (None, frame),
+            }
+        } else {
+            let (name, location) = line.split_once('@')?;
+            ((!name.is_empty()).then_some(name), location)
+        };
+        let (location, column) = location.rsplit_once(':')?;
+        let (file, line) = location.rsplit_once(':')?;
+        Some(StackFrame {
+            name: name.map(ToString::to_string),
+            file: file.to_string(),
+            line: line.parse().ok()?,
+            column: column.parse().ok()?,
+        })
+    }
+
+    /// The file without its `file://` or `turbopack://` scheme.
+    fn display_path(&self) -> &str {
+        self.file
+            .strip_prefix("file://")
+            .or_else(|| self.file.strip_prefix(SOURCE_MAP_PREFIX))
+            .unwrap_or(&self.file)
+    }
+}
+
+impl Display for StackFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self.display_path();
+        match &self.name {
+            Some(name) => write!(f, "{name} ({path}:{}:{})", self.line, self.column),
+            None => write!(f, "{path}:{}:{}", self.line, self.column),
+        }
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct RuntimeErrorIssue {
+    path: RcStr,
+    message: RcStr,
+    stack: RcStr,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for RuntimeErrorIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        IssueSeverity::Error.into()
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Other("runtime".into()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        ServerFileSystem::new()
+            .root()
+            .join(self.path.trim_start_matches('/').into())
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.message.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        if self.stack.is_empty() {
+            return Vc::cell(None);
+        }
+        Vc::cell(Some(StyledString::Text(self.stack.clone()).cell()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{url_path, StackFrame};
+
+    #[test]
+    fn parses_v8_frames() {
+        let frame =
+            StackFrame::parse("    at render (http://localhost:3000/_chunks/index.js:10:5)")
+                .unwrap();
+        assert_eq!(frame.name.as_deref(), Some("render"));
+        assert_eq!(frame.file, "http://localhost:3000/_chunks/index.js");
+        assert_eq!((frame.line, frame.column), (10, 5));
+
+        let frame = StackFrame::parse("    at http://localhost:3000/_chunks/index.js:1:2").unwrap();
+        assert_eq!(frame.name, None);
+        assert_eq!(frame.file, "http://localhost:3000/_chunks/index.js");
+        assert_eq!((frame.line, frame.column), (1, 2));
+    }
+
+    #[test]
+    fn parses_firefox_and_safari_frames() {
+        let frame =
+            StackFrame::parse("render@http://localhost:3000/_chunks/index.js:10:5").unwrap();
+        assert_eq!(frame.name.as_deref(), Some("render"));
+        assert_eq!(frame.file, "http://localhost:3000/_chunks/index.js");
+        assert_eq!((frame.line, frame.column), (10, 5));
+
+        let frame = StackFrame::parse("@http://localhost:3000/_chunks/index.js:1:2").unwrap();
+        assert_eq!(frame.name, None);
+    }
+
+    #[test]
+    fn skips_lines_without_location() {
+        assert!(StackFrame::parse("TypeError: x is undefined").is_none());
+        assert!(StackFrame::parse("    at render (native)").is_none());
+        assert!(StackFrame::parse("render@http://localhost:3000/index.js:a:b").is_none());
+    }
+
+    #[test]
+    fn displays_frames_without_scheme() {
+        let frame = StackFrame::parse("render@file:///project/src/index.ts:3:4").unwrap();
+        assert_eq!(frame.to_string(), "render (/project/src/index.ts:3:4)");
+    }
+
+    #[test]
+    fn returns_path_of_urls() {
+        assert_eq!(
+            url_path("http://localhost:3000/_chunks/index.js?v=1"),
+            "/_chunks/index.js"
+        );
+        assert_eq!(url_path("http://localhost:3000/index.js#hash"), "/index.js");
+        assert_eq!(url_path("http://localhost:3000"), "/");
+        assert_eq!(url_path("/_chunks/index.js"), "/_chunks/index.js");
+    }
+}
@@ -13,12 +13,19 @@ use tokio_stream::StreamMap;
 use tracing::{instrument, Level};
 use turbo_tasks::{TransientInstance, TurboTasksApi, Vc};
 use turbo_tasks_fs::json::parse_json_with_source_context;
-use turbopack_core::{error::PrettyPrintError, issue::IssueReporter, version::Update};
+use turbopack_core::{
+    error::PrettyPrintError,
+    issue::{handle_issues, IssueReporter, IssueSeverity},
+    version::Update,
+};
 use turbopack_ecmascript_hmr_protocol::{
     ClientMessage, ClientUpdateInstruction, Issue, ResourceIdentifier,
 };
 
-use super::stream::UpdateStream;
+use super::{
+    runtime_error::{add_runtime_error, RuntimeErrors},
+    stream::UpdateStream,
+};
 use crate::{
     source::{request::SourceRequest, resolve::resolve_source_request, Body},
     update::stream::UpdateStreamItem,
@@ -28,7 +35,6 @@ use crate::{
 /// A server that listens for updates and sends them to connected clients.
 pub(crate) struct UpdateServer<P: SourceProvider> {
     source_provider: P,
-    #[allow(dead_code)]
     issue_reporter: Vc<Box<dyn IssueReporter>>,
 }
 
@@ -56,6 +62,7 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
         let mut client: UpdateClient = ws.await?.into();
 
         let mut streams = StreamMap::new();
+        let runtime_errors = RuntimeErrors::new();
 
         loop {
             select! {
@@ -89,6 +96,30 @@ impl<P: SourceProvider + Clone + Send + Sync> UpdateServer<P> {
                         Some(ClientMessage::Unsubscribe { resource }) => {
                             streams.remove(&resource);
                         }
+                        Some(ClientMessage::RuntimeError { message, stack }) => {
+                            if let Err(err) = add_runtime_error(
+                                runtime_errors,
+                                self.source_provider.get_source(),
+                                message.into(),
+                                &stack.unwrap_or_default(),
+                            )
+                            .await
+                            {
+                                eprintln!("Failed to trace runtime error: {}", PrettyPrintError(&err));
+                                continue;
+                            }
+                            if let Err(err) = handle_issues(
+                                runtime_errors.report(),
+                                self.issue_reporter,
+                                IssueSeverity::Fatal.cell(),
+                                None,
+                                Some("report runtime error"),
+                            )
+                            .await
+                            {
+                                eprintln!("Failed to report runtime error: {}", PrettyPrintError(&err));
+                            }
+                        }
                         None => {
                             // WebSocket was closed, stop sending updates
                             break;
@@ -39,6 +39,14 @@ pub enum ClientMessage {
         #[serde(flatten)]
         resource: ResourceIdentifier,
     },
+    /// An uncaught error or unhandled rejection in the client. The stack
+    /// refers to positions in the served chunks.
+    #[serde(rename = "turbopack-runtime-error")]
+    RuntimeError {
+        message: String,
+        #[serde(default)]
+        stack: Option<String>,
+    },
 }
 
 #[derive(Serialize)]
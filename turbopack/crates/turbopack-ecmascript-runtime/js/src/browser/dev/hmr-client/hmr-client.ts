@@ -70,6 +70,15 @@ export function connect({
       subscribeToChunkUpdate(chunkPath, sendMessage, callback);
     }
   }
+
+  if (typeof window !== "undefined") {
+    window.addEventListener("error", (event) => {
+      reportRuntimeError(sendMessage, event.error ?? event.message);
+    });
+    window.addEventListener("unhandledrejection", (event) => {
+      reportRuntimeError(sendMessage, event.reason);
+    });
+  }
 }
 
 type UpdateCallbackSet = {
@@ -83,6 +92,22 @@ function sendJSON(sendMessage: SendMessage, message: ClientMessage) {
   sendMessage(JSON.stringify(message));
 }
 
+/**
+ * Reports an uncaught error to the dev server, which prints it with the stack
+ * mapped to the original sources.
+ */
+function reportRuntimeError(sendMessage: SendMessage, error: unknown) {
+  try {
+    sendJSON(sendMessage, {
+      type: "turbopack-runtime-error",
+      message: error instanceof Error ? error.message : String(error),
+      stack: error instanceof Error ? error.stack : undefined,
+    });
+  } catch {
+    // The socket might not be connected yet.
+  }
+}
+
 type ResourceKey = string;
 
 function resourceKey(resource: ResourceIdentifier): ResourceKey {
@@ -104,7 +104,16 @@ type ClientMessageUnsubscribe = {
   type: "turbopack-unsubscribe";
 } & ResourceIdentifier;
 
-type ClientMessage = ClientMessageSubscribe | ClientMessageUnsubscribe;
+type ClientMessageRuntimeError = {
+  type: "turbopack-runtime-error";
+  message: string;
+  stack?: string;
+};
+
+type ClientMessage =
+  | ClientMessageSubscribe
+  | ClientMessageUnsubscribe
+  | ClientMessageRuntimeError;
 
 type IssueSeverity =
   | "bug"
@@ -1681,6 +1681,22 @@ impl JsValue {
                         "import.meta",
                         "The import.meta object"
                     ),
+                    WellKnownObjectKind::Navigator => (
+                        "navigator",
+                        "The Navigator object: https://developer.mozilla.org/en-US/docs/Web/API/Navigator"
+                    ),
+                    WellKnownObjectKind::ServiceWorkerContainer => (
+                        "navigator.serviceWorker",
+                        "The ServiceWorkerContainer object: https://developer.mozilla.org/en-US/docs/Web/API/ServiceWorkerContainer"
+                    ),
+                    WellKnownObjectKind::CssNamespace => (
+                        "CSS",
+                        "The CSS namespace: https://developer.mozilla.org/en-US/docs/Web/API/CSS"
+                    ),
+                    WellKnownObjectKind::Worklet => (
+                        "Worklet",
+                        "A Worklet object: https://developer.mozilla.org/en-US/docs/Web/API/Worklet"
+                    ),
                 };
                 if depth > 0 {
                     let i = hints.len();
@@ -1791,6 +1807,18 @@ impl JsValue {
                       "Worker".to_string(),
                       "The standard Worker constructor: https://developer.mozilla.org/en-US/docs/Web/API/Worker/Worker"
                     ),
+                    WellKnownFunctionKind::SharedWorkerConstructor => (
+                      "SharedWorker".to_string(),
+                      "The standard SharedWorker constructor: https://developer.mozilla.org/en-US/docs/Web/API/SharedWorker/SharedWorker"
+                    ),
+                    WellKnownFunctionKind::ServiceWorkerRegister => (
+                      "register".to_string(),
+                      "navigator.serviceWorker.register: https://developer.mozilla.org/en-US/docs/Web/API/ServiceWorkerContainer/register"
+                    ),
+                    WellKnownFunctionKind::WorkletAddModule => (
+                      "addModule".to_string(),
+                      "Worklet.addModule: https://developer.mozilla.org/en-US/docs/Web/API/Worklet/addModule"
+                    ),
                     WellKnownFunctionKind::URLConstructor => (
                       "URL".to_string(),
                       "The standard URL constructor: https://developer.mozilla.org/en-US/docs/Web/API/URL/URL"
@@ -3636,6 +3664,10 @@ pub enum WellKnownObjectKind {
     NodeBuffer,
     RequireCache,
     ImportMeta,
+    Navigator,
+    ServiceWorkerContainer,
+    CssNamespace,
+    Worklet,
 }
 
 impl WellKnownObjectKind {
@@ -3653,6 +3685,9 @@ impl WellKnownObjectKind {
             Self::NodeBuffer => Some(&["Buffer"]),
             Self::RequireCache => Some(&["require", "cache"]),
             Self::ImportMeta => Some(&["import", "meta"]),
+            Self::Navigator => Some(&["navigator"]),
+            Self::ServiceWorkerContainer => Some(&["navigator", "serviceWorker"]),
+            Self::CssNamespace => Some(&["CSS"]),
             _ => None,
         }
     }
@@ -3810,6 +3845,9 @@ pub enum WellKnownFunctionKind {
     NodeResolveFrom,
     NodeProtobufLoad,
     WorkerConstructor,
+    SharedWorkerConstructor,
+    ServiceWorkerRegister,
+    WorkletAddModule,
     URLConstructor,
 }
 
@@ -3947,6 +3985,14 @@ pub mod test_utils {
                     true,
                     "ignored Worker constructor",
                 ),
+                "SharedWorker" => JsValue::unknown_if(
+                    ignore,
+                    JsValue::WellKnownFunction(WellKnownFunctionKind::SharedWorkerConstructor),
+                    true,
+                    "ignored SharedWorker constructor",
+                ),
+                "navigator" => JsValue::WellKnownObject(WellKnownObjectKind::Navigator),
+                "CSS" => JsValue::WellKnownObject(WellKnownObjectKind::CssNamespace),
                 "define" => JsValue::WellKnownFunction(WellKnownFunctionKind::Define),
                 "URL" => JsValue::WellKnownFunction(WellKnownFunctionKind::URLConstructor),
                 "process" => JsValue::WellKnownObject(WellKnownObjectKind::NodeProcess),
@@ -42,6 +42,11 @@ pub async fn replace_well_known(
         JsValue::Member(_, box JsValue::WellKnownFunction(kind), box prop) => {
             well_known_function_member(kind, prop)
         }
+        // `new AudioContext()` is not tracked, so any `audioWorklet` property is assumed to be the
+        // AudioWorklet of a BaseAudioContext.
+        JsValue::Member(_, _, box ref prop) if prop.as_str() == Some("audioWorklet") => {
+            (JsValue::WellKnownObject(WellKnownObjectKind::Worklet), true)
+        }
         _ => (value, false),
     })
 }
@@ -582,6 +587,10 @@ pub async fn well_known_object_member(
         WellKnownObjectKind::NodePreGyp => node_pre_gyp(prop),
         WellKnownObjectKind::NodeExpressApp => express(prop),
         WellKnownObjectKind::NodeProtobufLoader => protobuf_loader(prop),
+        WellKnownObjectKind::Navigator => navigator(prop),
+        WellKnownObjectKind::ServiceWorkerContainer => service_worker_container(prop),
+        WellKnownObjectKind::CssNamespace => css_namespace(prop),
+        WellKnownObjectKind::Worklet => worklet(prop),
         #[allow(unreachable_patterns)]
         _ => {
             return Ok((
@@ -804,3 +813,67 @@ fn protobuf_loader(prop: JsValue) -> JsValue {
         ),
     }
 }
+
+fn navigator(prop: JsValue) -> JsValue {
+    match prop.as_str() {
+        Some("serviceWorker") => {
+            JsValue::WellKnownObject(WellKnownObjectKind::ServiceWorkerContainer)
+        }
+        _ => JsValue::unknown(
+            JsValue::member(
+                Box::new(JsValue::WellKnownObject(WellKnownObjectKind::Navigator)),
+                Box::new(prop),
+            ),
+            true,
+            "unsupported property on navigator",
+        ),
+    }
+}
+
+fn service_worker_container(prop: JsValue) -> JsValue {
+    match prop.as_str() {
+        Some("register") => {
+            JsValue::WellKnownFunction(WellKnownFunctionKind::ServiceWorkerRegister)
+        }
+        _ => JsValue::unknown(
+            JsValue::member(
+                Box::new(JsValue::WellKnownObject(
+                    WellKnownObjectKind::ServiceWorkerContainer,
+                )),
+                Box::new(prop),
+            ),
+            true,
+            "unsupported property on navigator.serviceWorker",
+        ),
+    }
+}
+
+fn css_namespace(prop: JsValue) -> JsValue {
+    match prop.as_str() {
+        Some("paintWorklet" | "layoutWorklet" | "animationWorklet") => {
+            JsValue::WellKnownObject(WellKnownObjectKind::Worklet)
+        }
+        _ => JsValue::unknown(
+            JsValue::member(
+                Box::new(JsValue::WellKnownObject(WellKnownObjectKind::CssNamespace)),
+                Box::new(prop),
+            ),
+            true,
+            "unsupported property on CSS",
+        ),
+    }
+}
+
+fn worklet(prop: JsValue) -> JsValue {
+    match prop.as_str() {
+        Some("addModule") => JsValue::WellKnownFunction(WellKnownFunctionKind::WorkletAddModule),
+        _ => JsValue::unknown(
+            JsValue::member(
+                Box::new(JsValue::WellKnownObject(WellKnownObjectKind::Worklet)),
+                Box::new(prop),
+            ),
+            true,
+            "unsupported property on Worklet",
+        ),
+    }
+}
@@ -33,6 +33,8 @@ pub async fn minify(path: Vc<FileSystemPath>, code: Vc<Code>) -> Result<Vc<Code>
     let path = path.await?;
     let original_map = code.generate_source_map();
     let code = code.await?;
+    // Minifying a large chunk is expensive, skip it when the chunk is outdated already.
+    turbo_tasks::check_cancelled()?;
 
     let cm = Arc::new(SwcSourceMap::new(FilePathMapping::empty()));
     let compiler = Arc::new(Compiler::new(cm.clone()));
@@ -400,6 +400,8 @@ async fn parse_file_content(
             let span = tracing::trace_span!("transforms");
             async {
                 for transform in transforms.iter() {
+                    // Transforms can be slow, stop early when the result is no longer needed.
+                    turbo_tasks::check_cancelled()?;
                     transform
                         .apply(&mut parsed_program, &transform_context)
                         .await?;
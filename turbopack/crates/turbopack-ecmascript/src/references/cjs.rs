@@ -9,6 +9,7 @@ use turbopack_core::{
     chunk::{ChunkableModuleReference, ChunkingContext},
     issue::IssueSource,
     reference::ModuleReference,
+    reference_type::{CommonJsReferenceSubType, ReferenceType},
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 use turbopack_resolve::ecmascript::cjs_resolve;
@@ -58,6 +59,11 @@ impl ModuleReference for CjsAssetReference {
             self.in_try,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -114,6 +120,11 @@ impl ModuleReference for CjsRequireAssetReference {
             self.in_try,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -221,6 +232,11 @@ impl ModuleReference for CjsRequireResolveAssetReference {
             self.in_try,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::CommonJs(CommonJsReferenceSubType::Undefined).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
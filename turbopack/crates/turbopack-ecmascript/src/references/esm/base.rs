@@ -18,7 +18,7 @@ use turbopack_core::{
     },
     module::Module,
     reference::ModuleReference,
-    reference_type::{EcmaScriptModulesReferenceSubType, ImportWithType},
+    reference_type::{EcmaScriptModulesReferenceSubType, ImportWithType, ReferenceType},
     resolve::{
         origin::{ResolveOrigin, ResolveOriginExt},
         parse::Request,
@@ -117,6 +117,20 @@ impl EsmAssetReference {
         }
         origin
     }
+
+    fn sub_type(&self) -> EcmaScriptModulesReferenceSubType {
+        if let Some(ty) = self
+            .annotations
+            .module_type()
+            .and_then(ImportWithType::from_attribute)
+        {
+            EcmaScriptModulesReferenceSubType::ImportWithType(ty)
+        } else if let Some(part) = &self.export_name {
+            EcmaScriptModulesReferenceSubType::ImportPart(*part)
+        } else {
+            EcmaScriptModulesReferenceSubType::Import
+        }
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -150,13 +164,7 @@ impl EsmAssetReference {
 impl ModuleReference for EsmAssetReference {
     #[turbo_tasks::function]
     async fn resolve_reference(&self) -> Result<Vc<ModuleResolveResult>> {
-        let ty = if matches!(self.annotations.module_type(), Some("json")) {
-            EcmaScriptModulesReferenceSubType::ImportWithType(ImportWithType::Json)
-        } else if let Some(part) = &self.export_name {
-            EcmaScriptModulesReferenceSubType::ImportPart(*part)
-        } else {
-            EcmaScriptModulesReferenceSubType::Import
-        };
+        let ty = self.sub_type();
 
         if let Request::Module { module, .. } = &*self.request.await? {
             if module == TURBOPACK_PART_IMPORT_SOURCE {
@@ -208,6 +216,11 @@ impl ModuleReference for EsmAssetReference {
 
         Ok(result)
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::EcmaScriptModules(self.sub_type()).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
@@ -10,7 +10,7 @@ use turbopack_core::{
     environment::ChunkLoading,
     issue::IssueSource,
     reference::ModuleReference,
-    reference_type::EcmaScriptModulesReferenceSubType,
+    reference_type::{EcmaScriptModulesReferenceSubType, ReferenceType},
     resolve::{origin::ResolveOrigin, parse::Request, ModuleResolveResult},
 };
 use turbopack_resolve::ecmascript::esm_resolve;
@@ -67,6 +67,11 @@ impl ModuleReference for EsmAsyncAssetReference {
             Some(self.issue_source),
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::DynamicImport).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
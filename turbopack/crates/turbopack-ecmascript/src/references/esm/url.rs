@@ -98,6 +98,11 @@ impl ModuleReference for UrlAssetReference {
             self.in_try,
         )
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::Url(UrlReferenceSubType::EcmaScriptNewUrl).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
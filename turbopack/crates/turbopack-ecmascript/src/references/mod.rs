@@ -63,7 +63,7 @@ use turbopack_core::{
     issue::{analyze::AnalyzeIssue, IssueExt, IssueSeverity, IssueSource, StyledString},
     module::Module,
     reference::{ModuleReference, ModuleReferences, SourceMapReference},
-    reference_type::{CommonJsReferenceSubType, ReferenceType},
+    reference_type::{CommonJsReferenceSubType, ReferenceType, WorkerReferenceSubType},
     resolve::{
         find_context_file,
         origin::{PlainResolveOrigin, ResolveOrigin, ResolveOriginExt},
@@ -1323,6 +1323,70 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
             .await
     };
 
+    let worker = match &func {
+        JsValue::WellKnownFunction(WellKnownFunctionKind::WorkerConstructor) if new => {
+            Some(("new Worker", WorkerReferenceSubType::WebWorker))
+        }
+        JsValue::WellKnownFunction(WellKnownFunctionKind::SharedWorkerConstructor) if new => {
+            Some(("new SharedWorker", WorkerReferenceSubType::SharedWorker))
+        }
+        JsValue::WellKnownFunction(WellKnownFunctionKind::ServiceWorkerRegister) if !new => Some((
+            "navigator.serviceWorker.register",
+            WorkerReferenceSubType::ServiceWorker,
+        )),
+        JsValue::WellKnownFunction(WellKnownFunctionKind::WorkletAddModule) if !new => {
+            Some(("Worklet.addModule", WorkerReferenceSubType::Worklet))
+        }
+        _ => None,
+    };
+    if let Some((name, ty)) = worker {
+        let args = linked_args(args).await?;
+        // Further arguments are options like `{ type: "module" }` or `{ scope }`, which
+        // are passed through unchanged.
+        if let [url @ JsValue::Url(_, JsValueUrlKind::Relative), ..] = &args[..] {
+            let pat = js_value_to_pattern(url);
+            if !pat.has_constant_parts() {
+                let (args, hints) = explain_args(&args);
+                handler.span_warn_with_code(
+                    span,
+                    &format!("{name}({args}) is very dynamic{hints}",),
+                    DiagnosticId::Lint(
+                        errors::failed_to_analyse::ecmascript::NEW_WORKER.to_string(),
+                    ),
+                );
+                if ignore_dynamic_requests {
+                    return Ok(());
+                }
+            }
+
+            if *compile_time_info.environment().rendering().await? == Rendering::Client {
+                analysis.add_reference(WorkerAssetReference::new(
+                    origin,
+                    Request::parse(Value::new(pat)),
+                    Value::new(ty),
+                    Vc::cell(ast_path.to_vec()),
+                    issue_source(source, span),
+                    in_try,
+                ));
+            }
+
+            return Ok(());
+        }
+        // Shared workers, service workers and worklets are commonly created
+        // from runtime URLs like `register('/sw.js')`, which are left alone.
+        if matches!(ty, WorkerReferenceSubType::WebWorker) {
+            let (args, hints) = explain_args(&args);
+            handler.span_warn_with_code(
+                span,
+                &format!("{name}({args}) is not statically analyse-able{hints}",),
+                DiagnosticId::Error(
+                    errors::failed_to_analyse::ecmascript::DYNAMIC_IMPORT.to_string(),
+                ),
+            );
+        }
+        return Ok(());
+    }
+
     if new {
         match func {
             JsValue::WellKnownFunction(WellKnownFunctionKind::URLConstructor) => {
@@ -1364,46 +1428,6 @@ async fn handle_call<G: Fn(Vec<Effect>) + Send + Sync>(
                 }
                 return Ok(());
             }
-            JsValue::WellKnownFunction(WellKnownFunctionKind::WorkerConstructor) => {
-                let args = linked_args(args).await?;
-                if let [url @ JsValue::Url(_, JsValueUrlKind::Relative)] = &args[..] {
-                    let pat = js_value_to_pattern(url);
-                    if !pat.has_constant_parts() {
-                        let (args, hints) = explain_args(&args);
-                        handler.span_warn_with_code(
-                            span,
-                            &format!("new Worker({args}) is very dynamic{hints}",),
-                            DiagnosticId::Lint(
-                                errors::failed_to_analyse::ecmascript::NEW_WORKER.to_string(),
-                            ),
-                        );
-                        if ignore_dynamic_requests {
-                            return Ok(());
-                        }
-                    }
-
-                    if *compile_time_info.environment().rendering().await? == Rendering::Client {
-                        analysis.add_reference(WorkerAssetReference::new(
-                            origin,
-                            Request::parse(Value::new(pat)),
-                            Vc::cell(ast_path.to_vec()),
-                            issue_source(source, span),
-                            in_try,
-                        ));
-                    }
-
-                    return Ok(());
-                }
-                let (args, hints) = explain_args(&args);
-                handler.span_warn_with_code(
-                    span,
-                    &format!("new Worker({args}) is not statically analyse-able{hints}",),
-                    DiagnosticId::Error(
-                        errors::failed_to_analyse::ecmascript::DYNAMIC_IMPORT.to_string(),
-                    ),
-                );
-                return Ok(());
-            }
             _ => {}
         }
 
@@ -2545,6 +2569,14 @@ async fn value_visitor_inner(
                 true,
                 "ignored Worker constructor",
             ),
+            "SharedWorker" => JsValue::unknown_if(
+                ignore,
+                JsValue::WellKnownFunction(WellKnownFunctionKind::SharedWorkerConstructor),
+                true,
+                "ignored SharedWorker constructor",
+            ),
+            "navigator" => JsValue::WellKnownObject(WellKnownObjectKind::Navigator),
+            "CSS" => JsValue::WellKnownObject(WellKnownObjectKind::CssNamespace),
             "define" => JsValue::WellKnownFunction(WellKnownFunctionKind::Define),
             "URL" => JsValue::WellKnownFunction(WellKnownFunctionKind::URLConstructor),
             "process" => JsValue::WellKnownObject(WellKnownObjectKind::NodeProcess),
@@ -1,6 +1,6 @@
 use anyhow::{bail, Result};
 use swc_core::{
-    ecma::ast::{Expr, ExprOrSpread, Lit, NewExpr},
+    ecma::ast::{CallExpr, Expr, ExprOrSpread, Lit, NewExpr},
     quote_expr,
 };
 use turbo_tasks::{RcStr, ResolvedVc, Value, ValueToString, Vc};
@@ -25,6 +25,7 @@ use crate::{
 pub struct WorkerAssetReference {
     pub origin: Vc<Box<dyn ResolveOrigin>>,
     pub request: Vc<Request>,
+    pub ty: WorkerReferenceSubType,
     pub path: Vc<AstPath>,
     pub issue_source: Vc<IssueSource>,
     pub in_try: bool,
@@ -36,6 +37,7 @@ impl WorkerAssetReference {
     pub fn new(
         origin: Vc<Box<dyn ResolveOrigin>>,
         request: Vc<Request>,
+        ty: Value<WorkerReferenceSubType>,
         path: Vc<AstPath>,
         issue_source: Vc<IssueSource>,
         in_try: bool,
@@ -43,6 +45,7 @@ impl WorkerAssetReference {
         Self::cell(WorkerAssetReference {
             origin,
             request,
+            ty: ty.into_value(),
             path,
             issue_source,
             in_try,
@@ -57,8 +60,7 @@ impl WorkerAssetReference {
         let module = url_resolve(
             self.origin,
             self.request,
-            // TODO support more worker types
-            Value::new(ReferenceType::Worker(WorkerReferenceSubType::WebWorker)),
+            Value::new(ReferenceType::Worker(self.ty.clone())),
             Some(self.issue_source),
             self.in_try,
         );
@@ -79,7 +81,10 @@ impl WorkerAssetReference {
             return Ok(None);
         };
 
-        Ok(Some(WorkerLoaderModule::new(*chunkable)))
+        Ok(Some(WorkerLoaderModule::new(
+            *chunkable,
+            Value::new(self.ty.clone()),
+        )))
     }
 }
 
@@ -96,14 +101,25 @@ impl ModuleReference for WorkerAssetReference {
             Ok(ModuleResolveResult::unresolvable().cell())
         }
     }
+
+    #[turbo_tasks::function]
+    fn reference_type(&self) -> Vc<ReferenceType> {
+        ReferenceType::Worker(self.ty.clone()).cell()
+    }
 }
 
 #[turbo_tasks::value_impl]
 impl ValueToString for WorkerAssetReference {
     #[turbo_tasks::function]
     async fn to_string(&self) -> Result<Vc<RcStr>> {
+        let kind = match self.ty {
+            WorkerReferenceSubType::SharedWorker => "new SharedWorker",
+            WorkerReferenceSubType::ServiceWorker => "navigator.serviceWorker.register",
+            WorkerReferenceSubType::Worklet => "worklet.addModule",
+            _ => "new Worker",
+        };
         Ok(Vc::cell(
-            format!("new Worker {}", self.request.to_string().await?,).into(),
+            format!("{kind} {}", self.request.to_string().await?,).into(),
         ))
     }
 }
@@ -129,30 +145,29 @@ impl CodeGenerateable for WorkerAssetReference {
         let path = &self.path.await?;
 
         let visitor = create_visitor!(path, visit_mut_expr(expr: &mut Expr) {
-            let message = if let Expr::New(NewExpr { args, ..}) = expr {
-                if let Some(args) = args {
-                    match args.iter_mut().next() {
-                        Some(ExprOrSpread { spread: None, expr }) => {
-                            let item_id = Expr::Lit(Lit::Str(item_id.to_string().into()));
-                            *expr = quote_expr!(
-                                "__turbopack_require__($item_id)",
-                                item_id: Expr = item_id
-                            );
-                            return;
-                        }
-                        // These are SWC bugs: https://github.com/swc-project/swc/issues/5394
-                        Some(ExprOrSpread { spread: Some(_), expr: _ }) => {
-                            "spread operator is illegal in new Worker() expressions."
-                        }
-                        _ => {
-                            "new Worker() expressions require at least 1 argument"
-                        }
+            let args = match expr {
+                Expr::New(NewExpr { args, .. }) => Ok(args.as_mut()),
+                Expr::Call(CallExpr { args, .. }) => Ok(Some(args)),
+                _ => Err("visitor must be executed on a NewExpr or CallExpr"),
+            };
+            let message = match args {
+                Ok(Some(args)) => match args.iter_mut().next() {
+                    Some(ExprOrSpread { spread: None, expr }) => {
+                        let item_id = Expr::Lit(Lit::Str(item_id.to_string().into()));
+                        *expr = quote_expr!(
+                            "__turbopack_require__($item_id)",
+                            item_id: Expr = item_id
+                        );
+                        return;
+                    }
+                    // These are SWC bugs: https://github.com/swc-project/swc/issues/5394
+                    Some(ExprOrSpread { spread: Some(_), expr: _ }) => {
+                        "spread operator is illegal in worker expressions."
                     }
-                } else {
-                    "new Worker() expressions require at least 1 argument"
-                }
-            } else {
-                "visitor must be executed on a NewExpr"
+                    _ => "worker expressions require at least 1 argument",
+                },
+                Ok(None) => "worker expressions require at least 1 argument",
+                Err(message) => message,
             };
             *expr = *quote_expr!(
                 "(() => { throw new Error($message); })()",
@@ -1,10 +1,13 @@
 use anyhow::Result;
-use turbo_tasks::{RcStr, Vc};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use turbo_tasks::{RcStr, Value, Vc};
 use turbo_tasks_fs::FileContent;
 use turbopack_core::{
     asset::{Asset, AssetContent},
     ident::AssetIdent,
+    reference_type::ImportWithType,
     source::Source,
+    source_transform::SourceTransform,
 };
 
 use crate::utils::StringifyJs;
@@ -54,3 +57,140 @@ impl Asset for TextContentFileSource {
         Ok(AssetContent::file(content))
     }
 }
+
+#[turbo_tasks::function]
+fn bytes_modifier() -> Vc<RcStr> {
+    Vc::cell("bytes content".into())
+}
+
+/// A source asset that exports the content of an asset as a `Uint8Array`
+/// from the default export of a JS module.
+#[turbo_tasks::value]
+pub struct BytesContentFileSource {
+    pub source: Vc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl BytesContentFileSource {
+    #[turbo_tasks::function]
+    pub fn new(source: Vc<Box<dyn Source>>) -> Vc<Self> {
+        BytesContentFileSource { source }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for BytesContentFileSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source
+            .ident()
+            .with_modifier(bytes_modifier())
+            .rename_as("*.mjs".into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for BytesContentFileSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let source = self.source.content().file_content();
+        let FileContent::Content(content) = &*source.await? else {
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        };
+        let base64 = STANDARD.encode(content.content().to_bytes()?);
+        let code: RcStr = format!(
+            "export default Uint8Array.from(atob({}), (c) => c.charCodeAt(0));",
+            StringifyJs(&base64)
+        )
+        .into();
+        let content = FileContent::Content(code.into()).cell();
+        Ok(AssetContent::file(content))
+    }
+}
+
+#[turbo_tasks::function]
+fn css_style_sheet_modifier() -> Vc<RcStr> {
+    Vc::cell("css style sheet".into())
+}
+
+/// A source asset that exports a constructable `CSSStyleSheet` with the
+/// content of a CSS asset from the default export of a JS module. Like native
+/// CSS module scripts, `@import` rules are not supported.
+///
+/// `CSSStyleSheet` only exists in browsers, so during server rendering or in
+/// Node.js the CSS text is exported instead.
+#[turbo_tasks::value]
+pub struct CssStyleSheetFileSource {
+    pub source: Vc<Box<dyn Source>>,
+}
+
+#[turbo_tasks::value_impl]
+impl CssStyleSheetFileSource {
+    #[turbo_tasks::function]
+    pub fn new(source: Vc<Box<dyn Source>>) -> Vc<Self> {
+        CssStyleSheetFileSource { source }.cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Source for CssStyleSheetFileSource {
+    #[turbo_tasks::function]
+    fn ident(&self) -> Vc<AssetIdent> {
+        self.source
+            .ident()
+            .with_modifier(css_style_sheet_modifier())
+            .rename_as("*.mjs".into())
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl Asset for CssStyleSheetFileSource {
+    #[turbo_tasks::function]
+    async fn content(&self) -> Result<Vc<AssetContent>> {
+        let source = self.source.content().file_content();
+        let FileContent::Content(content) = &*source.await? else {
+            return Ok(AssetContent::file(FileContent::NotFound.cell()));
+        };
+        let text = content.content().to_str()?;
+        let code: RcStr = format!(
+            "const text = {};\nlet sheet = text;\nif (typeof CSSStyleSheet !== \"undefined\") \
+             {{\n  sheet = new CSSStyleSheet();\n  sheet.replaceSync(text);\n}}\nexport default \
+             sheet;",
+            StringifyJs(&text)
+        )
+        .into();
+        let content = FileContent::Content(code.into()).cell();
+        Ok(AssetContent::file(content))
+    }
+}
+
+/// Turns a source into a JS module exposing it in the form requested by the
+/// `type` import attribute. JSON is handled by its own module type instead.
+#[turbo_tasks::value]
+pub struct ImportWithTypeTransform {
+    ty: ImportWithType,
+}
+
+#[turbo_tasks::value_impl]
+impl ImportWithTypeTransform {
+    #[turbo_tasks::function]
+    pub fn new(ty: Value<ImportWithType>) -> Vc<Self> {
+        ImportWithTypeTransform {
+            ty: ty.into_value(),
+        }
+        .cell()
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceTransform for ImportWithTypeTransform {
+    #[turbo_tasks::function]
+    fn transform(&self, source: Vc<Box<dyn Source>>) -> Vc<Box<dyn Source>> {
+        match &self.ty {
+            ImportWithType::Json => source,
+            ImportWithType::Css => Vc::upcast(CssStyleSheetFileSource::new(source)),
+            ImportWithType::Text => Vc::upcast(TextContentFileSource::new(source)),
+            ImportWithType::Bytes => Vc::upcast(BytesContentFileSource::new(source)),
+        }
+    }
+}
@@ -1,15 +1,21 @@
-use anyhow::{bail, Result};
+use std::fmt::Write;
+
+use anyhow::{bail, Context, Result};
 use indoc::formatdoc;
 use turbo_tasks::{RcStr, TryJoinIterExt, Value, ValueToString, Vc};
+use turbo_tasks_fs::{File, FileContent};
 use turbopack_core::{
+    asset::AssetContent,
     chunk::{
         availability_info::AvailabilityInfo, ChunkData, ChunkItem, ChunkType, ChunkingContext,
         ChunkingContextExt, ChunksData, EvaluatableAsset, EvaluatableAssets,
     },
     ident::AssetIdent,
     module::Module,
-    output::OutputAssets,
+    output::{OutputAsset, OutputAssets},
     reference::{ModuleReferences, SingleOutputAssetReference},
+    reference_type::WorkerReferenceSubType,
+    virtual_output::VirtualOutputAsset,
 };
 
 use super::module::WorkerLoaderModule;
@@ -32,10 +38,28 @@ pub fn worker_modifier() -> Vc<RcStr> {
     Vc::cell("worker".into())
 }
 
+#[turbo_tasks::function]
+fn bootstrap_modifier() -> Vc<RcStr> {
+    Vc::cell("worker bootstrap".into())
+}
+
+/// Web workers are loaded from a blob URL. Shared workers would get a new
+/// instance for every blob URL, and service workers and worklets can't be
+/// loaded from blob URLs at all, so these get a bootstrap script at a stable
+/// URL instead.
+fn needs_bootstrap(ty: &WorkerReferenceSubType) -> bool {
+    matches!(
+        ty,
+        WorkerReferenceSubType::SharedWorker
+            | WorkerReferenceSubType::ServiceWorker
+            | WorkerReferenceSubType::Worklet
+    )
+}
+
 #[turbo_tasks::value_impl]
 impl WorkerLoaderChunkItem {
     #[turbo_tasks::function]
-    async fn chunks(&self) -> Result<Vc<OutputAssets>> {
+    async fn entries(&self) -> Result<Vc<EvaluatableAssets>> {
         let module = self.module.await?;
 
         let Some(evaluatable) =
@@ -47,17 +71,75 @@ impl WorkerLoaderChunkItem {
             );
         };
 
-        Ok(self.chunking_context.evaluated_chunk_group_assets(
+        Ok(EvaluatableAssets::empty().with_entry(evaluatable))
+    }
+
+    #[turbo_tasks::function]
+    async fn chunks(self: Vc<Self>) -> Result<Vc<OutputAssets>> {
+        let this = self.await?;
+        let module = this.module.await?;
+
+        Ok(this.chunking_context.evaluated_chunk_group_assets(
             AssetIdent::from_path(
-                self.chunking_context
+                this.chunking_context
                     .chunk_path(module.inner.ident(), ".js".into()),
             )
             .with_modifier(worker_modifier()),
-            EvaluatableAssets::empty().with_entry(evaluatable),
+            self.entries(),
             Value::new(AvailabilityInfo::Root),
         ))
     }
 
+    /// A script at a stable URL, for worker types that can't use a blob URL.
+    ///
+    /// Worklets can neither call `importScripts` nor access the DOM, so they
+    /// get a single file with the runtime and all of their chunks. The other
+    /// worker types get a script that loads the worker chunks.
+    #[turbo_tasks::function]
+    async fn bootstrap(self: Vc<Self>) -> Result<Vc<Box<dyn OutputAsset>>> {
+        let this = self.await?;
+        let module = this.module.await?;
+        let ident = module.inner.ident().with_modifier(bootstrap_modifier());
+
+        let path = match module.ty {
+            WorkerReferenceSubType::Worklet => {
+                let path = this.chunking_context.chunk_path(ident, ".js".into());
+                return Ok(this
+                    .chunking_context
+                    .single_file_chunk_group_asset(path, self.entries()));
+            }
+            // The scope of a service worker is limited to the directory of its
+            // script, so it's placed at the root of the output instead of the
+            // chunks directory.
+            WorkerReferenceSubType::ServiceWorker => this.chunking_context.output_root().join(
+                ident
+                    .output_name(this.chunking_context.context_path(), ".js".into())
+                    .await?
+                    .clone_value(),
+            ),
+            _ => this.chunking_context.chunk_path(ident, ".js".into()),
+        };
+        let dir = path.parent().await?;
+
+        let mut urls = Vec::new();
+        for chunk in self.chunks().await?.iter() {
+            let chunk_path = chunk.ident().path().await?;
+            let url = dir
+                .get_relative_path_to(&chunk_path)
+                .context("worker chunks must be on the same file system as the bootstrap")?;
+            urls.push(StringifyJs(&url).to_string());
+        }
+
+        let mut code = String::new();
+        writeln!(code, "TURBOPACK_WORKER_LOCATION = self.location.origin;")?;
+        writeln!(code, "importScripts({});", urls.join(", "))?;
+
+        Ok(Vc::upcast(VirtualOutputAsset::new(
+            path,
+            AssetContent::file(FileContent::Content(File::from(code)).cell()),
+        )))
+    }
+
     #[turbo_tasks::function]
     async fn chunks_data(self: Vc<Self>) -> Result<Vc<ChunksData>> {
         let this = self.await?;
@@ -77,6 +159,19 @@ impl EcmascriptChunkItem for WorkerLoaderChunkItem {
 
     #[turbo_tasks::function]
     async fn content(self: Vc<Self>) -> Result<Vc<EcmascriptChunkItemContent>> {
+        let this = self.await?;
+        if needs_bootstrap(&this.module.await?.ty) {
+            let url = this
+                .chunking_context
+                .asset_url(self.bootstrap().ident())
+                .await?;
+            return Ok(EcmascriptChunkItemContent {
+                inner_code: format!("__turbopack_export_value__({});", StringifyJs(&url)).into(),
+                ..Default::default()
+            }
+            .into());
+        }
+
         let chunks_data = self.chunks_data().await?;
         let chunks_data = chunks_data.iter().try_join().await?;
         let chunks_data: Vec<_> = chunks_data
@@ -104,6 +199,11 @@ fn chunk_reference_description() -> Vc<RcStr> {
     Vc::cell("worker chunk".into())
 }
 
+#[turbo_tasks::function]
+fn bootstrap_reference_description() -> Vc<RcStr> {
+    Vc::cell("worker bootstrap".into())
+}
+
 #[turbo_tasks::value_impl]
 impl ChunkItem for WorkerLoaderChunkItem {
     #[turbo_tasks::function]
@@ -118,10 +218,14 @@ impl ChunkItem for WorkerLoaderChunkItem {
 
     #[turbo_tasks::function]
     async fn references(self: Vc<Self>) -> Result<Vc<ModuleReferences>> {
-        let chunks = self.chunks();
+        let module = self.await?.module.await?;
+        let ty = &module.ty;
 
-        Ok(Vc::cell(
-            chunks
+        // The chunks of worklets are part of their bootstrap.
+        let mut references: Vec<_> = if matches!(ty, WorkerReferenceSubType::Worklet) {
+            Vec::new()
+        } else {
+            self.chunks()
                 .await?
                 .iter()
                 .copied()
@@ -131,8 +235,16 @@ impl ChunkItem for WorkerLoaderChunkItem {
                         chunk_reference_description(),
                     ))
                 })
-                .collect(),
-        ))
+                .collect()
+        };
+        if needs_bootstrap(ty) {
+            references.push(Vc::upcast(SingleOutputAssetReference::new(
+                self.bootstrap(),
+                bootstrap_reference_description(),
+            )));
+        }
+
+        Ok(Vc::cell(references))
     }
 
     #[turbo_tasks::function]
@@ -1,37 +1,57 @@
 use anyhow::Result;
-use turbo_tasks::{RcStr, Vc};
+use turbo_tasks::{RcStr, Value, Vc};
 use turbopack_core::{
     asset::{Asset, AssetContent},
     chunk::{ChunkableModule, ChunkingContext},
     ident::AssetIdent,
     module::Module,
     reference::{ModuleReferences, SingleModuleReference},
+    reference_type::WorkerReferenceSubType,
 };
 
 use super::chunk_item::WorkerLoaderChunkItem;
 
 #[turbo_tasks::function]
-fn modifier() -> Vc<RcStr> {
-    Vc::cell("worker loader".into())
+fn modifier(ty: Value<WorkerReferenceSubType>) -> Vc<RcStr> {
+    Vc::cell(
+        match ty.into_value() {
+            WorkerReferenceSubType::SharedWorker => "shared worker loader",
+            WorkerReferenceSubType::ServiceWorker => "service worker loader",
+            WorkerReferenceSubType::Worklet => "worklet loader",
+            _ => "worker loader",
+        }
+        .into(),
+    )
 }
 
 /// The WorkerLoaderModule is a module that creates a separate root chunk group for the given module
-/// and exports a URL to pass to the worker constructor.
+/// and exports a URL to pass to the worker constructor, `navigator.serviceWorker.register` or
+/// `Worklet.addModule`.
 #[turbo_tasks::value]
 pub struct WorkerLoaderModule {
     pub inner: Vc<Box<dyn ChunkableModule>>,
+    pub ty: WorkerReferenceSubType,
 }
 
 #[turbo_tasks::value_impl]
 impl WorkerLoaderModule {
     #[turbo_tasks::function]
-    pub fn new(module: Vc<Box<dyn ChunkableModule>>) -> Vc<Self> {
-        Self::cell(WorkerLoaderModule { inner: module })
+    pub fn new(
+        module: Vc<Box<dyn ChunkableModule>>,
+        ty: Value<WorkerReferenceSubType>,
+    ) -> Vc<Self> {
+        Self::cell(WorkerLoaderModule {
+            inner: module,
+            ty: ty.into_value(),
+        })
     }
 
     #[turbo_tasks::function]
-    pub fn asset_ident_for(module: Vc<Box<dyn ChunkableModule>>) -> Vc<AssetIdent> {
-        module.ident().with_modifier(modifier())
+    pub fn asset_ident_for(
+        module: Vc<Box<dyn ChunkableModule>>,
+        ty: Value<WorkerReferenceSubType>,
+    ) -> Vc<AssetIdent> {
+        module.ident().with_modifier(modifier(ty))
     }
 }
 
@@ -44,7 +64,7 @@ fn inner_module_reference_description() -> Vc<RcStr> {
 impl Module for WorkerLoaderModule {
     #[turbo_tasks::function]
     fn ident(&self) -> Vc<AssetIdent> {
-        Self::asset_ident_for(self.inner)
+        Self::asset_ident_for(self.inner, Value::new(self.ty.clone()))
     }
 
     #[turbo_tasks::function]
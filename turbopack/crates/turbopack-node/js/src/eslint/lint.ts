@@ -0,0 +1,58 @@
+declare const __turbopack_external_require__: (id: string) => any;
+
+import { resolve } from "path";
+import type { Ipc } from "../ipc/evaluate";
+
+export type LintMessage = {
+  ruleId: string | null;
+  /** 1 for warnings, 2 for errors. */
+  severity: number;
+  message: string;
+  /** 1-indexed. */
+  line: number | null;
+  /** 1-indexed. */
+  column: number | null;
+  endLine: number | null;
+  endColumn: number | null;
+};
+
+const contextDir = process.cwd();
+
+// The ESLint instance caches the resolved configs, so it's only created again
+// when the config file changes.
+let eslint: any;
+let eslintConfigVersion: string | undefined;
+
+export default async function lint(
+  ipc: Ipc<unknown, unknown>,
+  path: string,
+  content: string,
+  configVersion: string
+): Promise<LintMessage[]> {
+  if (!eslint || eslintConfigVersion !== configVersion) {
+    const { ESLint } = __turbopack_external_require__("eslint");
+    eslint = new ESLint({ cwd: contextDir });
+    eslintConfigVersion = configVersion;
+  }
+
+  const filePath = resolve(contextDir, path);
+  if (await eslint.isPathIgnored(filePath)) {
+    return [];
+  }
+  const [result] = await eslint.lintText(content, {
+    filePath,
+    warnIgnored: false,
+  });
+  if (!result) {
+    return [];
+  }
+  return result.messages.map((message: any) => ({
+    ruleId: message.ruleId ?? null,
+    severity: message.severity,
+    message: message.message,
+    line: message.line ?? null,
+    column: message.column ?? null,
+    endLine: message.endLine ?? null,
+    endColumn: message.endColumn ?? null,
+  }));
+}
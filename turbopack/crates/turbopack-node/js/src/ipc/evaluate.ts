@@ -18,6 +18,7 @@ type IpcOutgoingMessage =
       type: "end";
       data: string | undefined;
       duration: number;
+      rss: number;
     }
   | {
       type: "info";
@@ -95,6 +96,8 @@ export const run = async (
           data:
             value === undefined ? undefined : JSON.stringify(value, null, 2),
           duration: 0,
+          // Reported so that the pool can recycle workers that use too much memory
+          rss: process.memoryUsage.rss(),
         });
       } catch (e) {
         await ipc.sendError(e as Error);
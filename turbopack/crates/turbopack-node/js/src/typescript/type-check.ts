@@ -0,0 +1,207 @@
+declare const __turbopack_external_require__: (id: string) => any;
+
+import { dirname, resolve, sep } from "path";
+import type { Ipc } from "../ipc/evaluate";
+
+type TsConfig = {
+  /** Relative to the project. */
+  path: string;
+  json: any;
+};
+
+type SourceFile = {
+  /** Relative to the project. */
+  path: string;
+  /** A hash of the content, which changes when the file changes. */
+  version: string;
+};
+
+type Position = {
+  line: number;
+  column: number;
+};
+
+export type TypeCheckDiagnostic = {
+  /** Relative to the project. */
+  path: string | null;
+  start: Position | null;
+  end: Position | null;
+  message: string;
+  code: number;
+  category: "error" | "warning" | "suggestion" | "message";
+};
+
+const contextDir = process.cwd();
+
+function toAbsolute(path: string) {
+  const absolute = resolve(contextDir, path);
+  return sep !== "/" ? absolute.replaceAll(sep, "/") : absolute;
+}
+
+function toRelative(fileName: string) {
+  const prefix = toAbsolute(".") + "/";
+  return fileName.startsWith(prefix) ? fileName.slice(prefix.length) : fileName;
+}
+
+let ts: any;
+
+// The language service is kept between evaluations, so unchanged files are
+// not parsed and bound again.
+let service: any;
+let serviceConfigs: string | undefined;
+let compilerOptions: any;
+let rootFileNames: string[] = [];
+const sourceFiles = new Map<string, { version: string; content: string }>();
+
+/**
+ * `configs` starts with the tsconfig, followed by the configs it extends.
+ * Options are converted relative to the config they are declared in.
+ */
+function parseCompilerOptions(
+  configs: TsConfig[],
+  diagnostics: TypeCheckDiagnostic[]
+) {
+  let options = {};
+  for (const config of [...configs].reverse()) {
+    const { options: converted, errors } = ts.convertCompilerOptionsFromJson(
+      config.json.compilerOptions ?? {},
+      dirname(toAbsolute(config.path))
+    );
+    for (const error of errors) {
+      diagnostics.push({ ...toDiagnostic(error), path: config.path });
+    }
+    options = { ...options, ...converted };
+  }
+  return { ...options, noEmit: true };
+}
+
+/**
+ * Declaration files are not part of the module graph, but declare globals and
+ * ambient modules like `next-env.d.ts` does.
+ */
+function declarationFileNames(configs: TsConfig[]): string[] {
+  const config = configs.find(({ json }) => json.files || json.include);
+  if (!config) {
+    return [];
+  }
+  const dir = dirname(toAbsolute(config.path));
+  const files: string[] = (config.json.files ?? [])
+    .filter((file: string) => file.endsWith(".d.ts"))
+    .map((file: string) => toAbsolute(resolve(dir, file)));
+  const included: string[] = config.json.include
+    ? ts.sys.readDirectory(
+        dir,
+        [".d.ts"],
+        config.json.exclude ?? ["node_modules"],
+        config.json.include
+      )
+    : [];
+  return [...files, ...included];
+}
+
+function createService() {
+  return ts.createLanguageService(
+    {
+      getCompilationSettings: () => compilerOptions,
+      getScriptFileNames: () => rootFileNames,
+      getScriptVersion: (fileName: string) => {
+        const file = sourceFiles.get(fileName);
+        if (file) {
+          return file.version;
+        }
+        // Files outside of the module graph are read from disk.
+        return String(ts.sys.getModifiedTime?.(fileName)?.getTime() ?? 0);
+      },
+      getScriptSnapshot: (fileName: string) => {
+        const content =
+          sourceFiles.get(fileName)?.content ?? ts.sys.readFile(fileName);
+        return content === undefined
+          ? undefined
+          : ts.ScriptSnapshot.fromString(content);
+      },
+      getCurrentDirectory: () => contextDir,
+      getDefaultLibFileName: (options: any) =>
+        ts.getDefaultLibFilePath(options),
+      fileExists: (fileName: string) =>
+        sourceFiles.has(fileName) || ts.sys.fileExists(fileName),
+      readFile: (fileName: string) =>
+        sourceFiles.get(fileName)?.content ?? ts.sys.readFile(fileName),
+      readDirectory: ts.sys.readDirectory,
+      directoryExists: ts.sys.directoryExists,
+      getDirectories: ts.sys.getDirectories,
+    },
+    ts.createDocumentRegistry()
+  );
+}
+
+function toDiagnostic(diagnostic: any): TypeCheckDiagnostic {
+  let path = null;
+  let start = null;
+  let end = null;
+  if (diagnostic.file && diagnostic.start !== undefined) {
+    path = toRelative(diagnostic.file.fileName);
+    const startPosition = diagnostic.file.getLineAndCharacterOfPosition(
+      diagnostic.start
+    );
+    const endPosition = diagnostic.file.getLineAndCharacterOfPosition(
+      diagnostic.start + (diagnostic.length ?? 0)
+    );
+    start = { line: startPosition.line, column: startPosition.character };
+    end = { line: endPosition.line, column: endPosition.character };
+  }
+  return {
+    path,
+    start,
+    end,
+    message: ts.flattenDiagnosticMessageText(diagnostic.messageText, "\n"),
+    code: diagnostic.code,
+    category: ts.DiagnosticCategory[diagnostic.category].toLowerCase(),
+  };
+}
+
+export default async function typeCheck(
+  ipc: Ipc<unknown, unknown>,
+  configs: TsConfig[],
+  files: SourceFile[]
+): Promise<TypeCheckDiagnostic[]> {
+  ts ??= __turbopack_external_require__("typescript");
+
+  const diagnostics: TypeCheckDiagnostic[] = [];
+
+  const configsKey = JSON.stringify(configs);
+  if (!service || serviceConfigs !== configsKey) {
+    compilerOptions = parseCompilerOptions(configs, diagnostics);
+    service = createService();
+    serviceConfigs = configsKey;
+  }
+
+  const fileNames = files.map(({ path }) => toAbsolute(path));
+  // Only the files that changed since the last evaluation are read again.
+  files.forEach(({ version }, index) => {
+    const fileName = fileNames[index];
+    if (sourceFiles.get(fileName)?.version === version) {
+      return;
+    }
+    const content = ts.sys.readFile(fileName);
+    if (content === undefined) {
+      sourceFiles.delete(fileName);
+    } else {
+      sourceFiles.set(fileName, { version, content });
+    }
+  });
+  rootFileNames = [...fileNames, ...declarationFileNames(configs)];
+
+  for (const diagnostic of service.getCompilerOptionsDiagnostics()) {
+    diagnostics.push(toDiagnostic(diagnostic));
+  }
+  // Only the files of the module graph are reported.
+  for (const fileName of fileNames) {
+    for (const diagnostic of service.getSyntacticDiagnostics(fileName)) {
+      diagnostics.push(toDiagnostic(diagnostic));
+    }
+    for (const diagnostic of service.getSemanticDiagnostics(fileName)) {
+      diagnostics.push(toDiagnostic(diagnostic));
+    }
+  }
+  return diagnostics;
+}
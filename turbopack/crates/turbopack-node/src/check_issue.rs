@@ -0,0 +1,93 @@
+use anyhow::Result;
+use turbo_tasks::{RcStr, ResolvedVc, Vc};
+use turbo_tasks_fs::FileSystemPath;
+use turbopack_core::{
+    file_source::FileSource,
+    issue::{
+        Issue, IssueExt, IssueSeverity, IssueSource, IssueStage, OptionIssueSource,
+        OptionStyledString, StyledString,
+    },
+    source_pos::SourcePos,
+};
+
+/// A problem that a tool of the project, like the type checker or the linter,
+/// reported for a file.
+pub(crate) struct CheckProblem {
+    pub severity: IssueSeverity,
+    /// 0-indexed start and end of the problem in the file.
+    pub range: Option<(SourcePos, SourcePos)>,
+    pub title: RcStr,
+    pub description: Option<RcStr>,
+}
+
+impl CheckProblem {
+    /// Emits the problem as an issue of the current task.
+    pub async fn emit(self, stage: &str, file_path: ResolvedVc<FileSystemPath>) -> Result<()> {
+        let source = match self.range {
+            Some((start, end)) => Some(
+                IssueSource::from_line_col(Vc::upcast(FileSource::new(*file_path)), start, end)
+                    .to_resolved()
+                    .await?,
+            ),
+            None => None,
+        };
+        CheckIssue {
+            stage: stage.into(),
+            file_path,
+            source,
+            severity: self.severity.resolved_cell(),
+            title: self.title,
+            description: self.description,
+        }
+        .cell()
+        .emit();
+        Ok(())
+    }
+}
+
+#[turbo_tasks::value(shared)]
+struct CheckIssue {
+    stage: RcStr,
+    file_path: ResolvedVc<FileSystemPath>,
+    source: Option<ResolvedVc<IssueSource>>,
+    severity: ResolvedVc<IssueSeverity>,
+    title: RcStr,
+    description: Option<RcStr>,
+}
+
+#[turbo_tasks::value_impl]
+impl Issue for CheckIssue {
+    #[turbo_tasks::function]
+    fn severity(&self) -> Vc<IssueSeverity> {
+        *self.severity
+    }
+
+    #[turbo_tasks::function]
+    fn stage(&self) -> Vc<IssueStage> {
+        IssueStage::Other(self.stage.to_string()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn file_path(&self) -> Vc<FileSystemPath> {
+        *self.file_path
+    }
+
+    #[turbo_tasks::function]
+    fn title(&self) -> Vc<StyledString> {
+        StyledString::Text(self.title.clone()).cell()
+    }
+
+    #[turbo_tasks::function]
+    fn description(&self) -> Vc<OptionStyledString> {
+        Vc::cell(
+            self.description
+                .clone()
+                .map(|description| StyledString::Text(description).cell()),
+        )
+    }
+
+    #[turbo_tasks::function]
+    fn source(&self) -> Vc<OptionIssueSource> {
+        Vc::cell(self.source.map(|source| *source))
+    }
+}
@@ -0,0 +1,357 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+use turbo_tasks::{Completion, FxIndexSet, RcStr, TryJoinIterExt, Value, Vc};
+use turbo_tasks_bytes::stream::SingleValue;
+use turbo_tasks_fs::{json::parse_json_with_source_context, FileContent, FileSystemPath};
+use turbopack_core::{
+    asset::AssetContent,
+    context::{AssetContext, ProcessResult},
+    file_source::FileSource,
+    issue::IssueSeverity,
+    module::{Module, Modules},
+    reference::all_modules_and_affecting_sources,
+    reference_type::{InnerAssets, ReferenceType},
+    resolve::{find_context_file_or_package_key, FindContextFileResult},
+    source::Source,
+    source_pos::SourcePos,
+    virtual_source::VirtualSource,
+};
+
+use crate::{
+    check_issue::CheckProblem, embed_js::embed_file, evaluate::evaluate,
+    execution_context::ExecutionContext,
+};
+
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Default)]
+pub struct LintOptions {
+    /// Only the files in these directories, relative to the project, are
+    /// linted. All files are linted when empty.
+    pub dirs: Vec<RcStr>,
+    /// Whether ESLint warnings are reported as errors. Only set for builds.
+    pub warnings_as_errors: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LintMessage {
+    rule_id: Option<RcStr>,
+    severity: u8,
+    message: RcStr,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+}
+
+#[turbo_tasks::function]
+fn eslint_configs() -> Vc<Vec<RcStr>> {
+    Vc::cell(
+        [
+            "eslint.config.js",
+            "eslint.config.mjs",
+            "eslint.config.cjs",
+            ".eslintrc.js",
+            ".eslintrc.cjs",
+            ".eslintrc.yaml",
+            ".eslintrc.yml",
+            ".eslintrc.json",
+            ".eslintrc",
+        ]
+        .into_iter()
+        .map(RcStr::from)
+        .collect(),
+    )
+}
+
+/// Lints the JavaScript and TypeScript files of the module graph of `entries`
+/// with the `eslint` package of the project and emits the problems as issues.
+///
+/// Each file is linted in its own task, so only the files that changed are
+/// linted again. All files are linted again when the ESLint config of the
+/// project changes, but not when a config it extends changes.
+#[turbo_tasks::function]
+pub async fn lint(
+    evaluate_context: Vc<Box<dyn AssetContext>>,
+    execution_context: Vc<ExecutionContext>,
+    entries: Vc<Modules>,
+    options: Vc<LintOptions>,
+) -> Result<Vc<Completion>> {
+    let project_path = execution_context.await?.project_path;
+    let FindContextFileResult::Found(config, _) = *find_context_file_or_package_key(
+        *project_path,
+        eslint_configs(),
+        Value::new("eslintConfig".into()),
+    )
+    .await?
+    else {
+        return Ok(Completion::new());
+    };
+
+    let LintOptions {
+        dirs,
+        warnings_as_errors,
+    } = &*options.await?;
+    let project_path_ref = &*project_path.await?;
+    let dirs = dirs
+        .iter()
+        .map(|dir| project_path.join(dir.clone()))
+        .try_join()
+        .await?;
+
+    let mut modules = FxIndexSet::default();
+    for &entry in entries.await?.iter() {
+        modules.extend(
+            all_modules_and_affecting_sources(entry)
+                .await?
+                .iter()
+                .copied(),
+        );
+    }
+    let mut files = FxIndexSet::default();
+    for module in modules {
+        let path = module.ident().path().resolve().await?;
+        let path_ref = path.await?;
+        if !is_linted(&path_ref.path)
+            || !path_ref.is_inside_ref(project_path_ref)
+            || !(dirs.is_empty() || dirs.iter().any(|dir| path_ref.is_inside_ref(dir)))
+        {
+            continue;
+        }
+        files.insert(path);
+    }
+
+    files
+        .into_iter()
+        .map(|path| async move {
+            lint_file(
+                evaluate_context,
+                execution_context,
+                *config,
+                path,
+                *warnings_as_errors,
+            )
+            .await?;
+            Ok(())
+        })
+        .try_join()
+        .await?;
+
+    Ok(Completion::new())
+}
+
+/// JavaScript and TypeScript files outside of `node_modules`.
+fn is_linted(path: &str) -> bool {
+    !path.split('/').any(|segment| segment == "node_modules")
+        && [".js", ".jsx", ".mjs", ".cjs", ".ts", ".tsx", ".mts", ".cts"]
+            .iter()
+            .any(|extension| path.ends_with(extension))
+}
+
+#[turbo_tasks::function]
+async fn lint_executor(
+    evaluate_context: Vc<Box<dyn AssetContext>>,
+    config: Vc<FileSystemPath>,
+) -> Result<Vc<ProcessResult>> {
+    Ok(evaluate_context.process(
+        Vc::upcast(VirtualSource::new(
+            config.join("lint.ts".into()),
+            AssetContent::File(embed_file("eslint/lint.ts".into()).to_resolved().await?).cell(),
+        )),
+        Value::new(ReferenceType::Internal(InnerAssets::empty())),
+    ))
+}
+
+/// Lints a single file. The task reads the file and the ESLint config, so it
+/// only runs again when one of them changes.
+#[turbo_tasks::function]
+async fn lint_file(
+    evaluate_context: Vc<Box<dyn AssetContext>>,
+    execution_context: Vc<ExecutionContext>,
+    config: Vc<FileSystemPath>,
+    path: Vc<FileSystemPath>,
+    warnings_as_errors: bool,
+) -> Result<Vc<Completion>> {
+    let ExecutionContext {
+        project_path,
+        chunking_context,
+        env,
+        pool_options,
+    } = &*execution_context.await?;
+
+    let FileContent::Content(file) = &*path.read().await? else {
+        return Ok(Completion::new());
+    };
+    let Some(relative_path) = project_path.await?.get_relative_path_to(&*path.await?) else {
+        return Ok(Completion::new());
+    };
+    let config_version = *config.read().hash().await?;
+
+    let source: Vc<Box<dyn Source>> = Vc::upcast(FileSource::new(path));
+    let args = vec![
+        Vc::cell(json!(relative_path)),
+        Vc::cell(json!(file.content().to_str()?)),
+        Vc::cell(json!(config_version.to_string())),
+    ];
+    let result = evaluate(
+        lint_executor(evaluate_context, config).module(),
+        **project_path,
+        **env,
+        source.ident(),
+        evaluate_context,
+        **chunking_context,
+        None,
+        args,
+        Completion::immutable(),
+        **pool_options,
+        false,
+    )
+    .await?;
+    let SingleValue::Single(value) = result.try_into_single().await? else {
+        // An error happened, which has already been converted into an issue.
+        return Ok(Completion::new());
+    };
+    let messages: Vec<LintMessage> = parse_json_with_source_context(value.to_str()?)?;
+
+    let file_path = path.to_resolved().await?;
+    for message in messages {
+        check_problem(message, warnings_as_errors)
+            .emit("lint", file_path)
+            .await?;
+    }
+
+    Ok(Completion::new())
+}
+
+fn check_problem(message: LintMessage, warnings_as_errors: bool) -> CheckProblem {
+    let severity = if message.severity >= 2 || warnings_as_errors {
+        IssueSeverity::Error
+    } else {
+        IssueSeverity::Warning
+    };
+    // ESLint positions are 1-indexed, `SourcePos` is 0-indexed.
+    let range = match (message.line, message.column) {
+        (Some(line), Some(column)) => {
+            let start = SourcePos {
+                line: line.saturating_sub(1),
+                column: column.saturating_sub(1),
+            };
+            let end = match (message.end_line, message.end_column) {
+                (Some(line), Some(column)) => SourcePos {
+                    line: line.saturating_sub(1),
+                    column: column.saturating_sub(1),
+                },
+                _ => start,
+            };
+            Some((start, end))
+        }
+        _ => None,
+    };
+    // Parsing errors don't have a rule.
+    let title = match message.rule_id {
+        Some(rule_id) => format!("{}: {}", rule_id, message.message).into(),
+        None => message.message,
+    };
+    CheckProblem {
+        severity,
+        range,
+        title,
+        description: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use turbopack_core::{issue::IssueSeverity, source_pos::SourcePos};
+
+    use super::{check_problem, is_linted, LintMessage};
+
+    fn message(json: &str) -> LintMessage {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn lints_javascript_and_typescript_files_outside_of_node_modules() {
+        assert!(is_linted("pages/index.js"));
+        assert!(is_linted("app/page.tsx"));
+        assert!(is_linted("lib/config.cjs"));
+        assert!(!is_linted("styles/globals.css"));
+        assert!(!is_linted("node_modules/pkg/index.js"));
+        assert!(!is_linted("apps/web/node_modules/pkg/index.js"));
+        assert!(is_linted("lib/node_modules.js"));
+    }
+
+    #[test]
+    fn converts_positions_and_rules() {
+        let problem = check_problem(
+            message(
+                r#"{
+                    "ruleId": "no-unused-vars",
+                    "severity": 1,
+                    "message": "'a' is defined but never used.",
+                    "line": 3,
+                    "column": 7,
+                    "endLine": 3,
+                    "endColumn": 8
+                }"#,
+            ),
+            false,
+        );
+        assert_eq!(problem.severity, IssueSeverity::Warning);
+        assert_eq!(
+            problem.title.as_str(),
+            "no-unused-vars: 'a' is defined but never used."
+        );
+        assert_eq!(
+            problem.range,
+            Some((
+                SourcePos { line: 2, column: 6 },
+                SourcePos { line: 2, column: 7 }
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_parsing_errors_without_a_rule() {
+        let problem = check_problem(
+            message(
+                r#"{
+                    "ruleId": null,
+                    "severity": 2,
+                    "message": "Parsing error: Unexpected token",
+                    "line": 1,
+                    "column": 5,
+                    "endLine": null,
+                    "endColumn": null
+                }"#,
+            ),
+            false,
+        );
+        assert_eq!(problem.severity, IssueSeverity::Error);
+        assert_eq!(problem.title.as_str(), "Parsing error: Unexpected token");
+        assert_eq!(
+            problem.range,
+            Some((
+                SourcePos { line: 0, column: 4 },
+                SourcePos { line: 0, column: 4 }
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_warnings_as_errors() {
+        let warning = r#"{
+            "ruleId": "eqeqeq",
+            "severity": 1,
+            "message": "Expected '===' and instead saw '=='.",
+            "line": null,
+            "column": null,
+            "endLine": null,
+            "endColumn": null
+        }"#;
+        let problem = check_problem(message(warning), true);
+        assert_eq!(problem.severity, IssueSeverity::Error);
+        assert!(problem.range.is_none());
+    }
+}
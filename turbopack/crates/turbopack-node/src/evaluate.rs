@@ -12,8 +12,8 @@ use parking_lot::Mutex;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use turbo_tasks::{
-    duration_span, fxindexmap, mark_finished, prevent_gc, util::SharedError, Completion, RawVc,
-    ResolvedVc, TaskInput, TryJoinIterExt, Value, Vc,
+    cancellation_token, duration_span, fxindexmap, mark_finished, prevent_gc, util::SharedError,
+    Completion, RawVc, ResolvedVc, TaskCanceled, TaskInput, TryJoinIterExt, Value, Vc,
 };
 use turbo_tasks_bytes::{Bytes, Stream};
 use turbo_tasks_env::ProcessEnv;
@@ -36,7 +36,7 @@ use turbopack_core::{
 use crate::{
     embed_js::embed_file_path,
     emit, emit_package_json, internal_assets_for_source_mapping,
-    pool::{FormattingMode, NodeJsOperation, NodeJsPool},
+    pool::{FormattingMode, NodeJsOperation, NodeJsPool, NodeJsPoolOptions, OperationTimeoutError},
     source_map::StructuredError,
     AssetsForSourceMapping,
 };
@@ -56,9 +56,17 @@ enum EvalJavaScriptOutgoingMessage<'a> {
 #[derive(Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "camelCase")]
 enum EvalJavaScriptIncomingMessage {
-    Info { data: JsonValue },
-    Request { id: u64, data: JsonValue },
-    End { data: Option<String> },
+    Info {
+        data: JsonValue,
+    },
+    Request {
+        id: u64,
+        data: JsonValue,
+    },
+    End {
+        data: Option<String>,
+        rss: Option<u64>,
+    },
     Error(StructuredError),
 }
 
@@ -88,6 +96,7 @@ pub async fn get_evaluate_pool(
     chunking_context: Vc<Box<dyn ChunkingContext>>,
     runtime_entries: Option<Vc<EvaluatableAssets>>,
     additional_invalidation: Vc<Completion>,
+    pool_options: Vc<NodeJsPoolOptions>,
     debug: bool,
 ) -> Result<Vc<NodeJsPool>> {
     let runtime_asset = asset_context
@@ -180,6 +189,7 @@ pub async fn get_evaluate_pool(
         chunking_context.context_path().root().to_resolved().await?,
         available_parallelism().map_or(1, |v| v.get()),
         debug,
+        pool_options.await?.clone_value(),
     );
     additional_invalidation.await?;
     Ok(pool.cell())
@@ -196,7 +206,7 @@ impl futures_retry::ErrorHandler<anyhow::Error> for PoolErrorHandler {
     type OutError = anyhow::Error;
 
     fn handle(&mut self, attempt: usize, err: anyhow::Error) -> RetryPolicy<Self::OutError> {
-        if attempt >= MAX_ATTEMPTS {
+        if attempt >= MAX_ATTEMPTS || err.is::<TaskCanceled>() {
             RetryPolicy::ForwardError(err)
         } else if attempt >= MAX_FAST_ATTEMPTS {
             RetryPolicy::WaitRetry(Duration::from_secs(1))
@@ -295,6 +305,7 @@ pub fn evaluate(
     runtime_entries: Option<ResolvedVc<EvaluatableAssets>>,
     args: Vec<Vc<JsonValue>>,
     additional_invalidation: ResolvedVc<Completion>,
+    pool_options: ResolvedVc<NodeJsPoolOptions>,
     debug: bool,
 ) -> Vc<JavaScriptEvaluation> {
     custom_evaluate(BasicEvaluateContext {
@@ -307,6 +318,7 @@ pub fn evaluate(
         runtime_entries,
         args,
         additional_invalidation,
+        pool_options,
         debug,
     })
 }
@@ -321,6 +333,9 @@ pub async fn compute(
         return Ok(Default::default());
     };
 
+    // Canceled when nobody reads the stream any more, so the worker stops.
+    let cancellation = cancellation_token().child_token();
+    let operation_cancellation = cancellation.clone();
     let stream = generator! {
         let pool = evaluate_context.pool();
         let mut state = Default::default();
@@ -341,7 +356,7 @@ pub async fn compute(
 
         let (mut operation, _) = FutureRetry::new(
             || async {
-                let mut operation = pool.operation().await?;
+                let mut operation = pool.operation(operation_cancellation.clone()).await?;
                 operation
                     .send(EvalJavaScriptOutgoingMessage::Evaluate {
                         args: args.iter().map(|v| &**v).collect(),
@@ -389,10 +404,9 @@ pub async fn compute(
     let mut sender = (sender.get)();
     pin_mut!(stream);
     while let Some(value) = stream.next().await {
-        if sender.send(value).await.is_err() {
-            return Ok(Default::default());
-        }
-        if sender.flush().await.is_err() {
+        if sender.send(value).await.is_err() || sender.flush().await.is_err() {
+            // The operation might still be running, its worker can't be reused.
+            cancellation.cancel();
             return Ok(Default::default());
         }
     }
@@ -411,7 +425,24 @@ async fn pull_operation<T: EvaluateContext>(
     let guard = duration_span!("Node.js evaluation");
 
     let output = loop {
-        match operation.recv().await? {
+        let message = match operation.recv().await {
+            Ok(message) => message,
+            Err(error) => match error.downcast::<OperationTimeoutError>() {
+                Ok(timeout) => {
+                    // The worker has been killed, report the timeout like any other evaluation
+                    // error.
+                    evaluate_context
+                        .emit_error(
+                            StructuredError::from_message("TimeoutError", timeout.to_string()),
+                            pool,
+                        )
+                        .await?;
+                    break ControlFlow::Break(Ok(None));
+                }
+                Err(error) => return Err(error),
+            },
+        };
+        match message {
             EvalJavaScriptIncomingMessage::Error(error) => {
                 evaluate_context.emit_error(error, pool).await?;
                 // Do not reuse the process in case of error
@@ -419,7 +450,12 @@ async fn pull_operation<T: EvaluateContext>(
                 // Issue emitted, we want to break but don't want to return an error
                 break ControlFlow::Break(Ok(None));
             }
-            EvalJavaScriptIncomingMessage::End { data } => break ControlFlow::Break(Ok(data)),
+            EvalJavaScriptIncomingMessage::End { data, rss } => {
+                if let Some(rss) = rss {
+                    operation.report_rss(rss);
+                }
+                break ControlFlow::Break(Ok(data));
+            }
             EvalJavaScriptIncomingMessage::Info { data } => {
                 evaluate_context
                     .info(state, serde_json::from_value(data)?, pool)
@@ -476,6 +512,7 @@ struct BasicEvaluateContext {
     runtime_entries: Option<ResolvedVc<EvaluatableAssets>>,
     args: Vec<Vc<JsonValue>>,
     additional_invalidation: ResolvedVc<Completion>,
+    pool_options: ResolvedVc<NodeJsPoolOptions>,
     debug: bool,
 }
 
@@ -499,6 +536,7 @@ impl EvaluateContext for BasicEvaluateContext {
             *self.chunking_context,
             self.runtime_entries.map(|r| *r),
             *self.additional_invalidation,
+            *self.pool_options,
             self.debug,
         )
     }
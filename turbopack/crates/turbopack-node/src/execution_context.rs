@@ -3,11 +3,14 @@ use turbo_tasks_env::ProcessEnv;
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::chunk::ChunkingContext;
 
+use crate::pool::NodeJsPoolOptions;
+
 #[turbo_tasks::value]
 pub struct ExecutionContext {
     pub project_path: ResolvedVc<FileSystemPath>,
     pub chunking_context: ResolvedVc<Box<dyn ChunkingContext>>,
     pub env: ResolvedVc<Box<dyn ProcessEnv>>,
+    pub pool_options: ResolvedVc<NodeJsPoolOptions>,
 }
 
 #[turbo_tasks::value_impl]
@@ -22,6 +25,17 @@ impl ExecutionContext {
             project_path,
             chunking_context,
             env,
+            pool_options: NodeJsPoolOptions::default().resolved_cell(),
+        }
+        .cell()
+    }
+
+    /// Returns a copy of this context whose Node.js pools use `pool_options`.
+    #[turbo_tasks::function]
+    pub fn with_pool_options(&self, pool_options: ResolvedVc<NodeJsPoolOptions>) -> Vc<Self> {
+        ExecutionContext {
+            pool_options,
+            ..*self
         }
         .cell()
     }
@@ -40,4 +54,9 @@ impl ExecutionContext {
     pub fn env(&self) -> Vc<Box<dyn ProcessEnv>> {
         *self.env
     }
+
+    #[turbo_tasks::function]
+    pub fn pool_options(&self) -> Vc<NodeJsPoolOptions> {
+        *self.pool_options
+    }
 }
@@ -8,6 +8,7 @@ use std::{collections::HashMap, iter::once, thread::available_parallelism};
 
 use anyhow::{bail, Result};
 pub use node_entry::{NodeEntry, NodeRenderingEntries, NodeRenderingEntry};
+pub use pool::{NodeJsPoolConfig, NodeJsPoolOptions};
 use turbo_tasks::{
     graph::{AdjacencyMap, GraphTraversal},
     Completion, Completions, FxIndexSet, RcStr, ResolvedVc, TryJoinIterExt, ValueToString, Vc,
@@ -25,8 +26,10 @@ use turbopack_core::{
 
 use self::{pool::NodeJsPool, source_map::StructuredError};
 
+mod check_issue;
 pub mod debug;
 pub mod embed_js;
+pub mod eslint;
 pub mod evaluate;
 pub mod execution_context;
 mod node_entry;
@@ -35,6 +38,7 @@ pub mod render;
 pub mod route_matcher;
 pub mod source_map;
 pub mod transforms;
+pub mod typescript;
 
 #[turbo_tasks::function]
 async fn emit(
@@ -251,6 +255,7 @@ pub async fn get_renderer_pool(
         project_dir,
         available_parallelism().map_or(1, |v| v.get()),
         debug,
+        NodeJsPoolOptions::default(),
     )
     .cell())
 }
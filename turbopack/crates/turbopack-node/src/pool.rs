@@ -7,7 +7,7 @@ use std::{
     mem::take,
     path::{Path, PathBuf},
     process::{ExitStatus, Stdio},
-    sync::Arc,
+    sync::{Arc, Weak},
     time::{Duration, Instant},
 };
 
@@ -15,7 +15,7 @@ use anyhow::{bail, Context, Result};
 use futures::join;
 use owo_colors::{OwoColorize, Style};
 use parking_lot::Mutex;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::{
     io::{
         stderr, stdout, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
@@ -27,7 +27,10 @@ use tokio::{
     sync::{OwnedSemaphorePermit, Semaphore},
     time::{sleep, timeout},
 };
-use turbo_tasks::{duration_span, FxIndexSet, RcStr, ResolvedVc, Vc};
+use tokio_util::sync::CancellationToken;
+use turbo_tasks::{
+    duration_span, trace::TraceRawVcs, FxIndexSet, RcStr, ResolvedVc, TaskCanceled, Vc,
+};
 use turbo_tasks_fs::{json::parse_json_with_source_context, FileSystemPath};
 use turbopack_ecmascript::magic_identifier::unmangle_identifiers;
 
@@ -75,9 +78,21 @@ struct NodeJsPoolProcess {
     stdout_handler: OutputStreamHandler<ChildStdout, Stdout>,
     stderr_handler: OutputStreamHandler<ChildStderr, Stderr>,
     debug: bool,
+    /// Number of operations this process has completed
+    operations: u32,
+    /// The resident set size reported by the process after its last operation
+    rss: Option<u64>,
+    /// When the process was last returned to the pool
+    idle_since: Instant,
 }
 
 impl NodeJsPoolProcess {
+    /// Returns why this process should be replaced by a fresh one instead of
+    /// being returned to the pool, if it should.
+    fn recycle_reason(&self, options: &NodeJsPoolOptions) -> Option<&'static str> {
+        recycle_reason(self.operations, self.rss, options)
+    }
+
     pub async fn apply_source_mapping<'a>(
         &self,
         text: &'a str,
@@ -316,17 +331,21 @@ impl NodeJsPoolProcess {
         shared_stdout: SharedOutputSet,
         shared_stderr: SharedOutputSet,
         debug: bool,
+        options: &NodeJsPoolOptions,
     ) -> Result<Self> {
         let guard = Box::new(duration_span!("Node.js process startup"));
         let listener = TcpListener::bind("127.0.0.1:0")
             .await
             .context("binding to a port")?;
         let port = listener.local_addr().context("getting port")?.port();
-        let mut cmd = Command::new("node");
+        let mut cmd = Command::new(options.node_binary.as_deref().unwrap_or("node"));
         cmd.current_dir(cwd);
         if debug {
             cmd.arg("--inspect-brk");
         }
+        if let Some(max_old_space_size) = options.max_old_space_size {
+            cmd.arg(format!("--max-old-space-size={max_old_space_size}"));
+        }
         cmd.arg(entrypoint);
         cmd.arg(port.to_string());
         cmd.env_clear();
@@ -431,6 +450,9 @@ impl NodeJsPoolProcess {
             stdout_handler,
             stderr_handler,
             debug,
+            operations: 0,
+            rss: None,
+            idle_since: Instant::now(),
         };
 
         drop(guard);
@@ -524,6 +546,8 @@ struct NodeJsPoolStats {
     pub workers: u32,
     pub booting_workers: u32,
     pub queued_tasks: u32,
+    pub recycled_workers: u32,
+    pub idle_killed_workers: u32,
 }
 
 impl NodeJsPoolStats {
@@ -545,10 +569,24 @@ impl NodeJsPoolStats {
         self.workers -= 1;
     }
 
+    fn recycle_worker(&mut self) {
+        self.remove_worker();
+        self.recycled_workers += 1;
+    }
+
+    fn kill_idle_worker(&mut self) {
+        self.remove_worker();
+        self.idle_killed_workers += 1;
+    }
+
     fn add_queued_task(&mut self) {
         self.queued_tasks += 1;
     }
 
+    fn remove_queued_task(&mut self) {
+        self.queued_tasks -= 1;
+    }
+
     fn add_cold_process_time(&mut self, time: Duration) {
         self.total_cold_process_time += time;
         self.cold_process_count += 1;
@@ -654,6 +692,8 @@ impl Debug for NodeJsPoolStats {
             .field("bootup_count", &self.bootup_count)
             .field("cold_process_count", &self.cold_process_count)
             .field("warm_process_count", &self.warm_process_count)
+            .field("recycled_workers", &self.recycled_workers)
+            .field("idle_killed_workers", &self.idle_killed_workers)
             .finish()
     }
 }
@@ -674,6 +714,79 @@ enum AcquiredPermits {
     },
 }
 
+/// Controls how the workers of a [NodeJsPool] are spawned and when they are
+/// replaced. Workers live for the lifetime of the pool by default.
+#[turbo_tasks::value(shared)]
+#[derive(Clone, Debug, Default)]
+pub struct NodeJsPoolOptions {
+    /// The Node.js binary to spawn workers with. Defaults to `node` from the
+    /// `PATH`.
+    pub node_binary: Option<RcStr>,
+    /// Passed to workers as `--max-old-space-size`, in megabytes.
+    pub max_old_space_size: Option<u32>,
+    /// The maximum number of workers of a pool. Defaults to the available
+    /// parallelism.
+    pub max_workers: Option<usize>,
+    /// Replace a worker after it has completed this many operations.
+    pub max_operations_per_worker: Option<u32>,
+    /// Replace a worker when its resident set size exceeds this many bytes
+    /// after an operation. Only workers that report their memory usage (e.g.
+    /// the ones running [crate::evaluate::evaluate]) are affected.
+    pub max_worker_rss: Option<u64>,
+    /// Kill workers that have been idle for longer than this.
+    pub idle_timeout: Option<Duration>,
+    /// Kill the worker and fail the operation when a single operation takes
+    /// longer than this. Disabled in debug mode.
+    pub operation_timeout: Option<Duration>,
+}
+
+/// Returned by [NodeJsOperation::recv] when the operation took longer than
+/// [NodeJsPoolOptions::operation_timeout]. The worker is killed in that case.
+#[derive(Debug)]
+pub struct OperationTimeoutError {
+    pub timeout: Duration,
+}
+
+impl Display for OperationTimeoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Node.js operation timed out after {:?} and the worker was killed",
+            self.timeout
+        )
+    }
+}
+
+impl std::error::Error for OperationTimeoutError {}
+
+/// [NodeJsPoolOptions] as they are written in a configuration file, with
+/// sizes in megabytes and durations in milliseconds.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TraceRawVcs)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NodeJsPoolConfig {
+    pub node_binary: Option<RcStr>,
+    pub max_old_space_size: Option<u32>,
+    pub max_workers: Option<usize>,
+    pub max_operations_per_worker: Option<u32>,
+    pub max_worker_memory: Option<u64>,
+    pub idle_timeout: Option<u64>,
+    pub operation_timeout: Option<u64>,
+}
+
+impl NodeJsPoolConfig {
+    pub fn to_options(&self) -> NodeJsPoolOptions {
+        NodeJsPoolOptions {
+            node_binary: self.node_binary.clone(),
+            max_old_space_size: self.max_old_space_size,
+            max_workers: self.max_workers,
+            max_operations_per_worker: self.max_operations_per_worker,
+            max_worker_rss: self.max_worker_memory.map(|mb| mb * 1024 * 1024),
+            idle_timeout: self.idle_timeout.map(Duration::from_millis),
+            operation_timeout: self.operation_timeout.map(Duration::from_millis),
+        }
+    }
+}
+
 /// A pool of Node.js workers operating on [entrypoint] with specific [cwd] and
 /// [env].
 ///
@@ -708,6 +821,7 @@ pub struct NodeJsPool {
     #[turbo_tasks(trace_ignore, debug_ignore)]
     shared_stderr: SharedOutputSet,
     debug: bool,
+    options: NodeJsPoolOptions,
     #[turbo_tasks(trace_ignore, debug_ignore)]
     stats: Arc<Mutex<NodeJsPoolStats>>,
 }
@@ -715,6 +829,7 @@ pub struct NodeJsPool {
 impl NodeJsPool {
     /// * debug: Whether to automatically enable Node's `--inspect-brk` when spawning it. Note:
     ///   automatically overrides concurrency to 1.
+    /// * options: Controls how workers are spawned and recycled.
     pub(super) fn new(
         cwd: PathBuf,
         entrypoint: PathBuf,
@@ -724,8 +839,9 @@ impl NodeJsPool {
         project_dir: ResolvedVc<FileSystemPath>,
         concurrency: usize,
         debug: bool,
+        options: NodeJsPoolOptions,
     ) -> Self {
-        Self {
+        let pool = Self {
             cwd,
             entrypoint,
             env,
@@ -733,22 +849,49 @@ impl NodeJsPool {
             assets_root,
             project_dir,
             processes: Arc::new(Mutex::new(Vec::new())),
-            concurrency_semaphore: Arc::new(Semaphore::new(if debug { 1 } else { concurrency })),
+            concurrency_semaphore: Arc::new(Semaphore::new(if debug {
+                1
+            } else {
+                options.max_workers.map_or(concurrency, |max| max.max(1))
+            })),
             bootup_semaphore: Arc::new(Semaphore::new(1)),
             idle_process_semaphore: Arc::new(Semaphore::new(0)),
             shared_stdout: Arc::new(Mutex::new(FxIndexSet::default())),
             shared_stderr: Arc::new(Mutex::new(FxIndexSet::default())),
             debug,
+            options,
             stats: Default::default(),
+        };
+        if let Some(idle_timeout) = pool.options.idle_timeout.filter(|_| !debug) {
+            spawn_idle_process_reaper(
+                Arc::downgrade(&pool.processes),
+                Arc::downgrade(&pool.idle_process_semaphore),
+                Arc::downgrade(&pool.stats),
+                idle_timeout,
+            );
         }
+        pool
     }
 
-    async fn acquire_process(&self) -> Result<(NodeJsPoolProcess, AcquiredPermits)> {
-        {
-            self.stats.lock().add_queued_task();
-        }
+    async fn acquire_process(
+        &self,
+        cancellation: &CancellationToken,
+    ) -> Result<(NodeJsPoolProcess, AcquiredPermits)> {
+        let queued_tasks = {
+            let mut stats = self.stats.lock();
+            stats.add_queued_task();
+            stats.queued_tasks
+        };
 
-        let concurrency_permit = self.concurrency_semaphore.clone().acquire_owned().await?;
+        let queue_guard = duration_span!("Node.js pool queue", queued_tasks = queued_tasks);
+        let concurrency_permit = select! {
+            permit = self.concurrency_semaphore.clone().acquire_owned() => permit?,
+            _ = cancellation.cancelled() => {
+                self.stats.lock().remove_queued_task();
+                return Err(TaskCanceled.into());
+            }
+        };
+        drop(queue_guard);
 
         let bootup = async {
             let permit = self.bootup_semaphore.clone().acquire_owned().await;
@@ -783,6 +926,10 @@ impl NodeJsPool {
                 self.bootup_semaphore.add_permits(1);
                 Ok((process, AcquiredPermits::Fresh { concurrency_permit, bootup_permit }))
             }
+            _ = cancellation.cancelled() => {
+                self.stats.lock().remove_queued_task();
+                Err(TaskCanceled.into())
+            }
         }
     }
 
@@ -798,15 +945,19 @@ impl NodeJsPool {
             self.shared_stdout.clone(),
             self.shared_stderr.clone(),
             self.debug,
+            &self.options,
         )
         .await
         .context("creating new process")?;
         Ok((process, start.elapsed()))
     }
 
-    pub async fn operation(&self) -> Result<NodeJsOperation> {
+    /// Acquires a worker for an operation. Fails with [TaskCanceled] when
+    /// `cancellation` is canceled while waiting for a worker. Canceling it
+    /// during the operation kills the worker.
+    pub async fn operation(&self, cancellation: CancellationToken) -> Result<NodeJsOperation> {
         // Acquire a running process (handles concurrency limits, boots up the process)
-        let (process, permits) = self.acquire_process().await?;
+        let (process, permits) = self.acquire_process(&cancellation).await?;
 
         Ok(NodeJsOperation {
             process: Some(process),
@@ -815,11 +966,97 @@ impl NodeJsPool {
             idle_process_semaphore: self.idle_process_semaphore.clone(),
             start: Instant::now(),
             stats: self.stats.clone(),
+            options: self.options.clone(),
             allow_process_reuse: true,
+            cancellation,
         })
     }
 }
 
+fn recycle_reason(
+    operations: u32,
+    rss: Option<u64>,
+    options: &NodeJsPoolOptions,
+) -> Option<&'static str> {
+    if options
+        .max_operations_per_worker
+        .is_some_and(|max| operations >= max)
+    {
+        return Some("operation limit reached");
+    }
+    if let (Some(max), Some(rss)) = (options.max_worker_rss, rss) {
+        if rss > max {
+            return Some("memory limit exceeded");
+        }
+    }
+    None
+}
+
+/// Removes the processes that have been idle for at least `idle_timeout` from
+/// `processes`, consuming one idle process permit for each of them.
+fn take_expired_processes<T>(
+    processes: &mut Vec<T>,
+    idle_since: impl Fn(&T) -> Instant,
+    idle_timeout: Duration,
+    idle_process_semaphore: &Semaphore,
+) -> Vec<T> {
+    let mut expired = Vec::new();
+    // Idle processes are pushed to and taken from the end, so the ones that have been idle the
+    // longest are at the front.
+    while processes
+        .first()
+        .is_some_and(|process| idle_since(process).elapsed() >= idle_timeout)
+    {
+        // A permit that can't be acquired belongs to an operation that is about to take one of
+        // the idle processes.
+        let Ok(idle_process_permit) = idle_process_semaphore.try_acquire() else {
+            break;
+        };
+        idle_process_permit.forget();
+        expired.push(processes.remove(0));
+    }
+    expired
+}
+
+/// Kills processes that have been idle for longer than `idle_timeout`. Stops
+/// once the pool has been dropped.
+fn spawn_idle_process_reaper(
+    processes: Weak<Mutex<Vec<NodeJsPoolProcess>>>,
+    idle_process_semaphore: Weak<Semaphore>,
+    stats: Weak<Mutex<NodeJsPoolStats>>,
+    idle_timeout: Duration,
+) {
+    tokio::spawn(async move {
+        loop {
+            sleep(idle_timeout / 2).await;
+            let (Some(processes), Some(idle_process_semaphore), Some(stats)) = (
+                processes.upgrade(),
+                idle_process_semaphore.upgrade(),
+                stats.upgrade(),
+            ) else {
+                return;
+            };
+
+            let expired = take_expired_processes(
+                &mut processes.lock(),
+                |process| process.idle_since,
+                idle_timeout,
+                &idle_process_semaphore,
+            );
+
+            if !expired.is_empty() {
+                let mut stats = stats.lock();
+                for _ in &expired {
+                    stats.kill_idle_worker();
+                }
+                tracing::trace!(stats = ?*stats, "killed idle Node.js workers");
+            }
+            // Dropping the processes kills them
+            drop(expired);
+        }
+    });
+}
+
 pub struct NodeJsOperation {
     process: Option<NodeJsPoolProcess>,
     // This is used for drop
@@ -829,7 +1066,11 @@ pub struct NodeJsOperation {
     idle_process_semaphore: Arc<Semaphore>,
     start: Instant,
     stats: Arc<Mutex<NodeJsPoolStats>>,
+    options: NodeJsPoolOptions,
     allow_process_reuse: bool,
+    /// When canceled, the operation stops and the worker is killed, as it
+    /// might still be busy with the operation.
+    cancellation: CancellationToken,
 }
 
 impl NodeJsOperation {
@@ -846,7 +1087,10 @@ impl NodeJsOperation {
             bail!("Node.js process is no longer usable");
         }
 
-        let result = f(process).await;
+        let result = select! {
+            result = f(process) => result,
+            _ = self.cancellation.cancelled() => Err(TaskCanceled.into()),
+        };
         if result.is_err() && self.allow_process_reuse {
             self.stats.lock().remove_worker();
             self.allow_process_reuse = false;
@@ -854,13 +1098,28 @@ impl NodeJsOperation {
         result
     }
 
+    /// Receives the next message from the process. Fails with an
+    /// [OperationTimeoutError] when the operation exceeded
+    /// [NodeJsPoolOptions::operation_timeout].
     pub async fn recv<M>(&mut self) -> Result<M>
     where
         M: DeserializeOwned,
     {
+        let start = self.start;
+        let operation_timeout = self.options.operation_timeout;
         let message = self
             .with_process(|process| async move {
-                process.recv().await.context("failed to receive message")
+                let Some(operation_timeout) = operation_timeout.filter(|_| !process.debug) else {
+                    return process.recv().await.context("failed to receive message");
+                };
+                let remaining = operation_timeout.saturating_sub(start.elapsed());
+                match timeout(remaining, process.recv()).await {
+                    Ok(message) => message.context("failed to receive message"),
+                    Err(_) => Err(OperationTimeoutError {
+                        timeout: operation_timeout,
+                    }
+                    .into()),
+                }
             })
             .await?;
         let message = std::str::from_utf8(&message).context("message is not valid UTF-8")?;
@@ -907,6 +1166,15 @@ impl NodeJsOperation {
         Ok(status)
     }
 
+    /// Records the resident set size reported by the process, which is
+    /// compared against [NodeJsPoolOptions::max_worker_rss] when the operation
+    /// ends.
+    pub fn report_rss(&mut self, rss: u64) {
+        if let Some(process) = self.process.as_mut() {
+            process.rss = Some(rss);
+        }
+    }
+
     pub fn disallow_reuse(&mut self) {
         if self.allow_process_reuse {
             self.stats.lock().remove_worker();
@@ -929,19 +1197,152 @@ impl NodeJsOperation {
 
 impl Drop for NodeJsOperation {
     fn drop(&mut self) {
-        if let Some(process) = self.process.take() {
+        if self.cancellation.is_cancelled() {
+            self.disallow_reuse();
+        }
+        if let Some(mut process) = self.process.take() {
             let elapsed = self.start.elapsed();
+            process.operations += 1;
+            let recycle_reason = self
+                .allow_process_reuse
+                .then(|| process.recycle_reason(&self.options))
+                .flatten();
             {
                 let stats = &mut self.stats.lock();
                 match self.permits {
                     AcquiredPermits::Idle { .. } => stats.add_warm_process_time(elapsed),
                     AcquiredPermits::Fresh { .. } => stats.add_cold_process_time(elapsed),
                 }
+                if let Some(reason) = recycle_reason {
+                    stats.recycle_worker();
+                    tracing::debug!(reason, "recycling Node.js worker");
+                }
+                tracing::trace!(stats = ?**stats, "Node.js operation finished");
             }
-            if self.allow_process_reuse {
+            if self.allow_process_reuse && recycle_reason.is_none() {
+                process.idle_since = Instant::now();
                 self.processes.lock().push(process);
                 self.idle_process_semaphore.add_permits(1);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use tokio::sync::Semaphore;
+
+    use super::{recycle_reason, take_expired_processes, NodeJsPoolConfig, NodeJsPoolOptions};
+
+    #[test]
+    fn idle_processes_are_expired_oldest_first() {
+        let now = Instant::now();
+        let mut processes = vec![
+            ("oldest", now - Duration::from_secs(30)),
+            ("old", now - Duration::from_secs(20)),
+            ("recent", now),
+        ];
+        let semaphore = Semaphore::new(3);
+
+        let expired = take_expired_processes(
+            &mut processes,
+            |(_, idle_since)| *idle_since,
+            Duration::from_secs(10),
+            &semaphore,
+        );
+
+        let expired: Vec<_> = expired.into_iter().map(|(name, _)| name).collect();
+        assert_eq!(expired, vec!["oldest", "old"]);
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].0, "recent");
+        // The permits of the killed processes are gone for good.
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[test]
+    fn idle_processes_about_to_be_taken_are_kept() {
+        let now = Instant::now();
+        let mut processes = vec![
+            ("first", now - Duration::from_secs(30)),
+            ("second", now - Duration::from_secs(20)),
+        ];
+        // An operation already holds the permit for one of the idle processes.
+        let semaphore = Semaphore::new(1);
+
+        let expired = take_expired_processes(
+            &mut processes,
+            |(_, idle_since)| *idle_since,
+            Duration::from_secs(10),
+            &semaphore,
+        );
+
+        assert_eq!(expired.len(), 1);
+        assert_eq!(processes.len(), 1);
+        assert_eq!(semaphore.available_permits(), 0);
+    }
+
+    #[test]
+    fn no_idle_processes_expire_before_the_timeout() {
+        let mut processes = vec![("fresh", Instant::now())];
+        let semaphore = Semaphore::new(1);
+
+        let expired = take_expired_processes(
+            &mut processes,
+            |(_, idle_since)| *idle_since,
+            Duration::from_secs(10),
+            &semaphore,
+        );
+
+        assert!(expired.is_empty());
+        assert_eq!(processes.len(), 1);
+        assert_eq!(semaphore.available_permits(), 1);
+    }
+
+    #[test]
+    fn processes_are_recycled_when_a_limit_is_reached() {
+        let options = NodeJsPoolOptions {
+            max_operations_per_worker: Some(10),
+            max_worker_rss: Some(100),
+            ..Default::default()
+        };
+
+        assert_eq!(recycle_reason(9, Some(100), &options), None);
+        assert_eq!(recycle_reason(9, None, &options), None);
+        assert_eq!(
+            recycle_reason(10, Some(50), &options),
+            Some("operation limit reached")
+        );
+        assert_eq!(
+            recycle_reason(1, Some(101), &options),
+            Some("memory limit exceeded")
+        );
+        assert_eq!(
+            recycle_reason(u32::MAX, Some(u64::MAX), &NodeJsPoolOptions::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn config_units_are_converted() {
+        let config: NodeJsPoolConfig = serde_json::from_str(
+            r#"{
+                "maxWorkers": 2,
+                "maxWorkerMemory": 512,
+                "idleTimeout": 1500,
+                "operationTimeout": 60000
+            }"#,
+        )
+        .unwrap();
+        let options = config.to_options();
+
+        assert_eq!(options.max_workers, Some(2));
+        assert_eq!(options.max_worker_rss, Some(512 * 1024 * 1024));
+        assert_eq!(options.idle_timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(options.operation_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(options.max_operations_per_worker, None);
+
+        assert!(serde_json::from_str::<NodeJsPoolConfig>(r#"{"maxWorker": 2}"#).is_err());
+    }
+}
@@ -7,8 +7,8 @@ use futures::{
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    duration_span, mark_finished, prevent_gc, util::SharedError, RawVc, RcStr, ResolvedVc,
-    TaskInput, ValueToString, Vc,
+    cancellation_token, duration_span, mark_finished, prevent_gc, util::SharedError, RawVc, RcStr,
+    ResolvedVc, TaskInput, ValueToString, Vc,
 };
 use turbo_tasks_bytes::{Bytes, Stream};
 use turbo_tasks_env::ProcessEnv;
@@ -232,6 +232,9 @@ async fn render_stream_internal(
         return Ok(Default::default());
     };
 
+    // Canceled when nobody reads the stream any more, so the worker stops.
+    let cancellation = cancellation_token().child_token();
+    let operation_cancellation = cancellation.clone();
     let stream = generator! {
         let intermediate_asset = get_intermediate_asset(
             *chunking_context,
@@ -252,7 +255,7 @@ async fn render_stream_internal(
         // node.js code.
         let pool = pool.strongly_consistent().await?;
         let data = data.await?;
-        let mut operation = pool.operation().await?;
+        let mut operation = pool.operation(operation_cancellation.clone()).await?;
 
         // First, send the render data.
         operation
@@ -330,10 +333,9 @@ async fn render_stream_internal(
     let mut sender = (sender.get)();
     pin_mut!(stream);
     while let Some(value) = stream.next().await {
-        if sender.send(value).await.is_err() {
-            return Ok(Default::default());
-        }
-        if sender.flush().await.is_err() {
+        if sender.send(value).await.is_err() || sender.flush().await.is_err() {
+            // The operation might still be running, its worker can't be reused.
+            cancellation.cancel();
             return Ok(Default::default());
         }
     }
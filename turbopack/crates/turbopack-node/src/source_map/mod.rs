@@ -271,6 +271,17 @@ pub struct StructuredError {
 }
 
 impl StructuredError {
+    /// Creates an error without a stack trace, for failures that are detected
+    /// outside of the Node.js process.
+    pub(crate) fn from_message(name: &str, message: String) -> Self {
+        StructuredError {
+            name: name.to_string(),
+            message,
+            stack: Vec::new(),
+            cause: None,
+        }
+    }
+
     pub async fn print(
         &self,
         assets_for_source_mapping: Vc<AssetsForSourceMapping>,
@@ -439,6 +439,7 @@ impl PostCssTransformedAsset {
             project_path,
             chunking_context,
             env,
+            pool_options,
         } = &*self.execution_context.await?;
 
         // For this postcss transform, there is no gaurantee that looking up for the
@@ -509,6 +510,7 @@ impl PostCssTransformedAsset {
             resolve_options_context: None,
             args: vec![Vc::cell(content.into()), Vc::cell(css_path.into())],
             additional_invalidation: config_changed,
+            pool_options: *pool_options,
         })
         .await?;
 
@@ -50,7 +50,7 @@ use crate::{
         JavaScriptEvaluation, JavaScriptStreamSender,
     },
     execution_context::ExecutionContext,
-    pool::{FormattingMode, NodeJsPool},
+    pool::{FormattingMode, NodeJsPool, NodeJsPoolOptions},
     source_map::{StackFrame, StructuredError},
     AssetsForSourceMapping,
 };
@@ -196,6 +196,7 @@ impl WebpackLoadersProcessedAsset {
             project_path,
             chunking_context,
             env,
+            pool_options,
         } = *transform.execution_context.await?;
         let source_content = this.source.content();
         let AssetContent::File(file) = *source_content.await? else {
@@ -245,6 +246,7 @@ impl WebpackLoadersProcessedAsset {
                 Vc::cell(json!(*loaders)),
             ],
             additional_invalidation: Completion::immutable().to_resolved().await?,
+            pool_options,
         })
         .await?;
 
@@ -395,6 +397,7 @@ pub struct WebpackLoaderContext {
     pub resolve_options_context: Option<ResolvedVc<ResolveOptionsContext>>,
     pub args: Vec<Vc<JsonValue>>,
     pub additional_invalidation: ResolvedVc<Completion>,
+    pub pool_options: ResolvedVc<NodeJsPoolOptions>,
 }
 
 #[async_trait]
@@ -417,6 +420,7 @@ impl EvaluateContext for WebpackLoaderContext {
             *self.chunking_context,
             None,
             *self.additional_invalidation,
+            *self.pool_options,
             should_debug("webpack_loader"),
         )
     }
@@ -0,0 +1,352 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use turbo_tasks::{
+    Completion, FxIndexMap, FxIndexSet, RcStr, ResolvedVc, TryFlatJoinIterExt, Value, Vc,
+};
+use turbo_tasks_bytes::stream::SingleValue;
+use turbo_tasks_fs::{
+    json::parse_json_with_source_context, FileContent, FileJsonContent, FileSystemPath,
+};
+use turbopack_core::{
+    asset::AssetContent,
+    context::AssetContext,
+    file_source::FileSource,
+    issue::IssueSeverity,
+    module::{Module, Modules},
+    reference::all_modules_and_affecting_sources,
+    reference_type::{InnerAssets, ReferenceType},
+    resolve::node::node_cjs_resolve_options,
+    source::Source,
+    source_pos::SourcePos,
+    virtual_source::VirtualSource,
+};
+use turbopack_resolve::typescript::read_tsconfigs;
+
+use crate::{
+    check_issue::CheckProblem, embed_js::embed_file, evaluate::evaluate,
+    execution_context::ExecutionContext,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeCheckDiagnostic {
+    path: Option<RcStr>,
+    start: Option<TypeCheckPosition>,
+    end: Option<TypeCheckPosition>,
+    message: RcStr,
+    code: u32,
+    category: TypeCheckCategory,
+}
+
+/// 0-indexed. Columns are counted in UTF-16 code units.
+#[derive(Deserialize)]
+struct TypeCheckPosition {
+    line: usize,
+    column: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum TypeCheckCategory {
+    Error,
+    Warning,
+    Suggestion,
+    Message,
+}
+
+/// Type checks the TypeScript files of the module graph of `entries` with the
+/// `typescript` package of the project and emits the diagnostics as issues.
+///
+/// The files are read through the project filesystem, so the type check runs
+/// again when one of them changes. Only the paths and content hashes of the
+/// files are sent to the worker, which keeps its language service between runs
+/// and only reads the files with a new hash again. Files that
+/// are only imported for their types are not part of the module graph. They
+/// are read by `typescript` itself and not reported.
+#[turbo_tasks::function]
+pub async fn type_check(
+    evaluate_context: Vc<Box<dyn AssetContext>>,
+    execution_context: Vc<ExecutionContext>,
+    tsconfig: Vc<FileSystemPath>,
+    entries: Vc<Modules>,
+) -> Result<Vc<Completion>> {
+    let ExecutionContext {
+        project_path,
+        chunking_context,
+        env,
+        pool_options,
+    } = &*execution_context.await?;
+    let project_path_ref = &*project_path.await?;
+
+    let tsconfig_source = ResolvedVc::upcast(FileSource::new(tsconfig).to_resolved().await?);
+    let configs = read_tsconfigs(
+        tsconfig.read(),
+        tsconfig_source,
+        node_cjs_resolve_options(tsconfig.root()),
+    )
+    .await?;
+    if configs.is_empty() {
+        return Ok(Completion::new());
+    }
+    let configs = configs
+        .iter()
+        .map(|(json, source)| async move {
+            let FileJsonContent::Content(json) = &*json.await? else {
+                return Ok(None);
+            };
+            let path = source.ident().path().await?;
+            Ok(project_path_ref
+                .get_relative_path_to(&path)
+                .map(|path| json!({ "path": path, "json": json })))
+        })
+        .try_flat_join()
+        .await?;
+
+    let mut modules = FxIndexSet::default();
+    for &entry in entries.await?.iter() {
+        modules.extend(
+            all_modules_and_affecting_sources(entry)
+                .await?
+                .iter()
+                .copied(),
+        );
+    }
+    let mut files = modules
+        .iter()
+        .map(|module| async move {
+            let path = module.ident().path();
+            let path_ref = path.await?;
+            if !is_type_checked(&path_ref.path) {
+                return Ok(None);
+            }
+            let Some(relative_path) = project_path_ref.get_relative_path_to(&path_ref) else {
+                return Ok(None);
+            };
+            let content = path.read();
+            let FileContent::Content(_) = &*content.await? else {
+                return Ok(None);
+            };
+            let relative_path: RcStr = relative_path
+                .strip_prefix("./")
+                .unwrap_or(&relative_path)
+                .into();
+            let version = content.hash().await?.to_string();
+            Ok(Some((relative_path, (path.to_resolved().await?, version))))
+        })
+        .try_flat_join()
+        .await?
+        .into_iter()
+        .collect::<FxIndexMap<_, _>>();
+    files.sort_keys();
+
+    let executor = evaluate_context
+        .process(
+            Vc::upcast(VirtualSource::new(
+                tsconfig.join("type-check.ts".into()),
+                AssetContent::File(
+                    embed_file("typescript/type-check.ts".into())
+                        .to_resolved()
+                        .await?,
+                )
+                .cell(),
+            )),
+            Value::new(ReferenceType::Internal(InnerAssets::empty())),
+        )
+        .module();
+
+    let args = vec![
+        Vc::cell(JsonValue::Array(configs)),
+        Vc::cell(JsonValue::Array(
+            files
+                .iter()
+                .map(|(path, (_, version))| json!({ "path": path, "version": version }))
+                .collect(),
+        )),
+    ];
+    let result = evaluate(
+        executor,
+        **project_path,
+        **env,
+        tsconfig_source.ident(),
+        evaluate_context,
+        **chunking_context,
+        None,
+        args,
+        Completion::immutable(),
+        **pool_options,
+        false,
+    )
+    .await?;
+    let SingleValue::Single(value) = result.try_into_single().await? else {
+        // An error happened, which has already been converted into an issue.
+        return Ok(Completion::new());
+    };
+    let diagnostics: Vec<TypeCheckDiagnostic> = parse_json_with_source_context(value.to_str()?)?;
+
+    for diagnostic in diagnostics {
+        let file_path = match &diagnostic.path {
+            Some(path) => match files.get(path) {
+                Some((file_path, _)) => *file_path,
+                None => project_path.join(path.clone()).to_resolved().await?,
+            },
+            None => tsconfig.to_resolved().await?,
+        };
+        if let Some(problem) = check_problem(diagnostic) {
+            problem.emit("type check", file_path).await?;
+        }
+    }
+
+    Ok(Completion::new())
+}
+
+/// TypeScript files outside of `node_modules`.
+fn is_type_checked(path: &str) -> bool {
+    !path.split('/').any(|segment| segment == "node_modules")
+        && [".ts", ".tsx", ".mts", ".cts"]
+            .iter()
+            .any(|extension| path.ends_with(extension))
+}
+
+/// Suggestions and messages are not reported.
+fn check_problem(diagnostic: TypeCheckDiagnostic) -> Option<CheckProblem> {
+    let severity = match diagnostic.category {
+        TypeCheckCategory::Error => IssueSeverity::Error,
+        TypeCheckCategory::Warning => IssueSeverity::Warning,
+        TypeCheckCategory::Suggestion | TypeCheckCategory::Message => return None,
+    };
+    let range = match (diagnostic.start, diagnostic.end) {
+        (Some(start), Some(end)) => Some((
+            SourcePos {
+                line: start.line,
+                column: start.column,
+            },
+            SourcePos {
+                line: end.line,
+                column: end.column,
+            },
+        )),
+        _ => None,
+    };
+    // The chained messages explain the first line.
+    let (title, description) = match diagnostic.message.split_once('\n') {
+        Some((title, details)) => (title, Some(details.into())),
+        None => (&*diagnostic.message, None),
+    };
+    Some(CheckProblem {
+        severity,
+        range,
+        title: format!("TS{}: {}", diagnostic.code, title).into(),
+        description,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use turbopack_core::{issue::IssueSeverity, source_pos::SourcePos};
+
+    use super::{check_problem, is_type_checked, TypeCheckCategory, TypeCheckDiagnostic};
+
+    fn diagnostic(message: &str, category: TypeCheckCategory) -> TypeCheckDiagnostic {
+        TypeCheckDiagnostic {
+            path: None,
+            start: None,
+            end: None,
+            message: message.into(),
+            code: 2322,
+            category,
+        }
+    }
+
+    #[test]
+    fn type_checks_typescript_files_outside_of_node_modules() {
+        assert!(is_type_checked("app/page.tsx"));
+        assert!(is_type_checked("lib/utils.mts"));
+        assert!(is_type_checked("lib/types.d.ts"));
+        assert!(!is_type_checked("app/page.jsx"));
+        assert!(!is_type_checked("node_modules/pkg/index.ts"));
+        assert!(!is_type_checked("apps/web/node_modules/pkg/index.ts"));
+        assert!(is_type_checked("lib/node_modules.ts"));
+    }
+
+    #[test]
+    fn reports_errors_and_warnings() {
+        let severity = |category| check_problem(diagnostic("", category)).map(|p| p.severity);
+        assert_eq!(
+            severity(TypeCheckCategory::Error),
+            Some(IssueSeverity::Error)
+        );
+        assert_eq!(
+            severity(TypeCheckCategory::Warning),
+            Some(IssueSeverity::Warning)
+        );
+        assert_eq!(severity(TypeCheckCategory::Suggestion), None);
+        assert_eq!(severity(TypeCheckCategory::Message), None);
+    }
+
+    #[test]
+    fn splits_chained_messages() {
+        let problem = check_problem(diagnostic(
+            "Type 'A' is not assignable to type 'B'.\n  Property 'b' is missing.",
+            TypeCheckCategory::Error,
+        ))
+        .unwrap();
+        assert_eq!(
+            problem.title.as_str(),
+            "TS2322: Type 'A' is not assignable to type 'B'."
+        );
+        assert_eq!(
+            problem.description.as_deref(),
+            Some("  Property 'b' is missing.")
+        );
+
+        let problem = check_problem(diagnostic(
+            "Cannot find name 'x'.",
+            TypeCheckCategory::Error,
+        ))
+        .unwrap();
+        assert_eq!(problem.title.as_str(), "TS2322: Cannot find name 'x'.");
+        assert!(problem.description.is_none());
+    }
+
+    #[test]
+    fn parses_diagnostics() {
+        let diagnostics: Vec<TypeCheckDiagnostic> = serde_json::from_str(
+            r#"[
+                {
+                    "path": "app/page.tsx",
+                    "start": { "line": 2, "column": 4 },
+                    "end": { "line": 2, "column": 9 },
+                    "message": "Type 'string' is not assignable to type 'number'.",
+                    "code": 2322,
+                    "category": "error"
+                },
+                {
+                    "path": null,
+                    "start": null,
+                    "end": null,
+                    "message": "Option 'jsx' is deprecated.",
+                    "code": 5101,
+                    "category": "suggestion"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let Ok([error, suggestion]) = <[_; 2]>::try_from(diagnostics) else {
+            panic!("expected two diagnostics");
+        };
+        assert_eq!(error.path.as_deref(), Some("app/page.tsx"));
+        assert!(suggestion.path.is_none());
+        let problem = check_problem(error).unwrap();
+        assert_eq!(problem.severity, IssueSeverity::Error);
+        assert_eq!(
+            problem.range,
+            Some((
+                SourcePos { line: 2, column: 4 },
+                SourcePos { line: 2, column: 9 }
+            ))
+        );
+        assert!(check_problem(suggestion).is_none());
+    }
+}
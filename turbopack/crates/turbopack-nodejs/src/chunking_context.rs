@@ -394,6 +394,15 @@ impl ChunkingContext for NodeJsChunkingContext {
         bail!("the build chunking context does not support evaluated chunk groups")
     }
 
+    #[turbo_tasks::function]
+    fn single_file_chunk_group_asset(
+        self: Vc<Self>,
+        _path: Vc<FileSystemPath>,
+        _evaluatable_assets: Vc<EvaluatableAssets>,
+    ) -> Result<Vc<Box<dyn OutputAsset>>> {
+        bail!("the build chunking context does not support single file chunk groups")
+    }
+
     #[turbo_tasks::function]
     fn chunk_item_id_from_ident(&self, ident: Vc<AssetIdent>) -> Vc<ModuleId> {
         self.module_id_strategy.get_module_id(ident)
@@ -47,8 +47,9 @@ pub async fn apply_esm_specific_options(
     reference_type: Value<ReferenceType>,
 ) -> Result<Vc<ResolveOptions>> {
     let mut options: ResolveOptions = options.await?.clone_value();
-    // TODO set fully_specified when in strict ESM mode
-    // options.fully_specified = true;
+    if options.esm_fully_specified {
+        options.fully_specified = true;
+    }
     for conditions in get_condition_maps(&mut options) {
         conditions.insert("import".into(), ConditionValue::Set);
         conditions.insert("require".into(), ConditionValue::Unset);
@@ -266,6 +266,9 @@ async fn base_resolve_options(
         plugins,
         before_resolve_plugins: opt.before_resolve_plugins.clone(),
         loose_errors: opt.loose_errors,
+        url_imports: opt.url_imports,
+        extension_alias: opt.extension_alias.clone(),
+        esm_fully_specified: opt.esm_fully_specified,
         ..Default::default()
     }
     .into())
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use turbo_tasks::{RcStr, ResolvedVc, ValueDefault, Vc};
+use turbo_tasks::{FxIndexMap, RcStr, ResolvedVc, ValueDefault, Vc};
 use turbo_tasks_fs::FileSystemPath;
 use turbopack_core::{
     condition::ContextCondition,
@@ -7,6 +7,7 @@ use turbopack_core::{
     resolve::{
         options::{ImportMap, ResolvedMap},
         plugin::{AfterResolvePlugin, BeforeResolvePlugin},
+        url_imports::UrlImportsOptions,
     },
 };
 
@@ -48,6 +49,14 @@ pub struct ResolveOptionsContext {
     #[serde(default)]
     pub custom_extensions: Option<Vec<RcStr>>,
     #[serde(default)]
+    /// Extensions to try instead of the extension of a relative request, e.g.
+    /// `.js` -> [`.ts`, `.tsx`, `.js`].
+    pub extension_alias: FxIndexMap<RcStr, Vec<RcStr>>,
+    #[serde(default)]
+    /// Require ESM requests to be fully specified, i.e. without omitting the
+    /// extension. Can be enabled for some paths only via `rules`.
+    pub esm_fully_specified: bool,
+    #[serde(default)]
     /// An additional import map to use when resolving modules.
     ///
     /// If set, this import map will be applied to `ResolveOption::import_map`.
@@ -74,6 +83,10 @@ pub struct ResolveOptionsContext {
     pub before_resolve_plugins: Vec<ResolvedVc<Box<dyn BeforeResolvePlugin>>>,
     /// Warn instead of error for resolve errors
     pub loose_errors: bool,
+    #[serde(default)]
+    /// Resolve `http:` and `https:` requests to modules fetched from the
+    /// network instead of treating them as externals.
+    pub url_imports: Option<ResolvedVc<UrlImportsOptions>>,
 
     #[serde(default)]
     pub placeholder_for_future_extensions: (),
@@ -11,19 +11,21 @@ use anyhow::{Context, Result};
 use dunce::canonicalize;
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    debug::ValueDebugFormat, fxindexmap, trace::TraceRawVcs, Completion, RcStr, ResolvedVc,
-    TryJoinIterExt, TurboTasks, Value, Vc,
+    debug::ValueDebugFormat, fxindexmap, trace::TraceRawVcs, Completion, FxIndexMap, RcStr,
+    ResolvedVc, TryJoinIterExt, TurboTasks, Value, Vc,
 };
 use turbo_tasks_bytes::stream::SingleValue;
 use turbo_tasks_env::CommandLineProcessEnv;
 use turbo_tasks_fs::{
-    json::parse_json_with_source_context, util::sys_to_unix, DiskFileSystem, FileContent,
-    FileSystem, FileSystemEntryType, FileSystemPath,
+    glob::Glob, json::parse_json_with_source_context, util::sys_to_unix, DiskFileSystem,
+    FileContent, FileSystem, FileSystemEntryType, FileSystemPath,
 };
 use turbo_tasks_memory::MemoryBackend;
 use turbopack::{
     ecmascript::TreeShakingMode,
-    module_options::{EcmascriptOptionsContext, ModuleOptionsContext},
+    module_options::{
+        EcmascriptOptionsContext, ModuleOptionsContext, ModuleRule, ModuleRuleEffect, RuleCondition,
+    },
     ModuleAssetContext,
 };
 use turbopack_core::{
@@ -42,7 +44,7 @@ use turbopack_core::{
     source::Source,
 };
 use turbopack_ecmascript_runtime::RuntimeType;
-use turbopack_node::{debug::should_debug, evaluate::evaluate};
+use turbopack_node::{debug::should_debug, evaluate::evaluate, NodeJsPoolOptions};
 use turbopack_nodejs::NodeJsChunkingContext;
 use turbopack_resolve::resolve_options_context::ResolveOptionsContext;
 use turbopack_test_utils::jest::JestRunResult;
@@ -183,6 +185,10 @@ async fn run(resource: PathBuf, snapshot_mode: IssueSnapshotMode) -> Result<JsRe
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 struct TestOptions {
     tree_shaking_mode: Option<TreeShakingMode>,
+    /// Module rules that set whether ESM requests need to be fully specified,
+    /// keyed by the glob of the file name.
+    #[serde(default)]
+    fully_specified_rules: FxIndexMap<RcStr, bool>,
 }
 
 #[turbo_tasks::value]
@@ -277,6 +283,14 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
         ImportMapping::External(Some("*".into()), ExternalType::EcmaScriptModule).resolved_cell(),
     );
 
+    let mut module_rules = Vec::new();
+    for (glob, &fully_specified) in &options.fully_specified_rules {
+        module_rules.push(ModuleRule::new(
+            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?),
+            vec![ModuleRuleEffect::FullySpecified(fully_specified)],
+        ));
+    }
+
     let asset_context: Vc<Box<dyn AssetContext>> = Vc::upcast(ModuleAssetContext::new(
         Default::default(),
         compile_time_info,
@@ -296,6 +310,7 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
                 }
                 .cell(),
             )],
+            module_rules,
             ..Default::default()
         }
         .into(),
@@ -364,6 +379,7 @@ async fn run_test(prepared_test: Vc<PreparedTest>) -> Result<Vc<RunTestResult>>
         None,
         vec![],
         Completion::immutable(),
+        NodeJsPoolOptions::default().cell(),
         should_debug("execution_test"),
     )
     .await?;
@@ -0,0 +1,17 @@
+import sheet from "./style.css" with { type: "css" };
+import text from "./data.txt" with { type: "text" };
+import bytes from "./data.bin" with { type: "bytes" };
+
+it("exports the CSS text when CSSStyleSheet is not available", () => {
+  expect(typeof CSSStyleSheet).toBe("undefined");
+  expect(sheet).toBe(".red {\n  color: red;\n}\n");
+});
+
+it("exports the text content", () => {
+  expect(text).toBe("hello text\n");
+});
+
+it("exports the bytes content", () => {
+  expect(bytes).toBeInstanceOf(Uint8Array);
+  expect(Array.from(bytes)).toEqual([65, 66]);
+});
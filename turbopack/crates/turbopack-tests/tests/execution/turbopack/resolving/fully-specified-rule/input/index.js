@@ -0,0 +1,17 @@
+import { dep as strictDep, loadDep as loadStrict } from "./strict-esm.js";
+import { dep as looseDep, loadDep as loadLoose } from "./loose-esm.js";
+
+it("should resolve fully specified requests in modules matching the rule", async () => {
+  expect(strictDep).toBe("dep");
+  await expect(loadStrict("dep.js")).resolves.toHaveProperty("default", "dep");
+});
+
+it("should not add extensions to requests in modules matching the rule", async () => {
+  await expect(loadStrict("dep")).rejects.toThrowError();
+});
+
+it("should add extensions to requests in other modules", async () => {
+  expect(looseDep).toBe("dep");
+  await expect(loadLoose("dep")).resolves.toHaveProperty("default", "dep");
+  await expect(loadLoose("dep.js")).resolves.toHaveProperty("default", "dep");
+});
@@ -0,0 +1,3 @@
+export { default as dep } from "./dir/dep";
+
+export const loadDep = (name) => import(`./dir/${name}`);
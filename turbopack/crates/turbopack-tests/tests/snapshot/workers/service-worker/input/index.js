@@ -0,0 +1,3 @@
+navigator.serviceWorker.register(new URL("./sw.js", import.meta.url), {
+  scope: "/",
+});
@@ -0,0 +1,5 @@
+import { version } from "./version.js";
+
+self.addEventListener("install", () => {
+  console.log("installed", version);
+});
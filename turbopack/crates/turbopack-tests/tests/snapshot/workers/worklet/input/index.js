@@ -0,0 +1 @@
+CSS.paintWorklet.addModule(new URL("./paint.js", import.meta.url));
@@ -0,0 +1,11 @@
+import { color } from "./color.js";
+
+registerPaint(
+  "checkerboard",
+  class {
+    paint(ctx, size) {
+      ctx.fillStyle = color;
+      ctx.fillRect(0, 0, size.width, size.height);
+    }
+  }
+);
@@ -20,7 +20,7 @@ use std::{
 };
 
 use anyhow::{bail, Result};
-use css::{CssModuleAsset, ModuleCssAsset};
+use css::{CssModuleAsset, CssModulesOptions, ModuleCssAsset};
 use ecmascript::{
     chunk::EcmascriptChunkPlaceable,
     references::{follow_reexports, FollowExportsResult},
@@ -30,20 +30,20 @@ use ecmascript::{
 use graph::{aggregate, AggregatedGraph, AggregatedGraphNodeContent};
 use module_options::{ModuleOptions, ModuleOptionsContext, ModuleRuleEffect, ModuleType};
 use tracing::Instrument;
-use turbo_tasks::{Completion, RcStr, ResolvedVc, Value, ValueToString, Vc};
+use turbo_tasks::{Completion, RcStr, ResolvedVc, Value, ValueDefault, ValueToString, Vc};
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 pub use turbopack_core::condition;
 use turbopack_core::{
     asset::Asset,
     compile_time_info::CompileTimeInfo,
     context::{AssetContext, ProcessResult},
+    file_source::FileSource,
     issue::{module::ModuleIssue, IssueExt, StyledString},
     module::Module,
     output::OutputAsset,
     raw_module::RawModule,
     reference_type::{
-        CssReferenceSubType, EcmaScriptModulesReferenceSubType, ImportWithType, InnerAssets,
-        ReferenceType,
+        CssReferenceSubType, EcmaScriptModulesReferenceSubType, InnerAssets, ReferenceType,
     },
     resolve::{
         options::ResolveOptions, origin::PlainResolveOrigin, parse::Request, resolve, ExternalType,
@@ -68,6 +68,17 @@ use self::{
     transition::{Transition, TransitionOptions},
 };
 
+async fn css_modules_options(
+    module_asset_context: Vc<ModuleAssetContext>,
+) -> Result<Vc<CssModulesOptions>> {
+    Ok(module_asset_context
+        .module_options_context()
+        .await?
+        .css
+        .modules
+        .map_or_else(CssModulesOptions::value_default, |options| *options))
+}
+
 #[turbo_tasks::function]
 async fn apply_module_type(
     source: Vc<Box<dyn Source>>,
@@ -228,15 +239,20 @@ async fn apply_module_type(
             ))
         }
         ModuleType::CssModule => ResolvedVc::upcast(
-            ModuleCssAsset::new(source, Vc::upcast(module_asset_context))
-                .to_resolved()
-                .await?,
+            ModuleCssAsset::new(
+                source,
+                Vc::upcast(module_asset_context),
+                css_modules_options(module_asset_context).await?,
+            )
+            .to_resolved()
+            .await?,
         ),
         ModuleType::Css { ty, use_swc_css } => ResolvedVc::upcast(
             CssModuleAsset::new(
                 source,
                 Vc::upcast(module_asset_context),
                 *ty,
+                css_modules_options(module_asset_context).await?,
                 module_asset_context
                     .module_options_context()
                     .await?
@@ -503,19 +519,15 @@ async fn process_default_internal(
         _ => None,
     };
 
-    let mut has_type_attribute = false;
+    // The module type for import attributes is set by the first matching rule and can't be
+    // overridden by later rules.
+    let has_type_attribute = matches!(
+        &reference_type,
+        ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::ImportWithType(_))
+    );
 
     let mut current_source = source;
-    let mut current_module_type = match &reference_type {
-        ReferenceType::EcmaScriptModules(EcmaScriptModulesReferenceSubType::ImportWithType(ty)) => {
-            has_type_attribute = true;
-
-            match ty {
-                ImportWithType::Json => Some(ModuleType::Json),
-            }
-        }
-        _ => None,
-    };
+    let mut current_module_type = None;
 
     for (i, rule) in options.await?.rules.iter().enumerate() {
         if has_type_attribute && current_module_type.is_some() {
@@ -558,6 +570,8 @@ async fn process_default_internal(
                     ModuleRuleEffect::ModuleType(module) => {
                         current_module_type = Some(*module);
                     }
+                    // Applied when resolving the references of the module.
+                    ModuleRuleEffect::FullySpecified(_) => {}
                     ModuleRuleEffect::ExtendEcmascriptTransforms { prepend, append } => {
                         current_module_type = match current_module_type {
                             Some(ModuleType::Ecmascript {
@@ -630,6 +644,39 @@ async fn process_default_internal(
     ))
 }
 
+/// The [ModuleRuleEffect::FullySpecified] of the last module rule matching the
+/// module at `origin_path`, if any.
+async fn fully_specified_by_module_rules(
+    module_asset_context: Vc<ModuleAssetContext>,
+    origin_path: Vc<FileSystemPath>,
+) -> Result<Option<bool>> {
+    let options = ModuleOptions::new(
+        origin_path.parent(),
+        module_asset_context.module_options_context(),
+        module_asset_context.resolve_options_context(),
+    )
+    .await?;
+    let origin_path_ref = origin_path.await?;
+    let source = Vc::upcast(FileSource::new(origin_path));
+
+    let mut fully_specified = None;
+    for rule in options.rules.iter() {
+        let Some(value) = rule.effects().find_map(|effect| match effect {
+            ModuleRuleEffect::FullySpecified(value) => Some(*value),
+            _ => None,
+        }) else {
+            continue;
+        };
+        if rule
+            .matches(source, &origin_path_ref, &ReferenceType::Undefined)
+            .await?
+        {
+            fully_specified = Some(value);
+        }
+    }
+    Ok(fully_specified)
+}
+
 #[turbo_tasks::value_impl]
 impl AssetContext for ModuleAssetContext {
     #[turbo_tasks::function]
@@ -655,10 +702,16 @@ impl AssetContext for ModuleAssetContext {
             self
         };
         // TODO move `apply_commonjs/esm_resolve_options` etc. to here
-        Ok(resolve_options(
+        let options = resolve_options(
             origin_path.parent().resolve().await?,
             module_asset_context.await?.resolve_options_context,
-        ))
+        );
+        Ok(
+            match fully_specified_by_module_rules(module_asset_context, origin_path).await? {
+                Some(fully_specified) => options.with_esm_fully_specified(fully_specified),
+                None => options,
+            },
+        )
     }
 
     #[turbo_tasks::function]
@@ -10,14 +10,19 @@ pub use custom_module_type::CustomModuleType;
 pub use module_options_context::*;
 pub use module_rule::*;
 pub use rule_condition::*;
-use turbo_tasks::{RcStr, ResolvedVc, Vc};
+use turbo_tasks::{RcStr, ResolvedVc, Value, Vc};
 use turbo_tasks_fs::{glob::Glob, FileSystemPath};
 use turbopack_core::{
-    reference_type::{CssReferenceSubType, ReferenceType, UrlReferenceSubType},
+    reference_type::{
+        CssReferenceSubType, EcmaScriptModulesReferenceSubType, ImportWithType, ReferenceType,
+        UrlReferenceSubType,
+    },
     resolve::options::{ImportMap, ImportMapping},
 };
 use turbopack_css::CssModuleAssetType;
-use turbopack_ecmascript::{EcmascriptInputTransform, EcmascriptOptions, SpecifiedModuleType};
+use turbopack_ecmascript::{
+    text::ImportWithTypeTransform, EcmascriptInputTransform, EcmascriptOptions, SpecifiedModuleType,
+};
 use turbopack_mdx::MdxTransform;
 use turbopack_node::transforms::{postcss::PostCssTransform, webpack::WebpackLoaders};
 use turbopack_wasm::source::WebAssemblySourceType;
@@ -216,7 +221,41 @@ impl ModuleOptions {
             .collect(),
         );
 
+        let import_with_type = |ty: ImportWithType| {
+            RuleCondition::ReferenceType(ReferenceType::EcmaScriptModules(
+                EcmaScriptModulesReferenceSubType::ImportWithType(ty),
+            ))
+        };
         let mut rules = vec![
+            // Import attributes take precedence over the file extension.
+            ModuleRule::new(
+                import_with_type(ImportWithType::Json),
+                vec![ModuleRuleEffect::ModuleType(ModuleType::Json)],
+            ),
+            ModuleRule::new(
+                import_with_type(ImportWithType::Css),
+                vec![ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
+                    Vc::upcast(ImportWithTypeTransform::new(Value::new(
+                        ImportWithType::Css,
+                    ))),
+                ]))],
+            ),
+            ModuleRule::new(
+                import_with_type(ImportWithType::Text),
+                vec![ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
+                    Vc::upcast(ImportWithTypeTransform::new(Value::new(
+                        ImportWithType::Text,
+                    ))),
+                ]))],
+            ),
+            ModuleRule::new(
+                import_with_type(ImportWithType::Bytes),
+                vec![ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
+                    Vc::upcast(ImportWithTypeTransform::new(Value::new(
+                        ImportWithType::Bytes,
+                    ))),
+                ]))],
+            ),
             ModuleRule::new_all(
                 RuleCondition::ResourcePathEndsWith(".json".to_string()),
                 vec![ModuleRuleEffect::ModuleType(ModuleType::Json)],
@@ -549,19 +588,10 @@ impl ModuleOptions {
                 package_import_map_from_context("loader-runner".into(), path)
             };
             for (glob, rule) in webpack_loaders_options.rules.await?.iter() {
-                rules.push(ModuleRule::new(
-                    RuleCondition::All(vec![
-                        if !glob.contains('/') {
-                            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
-                        } else {
-                            RuleCondition::ResourcePathGlob {
-                                base: execution_context.project_path().await?,
-                                glob: Glob::new(glob.clone()).await?,
-                            }
-                        },
-                        RuleCondition::not(RuleCondition::ResourceIsVirtualSource),
-                    ]),
-                    vec![ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
+                let mut effects = Vec::new();
+                // A rule might only set `fully_specified`.
+                if !rule.loaders.await?.is_empty() || rule.rename_as.is_some() {
+                    effects.push(ModuleRuleEffect::SourceTransforms(Vc::cell(vec![
                         Vc::upcast(WebpackLoaders::new(
                             node_evaluate_asset_context(
                                 *execution_context,
@@ -575,7 +605,24 @@ impl ModuleOptions {
                             rule.rename_as.clone(),
                             resolve_options_context,
                         )),
-                    ]))],
+                    ])));
+                }
+                if let Some(fully_specified) = rule.fully_specified {
+                    effects.push(ModuleRuleEffect::FullySpecified(fully_specified));
+                }
+                rules.push(ModuleRule::new(
+                    RuleCondition::All(vec![
+                        if !glob.contains('/') {
+                            RuleCondition::ResourceBasePathGlob(Glob::new(glob.clone()).await?)
+                        } else {
+                            RuleCondition::ResourcePathGlob {
+                                base: execution_context.project_path().await?,
+                                glob: Glob::new(glob.clone()).await?,
+                            }
+                        },
+                        RuleCondition::not(RuleCondition::ResourceIsVirtualSource),
+                    ]),
+                    effects,
                 ));
             }
         }
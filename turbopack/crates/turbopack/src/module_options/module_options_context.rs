@@ -5,6 +5,7 @@ use turbopack_core::{
     chunk::MinifyType, condition::ContextCondition, environment::Environment,
     resolve::options::ImportMapping,
 };
+use turbopack_css::CssModulesOptions;
 use turbopack_ecmascript::{references::esm::UrlRewriteBehavior, TreeShakingMode};
 pub use turbopack_mdx::MdxTransformOptions;
 use turbopack_node::{
@@ -18,6 +19,9 @@ use super::ModuleRule;
 pub struct LoaderRuleItem {
     pub loaders: Vc<WebpackLoaderItems>,
     pub rename_as: Option<RcStr>,
+    /// Whether ESM requests of the matching modules need to include the
+    /// extension. See [super::ModuleRuleEffect::FullySpecified].
+    pub fully_specified: Option<bool>,
 }
 
 #[derive(Default)]
@@ -174,6 +178,9 @@ pub struct CssOptionsContext {
     /// the module graph, but neither asset types can be emitted directly.
     pub enable_raw_css: bool,
     pub use_swc_css: bool,
+    /// Options for CSS modules. Uses the defaults of [CssModulesOptions] when
+    /// not set.
+    pub modules: Option<ResolvedVc<CssModulesOptions>>,
 
     pub minify_type: MinifyType,
 
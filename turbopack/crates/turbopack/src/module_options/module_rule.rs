@@ -73,6 +73,11 @@ pub enum ModuleRuleEffect {
         append: Vc<EcmascriptInputTransforms>,
     },
     SourceTransforms(Vc<SourceTransforms>),
+    /// Whether ESM requests of the module need to include the extension, like
+    /// `resolve.fullySpecified` of a webpack module rule. Overrides
+    /// `esm_fully_specified` of the resolve options. The last matching rule
+    /// wins.
+    FullySpecified(bool),
 }
 
 #[turbo_tasks::value(serialization = "auto_for_input", shared)]